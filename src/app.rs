@@ -0,0 +1,571 @@
+//! An opt-in framework that ties terminal setup, input, signal handling and drawing together.
+//!
+//! The lower level modules (`base`, `input`, `widget` and `container`) remain usable on their own,
+//! but most applications wire them together in the same way: Set up a `Terminal`, read input from
+//! stdin, react to `SIGWINCH` and `SIGTSTP`, periodically toggle the cursor blink state and redraw
+//! after each event. Implement `Application` and call `run` to get all of this for free.
+//!
+//! Use `run_with` to configure the terminal before the application starts, e.g., to set the
+//! `Theme` that semantic colors are resolved with or to limit the output per frame (see
+//! `RunOptions`).
+//!
+//! Applications built from containers keep their `ContainerManager` (and the `ContainerProvider`)
+//! themselves, since the manager borrows its layout for the lifetime of the application, and
+//! forward to it from the methods of `Application`. Reporting the area of the active container
+//! via `Application::active_area` lets the driver write it first if the output is limited.
+//!
+//! # Examples:
+//! ```no_run //tests do not provide a fully functional terminal
+//! use unsegen::app::*;
+//! use unsegen::base::*;
+//! use unsegen::input::*;
+//! use unsegen::widget::builtin::*;
+//! use unsegen::widget::*;
+//! use std::time::Duration;
+//!
+//! struct Editor {
+//!     line: LineEdit,
+//! }
+//!
+//! impl Application for Editor {
+//!     fn handle_input(&mut self, input: Input) -> Control {
+//!         if input.matches(Key::Esc) {
+//!             return Control::Quit;
+//!         }
+//!         input
+//!             .chain(
+//!                 EditBehavior::new(&mut self.line)
+//!                     .left_on(Key::Left)
+//!                     .right_on(Key::Right)
+//!                     .delete_forwards_on(Key::Delete)
+//!                     .delete_backwards_on(Key::Backspace),
+//!             )
+//!             .finish();
+//!         Control::Continue
+//!     }
+//!
+//!     fn draw(&mut self, window: Window, hints: RenderingHints) {
+//!         self.line.as_widget().draw(window, hints);
+//!     }
+//!
+//!     fn tick_interval(&self) -> Option<Duration> {
+//!         Some(Duration::from_millis(500))
+//!     }
+//! }
+//!
+//! fn main() {
+//!     run(&mut Editor {
+//!         line: LineEdit::new(),
+//!     })
+//!     .unwrap();
+//! }
+//! ```
+//!
+//! An application consisting of containers (see `container`):
+//! ```no_run //tests do not provide a fully functional terminal
+//! use unsegen::app::*;
+//! use unsegen::base::*;
+//! use unsegen::container::*;
+//! use unsegen::input::*;
+//! use unsegen::widget::RenderingHints;
+//! use std::time::Duration;
+//!
+//! struct Panes<'a, C: ContainerProvider> {
+//!     manager: ContainerManager<'a, C>,
+//!     containers: C,
+//!     context: C::Context,
+//! }
+//!
+//! impl<'a, C: ContainerProvider> Application for Panes<'a, C> {
+//!     fn handle_input(&mut self, input: Input) -> Control {
+//!         if input.matches(Key::Ctrl('q')) {
+//!             return Control::Quit;
+//!         }
+//!         input
+//!             .chain(self.manager.mouse_behavior())
+//!             .chain(
+//!                 self.manager
+//!                     .active_container_behavior(&mut self.containers, &mut self.context),
+//!             );
+//!         Control::Continue
+//!     }
+//!
+//!     fn draw(&mut self, window: Window, hints: RenderingHints) {
+//!         let style = StyleModifier::new();
+//!         self.manager.draw(window, &mut self.containers, style, hints);
+//!     }
+//!
+//!     fn active_area(&self) -> Option<Rectangle> {
+//!         self.manager.active_area()
+//!     }
+//!
+//!     fn tick_interval(&self) -> Option<Duration> {
+//!         Some(Duration::from_millis(20))
+//!     }
+//!
+//!     fn tick(&mut self) -> Control {
+//!         self.manager.advance_layout_transition();
+//!         Control::Continue
+//!     }
+//! }
+//!
+//! fn start<C: ContainerProvider>(panes: &mut Panes<C>) {
+//!     let options = RunOptions::new()
+//!         .theme(Theme::new())
+//!         .output_budget(16 * 1024)
+//!         .probe_capabilities(Duration::from_millis(100));
+//!     run_with(panes, options).unwrap();
+//! }
+//! ```
+use base::cleanup::install_panic_hook;
+use base::{Rect, Terminal, Theme, Window};
+use input::Input;
+use nix::sys::signal::{pthread_sigmask, SigSet, SigmaskHow, Signal, SIGTSTP, SIGWINCH};
+use std::io;
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use widget::{Blink, RenderingHints};
+
+/// Tells the driver whether to continue or to stop the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Control {
+    Continue,
+    Quit,
+}
+
+/// An application driven by `run`.
+///
+/// Each iteration of the event loop processes all pending events (inputs, ticks), calls `update`
+/// and finally `draw`s the application to the whole terminal.
+pub trait Application {
+    /// Called once after the terminal has been set up and before the first draw.
+    fn init(&mut self) {}
+
+    /// React to user input. Return `Control::Quit` to stop the application.
+    fn handle_input(&mut self, input: Input) -> Control;
+
+    /// Called before each draw, e.g., to process data from other sources or to dispatch events
+    /// of an `EventBus` to a `ContainerManager`.
+    fn update(&mut self) {}
+
+    /// Draw the application to the root window of the terminal (e.g., using
+    /// `ContainerManager::draw`). The hints are active and have the current blink state.
+    fn draw(&mut self, window: Window, hints: RenderingHints);
+
+    /// The area of the root window that should be written to the terminal first if the output is
+    /// limited (see `RunOptions::output_budget`), e.g., `ContainerManager::active_area`.
+    fn active_area(&self) -> Option<Rect> {
+        None
+    }
+
+    /// The interval at which `tick` is called and the blink state is toggled (default: never).
+    fn tick_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Called periodically as specified by `tick_interval`. Return `Control::Quit` to stop the
+    /// application.
+    fn tick(&mut self) -> Control {
+        Control::Continue
+    }
+}
+
+enum AppEvent {
+    Input(Input),
+    Signal(Signal),
+    InputClosed,
+}
+
+/// Settings of the terminal that `run_with` applies before the application is initialized.
+#[derive(Clone, Debug, Default)]
+pub struct RunOptions {
+    theme: Option<Theme>,
+    output_budget: Option<usize>,
+    probe_timeout: Option<Duration>,
+}
+
+impl RunOptions {
+    /// Create options that leave the terminal as it is after `Terminal::new`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve semantic colors using `theme` (see `Terminal::set_theme`).
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Write at most `bytes` of content per frame (see `Terminal::set_output_budget`). The driver
+    /// writes deferred lines in subsequent frames without waiting for the next event.
+    pub fn output_budget(mut self, bytes: usize) -> Self {
+        self.output_budget = Some(bytes);
+        self
+    }
+
+    /// Query the terminal for its capabilities on startup and wait at most `timeout` for the
+    /// responses (see `Terminal::probe_capabilities`).
+    pub fn probe_capabilities(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = Some(timeout);
+        self
+    }
+}
+
+/// Run the application on stdin/stdout until it returns `Control::Quit` or stdin is closed.
+///
+/// This is a shorthand for `run_with(app, RunOptions::new())`.
+pub fn run<A: Application>(app: &mut A) -> io::Result<()> {
+    run_with(app, RunOptions::new())
+}
+
+/// Run the application on stdin/stdout after configuring the terminal as specified by `options`
+/// until the application returns `Control::Quit` or stdin is closed.
+///
+/// `SIGWINCH` and `SIGTSTP` are blocked in the calling thread (and thus all threads spawned by it
+/// afterwards) and handled by the driver: The application is redrawn when the terminal is resized
+/// and suspended properly (see `Terminal::handle_sigtstp`) on `SIGTSTP`.
+///
+/// If the calling thread panics, the terminal state is restored before the panic message is
+/// printed (see `base::cleanup::install_panic_hook`).
+///
+/// Input and signals are received on background threads that are not joined when this function
+/// returns, so it is meant to be called once per process.
+pub fn run_with<A: Application>(app: &mut A, options: RunOptions) -> io::Result<()> {
+    let mut signals = SigSet::empty();
+    signals.add(SIGWINCH);
+    signals.add(SIGTSTP);
+    pthread_sigmask(SigmaskHow::SIG_BLOCK, Some(&signals), None)?;
+
+    let stdout = io::stdout();
+    let mut terminal = Terminal::new(stdout.lock())?;
+    install_panic_hook();
+    if let Some(theme) = options.theme {
+        terminal.set_theme(theme);
+    }
+    terminal.set_output_budget(options.output_budget);
+    if let Some(timeout) = options.probe_timeout {
+        // Probe before the input thread starts reading from stdin.
+        terminal.probe_capabilities(&mut io::stdin(), timeout)?;
+    }
+
+    let (sender, receiver) = channel();
+    let input_sender = sender.clone();
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        for input in Input::read_all(stdin.lock()) {
+            match input {
+                Ok(input) => {
+                    if input_sender.send(AppEvent::Input(input)).is_err() {
+                        return;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        let _ = input_sender.send(AppEvent::InputClosed);
+    });
+    thread::spawn(move || {
+        while let Ok(signal) = signals.wait() {
+            if sender.send(AppEvent::Signal(signal)).is_err() {
+                return;
+            }
+        }
+    });
+
+    event_loop(app, &mut terminal, receiver)
+}
+
+/// The parts of `Terminal` that the event loop needs (and tests can provide without a tty).
+trait Screen {
+    fn create_root_window(&mut self) -> Window<'_>;
+    fn present(&mut self);
+    fn prioritize(&mut self, area: Rect);
+    fn has_pending_output(&self) -> bool;
+    fn handle_sigtstp(&mut self) -> io::Result<()>;
+}
+
+impl<'a, T: Write + AsRawFd> Screen for Terminal<'a, T> {
+    fn create_root_window(&mut self) -> Window<'_> {
+        Terminal::create_root_window(self)
+    }
+    fn present(&mut self) {
+        Terminal::present(self)
+    }
+    fn prioritize(&mut self, area: Rect) {
+        Terminal::prioritize(self, area)
+    }
+    fn has_pending_output(&self) -> bool {
+        Terminal::has_pending_output(self)
+    }
+    fn handle_sigtstp(&mut self) -> io::Result<()> {
+        Terminal::handle_sigtstp(self)
+    }
+}
+
+/// Drive `app` using the events from `receiver` until it quits or all senders are gone.
+fn event_loop<A: Application, S: Screen>(
+    app: &mut A,
+    terminal: &mut S,
+    receiver: Receiver<AppEvent>,
+) -> io::Result<()> {
+    let mut blink = Blink::On;
+    let mut next_tick = app.tick_interval().map(|i| Instant::now() + i);
+
+    app.init();
+    loop {
+        app.update();
+        app.draw(
+            terminal.create_root_window(),
+            RenderingHints::default().active(true).blink(blink),
+        );
+        if let Some(area) = app.active_area() {
+            terminal.prioritize(area);
+        }
+        terminal.present();
+
+        // Wait for the next event (unless output has been deferred), then process all pending
+        // ones before drawing again.
+        let timeout = if terminal.has_pending_output() {
+            Some(Duration::from_secs(0))
+        } else {
+            next_tick.map(|tick| tick.saturating_duration_since(Instant::now()))
+        };
+        let mut event = match timeout {
+            Some(timeout) => match receiver.recv_timeout(timeout) {
+                Ok(event) => Some(event),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            },
+            None => match receiver.recv() {
+                Ok(event) => Some(event),
+                Err(_) => return Ok(()),
+            },
+        };
+        loop {
+            let control = match event {
+                Some(AppEvent::Input(input)) => app.handle_input(input),
+                Some(AppEvent::Signal(SIGTSTP)) => {
+                    terminal.handle_sigtstp()?;
+                    Control::Continue
+                }
+                Some(AppEvent::Signal(_)) => Control::Continue,
+                Some(AppEvent::InputClosed) => Control::Quit,
+                None => Control::Continue,
+            };
+            if control == Control::Quit {
+                return Ok(());
+            }
+            if let (Some(tick), Some(interval)) = (next_tick, app.tick_interval()) {
+                if tick <= Instant::now() {
+                    next_tick = Some(Instant::now() + interval);
+                    blink.toggle();
+                    if app.tick() == Control::Quit {
+                        return Ok(());
+                    }
+                }
+            }
+            event = match receiver.try_recv() {
+                Ok(event) => Some(event),
+                Err(_) => break,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::terminal::test::FakeTerminal;
+    use input::{Event, Key, ToEvent};
+    use nix::sys::signal::SIGWINCH;
+    use std::sync::mpsc::Sender;
+    use widget::Widget;
+
+    impl Screen for FakeTerminal {
+        fn create_root_window(&mut self) -> Window<'_> {
+            FakeTerminal::create_root_window(self)
+        }
+        fn present(&mut self) {}
+        fn prioritize(&mut self, _area: Rect) {}
+        fn has_pending_output(&self) -> bool {
+            false
+        }
+        fn handle_sigtstp(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A screen that defers output for the first `pending` presents and quits the application
+    /// (by sending Esc) once all output has been written.
+    struct ThrottledScreen {
+        term: FakeTerminal,
+        pending: usize,
+        presents: usize,
+        prioritized: Option<Rect>,
+        sender: Sender<AppEvent>,
+    }
+
+    impl Screen for ThrottledScreen {
+        fn create_root_window(&mut self) -> Window<'_> {
+            self.term.create_root_window()
+        }
+        fn present(&mut self) {
+            self.presents += 1;
+            self.pending = self.pending.saturating_sub(1);
+            if self.pending == 0 {
+                self.sender
+                    .send(AppEvent::Input(Input::new(Key::Esc)))
+                    .unwrap();
+            }
+        }
+        fn prioritize(&mut self, area: Rect) {
+            self.prioritized = Some(area);
+        }
+        fn has_pending_output(&self) -> bool {
+            self.pending > 0
+        }
+        fn handle_sigtstp(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        initialized: bool,
+        inputs: Vec<Event>,
+        blinks: Vec<Blink>,
+        tick_interval: Option<Duration>,
+        ticks: usize,
+        active_area: Option<Rect>,
+    }
+
+    impl Application for Recorder {
+        fn init(&mut self) {
+            self.initialized = true;
+        }
+        fn handle_input(&mut self, input: Input) -> Control {
+            if input.matches(Key::Esc) {
+                return Control::Quit;
+            }
+            self.inputs.push(input.event);
+            Control::Continue
+        }
+        fn draw(&mut self, window: Window, hints: RenderingHints) {
+            assert!(self.initialized);
+            self.blinks.push(hints.blink);
+            format!("{}", self.inputs.len())
+                .as_str()
+                .draw(window, hints);
+        }
+        fn active_area(&self) -> Option<Rect> {
+            self.active_area.clone()
+        }
+        fn tick_interval(&self) -> Option<Duration> {
+            self.tick_interval
+        }
+        fn tick(&mut self) -> Control {
+            self.ticks += 1;
+            if self.ticks == 3 {
+                Control::Quit
+            } else {
+                Control::Continue
+            }
+        }
+    }
+
+    fn key(c: char) -> AppEvent {
+        AppEvent::Input(Input::new(Key::Char(c)))
+    }
+
+    #[test]
+    fn test_event_loop_input() {
+        let mut app = Recorder::default();
+        let mut term = FakeTerminal::with_size((1, 1));
+        let (sender, receiver) = channel();
+        let handle = thread::spawn(move || {
+            sender.send(key('a')).unwrap();
+            sender.send(AppEvent::Signal(SIGWINCH)).unwrap();
+            sender.send(AppEvent::Signal(SIGTSTP)).unwrap();
+            sender.send(key('b')).unwrap();
+            sender.send(AppEvent::InputClosed).unwrap();
+            sender.send(key('c')).unwrap();
+        });
+        event_loop(&mut app, &mut term, receiver).unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(
+            app.inputs,
+            vec![Key::Char('a').to_event(), Key::Char('b').to_event()]
+        );
+        assert!(app.blinks.iter().all(|&b| b == Blink::On));
+    }
+
+    #[test]
+    fn test_event_loop_quit() {
+        let mut app = Recorder::default();
+        let mut term = FakeTerminal::with_size((1, 1));
+        let (sender, receiver) = channel();
+        sender.send(key('a')).unwrap();
+        sender.send(AppEvent::Input(Input::new(Key::Esc))).unwrap();
+        sender.send(key('b')).unwrap();
+        event_loop(&mut app, &mut term, receiver).unwrap();
+
+        assert_eq!(app.inputs, vec![Key::Char('a').to_event()]);
+        assert_eq!(app.blinks.len(), 1);
+        term.assert_looks_like("0");
+    }
+
+    #[test]
+    fn test_event_loop_redraws_until_disconnected() {
+        let mut app = Recorder::default();
+        let mut term = FakeTerminal::with_size((1, 1));
+        let (sender, receiver) = channel();
+        sender.send(key('a')).unwrap();
+        drop(sender);
+        event_loop(&mut app, &mut term, receiver).unwrap();
+
+        assert_eq!(app.blinks.len(), 2);
+        term.assert_looks_like("1");
+    }
+
+    #[test]
+    fn test_event_loop_ticks() {
+        let mut app = Recorder {
+            tick_interval: Some(Duration::from_millis(1)),
+            ..Default::default()
+        };
+        let mut term = FakeTerminal::with_size((1, 1));
+        let (_sender, receiver) = channel();
+        event_loop(&mut app, &mut term, receiver).unwrap();
+
+        assert_eq!(app.ticks, 3);
+        assert_eq!(app.blinks, vec![Blink::On, Blink::Off, Blink::On]);
+    }
+
+    #[test]
+    fn test_event_loop_presents_pending_output() {
+        let area = Rect::new(0.into()..1.into(), 1.into()..2.into());
+        let mut app = Recorder {
+            active_area: Some(area.clone()),
+            ..Default::default()
+        };
+        let (sender, receiver) = channel();
+        let mut screen = ThrottledScreen {
+            term: FakeTerminal::with_size((1, 2)),
+            pending: 3,
+            presents: 0,
+            prioritized: None,
+            sender,
+        };
+        event_loop(&mut app, &mut screen, receiver).unwrap();
+
+        // The application is redrawn (and the output presented) without waiting for input.
+        assert_eq!(screen.presents, 3);
+        assert_eq!(app.blinks.len(), 3);
+        assert_eq!(screen.prioritized, Some(area));
+    }
+}
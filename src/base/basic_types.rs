@@ -222,6 +222,64 @@ impl<T: AxisDimension> AxisDiff<T> {
     pub fn positive_or_zero(self) -> PositiveAxisDiff<T> {
         PositiveAxisDiff::new_unchecked(self.val.max(0))
     }
+
+    /// Add two AxisDiffs, returning None if the result would overflow.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::ColDiff;
+    /// assert_eq!(ColDiff::new(27).checked_add(ColDiff::new(-37)), Some(ColDiff::new(-10)));
+    /// assert_eq!(ColDiff::new(i32::max_value()).checked_add(ColDiff::new(1)), None);
+    /// ```
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.val.checked_add(rhs.val).map(AxisDiff::new)
+    }
+
+    /// Add two AxisDiffs, saturating at the numeric bounds instead of overflowing.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::ColDiff;
+    /// assert_eq!(ColDiff::new(27).saturating_add(ColDiff::new(-37)), ColDiff::new(-10));
+    /// assert_eq!(
+    ///     ColDiff::new(i32::max_value()).saturating_add(ColDiff::new(1)),
+    ///     ColDiff::new(i32::max_value())
+    /// );
+    /// ```
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        AxisDiff::new(self.val.saturating_add(rhs.val))
+    }
+
+    /// Subtract two AxisDiffs, returning None if the result would overflow.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::ColDiff;
+    /// assert_eq!(ColDiff::new(27).checked_sub(ColDiff::new(37)), Some(ColDiff::new(-10)));
+    /// assert_eq!(ColDiff::new(i32::min_value()).checked_sub(ColDiff::new(1)), None);
+    /// ```
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.val.checked_sub(rhs.val).map(AxisDiff::new)
+    }
+
+    /// Subtract two AxisDiffs, saturating at the numeric bounds instead of overflowing.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::ColDiff;
+    /// assert_eq!(ColDiff::new(27).saturating_sub(ColDiff::new(37)), ColDiff::new(-10));
+    /// assert_eq!(
+    ///     ColDiff::new(i32::min_value()).saturating_sub(ColDiff::new(1)),
+    ///     ColDiff::new(i32::min_value())
+    /// );
+    /// ```
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        AxisDiff::new(self.val.saturating_sub(rhs.val))
+    }
 }
 impl<T: AxisDimension> From<i32> for AxisDiff<T> {
     fn from(v: i32) -> Self {
@@ -303,6 +361,14 @@ pub struct PositiveAxisDiff<T: AxisDimension> {
 }
 
 impl<T: AxisDimension> PositiveAxisDiff<T> {
+    /// The largest representable value. It is used to denote targets that do not have a maximum
+    /// size (e.g., see `ExtentEstimationWindow::unbounded`). Saturating operations never exceed
+    /// this value.
+    pub const UNBOUNDED: Self = PositiveAxisDiff {
+        val: i32::MAX,
+        _dim: PhantomData,
+    };
+
     /// Create a new PositiveAxisDiff from an i32.
     /// If v < 0 the behavior is unspecified.
     ///
@@ -390,6 +456,119 @@ impl<T: AxisDimension> PositiveAxisDiff<T> {
     pub fn to_signed(self) -> AxisDiff<T> {
         AxisDiff::new(self.val)
     }
+
+    /// Check whether the value is `UNBOUNDED`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::Width;
+    /// assert!(Width::UNBOUNDED.is_unbounded());
+    /// assert!(!Width::new(37).unwrap().is_unbounded());
+    /// ```
+    pub fn is_unbounded(self) -> bool {
+        self == Self::UNBOUNDED
+    }
+
+    /// Add two PositiveAxisDiffs, returning None if the result would overflow.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::Width;
+    /// assert_eq!(
+    ///     Width::new(27).unwrap().checked_add(Width::new(10).unwrap()),
+    ///     Some(Width::new(37).unwrap())
+    /// );
+    /// assert_eq!(Width::UNBOUNDED.checked_add(Width::new(1).unwrap()), None);
+    /// ```
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.val
+            .checked_add(rhs.val)
+            .map(PositiveAxisDiff::new_unchecked)
+    }
+
+    /// Add two PositiveAxisDiffs, saturating at `UNBOUNDED` instead of overflowing.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::Width;
+    /// assert_eq!(
+    ///     Width::new(27).unwrap().saturating_add(Width::new(10).unwrap()),
+    ///     Width::new(37).unwrap()
+    /// );
+    /// assert_eq!(Width::UNBOUNDED.saturating_add(Width::new(1).unwrap()), Width::UNBOUNDED);
+    /// ```
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        PositiveAxisDiff::new_unchecked(self.val.saturating_add(rhs.val))
+    }
+
+    /// Subtract two PositiveAxisDiffs, returning None if the result would be negative.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::Width;
+    /// assert_eq!(
+    ///     Width::new(37).unwrap().checked_sub(Width::new(10).unwrap()),
+    ///     Some(Width::new(27).unwrap())
+    /// );
+    /// assert_eq!(Width::new(10).unwrap().checked_sub(Width::new(37).unwrap()), None);
+    /// ```
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        PositiveAxisDiff::new(self.val - rhs.val).ok()
+    }
+
+    /// Subtract two PositiveAxisDiffs, saturating at zero.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::Width;
+    /// assert_eq!(
+    ///     Width::new(37).unwrap().saturating_sub(Width::new(10).unwrap()),
+    ///     Width::new(27).unwrap()
+    /// );
+    /// assert_eq!(
+    ///     Width::new(10).unwrap().saturating_sub(Width::new(37).unwrap()),
+    ///     Width::new(0).unwrap()
+    /// );
+    /// ```
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        PositiveAxisDiff::new_unchecked((self.val - rhs.val).max(0))
+    }
+
+    /// Multiply with a scalar, returning None if the result would overflow.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::Width;
+    /// assert_eq!(Width::new(3).unwrap().checked_mul(4), Some(Width::new(12).unwrap()));
+    /// assert_eq!(Width::UNBOUNDED.checked_mul(2), None);
+    /// ```
+    pub fn checked_mul(self, rhs: usize) -> Option<Self> {
+        if rhs > i32::MAX as usize {
+            return if self.val == 0 { Some(self) } else { None };
+        }
+        self.val
+            .checked_mul(rhs as i32)
+            .map(PositiveAxisDiff::new_unchecked)
+    }
+
+    /// Multiply with a scalar, saturating at `UNBOUNDED` instead of overflowing.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::Width;
+    /// assert_eq!(Width::new(3).unwrap().saturating_mul(4), Width::new(12).unwrap());
+    /// assert_eq!(Width::UNBOUNDED.saturating_mul(2), Width::UNBOUNDED);
+    /// ```
+    pub fn saturating_mul(self, rhs: usize) -> Self {
+        self.checked_mul(rhs).unwrap_or(Self::UNBOUNDED)
+    }
 }
 impl<T: AxisDimension> Into<i32> for PositiveAxisDiff<T> {
     fn into(self) -> i32 {
@@ -513,9 +692,34 @@ impl<'a, T: 'a + AxisDimension + PartialOrd + Ord> Sum<&'a AxisDiff<T>> for Axis
 // Concrete types for concrete dimensions -------------------------------------
 // ----------------------------------------------------------------------------
 
-/// Trait for all dimensions of a terminal grid. See RowDimension and ColDimension.
+/// Trait for all dimensions that `AxisIndex`, `AxisDiff` and `PositiveAxisDiff` (as well as
+/// `Rect` and `Demand`) can refer to. See RowDimension and ColDimension for the dimensions of the
+/// terminal grid.
+///
+/// Implement this trait to define your own typed axes, so that the compiler prevents you from
+/// mixing up, e.g., lines of a buffer and rows on the screen:
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::*;
+///
+/// #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct BufferLineDimension;
+/// impl AxisDimension for BufferLineDimension {}
+/// type BufferLine = AxisIndex<BufferLineDimension>;
+///
+/// let first_visible = BufferLine::new(10);
+/// let screen_row = RowIndex::new(3);
+/// let line = first_visible + screen_row.raw_value();
+/// assert_eq!(line, BufferLine::new(13));
+/// // let _ = line + screen_row.diff_to_origin(); // Does not compile
+/// ```
+pub trait AxisDimension: Copy {}
+
+/// Trait for the dimensions of the terminal grid (i.e., RowDimension and ColDimension) which are
+/// used to address the cells of a `Window`.
 /// You probably do not want to implement this trait yourself.
-pub trait AxisDimension: Copy {
+pub trait GridDimension: AxisDimension {
     /// The equivalent ndarray dimension. (Used in `Axis(...)`)
     const NDARRAY_AXIS_NUMBER: usize;
 
@@ -526,7 +730,8 @@ pub trait AxisDimension: Copy {
 /// The horizontal (i.e., x-) dimension of a terminal grid. See ColIndex, ColDiff, and Width.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ColDimension;
-impl AxisDimension for ColDimension {
+impl AxisDimension for ColDimension {}
+impl GridDimension for ColDimension {
     const NDARRAY_AXIS_NUMBER: usize = 1;
 
     fn get_dimension_value(val: (usize, usize)) -> usize {
@@ -537,7 +742,8 @@ impl AxisDimension for ColDimension {
 /// The vertical (i.e., y-) dimension of a terminal grid. See RowIndex, RowDiff and Height.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RowDimension;
-impl AxisDimension for RowDimension {
+impl AxisDimension for RowDimension {}
+impl GridDimension for RowDimension {
     const NDARRAY_AXIS_NUMBER: usize = 0;
 
     fn get_dimension_value(val: (usize, usize)) -> usize {
@@ -0,0 +1,339 @@
+//! Simplified bidirectional (bidi) text reordering.
+//!
+//! Text is stored in logical order (the order in which it is read), but right-to-left scripts such
+//! as Arabic or Hebrew have to be displayed in reverse visual order, and mixed with left-to-right
+//! text (including numbers) in a line. This module implements a simplified version of the Unicode
+//! Bidirectional Algorithm (UAX #9) that computes the visual order of the grapheme clusters of a
+//! single line:
+//!
+//! * Clusters are classified as strong left-to-right, strong right-to-left (by the code point
+//!   ranges of right-to-left scripts), numbers (ASCII digits) or neutral (everything else).
+//! * Numbers stay left-to-right, even within right-to-left text.
+//! * Neutrals take the direction of the surrounding strong text if it agrees on both sides, and the
+//!   paragraph direction otherwise.
+//! * Explicit embeddings, overrides and isolates are not supported.
+//!
+//! Reordering is applied by a `Cursor` (see `Cursor::bidi`) when writing text. How the cursor of
+//! text editing widgets (`TextEdit` and `LineEdit`) moves left and right in reordered lines is
+//! configurable (see `CursorMovement` and `set_cursor_movement`): By default, movement is logical,
+//! i.e., moving right always advances to the next cluster in the text, regardless of its visual
+//! position. With `CursorMovement::Visual`, the cursor moves to the cluster that is displayed next
+//! to it instead (see `visual_neighbor`).
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The direction of a run of text or a paragraph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Defines whether and how a line of text is reordered for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BidiMode {
+    /// Do not reorder text, i.e., display it in logical order.
+    #[default]
+    Disabled,
+    /// Reorder text with the paragraph direction determined by the first strong character of the
+    /// line (left-to-right if there is none).
+    Auto,
+    /// Reorder text with the specified paragraph direction.
+    Paragraph(TextDirection),
+}
+
+/// Defines how the cursor of a text editing widget moves left and right in reordered lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorMovement {
+    /// Move to the previous (left) or next (right) cluster in the text.
+    #[default]
+    Logical,
+    /// Move to the cluster that is displayed to the left or right of the current one.
+    Visual,
+}
+
+/// A grapheme cluster of a line in visual order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisualCluster {
+    /// The byte range of the cluster in the line.
+    pub range: Range<usize>,
+    /// The resolved direction of the cluster. Mirrorable characters (e.g., brackets) of right-to-
+    /// left clusters have to be displayed mirrored (see `mirrored`).
+    pub direction: TextDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Left,
+    Right,
+    Number,
+    Neutral,
+}
+
+fn is_rtl(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+    )
+}
+
+fn classify(cluster: &str) -> Class {
+    let c = cluster.chars().next().unwrap_or(' ');
+    if is_rtl(c) {
+        Class::Right
+    } else if c.is_ascii_digit() {
+        Class::Number
+    } else if c.is_alphabetic() {
+        Class::Left
+    } else {
+        Class::Neutral
+    }
+}
+
+/// Check whether the text contains any right-to-left characters, i.e., whether reordering it in
+/// `BidiMode::Auto` would make any difference.
+pub fn contains_rtl(text: &str) -> bool {
+    text.chars().any(is_rtl)
+}
+
+/// Determine the paragraph direction of the line from its first strong character.
+pub fn paragraph_direction(line: &str) -> TextDirection {
+    for cluster in line.graphemes(true) {
+        match classify(cluster) {
+            Class::Left => return TextDirection::LeftToRight,
+            Class::Right => return TextDirection::RightToLeft,
+            _ => {}
+        }
+    }
+    TextDirection::LeftToRight
+}
+
+/// Get the mirrored counterpart of a cluster that has to be displayed mirrored in right-to-left
+/// text (e.g., "(" for ")"), if there is one.
+pub fn mirrored(cluster: &str) -> Option<&'static str> {
+    Some(match cluster {
+        "(" => ")",
+        ")" => "(",
+        "[" => "]",
+        "]" => "[",
+        "{" => "}",
+        "}" => "{",
+        "<" => ">",
+        ">" => "<",
+        "«" => "»",
+        "»" => "«",
+        _ => return None,
+    })
+}
+
+/// Compute the grapheme clusters of a single line (i.e., without newlines) in visual order, i.e.,
+/// the order in which they should be displayed from left to right.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::*;
+///
+/// let visual = |line: &str, mode| {
+///     visual_order(line, mode)
+///         .into_iter()
+///         .map(|c| &line[c.range])
+///         .collect::<String>()
+/// };
+/// // Hebrew letters alef, bet, gimel:
+/// assert_eq!(visual("ab אבג 12 cd", BidiMode::Auto), "ab 12 גבא cd");
+/// assert_eq!(visual("אבג ab", BidiMode::Auto), "ab גבא");
+/// assert_eq!(visual("ab אבג", BidiMode::Paragraph(TextDirection::RightToLeft)), "גבא ab");
+/// assert_eq!(visual("ab אבג", BidiMode::Disabled), "ab אבג");
+/// ```
+pub fn visual_order(line: &str, mode: BidiMode) -> Vec<VisualCluster> {
+    let clusters = line
+        .grapheme_indices(true)
+        .map(|(offset, cluster)| (offset..offset + cluster.len(), classify(cluster)))
+        .collect::<Vec<_>>();
+
+    let paragraph = match mode {
+        BidiMode::Disabled => {
+            return clusters
+                .into_iter()
+                .map(|(range, _)| VisualCluster {
+                    range,
+                    direction: TextDirection::LeftToRight,
+                })
+                .collect();
+        }
+        BidiMode::Auto => paragraph_direction(line),
+        BidiMode::Paragraph(direction) => direction,
+    };
+    let rtl_paragraph = paragraph == TextDirection::RightToLeft;
+
+    // Embedding levels: even levels are left-to-right, odd levels right-to-left.
+    let base_level = if rtl_paragraph { 1 } else { 0 };
+    let left_level = if rtl_paragraph { 2 } else { 0 };
+    let right_level = 1;
+
+    let mut levels = vec![base_level; clusters.len()];
+    // The direction of the last strong cluster. Numbers following left-to-right text are treated
+    // as left-to-right text, otherwise as right-to-left text, when resolving neutrals.
+    let mut previous_strong = paragraph;
+    for (i, &(_, class)) in clusters.iter().enumerate() {
+        levels[i] = match class {
+            Class::Left => {
+                previous_strong = TextDirection::LeftToRight;
+                left_level
+            }
+            Class::Right => {
+                previous_strong = TextDirection::RightToLeft;
+                right_level
+            }
+            Class::Number => {
+                if previous_strong == TextDirection::RightToLeft || rtl_paragraph {
+                    2
+                } else {
+                    0
+                }
+            }
+            Class::Neutral => {
+                let next = clusters[i + 1..]
+                    .iter()
+                    .filter_map(|&(_, class)| match class {
+                        Class::Left => Some(TextDirection::LeftToRight),
+                        Class::Right => Some(TextDirection::RightToLeft),
+                        Class::Number => Some(previous_strong),
+                        Class::Neutral => None,
+                    })
+                    .next()
+                    .unwrap_or(paragraph);
+                if next != previous_strong {
+                    base_level
+                } else if next == TextDirection::LeftToRight {
+                    left_level
+                } else {
+                    right_level
+                }
+            }
+        };
+    }
+
+    // Reverse all maximal sequences at or above each level from the highest to the lowest odd
+    // level.
+    let mut order = (0..clusters.len()).collect::<Vec<_>>();
+    let max_level = levels.iter().cloned().max().unwrap_or(0);
+    for level in (1..=max_level).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] >= level {
+                let start = i;
+                while i < order.len() && levels[order[i]] >= level {
+                    i += 1;
+                }
+                order[start..i].reverse();
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|i| VisualCluster {
+            range: clusters[i].0.clone(),
+            direction: if levels[i] % 2 == 1 {
+                TextDirection::RightToLeft
+            } else {
+                TextDirection::LeftToRight
+            },
+        })
+        .collect()
+}
+
+/// Find the byte position in `line` that is displayed next to the cluster at byte position `pos`
+/// (to the right if `right` is true, to the left otherwise).
+///
+/// The position at the end of the line (`line.len()`) is displayed at the end of the paragraph
+/// direction, i.e., on the right for left-to-right and on the left for right-to-left paragraphs.
+/// `None` is returned if `pos` is not the start of a cluster or there is no neighbor.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::*;
+///
+/// // Displayed as "ab גבא": Moving right from 'b' lands on 'ג'.
+/// let line = "ab אבג";
+/// let gimel = line.find('ג').unwrap();
+/// assert_eq!(visual_neighbor(line, BidiMode::Auto, 1, true), Some(2));
+/// assert_eq!(visual_neighbor(line, BidiMode::Auto, 2, true), Some(gimel));
+/// assert_eq!(visual_neighbor(line, BidiMode::Auto, 3, true), Some(line.len()));
+/// assert_eq!(visual_neighbor(line, BidiMode::Auto, line.len(), true), None);
+/// ```
+pub fn visual_neighbor(line: &str, mode: BidiMode, pos: usize, right: bool) -> Option<usize> {
+    let mut positions = visual_order(line, mode)
+        .into_iter()
+        .map(|c| c.range.start)
+        .collect::<Vec<_>>();
+    let paragraph = match mode {
+        BidiMode::Disabled => TextDirection::LeftToRight,
+        BidiMode::Auto => paragraph_direction(line),
+        BidiMode::Paragraph(direction) => direction,
+    };
+    match paragraph {
+        TextDirection::LeftToRight => positions.push(line.len()),
+        TextDirection::RightToLeft => positions.insert(0, line.len()),
+    }
+    let current = positions.iter().position(|&p| p == pos)?;
+    let next = if right {
+        current + 1
+    } else {
+        current.checked_sub(1)?
+    };
+    positions.get(next).cloned()
+}
+
+/// Check whether the line is reordered for display in the specified mode.
+pub fn is_reordered(line: &str, mode: BidiMode) -> bool {
+    match mode {
+        BidiMode::Disabled => false,
+        BidiMode::Auto => contains_rtl(line),
+        BidiMode::Paragraph(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn visual(line: &str, mode: BidiMode) -> String {
+        visual_order(line, mode)
+            .into_iter()
+            .map(|c| {
+                let cluster = &line[c.range];
+                match c.direction {
+                    TextDirection::RightToLeft => mirrored(cluster).unwrap_or(cluster),
+                    TextDirection::LeftToRight => cluster,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_visual_order() {
+        assert_eq!(visual("", BidiMode::Auto), "");
+        assert_eq!(visual("abc def", BidiMode::Auto), "abc def");
+        assert_eq!(visual("אבג דה", BidiMode::Auto), "הד גבא");
+        assert_eq!(visual("אבג 123", BidiMode::Auto), "123 גבא");
+        assert_eq!(visual("ab (אב) cd", BidiMode::Auto), "ab (בא) cd");
+        assert_eq!(visual("אב (cd) גד", BidiMode::Auto), "דג (cd) בא");
+        assert_eq!(visual("אב (גד)", BidiMode::Auto), "(דג) בא");
+        assert_eq!(visual("ab 12 cd", BidiMode::Auto), "ab 12 cd");
+        assert_eq!(
+            visual("ab 12 cd", BidiMode::Paragraph(TextDirection::RightToLeft)),
+            "ab 12 cd"
+        );
+        assert_eq!(
+            visual("ab אב", BidiMode::Paragraph(TextDirection::RightToLeft)),
+            "בא ab"
+        );
+        assert_eq!(visual("אב, ", BidiMode::Auto), " ,בא");
+        assert_eq!(visual("ab אב, ", BidiMode::Auto), "ab בא, ");
+    }
+}
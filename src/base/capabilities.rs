@@ -0,0 +1,307 @@
+//! Detection of optional features supported by the terminal (emulator).
+//!
+//! `Capabilities` are initially guessed from the environment (`TERM`, `COLORTERM`,
+//! `TERM_PROGRAM`, ...) when a `Terminal` is created. For more accurate information, the terminal
+//! can be queried at runtime using `Terminal::probe_capabilities`.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::base::capabilities::*;
+//!
+//! let env = |name: &str| match name {
+//!     "TERM" => Some("xterm-256color".to_owned()),
+//!     "COLORTERM" => Some("truecolor".to_owned()),
+//!     _ => None,
+//! };
+//! let mut caps = Capabilities::from_vars(env);
+//! assert!(caps.truecolor);
+//! assert!(caps.mouse_sgr);
+//! assert!(!caps.synchronized_output);
+//!
+//! // Responses to the queries in `Capabilities::PROBE_QUERY`:
+//! assert!(!caps.apply_probe_response(b"\x1b[?2026;2$y"));
+//! assert!(caps.apply_probe_response(b"\x1b[?62;4;22c"));
+//! assert!(caps.synchronized_output);
+//! assert!(caps.sixel);
+//! ```
+//!
+//! Independent of the terminal emulator, the `RenderingProfile` specifies whether decorations
+//! (lines, markers, bars, ...) may use Unicode characters at all.
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Flags for optional features of the terminal.
+///
+/// All flags are `false` if the feature is not supported or if its support is unknown.
+// Non-exhaustive so that flags can be added in a backwards compatible way in future versions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// 256 color palette (`Color::Ansi`).
+    pub colors_256: bool,
+    /// 24 bit colors (`Color::Rgb`).
+    pub truecolor: bool,
+    /// SGR (1006) mouse reporting, i.e., mouse events beyond column/row 223.
+    pub mouse_sgr: bool,
+    /// Synchronized output (mode 2026), i.e., atomic presentation of frames.
+    pub synchronized_output: bool,
+    /// The progressive keyboard enhancement protocol of kitty.
+    pub kitty_keyboard: bool,
+    /// Hyperlinks (OSC 8).
+    pub hyperlinks: bool,
+    /// Sixel graphics.
+    pub sixel: bool,
+    /// The graphics protocol of kitty.
+    pub kitty_graphics: bool,
+    /// The inline image protocol of iTerm2.
+    pub iterm2_images: bool,
+}
+
+impl Capabilities {
+    /// Queries understood by `apply_probe_response`: DECRQM for synchronized output (2026) and
+    /// SGR mouse reporting (1006), the kitty keyboard protocol flags and finally primary device
+    /// attributes (DA1), which is answered by virtually all terminals and thus marks the end of
+    /// the responses.
+    pub const PROBE_QUERY: &'static str = "\x1b[?2026$p\x1b[?1006$p\x1b[?u\x1b[c";
+
+    /// Guess the capabilities from the environment variables of the process.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Guess the capabilities from environment variables that are looked up using `var`.
+    pub fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> Self {
+        let mut caps = Capabilities::default();
+        let term = var("TERM").unwrap_or_default();
+        if term.is_empty() || term == "dumb" {
+            return caps;
+        }
+        let colorterm = var("COLORTERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        let vte_version = var("VTE_VERSION")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(0);
+        let kitty = term == "xterm-kitty" || var("KITTY_WINDOW_ID").is_some();
+        let wezterm = program == "WezTerm";
+        let iterm = program == "iTerm.app";
+
+        caps.truecolor = colorterm == "truecolor" || colorterm == "24bit";
+        caps.colors_256 = caps.truecolor || term.contains("256color");
+        // All of the common terminal emulators (and multiplexers) are xterm compatible.
+        caps.mouse_sgr = term != "linux";
+
+        if kitty {
+            caps.kitty_keyboard = true;
+            caps.kitty_graphics = true;
+        }
+        if wezterm {
+            caps.kitty_graphics = true;
+            caps.sixel = true;
+        }
+        if iterm || wezterm {
+            caps.iterm2_images = true;
+        }
+        if kitty || wezterm || iterm || term.starts_with("alacritty") || term.starts_with("foot") {
+            caps.truecolor = true;
+            caps.synchronized_output = true;
+            caps.hyperlinks = true;
+        }
+        if vte_version >= 5000 || program == "vscode" {
+            caps.truecolor = true;
+            caps.hyperlinks = true;
+        }
+        caps.colors_256 |= caps.truecolor;
+        caps
+    }
+
+    /// Update the capabilities from (a part of) the terminal's response to `PROBE_QUERY`. Returns
+    /// whether the response contained the answer to the final (DA1) query.
+    ///
+    /// Unknown or incomplete sequences are ignored.
+    pub fn apply_probe_response(&mut self, response: &[u8]) -> bool {
+        let mut found_da1 = false;
+        let mut rest = response;
+        while let Some(start) = rest.windows(3).position(|w| w == b"\x1b[?") {
+            rest = &rest[start + 3..];
+            let end = match rest.iter().position(|&b| (0x40..=0x7e).contains(&b)) {
+                Some(end) => end,
+                None => break,
+            };
+            let (params, terminator) = (&rest[..end], rest[end]);
+            rest = &rest[end + 1..];
+            let params = String::from_utf8_lossy(params);
+            match terminator {
+                // DECRPM: CSI ? mode ; status $ y
+                b'y' if params.ends_with('$') => {
+                    let mut values = params.trim_end_matches('$').split(';');
+                    let mode = values.next();
+                    let supported = matches!(values.next(), Some("1") | Some("2") | Some("3"));
+                    match mode {
+                        Some("2026") => self.synchronized_output = supported,
+                        Some("1006") => self.mouse_sgr = supported,
+                        _ => {}
+                    }
+                }
+                // Kitty keyboard protocol: CSI ? flags u
+                b'u' => self.kitty_keyboard = true,
+                // DA1: CSI ? class ; attributes c
+                b'c' => {
+                    self.sixel |= params.split(';').skip(1).any(|a| a == "4");
+                    found_da1 = true;
+                }
+                _ => {}
+            }
+        }
+        found_da1
+    }
+}
+
+/// The characters that are used to draw decorations like box drawing lines, separators, markers
+/// and progress bars.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderingProfile {
+    /// Use Unicode characters (e.g., box drawing and block elements).
+    Unicode,
+    /// Use ASCII fallbacks (e.g., `+`, `-`, `|` and `#`) for environments without Unicode
+    /// support, such as serial consoles or non-UTF-8 locales.
+    Ascii,
+}
+
+// 0: Not determined yet, 1: Unicode, 2: Ascii
+static PROFILE: AtomicU8 = AtomicU8::new(0);
+
+impl RenderingProfile {
+    /// Guess the profile from the environment variables of the process.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Guess the profile from environment variables that are looked up using `var`: ASCII is used
+    /// for a `dumb` terminal and if the locale (`LC_ALL`, `LC_CTYPE` or `LANG`, whichever is set
+    /// first) does not specify UTF-8 as its encoding. If no locale is set, Unicode is assumed.
+    pub fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> Self {
+        if var("TERM").as_deref() == Some("dumb") {
+            return RenderingProfile::Ascii;
+        }
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| var(name))
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(locale) => {
+                let locale = locale.to_lowercase();
+                if locale.contains("utf-8") || locale.contains("utf8") {
+                    RenderingProfile::Unicode
+                } else {
+                    RenderingProfile::Ascii
+                }
+            }
+            None => RenderingProfile::Unicode,
+        }
+    }
+
+    /// Pick the `unicode` or the `ascii` variant of something (e.g., a character) according to
+    /// the profile.
+    pub fn select<T>(self, unicode: T, ascii: T) -> T {
+        match self {
+            RenderingProfile::Unicode => unicode,
+            RenderingProfile::Ascii => ascii,
+        }
+    }
+}
+
+/// Specify the `RenderingProfile` that is used by all widgets in the process, overriding the
+/// profile detected from the environment. Like `set_emoji_width_mode`, this should be set once
+/// before anything is drawn.
+pub fn set_rendering_profile(profile: RenderingProfile) {
+    let value = match profile {
+        RenderingProfile::Unicode => 1,
+        RenderingProfile::Ascii => 2,
+    };
+    PROFILE.store(value, Ordering::Relaxed);
+}
+
+/// Get the currently used `RenderingProfile`, which is detected from the environment (see
+/// `RenderingProfile::from_env`) unless it has been set using `set_rendering_profile`.
+pub fn rendering_profile() -> RenderingProfile {
+    match PROFILE.load(Ordering::Relaxed) {
+        1 => RenderingProfile::Unicode,
+        2 => RenderingProfile::Ascii,
+        _ => {
+            let profile = RenderingProfile::from_env();
+            // Do not override a profile that has been set concurrently.
+            let _ = PROFILE.compare_exchange(
+                0,
+                profile.select(1, 2),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+            profile
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn from_vars(vars: &[(&str, &str)]) -> Capabilities {
+        Capabilities::from_vars(|name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn test_capabilities_from_vars() {
+        assert_eq!(from_vars(&[]), Capabilities::default());
+        assert_eq!(from_vars(&[("TERM", "dumb")]), Capabilities::default());
+
+        let linux = from_vars(&[("TERM", "linux")]);
+        assert!(!linux.mouse_sgr && !linux.colors_256);
+
+        let kitty = from_vars(&[("TERM", "xterm-kitty")]);
+        assert!(kitty.truecolor && kitty.colors_256 && kitty.kitty_keyboard);
+        assert!(kitty.kitty_graphics && kitty.synchronized_output && !kitty.sixel);
+
+        let vte = from_vars(&[("TERM", "xterm-256color"), ("VTE_VERSION", "6003")]);
+        assert!(vte.truecolor && vte.hyperlinks && !vte.synchronized_output);
+    }
+
+    #[test]
+    fn test_rendering_profile_from_vars() {
+        let profile = |vars: &[(&str, &str)]| {
+            RenderingProfile::from_vars(|name| {
+                vars.iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, v)| v.to_string())
+            })
+        };
+        assert_eq!(profile(&[]), RenderingProfile::Unicode);
+        assert_eq!(profile(&[("TERM", "dumb")]), RenderingProfile::Ascii);
+        assert_eq!(profile(&[("LANG", "C")]), RenderingProfile::Ascii);
+        assert_eq!(
+            profile(&[("LANG", "de_DE.UTF-8"), ("LC_CTYPE", "")]),
+            RenderingProfile::Unicode
+        );
+        assert_eq!(
+            profile(&[("LANG", "en_US.utf8"), ("LC_ALL", "POSIX")]),
+            RenderingProfile::Ascii
+        );
+    }
+
+    #[test]
+    fn test_capabilities_probe_response() {
+        let mut caps = from_vars(&[("TERM", "xterm")]);
+        assert!(caps.mouse_sgr);
+        let response = b"\x1b[?2026;1$y\x1b[?1006;0$y\x1b[?1u\x1b[?62;22c";
+        assert!(caps.apply_probe_response(response));
+        assert!(caps.synchronized_output && !caps.mouse_sgr && caps.kitty_keyboard && !caps.sixel);
+
+        let mut caps = Capabilities::default();
+        assert!(!caps.apply_probe_response(b"\x1b[?2026;"));
+        assert!(!caps.apply_probe_response(b"\x1b[?2026;5$y"));
+        assert!(!caps.synchronized_output);
+    }
+}
@@ -0,0 +1,147 @@
+//! A process-wide registry of teardown actions that restore external state (e.g., the terminal
+//! mode) even if the application panics.
+//!
+//! Components that change state outside of the process register a cleanup action using
+//! `register_cleanup` and keep the returned `CleanupGuard` as long as the state is changed. If the
+//! component tears down the state itself, dropping the guard removes the action from the registry.
+//!
+//! All registered actions are executed (in reverse order of registration) by `run_cleanups`, which
+//! is called by the panic hook installed via `install_panic_hook` (e.g., by `app::run`) *before*
+//! the panic message is printed. Applications can also call `run_cleanups` before exiting the
+//! process using `std::process::exit`, where destructors are not run.
+//!
+//! `Terminal` registers an action that leaves the alternate screen, shows the cursor, disables
+//! mouse reporting and bracketed paste and restores the original terminal mode.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::base::cleanup::*;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//!
+//! let count = Arc::new(AtomicUsize::new(0));
+//! let c = count.clone();
+//! let guard = register_cleanup(move || {
+//!     c.fetch_add(1, Ordering::SeqCst);
+//! });
+//! run_cleanups();
+//! run_cleanups(); // Actions are only executed once
+//! assert_eq!(count.load(Ordering::SeqCst), 1);
+//! drop(guard);
+//! ```
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, Once};
+
+type CleanupAction = Box<dyn FnOnce() + Send>;
+
+static REGISTRY: Mutex<Vec<(usize, CleanupAction)>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+fn registry() -> MutexGuard<'static, Vec<(usize, CleanupAction)>> {
+    // A panic in a cleanup action must not prevent other actions from running later on.
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Keeps a cleanup action registered. Dropping the guard removes the action from the registry
+/// without executing it.
+#[must_use = "the cleanup action is unregistered when the guard is dropped"]
+pub struct CleanupGuard {
+    id: usize,
+}
+
+impl CleanupGuard {
+    /// Execute the action now (if it has not been executed already) and remove it from the
+    /// registry.
+    pub fn run(self) {
+        let action = {
+            let mut registry = registry();
+            registry
+                .iter()
+                .position(|(id, _)| *id == self.id)
+                .map(|i| registry.remove(i).1)
+        };
+        if let Some(action) = action {
+            action();
+        }
+    }
+}
+
+impl Drop for CleanupGuard {
+    fn drop(&mut self) {
+        registry().retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// Register an action that is executed by `run_cleanups` (e.g., when the application panics)
+/// unless the returned guard has been dropped before.
+pub fn register_cleanup<F: FnOnce() + Send + 'static>(action: F) -> CleanupGuard {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    registry().push((id, Box::new(action)));
+    CleanupGuard { id }
+}
+
+/// Execute all registered actions in reverse order of registration and remove them from the
+/// registry.
+pub fn run_cleanups() {
+    let actions = ::std::mem::take(&mut *registry());
+    for (_, action) in actions.into_iter().rev() {
+        action();
+    }
+}
+
+/// Install a panic hook that executes all registered actions (see `run_cleanups`) and then calls
+/// the previously installed hook (which usually prints the panic message).
+///
+/// Only panics on the calling thread (usually the main thread) trigger the cleanup, since panics on
+/// other threads (e.g., the reader threads of `widget::Tailer`) do not terminate the process. The
+/// hook cannot tell whether a panic will be caught, though, so do not install it if the calling
+/// thread catches panics (e.g., using `std::panic::catch_unwind`) and keeps running.
+///
+/// Installing the hook more than once has no effect.
+pub fn install_panic_hook() {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous = ::std::panic::take_hook();
+        let owner = ::std::thread::current().id();
+        ::std::panic::set_hook(Box::new(move |info| {
+            if ::std::thread::current().id() == owner {
+                run_cleanups();
+            }
+            previous(info);
+        }));
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+
+    // All checks share one test, since the registry is global.
+    #[test]
+    fn test_cleanup_registry() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let register = |name: &'static str| {
+            let log = log.clone();
+            register_cleanup(move || log.lock().unwrap().push(name))
+        };
+
+        let first = register("first");
+        let dropped = register("dropped");
+        let run_early = register("run early");
+        let last = register("last");
+
+        drop(dropped);
+        run_early.run();
+        assert_eq!(*log.lock().unwrap(), vec!["run early"]);
+
+        run_cleanups();
+        assert_eq!(*log.lock().unwrap(), vec!["run early", "last", "first"]);
+
+        // Guards of executed actions can be dropped or run without effect.
+        drop(first);
+        last.run();
+        run_cleanups();
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+}
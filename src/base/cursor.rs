@@ -1,11 +1,12 @@
 //! A Cursor can be used to render text to Windows and Window-like types.
 use super::{
-    ColDiff, ColIndex, GraphemeCluster, Height, IndexRange, RowDiff, RowIndex, Style,
-    StyleModifier, StyledGraphemeCluster, Width, Window,
+    cluster_width, is_reordered, mirrored, visual_order, BidiMode, ColDiff, ColIndex,
+    ExtentEstimationWindow, GraphemeCluster, Height, IndexRange, RowDiff, RowIndex, Style,
+    StyleModifier, StyledGraphemeCluster, TextDirection, Width, Window,
 };
 use std::cmp::max;
 use std::ops::Range;
-use unicode_segmentation::UnicodeSegmentation;
+use std::rc::Rc;
 
 /// Defines how a cursor behaves when arriving at the right-hand border of the CursorTarget.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -15,6 +16,78 @@ pub enum WrappingMode {
     NoWrap,
 }
 
+/// Defines where a cursor in `WrappingMode::Wrap` breaks text that does not fit into the remaining
+/// space of the current line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BreakPolicy {
+    /// Break at any grapheme cluster.
+    Anywhere,
+    /// Break after whitespace or punctuation if possible. Tokens that do not fit into a whole line
+    /// are still broken at any grapheme cluster.
+    AtPunctuation,
+}
+
+fn is_break_opportunity(cluster: &str) -> bool {
+    cluster
+        .chars()
+        .all(|c| c.is_whitespace() || c.is_ascii_punctuation())
+}
+
+/// The replacement of a grapheme cluster that is determined by a `Shaper`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shaped {
+    /// The text that is written instead of the cluster. It may consist of any number of grapheme
+    /// clusters (including none).
+    pub text: String,
+    /// Additional style modification of the replacement.
+    pub style: StyleModifier,
+}
+
+impl Shaped {
+    /// Replace a cluster with `text` without changing the style.
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Shaped {
+            text: text.into(),
+            style: StyleModifier::new(),
+        }
+    }
+
+    /// Modify the style of the replacement (default: no modification).
+    pub fn style(mut self, style: StyleModifier) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+/// A function that is called by a cursor for each grapheme cluster before it is written. If it
+/// returns a replacement, the replacement is written instead. See `Cursor::shaper`.
+pub type Shaper = Rc<dyn Fn(&str) -> Option<Shaped>>;
+
+/// Create a shaper (see `Cursor::shaper`) that replaces C0 control characters (including tab and
+/// carriage return) and DEL with the corresponding control pictures (e.g., '␛' for ESC), which are
+/// styled using `style`.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::*;
+///
+/// let shaper = control_pictures(StyleModifier::new());
+/// assert_eq!(shaper("\u{1b}"), Some(Shaped::new("␛")));
+/// assert_eq!(shaper("\t"), Some(Shaped::new("␉")));
+/// assert_eq!(shaper("a"), None);
+/// ```
+pub fn control_pictures(style: StyleModifier) -> impl Fn(&str) -> Option<Shaped> {
+    move |cluster| {
+        let mut chars = cluster.chars();
+        let picture = match (chars.next(), chars.next()) {
+            (Some('\u{7f}'), None) => '␡',
+            (Some(c), None) if c < ' ' => ::std::char::from_u32(0x2400 + c as u32)?,
+            _ => return None,
+        };
+        Some(Shaped::new(picture.to_string()).style(style))
+    }
+}
+
 /// Something that can be written to using a Cursor. A most prominent example would be a Window.
 pub trait CursorTarget {
     /// Return the actual width of the window. Writing to a column outside of this range is not
@@ -44,20 +117,16 @@ pub trait CursorTarget {
     fn get_default_style(&self) -> Style;
 }
 
-//FIXME: compile time evaluation, see https://github.com/rust-lang/rust/issues/24111
-//Update(02/2019): const fn are partially stable, but are not allowed for types with trait bounds other that
-//`Sized`.
-//pub const UNBOUNDED_WIDTH: Width = Width::new(2147483647).unwrap();//i32::max_value() as u32;
-//pub const UNBOUNDED_HEIGHT: Height = Height::new(2147483647).unwrap();//i32::max_value() as u32;
 /// A symbolic value that can be used to specify that a cursor target does not have a maximum
-/// width.
-pub const UNBOUNDED_WIDTH: i32 = 2147483647; //i32::max_value() as u32;
+/// width. This is the raw value of `Width::UNBOUNDED`.
+pub const UNBOUNDED_WIDTH: i32 = i32::MAX;
 /// A symbolic value that can be used to specify that a cursor target does not have a maximum
-/// height.
-pub const UNBOUNDED_HEIGHT: i32 = 2147483647; //i32::max_value() as u32;
+/// height. This is the raw value of `Height::UNBOUNDED`.
+pub const UNBOUNDED_HEIGHT: i32 = i32::MAX;
 
 /// The actual state of a Cursor in contrast to a Cursor instance itself, which also stored a
 /// reference to the target it writes to.
+#[derive(Clone)]
 pub struct CursorState {
     wrapping_mode: WrappingMode,
     style_modifier: StyleModifier,
@@ -65,6 +134,11 @@ pub struct CursorState {
     y: RowIndex,
     line_start_column: ColIndex,
     tab_column_width: Width,
+    bidi: BidiMode,
+    break_policy: BreakPolicy,
+    continuation_indent: Width,
+    truncation_marker: Option<GraphemeCluster>,
+    shaper: Option<Shaper>,
 }
 
 impl Default for CursorState {
@@ -76,10 +150,56 @@ impl Default for CursorState {
             y: RowIndex::new(0),
             line_start_column: ColIndex::new(0),
             tab_column_width: Width::new(4).unwrap(),
+            bidi: BidiMode::Disabled,
+            break_policy: BreakPolicy::Anywhere,
+            continuation_indent: Width::new(0).unwrap(),
+            truncation_marker: None,
+            shaper: None,
         }
     }
 }
 
+/// Calculate the space `text` occupies when written by a (wrapping) cursor into a target of the
+/// given `width`, starting at the origin.
+///
+/// The result is the number of rows touched and the column the cursor resides in afterwards. A
+/// line that exactly fills the width does not start a new row, but a trailing newline does.
+/// With `WrappingMode::NoWrap`, text beyond `width` is dropped just like `write` does, so use
+/// `Width::UNBOUNDED` to measure the natural width of a line.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::*;
+///
+/// let w = Width::new(4).unwrap();
+/// let tab = Width::new(4).unwrap();
+/// assert_eq!(
+///     text_extent("abcdef", w, tab, WrappingMode::Wrap),
+///     (Height::new(2).unwrap(), ColIndex::new(2))
+/// );
+/// assert_eq!(
+///     text_extent("abcd", w, tab, WrappingMode::Wrap),
+///     (Height::new(1).unwrap(), ColIndex::new(4))
+/// );
+/// assert_eq!(
+///     text_extent("ab\n", w, tab, WrappingMode::Wrap),
+///     (Height::new(2).unwrap(), ColIndex::new(0))
+/// );
+/// ```
+pub fn text_extent(
+    text: &str,
+    width: Width,
+    tab_width: Width,
+    wrapping: WrappingMode,
+) -> (Height, ColIndex) {
+    let mut window = ExtentEstimationWindow::with_width(width);
+    let mut cursor = Cursor::new(&mut window).wrapping_mode(wrapping);
+    cursor.set_tab_column_width(tab_width);
+    cursor.write(text);
+    let rows = (cursor.get_row().diff_to_origin() + 1).positive_or_zero();
+    (rows, cursor.get_col())
+}
+
 /// Something that can be used to easily write text to a CursorTarget (e.g., a Window).
 pub struct Cursor<'c, 'g: 'c, T: 'c + CursorTarget = Window<'g>> {
     window: &'c mut T,
@@ -243,6 +363,151 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
         self
     }
 
+    /// Change where lines are broken when wrapping.
+    pub fn set_break_policy(&mut self, policy: BreakPolicy) {
+        self.state.break_policy = policy;
+    }
+
+    /// Set where lines are broken when wrapping (default: `BreakPolicy::Anywhere`).
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((6, 3));
+    /// {
+    ///     let mut win = term.create_root_window();
+    ///     let mut cursor = Cursor::new(&mut win)
+    ///         .wrapping_mode(WrappingMode::Wrap)
+    ///         .break_policy(BreakPolicy::AtPunctuation);
+    ///     cursor.write("ab,cd efgh");
+    /// }
+    /// term.assert_looks_like("ab,cd |efgh  |      ");
+    /// ```
+    pub fn break_policy(mut self, policy: BreakPolicy) -> Self {
+        self.set_break_policy(policy);
+        self
+    }
+
+    /// Change the indentation of continuation lines when wrapping.
+    pub fn set_continuation_indent(&mut self, indent: Width) {
+        self.state.continuation_indent = indent;
+    }
+
+    /// Set the indentation (relative to the line start column) of lines that continue a line
+    /// which has been wrapped because it did not fit into the target (default: 0).
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((4, 3));
+    /// {
+    ///     let mut win = term.create_root_window();
+    ///     let mut cursor = Cursor::new(&mut win)
+    ///         .wrapping_mode(WrappingMode::Wrap)
+    ///         .continuation_indent(Width::new(2).unwrap());
+    ///     cursor.write("abcdef\ngh");
+    /// }
+    /// term.assert_looks_like("abcd|  ef|gh  ");
+    /// ```
+    pub fn continuation_indent(mut self, indent: Width) -> Self {
+        self.set_continuation_indent(indent);
+        self
+    }
+
+    /// Change the marker that indicates truncated lines.
+    pub fn set_truncation_marker(&mut self, marker: Option<GraphemeCluster>) {
+        self.state.truncation_marker = marker;
+    }
+
+    /// Set a marker (e.g., '…', which should be a single cell wide) that replaces the last cell
+    /// of a line that does not fit into the target in `WrappingMode::NoWrap` (default: None).
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((4, 2));
+    /// {
+    ///     let mut win = term.create_root_window();
+    ///     let mut cursor = Cursor::new(&mut win)
+    ///         .truncation_marker(Some(GraphemeCluster::try_from('…').unwrap()));
+    ///     cursor.write("abcdef\nabcd");
+    /// }
+    /// term.assert_looks_like("abc…|abcd");
+    /// ```
+    pub fn truncation_marker(mut self, marker: Option<GraphemeCluster>) -> Self {
+        self.set_truncation_marker(marker);
+        self
+    }
+
+    /// Change the shaper of the cursor (see `shaper`) or remove it using `None`.
+    pub fn set_shaper(&mut self, shaper: Option<Shaper>) {
+        self.state.shaper = shaper;
+    }
+
+    /// Set a function that can replace grapheme clusters before they are written (default: none).
+    ///
+    /// This can be used to make unprintable clusters visible (see `control_pictures`), to
+    /// substitute clusters that the terminal renders differently than expected (e.g., to split
+    /// emoji sequences joined by zero width joiners) or to highlight specific clusters. Newlines
+    /// are never passed to the shaper and replacements are not shaped again.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((6, 1));
+    /// {
+    ///     let mut win = term.create_root_window();
+    ///     let mut cursor = Cursor::new(&mut win).shaper(|c: &str| match c {
+    ///         "\u{0}" => Some(Shaped::new("^@")),
+    ///         _ => None,
+    ///     });
+    ///     cursor.write("a\u{0}b\u{0}");
+    /// }
+    /// term.assert_looks_like("a^@b^@");
+    /// ```
+    pub fn shaper<F: Fn(&str) -> Option<Shaped> + 'static>(mut self, shaper: F) -> Self {
+        self.set_shaper(Some(Rc::new(shaper)));
+        self
+    }
+
+    /// Change how lines containing right-to-left text are reordered for display.
+    pub fn set_bidi(&mut self, mode: BidiMode) {
+        self.state.bidi = mode;
+    }
+
+    /// Set how lines containing right-to-left text are reordered for display (default:
+    /// `BidiMode::Disabled`).
+    ///
+    /// Each line of the text passed to a single call of `write` is reordered separately, so a line
+    /// should be written in one call. In `BidiMode::Auto` lines without right-to-left characters
+    /// are not reordered at all.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((8, 1));
+    /// {
+    ///     let mut win = term.create_root_window();
+    ///     let mut cursor = Cursor::new(&mut win).bidi(BidiMode::Auto);
+    ///     cursor.write("ab (אבג)");
+    /// }
+    /// term.assert_looks_like("ab (גבא)");
+    /// ```
+    pub fn bidi(mut self, mode: BidiMode) -> Self {
+        self.set_bidi(mode);
+        self
+    }
+
     /// Set the column to which to cursor will jump automatically when wrapping at the end.
     /// Furthermore, when moving left, the cursor will wrap upwards if crossing over the start
     /// column.
@@ -381,6 +646,58 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
         self.carriage_return();
     }
 
+    /// Wrap to the beginning of the next line, indented as a continuation of the current one.
+    fn wrap_continuation(&mut self) {
+        self.wrap_line();
+        self.state.x += self.state.continuation_indent.raw_value();
+    }
+
+    /// Replace the last cell of the current line with the truncation marker (if there is one).
+    fn mark_truncation(&mut self, style: &Style) {
+        let marker = match self.state.truncation_marker {
+            Some(ref marker) => marker.clone(),
+            None => return,
+        };
+        let x = (self.window.get_width() - 1).from_origin();
+        if x >= self.state.line_start_column
+            && self.window.get_height().origin_range_contains(self.state.y)
+        {
+            let old_x = self.state.x;
+            self.state.x = x;
+            self.write_grapheme_cluster_unchecked(marker, *style);
+            self.state.x = old_x;
+        }
+    }
+
+    /// Wrap before the segment of text starting at `offset` in `line` (i.e., up to the next break
+    /// opportunity) if it does not fit into the rest of the current line, but into the next one.
+    ///
+    /// Returns the end of the segment.
+    fn break_before_segment(&mut self, line: &str, offset: usize) -> usize {
+        let mut end = offset;
+        let mut width = 0;
+        for cluster in GraphemeCluster::all_slices_from_str(&line[offset..]) {
+            end += cluster.len();
+            if is_break_opportunity(cluster) {
+                if !cluster.chars().all(char::is_whitespace) {
+                    width += cluster_width(cluster);
+                }
+                break;
+            }
+            width += cluster_width(cluster);
+        }
+        let continuation_start =
+            self.state.line_start_column + self.state.continuation_indent.raw_value();
+        let continuation_space = self.window.get_width().from_origin() - continuation_start;
+        if self.state.x > continuation_start
+            && self.remaining_space_in_line() < width as i32
+            && width as i32 <= continuation_space.raw_value()
+        {
+            self.wrap_continuation();
+        }
+        end
+    }
+
     /// Move the cursor to the beginning of the current line (but do not change the row position).
     pub fn carriage_return(&mut self) {
         self.state.x = self.state.line_start_column;
@@ -394,12 +711,19 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
 
     /// Calculate the number of wraps that are expected when writing the given text to the
     /// terminal, but do not write the text itself.
+    ///
+    /// The text is laid out exactly as `write` would (see `text_extent`), starting at the current
+    /// position of the cursor.
     pub fn num_expected_wraps(&self, line: &str) -> usize {
-        if self.state.wrapping_mode == WrappingMode::Wrap {
-            let num_chars = line.graphemes(true).count();
-            let virtual_x_pos: i32 = (self.state.x + num_chars as i32).into();
-            let w: i32 = self.window.get_width().into();
-            max(0, (virtual_x_pos / w) as usize)
+        // Nothing is written to (and thus nothing wraps in) an empty window.
+        if self.state.wrapping_mode == WrappingMode::Wrap && self.window.get_width() > 0 {
+            let mut state = self.state.clone();
+            state.y = RowIndex::new(0);
+            let mut window = ExtentEstimationWindow::with_width(self.window.get_width());
+            let mut cursor = Cursor::from_state(&mut window, state);
+            cursor.write(line);
+            let rows: i32 = cursor.get_row().into();
+            max(0, rows) as usize
         } else {
             0
         }
@@ -451,6 +775,11 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
             let mut current_x = target_cluster_x;
             let mut current_width = old_target_cluster_width;
             while current_width == 0 {
+                if current_x == 0 {
+                    // The start of the cluster is not part of this window (see below).
+                    current_width = Width::new(1).unwrap();
+                    break;
+                }
                 current_x -= 1;
                 current_width = Width::new(
                     self.window
@@ -465,9 +794,9 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
             // Clear all cells (except the newly written one)
             let start_cluster_x = current_x;
             let start_cluster_width = current_width;
-            for x_to_clear in
-                IndexRange(start_cluster_x.into()..(start_cluster_x + start_cluster_width))
-            {
+            let end_cluster_x =
+                (start_cluster_x + start_cluster_width).min(self.window.get_width().from_origin());
+            for x_to_clear in IndexRange(start_cluster_x..end_cluster_x) {
                 if x_to_clear != target_cluster_x {
                     self.window
                         .get_cell_mut(x_to_clear, y)
@@ -477,26 +806,22 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
                 }
             }
         }
-        // This should cover (almost) all cases where we overwrite wide grapheme clusters.
-        // Unfortunately, with the current design it is possible to split windows exactly at a
-        // multicell wide grapheme cluster, e.g.: [f,o,o,b,a,r] => [f,o,沐,,a,r] => [f,o,沐|,a,r]
-        // Now, when writing to to [f,o,沐| will trigger an out of bound access
-        // => "overwrite cluster cells in bounds" will fail
-        //
-        // Alternatively: writing to |,a,r] will cause an under/overflow in
-        // current_x -= 1;
-        //
-        // I will call this good for now, as these problems will likely not (or only rarely) arise
-        // in pratice. If they do... we have to think of something...
+        // With the current design it is possible to split windows exactly at a multicell wide
+        // grapheme cluster, e.g.: [f,o,o,b,a,r] => [f,o,沐,,a,r] => [f,o,沐|,a,r]
+        // Writing to [f,o,沐| only clears the cells of the cluster within the window and writing
+        // to |,a,r] only overwrites the cells within the window, i.e., the remaining part of the
+        // cluster outside of the window is left as is.
     }
 
     /// Write a grapheme cluster to the target at the specified position. The cursor will be
     /// advanced accordingly. Wrapping and width of the terminal are handled as well.
+    ///
+    /// Returns the position that the cluster was written to.
     fn write_cluster(
         &mut self,
         grapheme_cluster: GraphemeCluster,
         style: &Style,
-    ) -> Result<(), ()> {
+    ) -> Result<(ColIndex, RowIndex), ()> {
         let cluster_width: Width =
             Width::new(grapheme_cluster.width() as i32).expect("width is non-negative");
 
@@ -511,7 +836,7 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
                 self.state.x += 1;
             }
             if self.state.wrapping_mode == WrappingMode::Wrap {
-                self.wrap_line();
+                self.wrap_continuation();
                 if self.remaining_space_in_line() < cluster_width {
                     // Still no space for the cluster after line wrap: We have to give up.
                     // There is no way we can write our cluster anywhere.
@@ -519,32 +844,47 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
                 }
             } else {
                 // We do not wrap, so we are outside of the window now
+                self.mark_truncation(style);
                 return Err(());
             }
         }
-        if self.window.get_width().origin_range_contains(self.state.x)
-            && self.window.get_height().origin_range_contains(self.state.y)
-        {
+        let position = (self.state.x, self.state.y);
+        let start_visible = self.window.get_width().origin_range_contains(self.state.x);
+        if start_visible && self.window.get_height().origin_range_contains(self.state.y) {
             if cluster_width == 0 {
-                self.get_current_cell_mut()
+                // Zero width clusters that would change the width of the cluster they are
+                // appended to (e.g., a separately written emoji presentation selector) are
+                // dropped, since the following cells may already be occupied.
+                let _ = self
+                    .get_current_cell_mut()
                     .expect("cursor in bounds")
                     .grapheme_cluster
                     .merge_zero_width(grapheme_cluster);
-                return Ok(());
+                return Ok(position);
             }
 
             self.write_grapheme_cluster_unchecked(grapheme_cluster, style.clone());
         }
         self.state.x += 1;
-        if cluster_width > 1 && self.window.get_height().origin_range_contains(self.state.y) {
+        if cluster_width > 1 {
+            // The cursor advances by the full width even if the row is not visible, so that
+            // wrapping into the window (e.g., when drawing bottom up) is not affected.
+            let row_visible = self.window.get_height().origin_range_contains(self.state.y);
             for _ in 1..cluster_width.into() {
-                if self.window.get_width().origin_range_contains(self.state.x) {
-                    self.write_grapheme_cluster_unchecked(GraphemeCluster::empty(), style.clone());
+                if row_visible && self.window.get_width().origin_range_contains(self.state.x) {
+                    // A cluster that starts left of the window (e.g., in a horizontally scrolled
+                    // view) cannot be displayed partially, so its visible cells are blanked.
+                    let continuation = if start_visible {
+                        GraphemeCluster::empty()
+                    } else {
+                        GraphemeCluster::space()
+                    };
+                    self.write_grapheme_cluster_unchecked(continuation, style.clone());
                 }
                 self.state.x += 1;
             }
         }
-        Ok(())
+        Ok(position)
     }
 
     /// Calculate the number of remaining cells in the current line.
@@ -598,37 +938,235 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
 
     /// Write a string to the target at the current cursor position.
     pub fn write(&mut self, text: &str) {
+        self.write_with(text, |_, _, _, _| {});
+    }
+
+    /// Write a single line of text using at most `max_width` cells (and never more than the
+    /// remaining space in the current line, i.e., the text never wraps). If the text does not fit,
+    /// it is cut off and `ellipsis` (e.g., "…") is appended. Wide clusters that would straddle the
+    /// limit are omitted, so fewer than `max_width` cells may be used.
+    ///
+    /// See `truncated` for a version that can be used with `write!`.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((8, 1));
+    /// {
+    ///     let mut win = term.create_root_window();
+    ///     let mut cursor = Cursor::new(&mut win).wrapping_mode(WrappingMode::Wrap);
+    ///     cursor.write_truncated("status: ok", Width::new(6).unwrap(), "…");
+    ///     cursor.write("|x");
+    /// }
+    /// term.assert_looks_like("statu…|x");
+    /// ```
+    pub fn write_truncated(&mut self, text: &str, max_width: Width, ellipsis: &str) {
+        let budget = max_width.min(self.remaining_space_in_line()).raw_value() as usize;
+        let clusters = GraphemeCluster::all_slices_from_str(text).collect::<Vec<_>>();
+        if clusters.iter().map(|c| cluster_width(c)).sum::<usize>() <= budget {
+            self.write(text);
+            return;
+        }
+        let ellipsis_width = GraphemeCluster::all_slices_from_str(ellipsis)
+            .map(cluster_width)
+            .sum::<usize>();
+        let (ellipsis, budget) = if ellipsis_width <= budget {
+            (ellipsis, budget - ellipsis_width)
+        } else {
+            ("", budget)
+        };
+        let mut used = 0;
+        let mut end = 0;
+        for cluster in clusters {
+            used += cluster_width(cluster);
+            if used > budget {
+                break;
+            }
+            end += cluster.len();
+        }
+        self.write(&text[..end]);
+        self.write(ellipsis);
+    }
+
+    /// Write a string to the target at the current cursor position (like `write`) and call
+    /// `on_cluster` with the byte offset in `text`, the content and the position of each written
+    /// grapheme cluster.
+    ///
+    /// This can be used to map positions in the target back to the text, e.g., to place a text
+    /// cursor at the position of a mouse click. Note that positions may lie outside of the target,
+    /// e.g., if the text is scrolled out of view.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((3, 2));
+    /// let mut win = term.create_root_window();
+    /// let mut cursor = Cursor::new(&mut win).wrapping_mode(WrappingMode::Wrap);
+    /// let mut positions = Vec::new();
+    /// cursor.write_with("ab沐", |offset, _, x, y| positions.push((offset, x, y)));
+    /// assert_eq!(
+    ///     positions,
+    ///     vec![
+    ///         (0, ColIndex::new(0), RowIndex::new(0)),
+    ///         (1, ColIndex::new(1), RowIndex::new(0)),
+    ///         (2, ColIndex::new(0), RowIndex::new(1)), // Wrapped, since it is too wide
+    ///     ]
+    /// );
+    /// ```
+    pub fn write_with<F: FnMut(usize, &str, ColIndex, RowIndex)>(
+        &mut self,
+        text: &str,
+        mut on_cluster: F,
+    ) {
+        self.write_clusters(text, None, &mut on_cluster);
+    }
+
+    /// Write a string to the target at the current cursor position (like `write`), but with an
+    /// additional style modifier for each grapheme cluster that is computed by `style_at` from the
+    /// byte offset of the cluster in `text`.
+    ///
+    /// In contrast to writing the differently styled parts of a line separately, this keeps the
+    /// styles attached to the right clusters if the line is reordered (see `bidi`).
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((4, 1));
+    /// {
+    ///     let mut win = term.create_root_window();
+    ///     let mut cursor = Cursor::new(&mut win);
+    ///     cursor.write_styled("abcd", |offset| StyleModifier::new().bold(offset % 2 == 0));
+    /// }
+    /// term.assert_looks_like("*a*b*c*d");
+    /// ```
+    pub fn write_styled<S: FnMut(usize) -> StyleModifier>(&mut self, text: &str, mut style_at: S) {
+        self.write_clusters(text, Some(&mut style_at), &mut |_, _, _, _| {});
+    }
+
+    fn write_clusters(
+        &mut self,
+        text: &str,
+        mut style_at: Option<&mut dyn FnMut(usize) -> StyleModifier>,
+        on_cluster: &mut dyn FnMut(usize, &str, ColIndex, RowIndex),
+    ) {
         if self.window.get_width() == 0 || self.window.get_height() == 0 {
             return;
         }
         let style = self.active_style();
 
+        let mut line_offset = 0;
         let mut line_it = text.split('\n').peekable(); //.lines() swallows a terminal newline
         while let Some(line) = line_it.next() {
-            for mut grapheme_cluster in GraphemeCluster::all_from_str(line) {
-                match grapheme_cluster.as_str() {
-                    "\t" => {
-                        let tw = self.state.tab_column_width.from_origin();
-                        let x = self.state.x;
-                        let width = (tw - (x % tw)).try_into_positive().unwrap();
-                        grapheme_cluster = Self::create_tab_cluster(width)
-                    }
-                    "\r" => {
-                        self.carriage_return();
-                        continue;
+            if is_reordered(line, self.state.bidi) {
+                for cluster in visual_order(line, self.state.bidi) {
+                    let content = &line[cluster.range.clone()];
+                    let displayed = match cluster.direction {
+                        TextDirection::RightToLeft => mirrored(content).unwrap_or(content),
+                        TextDirection::LeftToRight => content,
+                    };
+                    let grapheme_cluster = displayed.parse::<GraphemeCluster>().unwrap();
+                    let offset = line_offset + cluster.range.start;
+                    let style = match style_at {
+                        Some(ref mut style_at) => style_at(offset).apply(style),
+                        None => style,
+                    };
+                    match self.write_shaped_cluster(grapheme_cluster, &style) {
+                        Ok(Some((x, y))) => on_cluster(offset, content, x, y),
+                        Ok(None) => {}
+                        Err(()) => break,
                     }
-                    _ => {}
                 }
-                if self.write_cluster(grapheme_cluster, &style).is_err() {
-                    break;
+            } else {
+                let break_at_punctuation = self.state.wrapping_mode == WrappingMode::Wrap
+                    && self.state.break_policy == BreakPolicy::AtPunctuation;
+                let mut segment_end = 0;
+                let mut offset = line_offset;
+                for grapheme_cluster in GraphemeCluster::all_from_str(line) {
+                    let cluster_offset = offset;
+                    offset += grapheme_cluster.as_str().len();
+                    let content = &text[cluster_offset..offset];
+                    if break_at_punctuation {
+                        if content.chars().all(char::is_whitespace)
+                            && self.remaining_space_in_line() == 0
+                        {
+                            // Do not carry whitespace over to the next line
+                            self.wrap_continuation();
+                            continue;
+                        }
+                        if cluster_offset - line_offset >= segment_end {
+                            segment_end =
+                                self.break_before_segment(line, cluster_offset - line_offset);
+                        }
+                    }
+                    let style = match style_at {
+                        Some(ref mut style_at) => style_at(cluster_offset).apply(style),
+                        None => style,
+                    };
+                    match self.write_shaped_cluster(grapheme_cluster, &style) {
+                        Ok(Some((x, y))) => on_cluster(cluster_offset, content, x, y),
+                        Ok(None) => {}
+                        Err(()) => break,
+                    }
                 }
             }
+            line_offset += line.len() + 1;
             if line_it.peek().is_some() {
                 self.wrap_line();
             }
         }
     }
 
+    /// Write a cluster of text or its replacement by the shaper (if any) and return the position
+    /// of the first written cluster.
+    fn write_shaped_cluster(
+        &mut self,
+        grapheme_cluster: GraphemeCluster,
+        style: &Style,
+    ) -> Result<Option<(ColIndex, RowIndex)>, ()> {
+        let shaped = match self.state.shaper {
+            Some(ref shaper) => shaper(grapheme_cluster.as_str()),
+            None => None,
+        };
+        let shaped = match shaped {
+            Some(shaped) => shaped,
+            None => return self.write_text_cluster(grapheme_cluster, style),
+        };
+        let style = shaped.style.apply(*style);
+        let mut position = None;
+        for grapheme_cluster in GraphemeCluster::all_from_str(&shaped.text) {
+            let written = self.write_text_cluster(grapheme_cluster, &style)?;
+            position = position.or(written);
+        }
+        Ok(position)
+    }
+
+    /// Write a cluster of text (i.e., also handle tabs and carriage returns) and return its
+    /// position if it has been written.
+    fn write_text_cluster(
+        &mut self,
+        grapheme_cluster: GraphemeCluster,
+        style: &Style,
+    ) -> Result<Option<(ColIndex, RowIndex)>, ()> {
+        let grapheme_cluster = match grapheme_cluster.as_str() {
+            "\t" => {
+                let tw = self.state.tab_column_width.from_origin();
+                let x = self.state.x;
+                let width = (tw - (x % tw)).try_into_positive().unwrap();
+                Self::create_tab_cluster(width)
+            }
+            "\r" => {
+                self.carriage_return();
+                return Ok(None);
+            }
+            _ => grapheme_cluster,
+        };
+        self.write_cluster(grapheme_cluster, style).map(Some)
+    }
+
     /// Write the given text and wrap the line
     pub fn writeln(&mut self, text: &str) {
         self.write(text);
@@ -660,6 +1198,38 @@ impl<'c, 'g: 'c, T: 'c + CursorTarget> Cursor<'c, 'g, T> {
         CursorRestorer::new(self)
     }
 
+    /// Create a `fmt::Write` adapter that writes at most `max_width` cells (see
+    /// `write_truncated`). Since the text can only be truncated once it is complete, it is written
+    /// when the adapter is dropped.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::*;
+    /// use std::fmt::Write;
+    ///
+    /// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((6, 1));
+    /// {
+    ///     let mut win = term.create_root_window();
+    ///     let mut cursor = Cursor::new(&mut win);
+    ///     write!(cursor.truncated(Width::new(5).unwrap(), "…"), "{} files", 1234).unwrap();
+    ///     cursor.write("|");
+    /// }
+    /// term.assert_looks_like("1234…|");
+    /// ```
+    pub fn truncated<'a>(
+        &'a mut self,
+        max_width: Width,
+        ellipsis: &'a str,
+    ) -> TruncatingWriter<'a, 'c, 'g, T> {
+        TruncatingWriter {
+            cursor: self,
+            text: String::new(),
+            max_width,
+            ellipsis,
+        }
+    }
+
     /// Return a reference to the target of the cursor.
     pub fn target(&self) -> &T {
         self.window
@@ -756,6 +1326,33 @@ impl<'a, 'c: 'a, 'g: 'c, T: 'c + CursorTarget> ::std::ops::Deref for CursorResto
     }
 }
 
+/// A `fmt::Write` adapter that writes at most a fixed number of cells to a cursor. Created using
+/// `Cursor::truncated`.
+pub struct TruncatingWriter<'a, 'c: 'a, 'g: 'c, T: 'c + CursorTarget> {
+    cursor: &'a mut Cursor<'c, 'g, T>,
+    text: String,
+    max_width: Width,
+    ellipsis: &'a str,
+}
+
+impl<'a, 'c: 'a, 'g: 'c, T: 'c + CursorTarget> ::std::fmt::Write
+    for TruncatingWriter<'a, 'c, 'g, T>
+{
+    fn write_str(&mut self, s: &str) -> ::std::fmt::Result {
+        self.text.push_str(s);
+        Ok(())
+    }
+}
+
+impl<'a, 'c: 'a, 'g: 'c, T: 'c + CursorTarget> ::std::ops::Drop
+    for TruncatingWriter<'a, 'c, 'g, T>
+{
+    fn drop(&mut self) {
+        self.cursor
+            .write_truncated(&self.text, self.max_width, self.ellipsis);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -787,6 +1384,24 @@ mod test {
         test_cursor((5, 1), "testy", |_| {}, |c| c.write("testy"));
     }
 
+    #[test]
+    fn test_cursor_break_policy() {
+        let at_punctuation = |c: &mut Cursor| {
+            c.set_wrapping_mode(WrappingMode::Wrap);
+            c.set_break_policy(BreakPolicy::AtPunctuation);
+        };
+        test_cursor((4, 3), "ab a|bcde|fg__", at_punctuation, |c| {
+            c.write("ab abcdefg")
+        });
+        test_cursor((4, 3), "ab _|abc |d___", at_punctuation, |c| {
+            c.write("ab abc d")
+        });
+        test_cursor((4, 3), "ab _|__cd|__ef", at_punctuation, |c| {
+            c.set_continuation_indent(Width::new(2).unwrap());
+            c.write("ab cd ef")
+        });
+    }
+
     #[test]
     fn test_cursor_no_wrap() {
         test_cursor((2, 2), "__|__", |_| {}, |c| c.write(""));
@@ -922,6 +1537,64 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_cursor_num_expected_wraps_wide_cluster() {
+        let mut term = FakeTerminal::with_size((4, 2));
+        let mut window = term.create_root_window();
+        let cursor = Cursor::new(&mut window).wrapping_mode(WrappingMode::Wrap);
+        assert_eq!(cursor.num_expected_wraps("abc"), 0);
+        assert_eq!(cursor.num_expected_wraps("沐a"), 0);
+        assert_eq!(cursor.num_expected_wraps("沐沐沐"), 1);
+        assert_eq!(cursor.num_expected_wraps("abcd"), 0);
+        assert_eq!(cursor.num_expected_wraps("aaa沐a沐a沐"), 3);
+    }
+
+    #[test]
+    fn test_text_extent() {
+        let w = Width::new(4).unwrap();
+        let tab = Width::new(4).unwrap();
+        let extent = |text| text_extent(text, w, tab, WrappingMode::Wrap);
+        assert_eq!(extent(""), (Height::new(1).unwrap(), ColIndex::new(0)));
+        assert_eq!(extent("abcd"), (Height::new(1).unwrap(), ColIndex::new(4)));
+        assert_eq!(extent("abcde"), (Height::new(2).unwrap(), ColIndex::new(1)));
+        assert_eq!(
+            extent("aaa沐a沐"),
+            (Height::new(3).unwrap(), ColIndex::new(2))
+        );
+        assert_eq!(extent("a\tb"), (Height::new(2).unwrap(), ColIndex::new(1)));
+        assert_eq!(
+            extent("ab\ncd\n"),
+            (Height::new(3).unwrap(), ColIndex::new(0))
+        );
+        assert_eq!(
+            text_extent("abcdefgh", w, tab, WrappingMode::NoWrap),
+            (Height::new(1).unwrap(), ColIndex::new(4))
+        );
+    }
+
+    #[test]
+    fn test_cursor_wide_cluster_outside_window() {
+        test_cursor(
+            (4, 1),
+            "z___",
+            |_| {},
+            |c| {
+                c.set_wrapping_mode(WrappingMode::Wrap);
+                c.move_to(ColIndex::new(0), RowIndex::new(-1));
+                c.write("沐xyz");
+            },
+        );
+        test_cursor(
+            (4, 1),
+            " xy_",
+            |_| {},
+            |c| {
+                c.move_to(ColIndex::new(-1), RowIndex::new(0));
+                c.write("沐xy");
+            },
+        );
+    }
+
     #[test]
     fn test_cursor_wide_cluster_overwrite() {
         test_cursor(
@@ -976,6 +1649,134 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_cursor_emoji_sequences() {
+        let family = "👨\u{200d}👩\u{200d}👧";
+        test_cursor(
+            (5, 1),
+            &format!("{}x__", family),
+            |_| {},
+            |c| {
+                c.write(family);
+                c.write("x");
+            },
+        );
+        test_cursor(
+            (5, 1),
+            " yx__",
+            |_| {},
+            |c| {
+                c.write(family);
+                c.write("x");
+                c.move_to(ColIndex::new(1), RowIndex::new(0));
+                c.write("y");
+            },
+        );
+        test_cursor((5, 1), "🇩🇪👍🏽_", |_| {}, |c| c.write("🇩🇪👍🏽"));
+        // A separately written presentation selector would widen the cluster and is dropped.
+        test_cursor(
+            (5, 1),
+            "❤x___",
+            |_| {},
+            |c| {
+                c.write("❤");
+                c.write("\u{fe0f}x");
+            },
+        );
+    }
+
+    #[test]
+    fn test_cursor_wide_cluster_overwrite_split_window() {
+        let mut term = FakeTerminal::with_size((5, 1));
+        {
+            let mut window = term.create_root_window();
+            window.fill(GraphemeCluster::try_from('_').unwrap());
+            Cursor::new(&mut window)
+                .position(ColIndex::new(1), RowIndex::new(0))
+                .write("沐");
+            let (mut left, mut right) = window.split(ColIndex::new(2)).unwrap();
+            Cursor::new(&mut right).write("X");
+            Cursor::new(&mut left)
+                .position(ColIndex::new(1), RowIndex::new(0))
+                .write("Y");
+        }
+        term.assert_looks_like("_YX__");
+    }
+
+    #[test]
+    fn test_cursor_write_truncated() {
+        let w = |w| Width::new(w).unwrap();
+        test_cursor(
+            (5, 1),
+            "abc__",
+            |_| {},
+            |c| c.write_truncated("abc", w(3), "…"),
+        );
+        test_cursor(
+            (5, 1),
+            "ab…__",
+            |_| {},
+            |c| c.write_truncated("abcd", w(3), "…"),
+        );
+        test_cursor(
+            (5, 1),
+            "a沐…_",
+            |_| {},
+            |c| c.write_truncated("a沐沐", w(4), "…"),
+        );
+        // The second wide cluster would straddle the limit:
+        test_cursor(
+            (5, 1),
+            "a…___",
+            |_| {},
+            |c| c.write_truncated("a沐沐", w(3), "…"),
+        );
+        test_cursor(
+            (5, 1),
+            "~~___",
+            |_| {},
+            |c| c.write_truncated("沐沐", w(3), "~~"),
+        );
+        test_cursor(
+            (5, 1),
+            "ab___",
+            |_| {},
+            |c| c.write_truncated("abc", w(2), "..."),
+        );
+        test_cursor(
+            (5, 2),
+            "___ab|_____",
+            |c| c.set_wrapping_mode(WrappingMode::Wrap),
+            |c| {
+                c.move_to_x(ColIndex::new(3));
+                c.write_truncated("abc", w(10), "");
+            },
+        );
+        test_cursor(
+            (5, 2),
+            "___a…|_____",
+            |c| c.set_wrapping_mode(WrappingMode::Wrap),
+            |c| {
+                c.move_to_x(ColIndex::new(3));
+                c.write_truncated("a沐", w(10), "…");
+            },
+        );
+    }
+
+    #[test]
+    fn test_cursor_truncating_writer() {
+        use std::fmt::Write;
+        test_cursor(
+            (5, 1),
+            "1,2…x",
+            |_| {},
+            |c| {
+                write!(c.truncated(Width::new(4).unwrap(), "…"), "{},{}", 1, 234).unwrap();
+                c.write("x");
+            },
+        );
+    }
+
     #[test]
     fn test_cursor_tabs_overwrite() {
         test_cursor(
@@ -1019,4 +1820,46 @@ mod test {
             },
         );
     }
+
+    #[test]
+    fn test_cursor_shaper() {
+        test_cursor(
+            (6, 1),
+            "a*␛*b*␉*__",
+            |c| {
+                let shaper = control_pictures(StyleModifier::new().bold(true));
+                c.set_shaper(Some(Rc::new(shaper)))
+            },
+            |c| c.write("a\u{1b}b\t"),
+        );
+        // Split a family emoji into its members and remove zero width joiners
+        test_cursor(
+            (7, 1),
+            "a👩👧b_",
+            |c| {
+                c.set_shaper(Some(Rc::new(|cluster: &str| {
+                    if cluster.contains('\u{200d}') {
+                        Some(Shaped::new(cluster.replace('\u{200d}', "")))
+                    } else {
+                        None
+                    }
+                })))
+            },
+            |c| c.write("a👩\u{200d}👧b"),
+        );
+        test_cursor(
+            (3, 1),
+            "ac_",
+            |c| {
+                c.set_shaper(Some(Rc::new(|cluster: &str| {
+                    if cluster == "b" {
+                        Some(Shaped::new(""))
+                    } else {
+                        None
+                    }
+                })))
+            },
+            |c| c.write("abc"),
+        );
+    }
 }
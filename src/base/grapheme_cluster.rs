@@ -1,7 +1,10 @@
 //! Types related to grapheme cluster in utf8 encoding.
 
 use smallvec::SmallVec;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use unicode_segmentation::{Graphemes, UnicodeSegmentation};
 
 /// A single grapheme cluster encoded in utf8. It may consist of multiple bytes or even multiple chars. For details
@@ -46,9 +49,20 @@ impl GraphemeCluster {
     }
 
     /// Add other to the current grapheme cluster. other MUST have a width of zero.
-    pub(in base) fn merge_zero_width(&mut self, other: Self) {
+    ///
+    /// Some zero width characters (e.g., the emoji variation selector) change the width of the
+    /// cluster they are appended to. In that case the cluster is left unchanged (and false is
+    /// returned), since the cells occupied by the cluster cannot grow after the fact.
+    pub(in base) fn merge_zero_width(&mut self, other: Self) -> bool {
         assert!(other.width() == 0, "Invalid merge");
+        let old_width = self.width();
+        let len = self.bytes.len();
         self.bytes.extend_from_slice(&other.bytes[..]);
+        if self.width() != old_width {
+            self.bytes.truncate(len);
+            return false;
+        }
+        true
     }
 
     /// Safely create a single space character (i.e., 0x20) grapheme cluster.
@@ -107,6 +121,22 @@ impl GraphemeCluster {
         GraphemeClusterIter::new(string)
     }
 
+    /// Retrieve all grapheme clusters from the given string as slices of the string, i.e., without
+    /// copying them.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// use unsegen::base::GraphemeCluster;
+    /// let clusters = GraphemeCluster::all_slices_from_str("ae\u{301} d").collect::<Vec<_>>();
+    /// assert_eq!(clusters, vec!["a", "e\u{301}", " ", "d"]);
+    /// ```
+    pub fn all_slices_from_str<'a>(string: &'a str) -> GraphemeClusterSliceIter<'a> {
+        GraphemeClusterSliceIter {
+            graphemes: string.graphemes(true),
+        }
+    }
+
     /// Calculate the unicode width of the given grapheme cluster.
     ///
     /// # Examples:
@@ -116,10 +146,137 @@ impl GraphemeCluster {
     /// assert_eq!(GraphemeCluster::try_from('a').unwrap().width(), 1);
     /// ```
     pub fn width(&self) -> usize {
-        ::unicode_width::UnicodeWidthStr::width(self.as_str())
+        cluster_width(self.as_str())
     }
 }
 
+/// How terminals display emoji sequences, i.e., emoji that consist of multiple characters such
+/// as zero width joiner sequences ("👨\u{200d}👩\u{200d}👧"), emoji with skin tone modifiers
+/// ("👍🏽"), flags ("🇩🇪") or characters with emoji presentation selector ("❤\u{fe0f}").
+///
+/// The width of these sequences is not well defined, so the width calculated by unsegen has to
+/// match the behavior of the terminal. Otherwise, the content following an emoji (e.g., the
+/// remaining columns of a `Table` row) is misaligned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmojiWidthMode {
+    /// Every emoji sequence is displayed as a single glyph of 2 cells. This is the behavior of
+    /// most modern terminals (and the default).
+    Wide,
+    /// The components of emoji sequences are displayed separately, i.e., the width of a sequence
+    /// is the sum of the widths of its components. This is the behavior of terminals without
+    /// support for emoji sequences.
+    Split,
+}
+
+static SPLIT_EMOJI: AtomicBool = AtomicBool::new(false);
+
+/// Specify how the terminal displays emoji sequences (see `EmojiWidthMode`). This affects the
+/// width of grapheme clusters in the whole process and should thus be set once (e.g., from a
+/// command line option or configuration file) before anything is drawn.
+pub fn set_emoji_width_mode(mode: EmojiWidthMode) {
+    SPLIT_EMOJI.store(mode == EmojiWidthMode::Split, Ordering::Relaxed);
+}
+
+/// Get the currently used `EmojiWidthMode` (see `set_emoji_width_mode`).
+pub fn emoji_width_mode() -> EmojiWidthMode {
+    if SPLIT_EMOJI.load(Ordering::Relaxed) {
+        EmojiWidthMode::Split
+    } else {
+        EmojiWidthMode::Wide
+    }
+}
+
+const ZERO_WIDTH_JOINER: char = '\u{200d}';
+const EMOJI_PRESENTATION_SELECTOR: char = '\u{fe0f}';
+const COMBINING_KEYCAP: char = '\u{20e3}';
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+fn is_skin_tone_modifier(c: char) -> bool {
+    ('\u{1f3fb}'..='\u{1f3ff}').contains(&c)
+}
+
+/// Check whether the cluster is an emoji sequence (see `EmojiWidthMode`).
+fn is_emoji_sequence(cluster: &str) -> bool {
+    let mut chars = cluster.chars();
+    let first = match chars.next() {
+        Some(c) => c,
+        None => return false,
+    };
+    if chars.next().is_none() {
+        return false;
+    }
+    is_regional_indicator(first)
+        || cluster.chars().any(|c| {
+            c == ZERO_WIDTH_JOINER
+                || c == EMOJI_PRESENTATION_SELECTOR
+                || c == COMBINING_KEYCAP
+                || is_skin_tone_modifier(c)
+        })
+}
+
+fn calculate_width(cluster: &str, mode: EmojiWidthMode) -> usize {
+    if !is_emoji_sequence(cluster) {
+        return ::unicode_width::UnicodeWidthStr::width(cluster);
+    }
+    match mode {
+        EmojiWidthMode::Wide => 2,
+        EmojiWidthMode::Split => cluster
+            .chars()
+            .map(|c| ::unicode_width::UnicodeWidthChar::width(c).unwrap_or(0))
+            .sum(),
+    }
+}
+
+/// Maximum number of entries in the per-thread cluster width cache before it is reset.
+const WIDTH_CACHE_CAPACITY: usize = 4096;
+
+thread_local! {
+    static WIDTH_CACHE: RefCell<(EmojiWidthMode, HashMap<String, usize>)> =
+        RefCell::new((EmojiWidthMode::Wide, HashMap::new()));
+}
+
+/// Calculate the unicode width of a single grapheme cluster given as a string slice.
+///
+/// The width of emoji sequences depends on the `EmojiWidthMode` (see `set_emoji_width_mode`).
+///
+/// Widths of non-ascii clusters are cached (per thread), so that measuring the same text over and
+/// over again (e.g., on every draw) is cheap.
+///
+/// # Examples:
+///
+/// ```
+/// use unsegen::base::cluster_width;
+/// assert_eq!(cluster_width("a"), 1);
+/// assert_eq!(cluster_width("e\u{301}"), 1);
+/// assert_eq!(cluster_width("沐"), 2);
+/// assert_eq!(cluster_width("👍🏽"), 2);
+/// ```
+pub fn cluster_width(cluster: &str) -> usize {
+    if cluster.is_ascii() {
+        return ::unicode_width::UnicodeWidthStr::width(cluster);
+    }
+    let mode = emoji_width_mode();
+    WIDTH_CACHE.with(|cache| {
+        let (ref mut cache_mode, ref mut cache) = *cache.borrow_mut();
+        if *cache_mode != mode {
+            cache.clear();
+            *cache_mode = mode;
+        }
+        if let Some(&width) = cache.get(cluster) {
+            return width;
+        }
+        if cache.len() >= WIDTH_CACHE_CAPACITY {
+            cache.clear();
+        }
+        let width = calculate_width(cluster, mode);
+        cache.insert(cluster.to_owned(), width);
+        width
+    })
+}
+
 /// An iterator over a sequence of grapheme clusters
 pub struct GraphemeClusterIter<'a> {
     graphemes: Graphemes<'a>,
@@ -142,6 +299,18 @@ impl<'a> Iterator for GraphemeClusterIter<'a> {
     }
 }
 
+/// An iterator over a sequence of grapheme clusters as slices of the original string
+pub struct GraphemeClusterSliceIter<'a> {
+    graphemes: Graphemes<'a>,
+}
+
+impl<'a> Iterator for GraphemeClusterSliceIter<'a> {
+    type Item = &'a str;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.graphemes.next()
+    }
+}
+
 /// An error associated with the creation of GraphemeCluster from arbitrary strings.
 #[derive(Debug)]
 #[allow(missing_docs)]
@@ -179,3 +348,50 @@ impl FromStr for GraphemeCluster {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FAMILY: &str = "👨\u{200d}👩\u{200d}👧";
+    const THUMBS_UP: &str = "👍🏽";
+    const FLAG: &str = "🇩🇪";
+    const HEART: &str = "❤\u{fe0f}";
+    const KEYCAP: &str = "1\u{fe0f}\u{20e3}";
+
+    #[test]
+    fn test_emoji_sequences_are_single_clusters() {
+        for s in &[FAMILY, THUMBS_UP, FLAG, HEART, KEYCAP] {
+            assert!(s.parse::<GraphemeCluster>().is_ok(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_emoji_width_wide() {
+        for s in &[FAMILY, THUMBS_UP, FLAG, HEART, KEYCAP, "👍"] {
+            assert_eq!(calculate_width(s, EmojiWidthMode::Wide), 2, "{:?}", s);
+        }
+        assert_eq!(calculate_width("e\u{301}", EmojiWidthMode::Wide), 1);
+        assert_eq!(calculate_width("☺", EmojiWidthMode::Wide), 1);
+    }
+
+    #[test]
+    fn test_emoji_width_split() {
+        assert_eq!(calculate_width(FAMILY, EmojiWidthMode::Split), 6);
+        assert_eq!(calculate_width(THUMBS_UP, EmojiWidthMode::Split), 4);
+        assert_eq!(calculate_width(FLAG, EmojiWidthMode::Split), 2);
+        assert_eq!(calculate_width(HEART, EmojiWidthMode::Split), 1);
+        assert_eq!(calculate_width("👍", EmojiWidthMode::Split), 2);
+        assert_eq!(calculate_width("e\u{301}", EmojiWidthMode::Split), 1);
+    }
+
+    #[test]
+    fn test_merge_zero_width_keeps_width() {
+        let mut cluster = GraphemeCluster::try_from('e').unwrap();
+        assert!(cluster.merge_zero_width("\u{301}".parse().unwrap()));
+        assert_eq!(cluster.as_str(), "e\u{301}");
+        let mut cluster = GraphemeCluster::try_from('❤').unwrap();
+        assert!(!cluster.merge_zero_width("\u{fe0f}".parse().unwrap()));
+        assert_eq!(cluster.as_str(), "❤");
+    }
+}
@@ -32,15 +32,30 @@
 //! ```
 
 pub mod basic_types;
+pub mod bidi;
+pub mod capabilities;
+pub mod cleanup;
 pub mod cursor;
 pub mod grapheme_cluster;
+pub mod pattern;
+pub mod rect;
+pub mod redraw;
+pub mod sanitize;
 pub mod style;
+pub mod styled_buffer;
 pub mod terminal;
 pub mod window;
 
 pub use self::basic_types::*;
+pub use self::bidi::*;
+pub use self::capabilities::*;
+pub use self::cleanup::*;
 pub use self::cursor::*;
 pub use self::grapheme_cluster::*;
+pub use self::rect::*;
+pub use self::redraw::*;
+pub use self::sanitize::*;
 pub use self::style::*;
+pub use self::styled_buffer::*;
 pub use self::terminal::*;
 pub use self::window::*;
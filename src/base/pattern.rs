@@ -0,0 +1,111 @@
+//! Common per-cell styles for `Window::fill_styled` and `Window::clear_styled`, e.g., gradient
+//! backgrounds or striped "disabled" areas.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::base::*;
+//! use unsegen::base::terminal::test::FakeTerminal;
+//!
+//! let mut term = FakeTerminal::with_size((6, 2));
+//! let bold = StyleModifier::new().bold(true);
+//! let stripes = pattern::stripes(bold, StyleModifier::new(), 2);
+//! term.create_root_window().fill_styled(GraphemeCluster::try_from('/').unwrap(), stripes);
+//! term.assert_looks_like("*/**/*//*/**/*|*/*//*/**/*/");
+//! ```
+use base::basic_types::*;
+use base::style::{Color, StyleModifier};
+use util::Lerp;
+
+fn interpolate(from: (u8, u8, u8), to: (u8, u8, u8), pos: i32, len: i32) -> Color {
+    let t = if len > 1 {
+        (pos.max(0).min(len - 1)) as f64 / (len - 1) as f64
+    } else {
+        0.0
+    };
+    Color::Rgb {
+        r: from.0.lerp(&to.0, t),
+        g: from.1.lerp(&to.1, t),
+        b: from.2.lerp(&to.2, t),
+    }
+}
+
+/// A background color gradient from `top` to `bottom` (as rgb values) over `height` rows.
+///
+/// Note that rgb colors require a terminal with truecolor support.
+pub fn vertical_gradient(
+    top: (u8, u8, u8),
+    bottom: (u8, u8, u8),
+    height: Height,
+) -> impl Fn(ColIndex, RowIndex) -> StyleModifier {
+    move |_, y| {
+        StyleModifier::new().bg_color(interpolate(top, bottom, y.raw_value(), height.raw_value()))
+    }
+}
+
+/// A background color gradient from `left` to `right` (as rgb values) over `width` columns.
+///
+/// Note that rgb colors require a terminal with truecolor support.
+pub fn horizontal_gradient(
+    left: (u8, u8, u8),
+    right: (u8, u8, u8),
+    width: Width,
+) -> impl Fn(ColIndex, RowIndex) -> StyleModifier {
+    move |x, _| {
+        StyleModifier::new().bg_color(interpolate(left, right, x.raw_value(), width.raw_value()))
+    }
+}
+
+/// Alternate between the styles `a` and `b` from cell to cell (in both directions).
+pub fn checkerboard(
+    a: StyleModifier,
+    b: StyleModifier,
+) -> impl Fn(ColIndex, RowIndex) -> StyleModifier {
+    move |x, y| {
+        if (x.raw_value() + y.raw_value()).rem_euclid(2) == 0 {
+            a
+        } else {
+            b
+        }
+    }
+}
+
+/// Diagonal stripes of the styles `a` and `b`, each `width` cells wide (measured horizontally).
+pub fn stripes(
+    a: StyleModifier,
+    b: StyleModifier,
+    width: usize,
+) -> impl Fn(ColIndex, RowIndex) -> StyleModifier {
+    let width = width.max(1) as i32;
+    move |x, y| {
+        if ((x.raw_value() + y.raw_value()) / width).rem_euclid(2) == 0 {
+            a
+        } else {
+            b
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gradients() {
+        let bg = |r, g, b| StyleModifier::new().bg_color(Color::Rgb { r, g, b });
+        let g = vertical_gradient((0, 0, 0), (100, 200, 10), Height::new(3).unwrap());
+        assert_eq!(g(ColIndex::new(5), RowIndex::new(0)), bg(0, 0, 0));
+        assert_eq!(g(ColIndex::new(5), RowIndex::new(1)), bg(50, 100, 5));
+        assert_eq!(g(ColIndex::new(0), RowIndex::new(2)), bg(100, 200, 10));
+        let g = horizontal_gradient((10, 10, 10), (0, 0, 0), Width::new(1).unwrap());
+        assert_eq!(g(ColIndex::new(0), RowIndex::new(0)), bg(10, 10, 10));
+    }
+
+    #[test]
+    fn test_checkerboard() {
+        let bold = StyleModifier::new().bold(true);
+        let c = checkerboard(bold, StyleModifier::new());
+        assert_eq!(c(ColIndex::new(0), RowIndex::new(0)), bold);
+        assert_eq!(c(ColIndex::new(1), RowIndex::new(0)), StyleModifier::new());
+        assert_eq!(c(ColIndex::new(1), RowIndex::new(1)), bold);
+    }
+}
@@ -0,0 +1,236 @@
+//! Axis aligned rectangles with typed coordinates.
+use base::basic_types::*;
+use std::ops::Range;
+
+/// An axis aligned rectangle, i.e., the product of a range in x-dimension and a range in
+/// y-dimension. By default, the rectangle refers to cells of the terminal grid.
+///
+/// The ranges are half open and a rectangle with an empty range is empty. Operations on
+/// rectangles assume that the start of each range is not larger than its end.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::*;
+///
+/// let a = Rect::new(ColIndex::new(0)..ColIndex::new(4), RowIndex::new(0)..RowIndex::new(2));
+/// let b = Rect::new(ColIndex::new(2)..ColIndex::new(6), RowIndex::new(1)..RowIndex::new(5));
+///
+/// let i = a.intersection(&b).unwrap();
+/// assert_eq!(i, Rect::new(ColIndex::new(2)..ColIndex::new(4), RowIndex::new(1)..RowIndex::new(2)));
+/// assert_eq!(a.union(&b).width(), Width::new(6).unwrap());
+/// assert!(i.contains(ColIndex::new(3), RowIndex::new(1)));
+/// assert_eq!(
+///     b.clamp(ColIndex::new(9), RowIndex::new(0)),
+///     (ColIndex::new(5), RowIndex::new(1))
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub struct Rect<X: AxisDimension = ColDimension, Y: AxisDimension = RowDimension> {
+    pub x_range: Range<AxisIndex<X>>,
+    pub y_range: Range<AxisIndex<Y>>,
+}
+
+fn range_len<T: AxisDimension>(range: &Range<AxisIndex<T>>) -> PositiveAxisDiff<T> {
+    (range.end - range.start).positive_or_zero()
+}
+
+fn intersect_ranges<T: AxisDimension>(
+    a: &Range<AxisIndex<T>>,
+    b: &Range<AxisIndex<T>>,
+) -> Range<AxisIndex<T>> {
+    let start = a.start.raw_value().max(b.start.raw_value());
+    let end = a.end.raw_value().min(b.end.raw_value());
+    AxisIndex::new(start)..AxisIndex::new(end.max(start))
+}
+
+fn unite_ranges<T: AxisDimension>(
+    a: &Range<AxisIndex<T>>,
+    b: &Range<AxisIndex<T>>,
+) -> Range<AxisIndex<T>> {
+    AxisIndex::new(a.start.raw_value().min(b.start.raw_value()))
+        ..AxisIndex::new(a.end.raw_value().max(b.end.raw_value()))
+}
+
+fn clamp_to_range<T: AxisDimension>(i: AxisIndex<T>, range: &Range<AxisIndex<T>>) -> AxisIndex<T> {
+    let last = (range.end.raw_value() - 1).max(range.start.raw_value());
+    AxisIndex::new(i.raw_value().max(range.start.raw_value()).min(last))
+}
+
+impl<X: AxisDimension, Y: AxisDimension> Rect<X, Y> {
+    /// Create a rectangle from its ranges.
+    pub fn new(x_range: Range<AxisIndex<X>>, y_range: Range<AxisIndex<Y>>) -> Self {
+        Rect { x_range, y_range }
+    }
+
+    /// Calculate the total number of columns occupied by the rectangle.
+    pub fn width(&self) -> PositiveAxisDiff<X> {
+        range_len(&self.x_range)
+    }
+
+    /// Calculate the total number of rows occupied by the rectangle.
+    pub fn height(&self) -> PositiveAxisDiff<Y> {
+        range_len(&self.y_range)
+    }
+
+    /// Check whether the rectangle does not contain any points.
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    /// Check whether the point (x, y) is inside the rectangle.
+    pub fn contains(&self, x: AxisIndex<X>, y: AxisIndex<Y>) -> bool {
+        self.x_range.start <= x
+            && x < self.x_range.end
+            && self.y_range.start <= y
+            && y < self.y_range.end
+    }
+
+    /// Check whether the other rectangle is completely inside this one.
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        self.x_range.start <= other.x_range.start
+            && other.x_range.end <= self.x_range.end
+            && self.y_range.start <= other.y_range.start
+            && other.y_range.end <= self.y_range.end
+    }
+
+    /// Calculate the area that is covered by both rectangles, if any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let res = Rect::new(
+            intersect_ranges(&self.x_range, &other.x_range),
+            intersect_ranges(&self.y_range, &other.y_range),
+        );
+        if res.is_empty() {
+            None
+        } else {
+            Some(res)
+        }
+    }
+
+    /// Calculate the smallest rectangle that contains both rectangles.
+    pub fn union(&self, other: &Self) -> Self {
+        Rect::new(
+            unite_ranges(&self.x_range, &other.x_range),
+            unite_ranges(&self.y_range, &other.y_range),
+        )
+    }
+
+    /// Move the point (x, y) to the closest point inside the rectangle. Points are clamped to the
+    /// start of the ranges of an empty rectangle.
+    pub fn clamp(&self, x: AxisIndex<X>, y: AxisIndex<Y>) -> (AxisIndex<X>, AxisIndex<Y>) {
+        (
+            clamp_to_range(x, &self.x_range),
+            clamp_to_range(y, &self.y_range),
+        )
+    }
+
+    /// Check whether the other rectangle overlaps or shares (a part of) an edge or a corner with
+    /// this one.
+    pub fn touches(&self, other: &Self) -> bool {
+        let x_overlap =
+            self.x_range.start <= other.x_range.end && other.x_range.start <= self.x_range.end;
+        let y_overlap =
+            self.y_range.start <= other.y_range.end && other.y_range.start <= self.y_range.end;
+        x_overlap && y_overlap
+    }
+
+    /// Create a rectangle with the same y-range, but the specified x-range, which must be a part
+    /// of the x-range of this rectangle.
+    pub fn slice_range_x(&self, range: Range<AxisIndex<X>>) -> Self {
+        debug_assert!(
+            self.x_range.start <= range.start && range.end <= self.x_range.end,
+            "Invalid slice argument"
+        );
+        Rect::new(range, self.y_range.clone())
+    }
+
+    /// Create a rectangle with the same x-range, but the specified y-range, which must be a part
+    /// of the y-range of this rectangle.
+    pub fn slice_range_y(&self, range: Range<AxisIndex<Y>>) -> Self {
+        debug_assert!(
+            self.y_range.start <= range.start && range.end <= self.y_range.end,
+            "Invalid slice argument"
+        );
+        Rect::new(self.x_range.clone(), range)
+    }
+
+    /// Check whether the point (x, y) is on the border around (i.e., just outside of) the
+    /// rectangle, including the corners.
+    pub fn is_on_border(&self, x: AxisIndex<X>, y: AxisIndex<Y>) -> bool {
+        let x_l = self.x_range.start - 1;
+        let x_r = self.x_range.end;
+        let y_l = self.y_range.start - 1;
+        let y_r = self.y_range.end;
+        (x == x_l || x == x_r) && y_l <= y && y <= y_r
+            || (y == y_l || y == y_r) && x_l <= x && x <= x_r
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rect(x: Range<i32>, y: Range<i32>) -> Rect {
+        Rect::new(
+            ColIndex::new(x.start)..ColIndex::new(x.end),
+            RowIndex::new(y.start)..RowIndex::new(y.end),
+        )
+    }
+
+    #[test]
+    fn test_rect_intersection_union() {
+        let a = rect(0..4, 0..4);
+        assert_eq!(a.intersection(&rect(2..6, -2..2)), Some(rect(2..4, 0..2)));
+        assert_eq!(a.intersection(&rect(4..6, 0..4)), None);
+        assert_eq!(a.intersection(&rect(1..3, 1..3)), Some(rect(1..3, 1..3)));
+        assert_eq!(a.union(&rect(5..6, -1..1)), rect(0..6, -1..4));
+        assert!(a.contains_rect(&rect(1..3, 0..4)));
+        assert!(!a.contains_rect(&rect(1..5, 0..4)));
+    }
+
+    #[test]
+    fn test_rect_contains_clamp() {
+        let a = rect(1..3, 1..2);
+        assert!(a.contains(ColIndex::new(2), RowIndex::new(1)));
+        assert!(!a.contains(ColIndex::new(3), RowIndex::new(1)));
+        assert!(!a.contains(ColIndex::new(1), RowIndex::new(2)));
+        assert_eq!(
+            a.clamp(ColIndex::new(-5), RowIndex::new(5)),
+            (ColIndex::new(1), RowIndex::new(1))
+        );
+        let empty = rect(2..2, 3..3);
+        assert!(empty.is_empty());
+        assert_eq!(
+            empty.clamp(ColIndex::new(0), RowIndex::new(9)),
+            (ColIndex::new(2), RowIndex::new(3))
+        );
+    }
+
+    #[test]
+    fn test_rect_touches_border() {
+        let a = rect(1..3, 1..3);
+        assert!(a.touches(&rect(3..5, 0..1)));
+        assert!(!a.touches(&rect(4..5, 0..1)));
+        assert!(a.is_on_border(ColIndex::new(0), RowIndex::new(0)));
+        assert!(a.is_on_border(ColIndex::new(3), RowIndex::new(2)));
+        assert!(a.is_on_border(ColIndex::new(2), RowIndex::new(3)));
+        assert!(!a.is_on_border(ColIndex::new(2), RowIndex::new(2)));
+        assert!(!a.is_on_border(ColIndex::new(4), RowIndex::new(2)));
+    }
+
+    #[test]
+    fn test_rect_slice() {
+        let a = rect(0..4, 0..4);
+        assert_eq!(
+            a.slice_range_x(ColIndex::new(1)..ColIndex::new(2)),
+            rect(1..2, 0..4)
+        );
+        assert_eq!(
+            a.slice_range_y(RowIndex::new(3)..RowIndex::new(4)),
+            rect(0..4, 3..4)
+        );
+        assert_eq!(a.width(), Width::new(4).unwrap());
+        let (start, end) = (3, 1);
+        assert_eq!(rect(start..end, 0..1).width(), Width::new(0).unwrap());
+    }
+}
@@ -0,0 +1,148 @@
+//! Dirty-flag driven redraw scheduling with a maximum frame rate.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A handle to mark the content of the terminal as outdated, i.e., requiring a redraw.
+///
+/// The handle can be cloned and sent to other threads (e.g., ones that produce data displayed in
+/// widgets).
+#[derive(Clone, Debug)]
+pub struct DirtyFlag(Arc<AtomicBool>);
+
+impl DirtyFlag {
+    /// Request a redraw from the associated `RedrawScheduler`.
+    pub fn mark_dirty(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Check whether a redraw has been requested, but has not happened yet.
+    pub fn is_dirty(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Coalesces redraw requests (see `DirtyFlag`) and limits the number of redraws per second.
+///
+/// Instead of drawing and presenting after every input event or timer tick, the main loop of an
+/// application asks the scheduler whether a redraw is due. Mostly idle applications thus do not
+/// waste cpu time on redrawing unchanged content.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::RedrawScheduler;
+/// use std::time::Duration;
+///
+/// let mut scheduler = RedrawScheduler::with_max_fps(1);
+/// let dirty = scheduler.dirty_flag();
+///
+/// // Nothing has changed, so there is nothing to do.
+/// assert!(!scheduler.should_redraw());
+///
+/// dirty.mark_dirty();
+/// assert!(scheduler.should_redraw());
+/// // Draw widgets and present the terminal here...
+///
+/// // Further changes are coalesced until the next frame is due.
+/// dirty.mark_dirty();
+/// dirty.mark_dirty();
+/// assert!(!scheduler.should_redraw());
+/// assert!(scheduler.time_until_redraw().unwrap() <= Duration::from_secs(1));
+/// ```
+#[derive(Debug)]
+pub struct RedrawScheduler {
+    dirty: DirtyFlag,
+    min_frame_interval: Duration,
+    last_redraw: Option<Instant>,
+}
+
+impl RedrawScheduler {
+    /// Create a scheduler that redraws at most once in the specified interval.
+    pub fn new(min_frame_interval: Duration) -> Self {
+        RedrawScheduler {
+            dirty: DirtyFlag(Arc::new(AtomicBool::new(false))),
+            min_frame_interval,
+            last_redraw: None,
+        }
+    }
+
+    /// Create a scheduler that redraws at most `fps` times per second.
+    ///
+    /// Panics if `fps` is zero.
+    pub fn with_max_fps(fps: u32) -> Self {
+        assert!(fps > 0, "fps must be positive");
+        Self::new(Duration::from_secs(1) / fps)
+    }
+
+    /// Get a handle to request redraws.
+    pub fn dirty_flag(&self) -> DirtyFlag {
+        self.dirty.clone()
+    }
+
+    /// Request a redraw. Equivalent to `dirty_flag().mark_dirty()`.
+    pub fn mark_dirty(&self) {
+        self.dirty.mark_dirty();
+    }
+
+    /// Check whether a redraw has been requested and the previous redraw is long enough ago. If
+    /// so, the request is considered handled, i.e., the caller must draw and present.
+    pub fn should_redraw(&mut self) -> bool {
+        self.should_redraw_at(Instant::now())
+    }
+
+    /// Same as `should_redraw`, but with an explicitly specified current time.
+    pub fn should_redraw_at(&mut self, now: Instant) -> bool {
+        if self.time_until_redraw_at(now) == Some(Duration::from_secs(0)) {
+            self.dirty.0.store(false, Ordering::Release);
+            self.last_redraw = Some(now);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the time until a requested redraw will be due, or `None` if no redraw has been
+    /// requested. This can be used as a timeout when waiting for the next input event.
+    pub fn time_until_redraw(&self) -> Option<Duration> {
+        self.time_until_redraw_at(Instant::now())
+    }
+
+    /// Same as `time_until_redraw`, but with an explicitly specified current time.
+    pub fn time_until_redraw_at(&self, now: Instant) -> Option<Duration> {
+        if !self.dirty.is_dirty() {
+            return None;
+        }
+        Some(match self.last_redraw {
+            Some(last) => (last + self.min_frame_interval)
+                .checked_duration_since(now)
+                .unwrap_or_default(),
+            None => Duration::from_secs(0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_redraw_scheduler_coalesces() {
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let mut scheduler = RedrawScheduler::new(ms(100));
+        let dirty = scheduler.dirty_flag();
+
+        assert!(!scheduler.should_redraw_at(start));
+        assert_eq!(scheduler.time_until_redraw_at(start), None);
+
+        dirty.mark_dirty();
+        assert!(scheduler.should_redraw_at(start));
+        assert!(!dirty.is_dirty());
+
+        dirty.mark_dirty();
+        assert!(!scheduler.should_redraw_at(start + ms(50)));
+        assert_eq!(scheduler.time_until_redraw_at(start + ms(50)), Some(ms(50)));
+        assert!(scheduler.should_redraw_at(start + ms(100)));
+        assert!(!scheduler.should_redraw_at(start + ms(300)));
+    }
+}
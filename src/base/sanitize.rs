@@ -0,0 +1,121 @@
+//! Safe display of untrusted text, e.g., the output of subprocesses.
+//!
+//! Writing arbitrary data to the terminal can move the cursor, change terminal modes or otherwise
+//! corrupt the display. The `sanitizer` shaper (see `Cursor::shaper`) instead renders control
+//! characters as visible escapes. Invalid UTF-8 is preserved for display by `decode_escaped`.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::base::*;
+//!
+//! let text = decode_escaped(b"\x1b[2Jok\xff\n");
+//! let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((14, 1));
+//! {
+//!     let mut win = term.create_root_window();
+//!     let mut cursor = Cursor::new(&mut win).shaper(sanitizer(StyleModifier::new()));
+//!     cursor.write(&text);
+//! }
+//! term.assert_looks_like("␛[2Jok\\xFF    ");
+//! ```
+use base::cursor::Shaped;
+use base::style::StyleModifier;
+use std::borrow::Cow;
+use std::char;
+use std::str;
+
+// Invalid bytes are mapped to the end of the supplementary private use area B (similar to the
+// "surrogateescape" error handler of python).
+const ESCAPED_BYTE_BASE: u32 = 0x10_FF00;
+
+/// Decode `bytes` as UTF-8, but (unlike `String::from_utf8_lossy`) keep invalid bytes as private
+/// use characters, which are displayed as `\xNN` by a `sanitizer`.
+pub fn decode_escaped(bytes: &[u8]) -> Cow<'_, str> {
+    let mut rest = match str::from_utf8(bytes) {
+        Ok(s) => return Cow::Borrowed(s),
+        Err(_) => bytes,
+    };
+    let mut res = String::with_capacity(bytes.len());
+    loop {
+        match str::from_utf8(rest) {
+            Ok(s) => {
+                res.push_str(s);
+                return Cow::Owned(res);
+            }
+            Err(e) => {
+                let (valid, invalid) = rest.split_at(e.valid_up_to());
+                res.push_str(str::from_utf8(valid).expect("valid up to here"));
+                let num_invalid = e.error_len().unwrap_or(invalid.len());
+                for &b in &invalid[..num_invalid] {
+                    res.push(escaped_byte(b));
+                }
+                rest = &invalid[num_invalid..];
+            }
+        }
+    }
+}
+
+fn escaped_byte(b: u8) -> char {
+    char::from_u32(ESCAPED_BYTE_BASE + b as u32).expect("private use area character")
+}
+
+fn escape(c: char) -> Option<String> {
+    match c as u32 {
+        n @ 0x00..=0x1f => char::from_u32(0x2400 + n).map(|p| p.to_string()),
+        0x7f => Some("␡".to_owned()),
+        n @ 0x80..=0x9f => Some(format!("\\x{:02X}", n)),
+        n if (ESCAPED_BYTE_BASE..ESCAPED_BYTE_BASE + 0x100).contains(&n) => {
+            Some(format!("\\x{:02X}", n - ESCAPED_BYTE_BASE))
+        }
+        _ => None,
+    }
+}
+
+/// Create a shaper (see `Cursor::shaper`) that renders C0 control characters (including tab and
+/// carriage return) and DEL as control pictures (see `control_pictures`), C1 control characters
+/// as `\xNN` and invalid bytes (see `decode_escaped`) as `\xNN`. The replacements are styled using
+/// `style`.
+pub fn sanitizer(style: StyleModifier) -> impl Fn(&str) -> Option<Shaped> {
+    move |cluster| {
+        if !cluster.chars().any(|c| escape(c).is_some()) {
+            return None;
+        }
+        let text = cluster
+            .chars()
+            .map(|c| escape(c).unwrap_or_else(|| c.to_string()))
+            .collect::<String>();
+        Some(Shaped::new(text).style(style))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_escaped() {
+        assert_eq!(decode_escaped(b"abc"), Cow::Borrowed("abc"));
+        let decoded = decode_escaped(b"a\xffb\xe2\x82");
+        assert_eq!(
+            decoded.chars().collect::<Vec<_>>(),
+            vec![
+                'a',
+                escaped_byte(0xff),
+                'b',
+                escaped_byte(0xe2),
+                escaped_byte(0x82)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sanitizer() {
+        let s = sanitizer(StyleModifier::new());
+        assert_eq!(s("a"), None);
+        assert_eq!(s("ä"), None);
+        assert_eq!(s("\u{1b}"), Some(Shaped::new("␛")));
+        assert_eq!(s("\u{7f}"), Some(Shaped::new("␡")));
+        assert_eq!(s("\u{9b}"), Some(Shaped::new("\\x9B")));
+        let invalid = decode_escaped(b"\xc3");
+        assert_eq!(s(&invalid), Some(Shaped::new("\\xC3")));
+    }
+}
@@ -1,5 +1,6 @@
 //! Types related to the visual representation (i.e., style) of text when drawn to the terminal.
 //! This includes formatting (bold, italic, ...) and colors.
+use std::collections::HashMap;
 use std::io::Write;
 use termion;
 
@@ -267,7 +268,9 @@ impl Default for TextFormatModifier {
 ///     - Default (i.e., terminal color is reset)
 ///     - Named (Black, Yellow, LightRed, ...)
 ///     - Ansi (8 bit)
-///     - or Rgb.
+///     - Rgb
+///     - or Semantic, i.e., a token (like "accent") that is resolved using the `Theme` of the
+///       terminal when the content is presented.
 ///
 /// Not all terminals may support Rgb, though.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -292,6 +295,7 @@ pub enum Color {
     LightRed,
     LightWhite,
     LightYellow,
+    Semantic(&'static str),
 }
 
 impl Default for Color {
@@ -321,7 +325,7 @@ impl Color {
     fn set_terminal_attributes_fg<W: Write>(self, terminal: &mut W) -> ::std::io::Result<()> {
         use termion::color::Fg as Target;
         match self {
-            Color::Default => Ok(()),
+            Color::Default | Color::Semantic(_) => Ok(()),
             Color::Rgb { r, g, b } => write!(terminal, "{}", Target(termion::color::Rgb(r, g, b))),
             Color::Ansi(v) => write!(terminal, "{}", Target(termion::color::AnsiValue(v))),
             Color::Black => write!(terminal, "{}", Target(termion::color::Black)),
@@ -347,7 +351,7 @@ impl Color {
     fn set_terminal_attributes_bg<W: Write>(self, terminal: &mut W) -> ::std::io::Result<()> {
         use termion::color::Bg as Target;
         match self {
-            Color::Default => Ok(()),
+            Color::Default | Color::Semantic(_) => Ok(()),
             Color::Rgb { r, g, b } => write!(terminal, "{}", Target(termion::color::Rgb(r, g, b))),
             Color::Ansi(v) => write!(terminal, "{}", Target(termion::color::AnsiValue(v))),
             Color::Black => write!(terminal, "{}", Target(termion::color::Black)),
@@ -391,6 +395,25 @@ impl Style {
         self.format
     }
 
+    /// Access the foreground color of the style
+    pub fn fg_color(&self) -> Color {
+        self.fg_color
+    }
+
+    /// Access the background color of the style
+    pub fn bg_color(&self) -> Color {
+        self.bg_color
+    }
+
+    /// Replace semantic colors of the style by the concrete colors of `theme`.
+    pub fn resolve(self, theme: &Theme) -> Style {
+        Style {
+            fg_color: theme.resolve(self.fg_color),
+            bg_color: theme.resolve(self.bg_color),
+            format: self.format,
+        }
+    }
+
     /// Set the attributes of the given ANSI terminal to match the current Style.
     pub(crate) fn set_terminal_attributes<W: Write>(self, terminal: &mut W) {
         // Since we cannot rely on NoBold reseting the bold style (see
@@ -415,6 +438,76 @@ impl Style {
     }
 }
 
+/// The maximum number of tokens that are followed when resolving a semantic color that is mapped
+/// to another semantic color.
+const MAX_SEMANTIC_INDIRECTIONS: usize = 8;
+
+/// Concrete colors for semantic color tokens (see `Color::Semantic`).
+///
+/// Widgets (or their users) only store tokens in styles, which are resolved using the theme of
+/// the `Terminal` when the content is presented. Thus, switching the theme (see
+/// `Terminal::set_theme`) takes effect immediately, without rerendering any widget.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::{Color, StyleModifier, Theme};
+///
+/// let theme = Theme::new()
+///     .color("accent", Color::Blue)
+///     .color("link", Color::Semantic("accent"));
+/// assert_eq!(theme.resolve(Color::Semantic("link")), Color::Blue);
+/// assert_eq!(theme.resolve(Color::Semantic("unknown")), Color::Default);
+/// assert_eq!(theme.resolve(Color::Red), Color::Red);
+///
+/// let style = StyleModifier::new()
+///     .fg_color(Color::Semantic("accent"))
+///     .apply_to_default();
+/// assert_eq!(style.resolve(&theme).fg_color(), Color::Blue);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    colors: HashMap<String, Color>,
+}
+
+impl Theme {
+    /// Create a theme without any tokens, i.e., all semantic colors resolve to `Color::Default`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map `token` to `color`, which may be another semantic color.
+    pub fn color(mut self, token: &str, color: Color) -> Self {
+        self.set_color(token, color);
+        self
+    }
+
+    /// Map `token` to `color` (replacing the previous color of the token), which may be another
+    /// semantic color.
+    pub fn set_color(&mut self, token: &str, color: Color) {
+        self.colors.insert(token.to_owned(), color);
+    }
+
+    /// Get the color that `token` is mapped to (without resolving it).
+    pub fn get(&self, token: &str) -> Option<Color> {
+        self.colors.get(token).cloned()
+    }
+
+    /// Get the concrete color for `color`. Semantic colors without a (concrete) color in the theme
+    /// (including cyclic mappings) resolve to `Color::Default`, all others are returned unchanged.
+    pub fn resolve(&self, mut color: Color) -> Color {
+        for _ in 0..MAX_SEMANTIC_INDIRECTIONS {
+            match color {
+                Color::Semantic(token) => color = self.get(token).unwrap_or(Color::Default),
+                concrete => return concrete,
+            }
+        }
+        match color {
+            Color::Semantic(_) => Color::Default,
+            concrete => concrete,
+        }
+    }
+}
+
 /// Defines a set of modifications on a style. Multiple modifiers can be combined before applying
 /// them to a style.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
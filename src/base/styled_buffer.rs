@@ -0,0 +1,313 @@
+//! An owned, growable grid of styled grapheme clusters that can be written to using a Cursor.
+use super::{CursorTarget, GraphemeCluster, Style, StyledGraphemeCluster, Window};
+use base::basic_types::*;
+use std::fmt;
+use std::ops::Range;
+
+/// An owned, offscreen buffer of styled grapheme clusters.
+///
+/// In contrast to a `WindowBuffer`, a `StyledBuffer` does not have a fixed size: It grows (in
+/// height and up to its maximum width) as content is written to it, e.g., using a `Cursor`. This
+/// makes it suitable for rendering content of unknown size (for measurement, scrollback or
+/// snapshot tests) that is later (partially) copied to a `Window` using `blit`.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::*;
+///
+/// let mut buffer = StyledBuffer::with_width(Width::new(4).unwrap());
+/// Cursor::new(&mut buffer)
+///     .wrapping_mode(WrappingMode::Wrap)
+///     .write("foobar");
+///
+/// assert_eq!(buffer.extent_x(), Width::new(4).unwrap());
+/// assert_eq!(buffer.extent_y(), Height::new(2).unwrap());
+/// assert_eq!(buffer.to_string(), "foob\nar");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyledBuffer {
+    lines: Vec<Vec<StyledGraphemeCluster>>,
+    width: Width,
+    blank: StyledGraphemeCluster,
+}
+
+impl Default for StyledBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StyledBuffer {
+    /// Create an empty buffer without a maximum width.
+    pub fn new() -> Self {
+        Self::with_width(Width::UNBOUNDED)
+    }
+
+    /// Create an empty buffer with the specified maximum width. Cursors writing to the buffer will
+    /// wrap (or clip) at this width.
+    pub fn with_width(width: Width) -> Self {
+        StyledBuffer {
+            lines: Vec::new(),
+            width,
+            blank: StyledGraphemeCluster::new(GraphemeCluster::space(), Style::default()),
+        }
+    }
+
+    /// Specify the default style of the buffer, i.e., the style of blank cells and the base for
+    /// style modifications of cursors writing to the buffer.
+    pub fn set_default_style(&mut self, style: Style) {
+        self.blank.style = style;
+    }
+
+    /// Get the number of columns of the longest line of the buffer.
+    pub fn extent_x(&self) -> Width {
+        self.lines
+            .iter()
+            .map(|l| Width::from(l.len()))
+            .max()
+            .unwrap_or_else(|| Width::new(0).unwrap())
+    }
+
+    /// Get the number of lines of the buffer.
+    pub fn extent_y(&self) -> Height {
+        Height::from(self.lines.len())
+    }
+
+    /// Get the cells of the specified line, if present. Note that lines can have different
+    /// lengths.
+    pub fn line(&self, y: RowIndex) -> Option<&[StyledGraphemeCluster]> {
+        if y < 0 {
+            None
+        } else {
+            self.lines.get(y.raw_value() as usize).map(|l| l.as_slice())
+        }
+    }
+
+    /// Remove all content from the buffer.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Copy the specified region of the buffer into a new buffer (with the same maximum width).
+    ///
+    /// Grapheme clusters that are cut off at the borders of the region are replaced by spaces.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    ///
+    /// let mut buffer = StyledBuffer::new();
+    /// Cursor::new(&mut buffer).write("abcd\nefgh\nijkl");
+    ///
+    /// let slice = buffer.slice(ColIndex::new(1)..ColIndex::new(3), RowIndex::new(1)..RowIndex::new(5));
+    /// assert_eq!(slice.to_string(), "fg\njk");
+    /// ```
+    pub fn slice(&self, x_range: Range<ColIndex>, y_range: Range<RowIndex>) -> StyledBuffer {
+        let mut res = StyledBuffer {
+            lines: Vec::new(),
+            width: self.width,
+            blank: self.blank.clone(),
+        };
+        for y in IndexRange(y_range.start.positive_or_zero()..y_range.end) {
+            let line = if let Some(line) = self.line(y) {
+                line
+            } else {
+                break;
+            };
+            let mut new_line = Vec::new();
+            for x in IndexRange(x_range.start.positive_or_zero()..x_range.end) {
+                if let Some(cell) = line.get(x.raw_value() as usize) {
+                    new_line.push(cell.clone());
+                } else {
+                    break;
+                }
+            }
+            fix_cut_clusters(&mut new_line);
+            res.lines.push(new_line);
+        }
+        res
+    }
+
+    /// Copy the contents of the buffer into the window, starting at `origin` in the buffer.
+    ///
+    /// Cells of the window that do not correspond to a cell of the buffer are cleared. Grapheme
+    /// clusters that are cut off at the borders of the window are replaced by spaces.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    /// use unsegen::base::terminal::test::FakeTerminal;
+    ///
+    /// let mut buffer = StyledBuffer::new();
+    /// Cursor::new(&mut buffer).write("abc\ndef\nghi");
+    ///
+    /// let mut term = FakeTerminal::with_size((2, 2));
+    /// buffer.blit(&mut term.create_root_window(), (ColIndex::new(1), RowIndex::new(1)));
+    /// term.assert_looks_like("ef|hi");
+    /// ```
+    pub fn blit(&self, window: &mut Window, origin: (ColIndex, RowIndex)) {
+        let (origin_x, origin_y) = origin;
+        window.clear();
+        let width = window.get_width();
+        let slice = self.slice(
+            origin_x..(origin_x + width),
+            origin_y..(origin_y + window.get_height()),
+        );
+        for (y, line) in slice.lines.into_iter().enumerate() {
+            for (x, cell) in line.into_iter().enumerate() {
+                if let Some(target) =
+                    window.get_cell_mut(ColIndex::from(x as i32), RowIndex::from(y as i32))
+                {
+                    *target = cell;
+                }
+            }
+        }
+    }
+}
+
+/// Replace clusters at the start or end of the line that have been cut in half (wide clusters
+/// at the end or their remaining empty cells at the beginning) with spaces.
+fn fix_cut_clusters(line: &mut [StyledGraphemeCluster]) {
+    for cell in line.iter_mut() {
+        if cell.grapheme_cluster.as_str().is_empty() {
+            *cell = StyledGraphemeCluster::new(GraphemeCluster::space(), cell.style);
+        } else {
+            break;
+        }
+    }
+    let len = line.len();
+    let mut x = 0;
+    while x < len {
+        let cluster_width = line[x].grapheme_cluster.width().max(1);
+        if x + cluster_width > len {
+            for cell in line[x..].iter_mut() {
+                *cell = StyledGraphemeCluster::new(GraphemeCluster::space(), cell.style);
+            }
+        }
+        x += cluster_width;
+    }
+}
+
+/// Serialize the content of the buffer as plain text, i.e., without style information. Lines are
+/// separated by newline characters.
+impl fmt::Display for StyledBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, line) in self.lines.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            for cell in line {
+                write!(f, "{}", cell.grapheme_cluster.as_str())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CursorTarget for StyledBuffer {
+    fn get_width(&self) -> Width {
+        self.width
+    }
+    /// For buffers without a maximum width, this is the current extent (but at least 1), so that
+    /// cursor operations that work up to the end of the line (e.g., `Cursor::clear_line`) do not
+    /// grow the lines indefinitely.
+    fn get_soft_width(&self) -> Width {
+        if self.width == Width::UNBOUNDED {
+            ::std::cmp::max(self.extent_x(), Width::new(1).unwrap())
+        } else {
+            self.width
+        }
+    }
+    fn get_height(&self) -> Height {
+        Height::UNBOUNDED
+    }
+    fn get_cell_mut(&mut self, x: ColIndex, y: RowIndex) -> Option<&mut StyledGraphemeCluster> {
+        if x < 0 || y < 0 || x >= self.width.from_origin() {
+            return None;
+        }
+        let x = x.raw_value() as usize;
+        let y = y.raw_value() as usize;
+        if self.lines.len() <= y {
+            self.lines.resize(y + 1, Vec::new());
+        }
+        let line = &mut self.lines[y];
+        if line.len() <= x {
+            line.resize(x + 1, self.blank.clone());
+        }
+        line.get_mut(x)
+    }
+    fn get_cell(&self, x: ColIndex, y: RowIndex) -> Option<&StyledGraphemeCluster> {
+        if x < 0 || y < 0 || x >= self.width.from_origin() {
+            return None;
+        }
+        Some(
+            self.lines
+                .get(y.raw_value() as usize)
+                .and_then(|line| line.get(x.raw_value() as usize))
+                .unwrap_or(&self.blank),
+        )
+    }
+    fn get_default_style(&self) -> Style {
+        self.blank.style
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+    use base::Cursor;
+
+    #[test]
+    fn test_styled_buffer_unbounded_line_operations() {
+        let mut buffer = StyledBuffer::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            cursor.write("abcd");
+            cursor.move_to_x(ColIndex::new(2));
+            cursor.clear_line_right();
+            cursor.wrap_line();
+            cursor.write("ef");
+            cursor.clear_line();
+            cursor.write("g");
+            cursor.fill_and_wrap_line();
+            cursor.write("h");
+        }
+        assert_eq!(buffer.extent_x(), Width::new(4).unwrap());
+        assert_eq!(buffer.to_string(), "ab  \n  g \nh");
+    }
+
+    #[test]
+    fn test_styled_buffer_grows() {
+        let mut buffer = StyledBuffer::new();
+        {
+            let mut cursor = Cursor::new(&mut buffer);
+            cursor.write("a");
+            cursor.move_to(ColIndex::new(3), RowIndex::new(2));
+            cursor.write("b");
+        }
+        assert_eq!(buffer.extent_x(), Width::new(4).unwrap());
+        assert_eq!(buffer.extent_y(), Height::new(3).unwrap());
+        assert_eq!(buffer.to_string(), "a\n\n   b");
+    }
+
+    #[test]
+    fn test_styled_buffer_blit_wide_clusters() {
+        let mut buffer = StyledBuffer::new();
+        Cursor::new(&mut buffer).write("a沐b\n沐沐");
+
+        let mut term = FakeTerminal::with_size((2, 2));
+        buffer.blit(
+            &mut term.create_root_window(),
+            (ColIndex::new(2), RowIndex::new(0)),
+        );
+        term.assert_looks_like(" b|沐");
+
+        let mut term = FakeTerminal::with_size((2, 2));
+        buffer.blit(
+            &mut term.create_root_window(),
+            (ColIndex::new(0), RowIndex::new(0)),
+        );
+        term.assert_looks_like("a |沐");
+    }
+}
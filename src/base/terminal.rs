@@ -24,16 +24,28 @@
 //!
 //! }
 //! ```
-use base::{Height, Style, Width, Window, WindowBuffer};
+use base::{
+    register_cleanup, Capabilities, CleanupGuard, Height, Rect, Style, StyledGraphemeCluster,
+    Theme, Width, Window, WindowBuffer,
+};
 use ndarray::Axis;
 use raw_tty::TtyWithGuard;
 use std::io;
-use std::io::{StdoutLock, Write};
+use std::io::{Read, StdoutLock, Write};
+use std::ops::Range;
 use std::os::unix::io::AsRawFd;
+use std::time::{Duration, Instant};
 use termion;
 
+use nix::poll::{poll, PollFd, PollFlags};
 use nix::sys::signal::{killpg, pthread_sigmask, SigSet, SigmaskHow, SIGCONT, SIGTSTP};
-use nix::unistd::getpgrp;
+use nix::sys::termios::{tcgetattr, tcsetattr, SetArg};
+use nix::unistd::{getpgrp, write};
+
+/// Disables all mouse reporting modes (normal, button and any event tracking as well as the UTF-8,
+/// SGR and urxvt encodings) and bracketed paste, which applications may have enabled.
+const RESET_INPUT_MODES: &str =
+    "\x1b[?1000l\x1b[?1002l\x1b[?1003l\x1b[?1005l\x1b[?1006l\x1b[?1015l\x1b[?2004l";
 
 /// A type providing an interface to the underlying physical terminal.
 /// This also provides the entry point for any rendering to the terminal buffer.
@@ -45,7 +57,14 @@ where
     old_values: WindowBuffer,
     terminal: TtyWithGuard<T>,
     size_has_changed_since_last_present: bool,
+    unknown_lines: Vec<bool>,
+    output_budget: Option<usize>,
+    priority_rows: Range<usize>,
     bell_to_emit: bool,
+    clipboard_to_set: Option<String>,
+    capabilities: Capabilities,
+    theme: Theme,
+    _cleanup: CleanupGuard,
     _phantom: ::std::marker::PhantomData<&'a ()>,
 }
 
@@ -55,7 +74,24 @@ impl<'a, T: Write + AsRawFd> Terminal<'a, T> {
     ///
     /// If the terminal cannot be created (e.g., because the provided io sink does not allow for
     /// setting up raw mode), the error is returned.
+    ///
+    /// The terminal registers a cleanup action that restores the terminal state (see
+    /// `base::cleanup`), which is executed on panic once `cleanup::install_panic_hook` has been
+    /// called (e.g., by `app::run`).
     pub fn new(sink: T) -> io::Result<Self> {
+        let fd = sink.as_raw_fd();
+        let original_mode = tcgetattr(fd)?;
+        let leave_sequence = format!(
+            "{}{}{}",
+            RESET_INPUT_MODES,
+            termion::screen::ToMainScreen,
+            termion::cursor::Show
+        );
+        let cleanup = register_cleanup(move || {
+            let _ = write(fd, leave_sequence.as_bytes());
+            let _ = tcsetattr(fd, SetArg::TCSANOW, &original_mode);
+        });
+
         let mut terminal = TtyWithGuard::new(sink)?;
         terminal.set_raw_mode()?;
         let mut term = Terminal {
@@ -63,7 +99,14 @@ impl<'a, T: Write + AsRawFd> Terminal<'a, T> {
             old_values: WindowBuffer::new(Width::new(0).unwrap(), Height::new(0).unwrap()),
             terminal,
             size_has_changed_since_last_present: true,
+            unknown_lines: Vec::new(),
+            output_budget: None,
+            priority_rows: 0..0,
             bell_to_emit: false,
+            clipboard_to_set: None,
+            capabilities: Capabilities::from_env(),
+            theme: Theme::new(),
+            _cleanup: cleanup,
             _phantom: Default::default(),
         };
         term.enter_tui()?;
@@ -101,6 +144,67 @@ impl<'a, T: Write + AsRawFd> Terminal<'a, T> {
         self.enter_tui()
     }
 
+    /// Get the capabilities of the terminal, initially guessed from the environment (see
+    /// `Capabilities::from_env`) and refined by `probe_capabilities`.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Query the terminal for its capabilities (see `Capabilities::PROBE_QUERY`) and wait for the
+    /// responses on `input` (usually stdin) for at most `timeout`.
+    ///
+    /// Call this before any other thread starts reading from `input`, as the responses would
+    /// otherwise be interpreted as user input (and vice versa: user input that arrives during
+    /// probing is discarded). If the terminal does not respond in time, the capabilities guessed
+    /// from the environment are kept.
+    pub fn probe_capabilities<R: Read + AsRawFd>(
+        &mut self,
+        input: &mut R,
+        timeout: Duration,
+    ) -> io::Result<()> {
+        write!(self.terminal, "{}", Capabilities::PROBE_QUERY)?;
+        self.terminal.flush()?;
+
+        let deadline = Instant::now() + timeout;
+        let mut caps = self.capabilities;
+        let mut response = Vec::new();
+        let mut buf = [0u8; 256];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let mut fds = [PollFd::new(input.as_raw_fd(), PollFlags::POLLIN)];
+            if remaining == Duration::from_secs(0)
+                || poll(&mut fds, remaining.as_millis() as i32)? == 0
+            {
+                return Ok(());
+            }
+            let n = input.read(&mut buf)?;
+            if n == 0 {
+                return Ok(());
+            }
+            // Sequences may be split across reads, so always parse the whole response.
+            response.extend_from_slice(&buf[..n]);
+            if caps.apply_probe_response(&response) {
+                self.capabilities = caps;
+                return Ok(());
+            }
+        }
+    }
+
+    /// Get the theme that semantic colors (see `Color::Semantic`) are resolved with.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Resolve semantic colors (see `Color::Semantic`) using `theme` from now on. All lines are
+    /// written again during the next `present`, so the switch takes effect without rerendering
+    /// any widget.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        for unknown in self.unknown_lines.iter_mut() {
+            *unknown = true;
+        }
+    }
+
     /// Set up the terminal for "full screen" work (i.e., hide cursor, switch to alternate screen).
     fn enter_tui(&mut self) -> io::Result<()> {
         write!(
@@ -165,63 +269,198 @@ impl<'a, T: Write + AsRawFd> Terminal<'a, T> {
         self.bell_to_emit = true;
     }
 
-    /// Present the current buffer content to the actual terminal.
-    pub fn present(&mut self) {
-        let mut current_style = Style::default();
+    /// Set the system clipboard to `text` on the next call to `present` using OSC 52.
+    ///
+    /// This also works over ssh, but some terminal emulators ignore (or ask the user to confirm)
+    /// the request.
+    pub fn set_clipboard(&mut self, text: &str) {
+        self.clipboard_to_set = Some(text.to_owned());
+    }
+
+    /// Limit the number of bytes that `present` writes for the content of the terminal (default:
+    /// unlimited), e.g., to keep the application responsive over a slow connection.
+    ///
+    /// Lines that do not fit into the budget are deferred to the next call to `present` (see
+    /// `has_pending_output`). At least one changed line is written per call, even if it exceeds
+    /// the budget, so that the output always makes progress.
+    pub fn set_output_budget(&mut self, bytes: Option<usize>) {
+        self.output_budget = bytes;
+    }
 
-        let mut num_potentially_unchanged_lines = self.old_values.storage().dim().0;
+    /// Write the lines covering `area` before all other lines in the next call to `present`, e.g.,
+    /// the area of the active container (see `ContainerManager::active_area`). This only makes a
+    /// difference if an output budget is set (see `set_output_budget`).
+    pub fn prioritize(&mut self, area: Rect) {
+        self.priority_rows = area.y_range.start.raw_value().max(0) as usize
+            ..area.y_range.end.raw_value().max(0) as usize;
+    }
+
+    /// Check whether the last call to `present` deferred lines because of the output budget. If
+    /// so, call `present` again (e.g., after a short timeout) even if nothing has been redrawn.
+    pub fn has_pending_output(&self) -> bool {
+        !changed_lines(&self.values, &self.old_values, &self.unknown_lines).is_empty()
+    }
 
+    /// Present the current buffer content to the actual terminal.
+    ///
+    /// Only lines that changed since the last call are written, within the output budget (see
+    /// `set_output_budget`).
+    pub fn present(&mut self) {
         if self.size_has_changed_since_last_present {
             write!(self.terminal, "{}", termion::clear::All).expect("clear");
             self.size_has_changed_since_last_present = false;
-            num_potentially_unchanged_lines = 0;
+            let (height, width) = self.values.storage().dim();
+            self.old_values = WindowBuffer::new(
+                Width::new(width as i32).unwrap(),
+                Height::new(height as i32).unwrap(),
+            );
+            self.unknown_lines = vec![true; height];
         }
         if self.bell_to_emit {
             write!(self.terminal, "\x07").expect("emit bell");
             self.bell_to_emit = false;
         }
-        for (y, line) in self.values.storage().axis_iter(Axis(0)).enumerate() {
-            if y < num_potentially_unchanged_lines
-                && self.old_values.storage().subview(Axis(0), y) == line
-            {
-                continue;
-            }
-            write!(
-                self.terminal,
-                "{}",
-                termion::cursor::Goto(1, (y + 1) as u16)
-            )
-            .expect("move cursor");
-            let mut buffer = String::with_capacity(line.len());
-            for c in line.iter() {
-                if c.style != current_style {
-                    current_style.set_terminal_attributes(&mut self.terminal);
-                    write!(self.terminal, "{}", buffer).expect("write buffer");
-                    buffer.clear();
-                    current_style = c.style;
-                }
-                let grapheme_cluster = match c.grapheme_cluster.as_str() {
-                    c @ "\t" | c @ "\n" | c @ "\r" | c @ "\0" => {
-                        panic!("Invalid grapheme cluster written to terminal: {:?}", c)
-                    }
-                    x => x,
-                };
-                buffer.push_str(grapheme_cluster);
-            }
-            current_style.set_terminal_attributes(&mut self.terminal);
-            write!(self.terminal, "{}", buffer).expect("write leftover buffer contents");
+        if let Some(text) = self.clipboard_to_set.take() {
+            write!(self.terminal, "\x1b]52;c;{}\x07", base64(text.as_bytes()))
+                .expect("set clipboard");
         }
+        let priority_rows = ::std::mem::replace(&mut self.priority_rows, 0..0);
+        write_changed_lines(
+            &self.values,
+            &mut self.old_values,
+            &mut self.unknown_lines,
+            &priority_rows,
+            self.output_budget,
+            &self.theme,
+            &mut self.terminal,
+        );
         let _ = self.terminal.flush();
-        self.old_values = self.values.clone();
     }
 }
 
+/// Collect the indices of all lines of `values` that differ from `old_values` (i.e., the content
+/// last written to the terminal) or whose content on the terminal is unknown.
+fn changed_lines(
+    values: &WindowBuffer,
+    old_values: &WindowBuffer,
+    unknown_lines: &[bool],
+) -> Vec<usize> {
+    let old = old_values.storage();
+    values
+        .storage()
+        .axis_iter(Axis(0))
+        .enumerate()
+        .filter(|&(y, ref line)| {
+            unknown_lines.get(y).cloned().unwrap_or(true) || old.subview(Axis(0), y) != *line
+        })
+        .map(|(y, _)| y)
+        .collect()
+}
+
+/// Write all changed lines of `values` (lines in `priority` first) to `output`, but stop before
+/// the first line that would exceed `budget` bytes (if any). The first line is always written.
+/// `old_values` and `unknown_lines` are updated for all written lines, so that the remaining ones
+/// are written by the next call.
+fn write_changed_lines<W: Write>(
+    values: &WindowBuffer,
+    old_values: &mut WindowBuffer,
+    unknown_lines: &mut [bool],
+    priority: &Range<usize>,
+    budget: Option<usize>,
+    theme: &Theme,
+    output: &mut W,
+) {
+    let changed = changed_lines(values, old_values, unknown_lines);
+    let mut current_style = Style::default();
+    let mut num_written_bytes = 0;
+    let mut line_output = Vec::new();
+    for y in present_order(changed, priority) {
+        let line = values.storage().subview(Axis(0), y);
+        let mut style = current_style;
+        line_output.clear();
+        render_line(y, line.iter(), theme, &mut style, &mut line_output);
+        if let Some(budget) = budget {
+            if num_written_bytes > 0 && num_written_bytes + line_output.len() > budget {
+                break;
+            }
+        }
+        output.write_all(&line_output).expect("write line");
+        num_written_bytes += line_output.len();
+        current_style = style;
+        old_values
+            .storage_mut()
+            .subview_mut(Axis(0), y)
+            .assign(&line);
+        unknown_lines[y] = false;
+    }
+}
+
+/// Order the indices of changed lines for presenting: lines in `priority` first, then all others
+/// from top to bottom.
+fn present_order(mut changed: Vec<usize>, priority: &Range<usize>) -> Vec<usize> {
+    changed.sort_by_key(|y| (!priority.contains(y), *y));
+    changed
+}
+
+/// Render a line (at row `y`) of the terminal to `output`, starting with the terminal in
+/// `current_style`, which is updated to the style the terminal is in afterwards. Semantic colors
+/// are resolved using `theme`.
+fn render_line<'a, I: Iterator<Item = &'a StyledGraphemeCluster>>(
+    y: usize,
+    line: I,
+    theme: &Theme,
+    current_style: &mut Style,
+    output: &mut Vec<u8>,
+) {
+    write!(output, "{}", termion::cursor::Goto(1, (y + 1) as u16)).expect("move cursor");
+    let mut buffer = String::new();
+    for c in line {
+        if c.style != *current_style {
+            current_style.resolve(theme).set_terminal_attributes(output);
+            write!(output, "{}", buffer).expect("write buffer");
+            buffer.clear();
+            *current_style = c.style;
+        }
+        let grapheme_cluster = match c.grapheme_cluster.as_str() {
+            c @ "\t" | c @ "\n" | c @ "\r" | c @ "\0" => {
+                panic!("Invalid grapheme cluster written to terminal: {:?}", c)
+            }
+            x => x,
+        };
+        buffer.push_str(grapheme_cluster);
+    }
+    current_style.resolve(theme).set_terminal_attributes(output);
+    write!(output, "{}", buffer).expect("write leftover buffer contents");
+}
+
 impl<'a, T: Write + AsRawFd> Drop for Terminal<'a, T> {
     fn drop(&mut self) {
         let _ = self.leave_tui();
     }
 }
 
+/// Encode `bytes` as base64 (with padding).
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut res = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                res.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                res.push('=');
+            }
+        }
+    }
+    res
+}
+
 /// Contains a FakeTerminal useful for tests
 pub mod test {
     use super::super::{
@@ -307,23 +546,364 @@ pub mod test {
         }
     }
 
+    impl FakeTerminal {
+        /// Compare the contents of the terminal (the actual contents) to `expected` and describe
+        /// all differences. Returns `None` if both terminals are equal.
+        ///
+        /// The description shows the expected and actual rows side by side (in the format of
+        /// `assert_looks_like`, rows that differ are marked with `>`) and lists every cell that
+        /// differs, either in content or in style (foreground color, background color or text
+        /// attributes), each as "expected vs actual". This is what `assert_term_eq!` prints on
+        /// failure.
+        pub fn diff(&self, expected: &FakeTerminal) -> Option<String> {
+            if self == expected {
+                return None;
+            }
+            use std::fmt::Write;
+            let actual_values = self.values.storage();
+            let expected_values = expected.values.storage();
+            let mut report = String::new();
+            if actual_values.dim() != expected_values.dim() {
+                let _ = writeln!(
+                    report,
+                    "size differs: expected {}x{}, actual {}x{}",
+                    expected_values.dim().1,
+                    expected_values.dim().0,
+                    actual_values.dim().1,
+                    actual_values.dim().0
+                );
+            }
+
+            let expected_rows = expected.rows();
+            let actual_rows = self.rows();
+            let column_width = expected_rows
+                .iter()
+                .map(|r| r.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max("expected".len());
+            let _ = writeln!(report, "  {:<1$} | actual", "expected", column_width);
+            for r in 0..expected_rows.len().max(actual_rows.len()) {
+                let e = expected_rows.get(r).map(String::as_str).unwrap_or("");
+                let a = actual_rows.get(r).map(String::as_str).unwrap_or("");
+                let marker = if e == a { ' ' } else { '>' };
+                let padding = " ".repeat(column_width - e.chars().count());
+                let _ = writeln!(report, "{} {}{} | {}", marker, e, padding, a);
+            }
+
+            let rows = expected_values.dim().0.min(actual_values.dim().0);
+            let cols = expected_values.dim().1.min(actual_values.dim().1);
+            for r in 0..rows {
+                for c in 0..cols {
+                    let e = &expected_values[(r, c)];
+                    let a = &actual_values[(r, c)];
+                    let mut differences = Vec::new();
+                    if e.grapheme_cluster != a.grapheme_cluster {
+                        differences.push(format!(
+                            "content {:?} vs {:?}",
+                            e.grapheme_cluster.as_str(),
+                            a.grapheme_cluster.as_str()
+                        ));
+                    }
+                    if e.style.fg_color() != a.style.fg_color() {
+                        differences.push(format!(
+                            "fg {:?} vs {:?}",
+                            e.style.fg_color(),
+                            a.style.fg_color()
+                        ));
+                    }
+                    if e.style.bg_color() != a.style.bg_color() {
+                        differences.push(format!(
+                            "bg {:?} vs {:?}",
+                            e.style.bg_color(),
+                            a.style.bg_color()
+                        ));
+                    }
+                    let (ef, af) = (e.style.format(), a.style.format());
+                    for &(name, e, a) in &[
+                        ("bold", ef.bold, af.bold),
+                        ("italic", ef.italic, af.italic),
+                        ("invert", ef.invert, af.invert),
+                        ("underline", ef.underline, af.underline),
+                    ] {
+                        if e != a {
+                            differences.push(format!("{} {} vs {}", name, e, a));
+                        }
+                    }
+                    if !differences.is_empty() {
+                        let _ = writeln!(
+                            report,
+                            "cell (x: {}, y: {}): {}",
+                            c,
+                            r,
+                            differences.join(", ")
+                        );
+                    }
+                }
+            }
+            Some(report)
+        }
+
+        fn rows(&self) -> Vec<String> {
+            let raw_values = self.values.storage();
+            (0..raw_values.dim().0)
+                .map(|r| {
+                    let mut row = String::new();
+                    for c in 0..raw_values.dim().1 {
+                        let c = raw_values.get((r, c)).expect("debug: in bounds");
+                        if c.style.format().bold {
+                            row.push('*');
+                            row.push_str(c.grapheme_cluster.as_str());
+                            row.push('*');
+                        } else {
+                            row.push_str(c.grapheme_cluster.as_str());
+                        }
+                    }
+                    row
+                })
+                .collect()
+        }
+    }
+
     impl ::std::fmt::Debug for FakeTerminal {
         fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-            let raw_values = self.values.storage();
-            for r in 0..raw_values.dim().0 {
-                for c in 0..raw_values.dim().1 {
-                    let c = raw_values.get((r, c)).expect("debug: in bounds");
-                    if c.style.format().bold {
-                        write!(f, "*{}*", c.grapheme_cluster.as_str())?;
-                    } else {
-                        write!(f, "{}", c.grapheme_cluster.as_str())?;
+            write!(f, "{}", self.rows().join("|"))
+        }
+    }
+
+    /// Assert that two `FakeTerminal`s (the actual and the expected one) are equal, including the
+    /// style of all cells.
+    ///
+    /// On failure, the panic message shows both terminals side by side and lists all cells that
+    /// differ in content or style (see `FakeTerminal::diff`). Like `assert_eq!`, an additional
+    /// message can be passed as format arguments.
+    ///
+    /// # Examples:
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate unsegen;
+    /// use unsegen::base::terminal::test::FakeTerminal;
+    /// use unsegen::base::{GraphemeCluster, StyleModifier};
+    ///
+    /// # fn main() {
+    /// let mut term = FakeTerminal::with_size((2, 1));
+    /// term.create_root_window().fill_styled(
+    ///     GraphemeCluster::try_from('a').unwrap(),
+    ///     |x, _| StyleModifier::new().bold(x.raw_value() == 1),
+    /// );
+    ///
+    /// assert_term_eq!(term, FakeTerminal::from_str((2, 1), "a*a*").unwrap());
+    /// # }
+    /// ```
+    #[macro_export]
+    macro_rules! assert_term_eq {
+        ($actual:expr, $expected:expr) => {
+            match (&$actual, &$expected) {
+                (actual, expected) => {
+                    if let Some(report) =
+                        $crate::base::terminal::test::FakeTerminal::diff(actual, expected)
+                    {
+                        panic!("assertion failed: terminals differ\n{}", report);
                     }
                 }
-                if r != raw_values.dim().0 - 1 {
-                    write!(f, "|")?;
+            }
+        };
+        ($actual:expr, $expected:expr, $($arg:tt)+) => {
+            match (&$actual, &$expected) {
+                (actual, expected) => {
+                    if let Some(report) =
+                        $crate::base::terminal::test::FakeTerminal::diff(actual, expected)
+                    {
+                        panic!(
+                            "assertion failed: terminals differ: {}\n{}",
+                            format_args!($($arg)+),
+                            report
+                        );
+                    }
                 }
             }
-            Ok(())
+        };
+    }
+
+    #[test]
+    fn test_diff() {
+        let expected = FakeTerminal::from_str((3, 2), "abc d*e*f").unwrap();
+        assert_eq!(expected.diff(&expected), None);
+
+        let mut actual = FakeTerminal::with_size((3, 2));
+        {
+            let mut win = actual.create_root_window();
+            let mut cursor = ::base::Cursor::new(&mut win);
+            cursor.write("abc");
+            cursor.wrap_line();
+            cursor.set_style_modifier(StyleModifier::new().fg_color(::base::Color::Red));
+            cursor.write("dex");
         }
+        assert_eq!(
+            actual.diff(&expected).unwrap(),
+            concat!(
+                "  expected | actual\n",
+                "  abc      | abc\n",
+                "> d*e*f    | dex\n",
+                "cell (x: 0, y: 1): fg Default vs Red\n",
+                "cell (x: 1, y: 1): fg Default vs Red, bold true vs false\n",
+                "cell (x: 2, y: 1): content \"f\" vs \"x\", fg Default vs Red\n",
+            )
+        );
+        assert!(FakeTerminal::with_size((1, 1))
+            .diff(&expected)
+            .unwrap()
+            .starts_with("size differs: expected 3x2, actual 1x1\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "terminals differ: row 1")]
+    fn test_assert_term_eq() {
+        assert_term_eq!(
+            FakeTerminal::with_size((1, 1)),
+            FakeTerminal::from_str((1, 1), "a").unwrap(),
+            "row {}",
+            1
+        );
+    }
+
+    /// Render row `y` of `term` as `present` would with the terminal in the default style.
+    #[cfg(test)]
+    fn render_row(term: &FakeTerminal, y: usize) -> Vec<u8> {
+        use base::Theme;
+        use ndarray::Axis;
+        let mut output = Vec::new();
+        super::render_line(
+            y,
+            term.values.storage().subview(Axis(0), y).iter(),
+            &Theme::new(),
+            &mut Style::default(),
+            &mut output,
+        );
+        output
+    }
+
+    #[test]
+    fn test_present_order() {
+        assert_eq!(super::present_order(vec![4, 0, 2], &(0..0)), vec![0, 2, 4]);
+        assert_eq!(
+            super::present_order(vec![0, 1, 2, 3, 4], &(2..4)),
+            vec![2, 3, 0, 1, 4]
+        );
+        assert_eq!(super::present_order(vec![0, 1], &(5..7)), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_render_line() {
+        use base::Theme;
+        use ndarray::Axis;
+        let term = FakeTerminal::from_str((2, 2), "xx a*b*").unwrap();
+        let mut style = Style::default();
+        let mut output = Vec::new();
+        super::render_line(
+            1,
+            term.values.storage().subview(Axis(0), 1).iter(),
+            &Theme::new(),
+            &mut style,
+            &mut output,
+        );
+        let reset = "\x1b[m\x1b[39m\x1b[49m";
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("\x1b[2;1H{}a{}\x1b[1mb", reset, reset)
+        );
+        assert!(style.format().bold);
+    }
+
+    #[test]
+    fn test_write_changed_lines_budget() {
+        use base::Theme;
+        let term = FakeTerminal::from_str((2, 3), "aa bb cc").unwrap();
+        let mut old = WindowBuffer::new(Width::new(2).unwrap(), Height::new(3).unwrap());
+        let mut unknown = vec![true; 3];
+        let line_len = render_row(&term, 0).len();
+
+        // The first (priority) line is written even though it exceeds the budget.
+        let mut output = Vec::new();
+        super::write_changed_lines(
+            &term.values,
+            &mut old,
+            &mut unknown,
+            &(1..2),
+            Some(1),
+            &Theme::new(),
+            &mut output,
+        );
+        assert_eq!(output, render_row(&term, 1));
+        assert_eq!(
+            super::changed_lines(&term.values, &old, &unknown),
+            vec![0, 2]
+        );
+
+        // Deferred lines are written by the next call, as far as the budget permits.
+        output.clear();
+        super::write_changed_lines(
+            &term.values,
+            &mut old,
+            &mut unknown,
+            &(0..0),
+            Some(2 * line_len - 1),
+            &Theme::new(),
+            &mut output,
+        );
+        assert_eq!(output, render_row(&term, 0));
+        assert_eq!(super::changed_lines(&term.values, &old, &unknown), vec![2]);
+
+        let mut unknown = vec![true; 3];
+        output.clear();
+        super::write_changed_lines(
+            &term.values,
+            &mut old,
+            &mut unknown,
+            &(0..0),
+            Some(2 * line_len),
+            &Theme::new(),
+            &mut output,
+        );
+        let mut expected = render_row(&term, 0);
+        expected.extend(render_row(&term, 1));
+        assert_eq!(output, expected);
+        assert_eq!(super::changed_lines(&term.values, &old, &unknown), vec![2]);
+
+        output.clear();
+        super::write_changed_lines(
+            &term.values,
+            &mut old,
+            &mut unknown,
+            &(0..0),
+            Some(2 * line_len),
+            &Theme::new(),
+            &mut output,
+        );
+        assert_eq!(output, render_row(&term, 2));
+        assert!(super::changed_lines(&term.values, &old, &unknown).is_empty());
+
+        // Nothing is written if nothing changed.
+        output.clear();
+        super::write_changed_lines(
+            &term.values,
+            &mut old,
+            &mut unknown,
+            &(0..0),
+            None,
+            &Theme::new(),
+            &mut output,
+        );
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(super::base64(b""), "");
+        assert_eq!(super::base64(b"f"), "Zg==");
+        assert_eq!(super::base64(b"fo"), "Zm8=");
+        assert_eq!(super::base64(b"foo"), "Zm9v");
+        assert_eq!(super::base64(b"foob\xff"), "Zm9vYv8=");
     }
 }
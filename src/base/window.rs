@@ -1,7 +1,6 @@
 //! Types associated with Windows, i.e., rectangular views into a terminal buffer.
 use super::{CursorTarget, GraphemeCluster, Style, StyleModifier};
 use base::basic_types::*;
-use base::cursor::{UNBOUNDED_HEIGHT, UNBOUNDED_WIDTH};
 use ndarray::{Array, ArrayViewMut, Axis, Ix, Ix2};
 use std::cmp::max;
 use std::fmt;
@@ -62,6 +61,11 @@ impl WindowBuffer {
     pub(in base) fn storage(&self) -> &CharMatrix {
         &self.storage
     }
+
+    /// Get the underlying CharMatrix storage for modification.
+    pub(in base) fn storage_mut(&mut self) -> &mut CharMatrix {
+        &mut self.storage
+    }
 }
 
 type CharMatrixView<'w> = ArrayViewMut<'w, StyledGraphemeCluster, Ix2>;
@@ -98,7 +102,7 @@ impl<'w> Window<'w> {
     }
 
     /// Get the extent of the window in the specified dimension (i.e., its width or height)
-    pub fn get_extent<D: AxisDimension>(&self) -> PositiveAxisDiff<D> {
+    pub fn get_extent<D: GridDimension>(&self) -> PositiveAxisDiff<D> {
         PositiveAxisDiff::new(D::get_dimension_value(self.values.dim()) as i32).unwrap()
     }
 
@@ -217,7 +221,7 @@ impl<'w> Window<'w> {
     ///     assert_eq!(w2.get_width(), Width::new(2).unwrap());
     /// }
     /// ```
-    pub fn split<D: AxisDimension>(self, split_pos: AxisIndex<D>) -> Result<(Self, Self), Self> {
+    pub fn split<D: GridDimension>(self, split_pos: AxisIndex<D>) -> Result<(Self, Self), Self> {
         if (self.get_extent() + PositiveAxisDiff::<D>::new(1).unwrap())
             .origin_range_contains(split_pos)
         {
@@ -255,20 +259,45 @@ impl<'w> Window<'w> {
     /// // Every row of wb now contains two '山', while the last column cotains spaces.
     /// ```
     pub fn fill(&mut self, c: GraphemeCluster) {
+        self.fill_styled(c, |_, _| StyleModifier::new());
+    }
+
+    /// Fill the window with the specified GraphemeCluster (like `fill`), but modify the default
+    /// style of each cell using the style returned by `style_at` for the position of the cell.
+    ///
+    /// `style_at` is called exactly once for each cell. See `base::pattern` for common styles,
+    /// e.g., gradients.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    /// use unsegen::base::terminal::test::FakeTerminal;
+    ///
+    /// let mut term = FakeTerminal::with_size((4, 2));
+    /// term.create_root_window().fill_styled(GraphemeCluster::try_from('x').unwrap(), |x, y| {
+    ///     StyleModifier::new().bold(x.raw_value() == y.raw_value())
+    /// });
+    /// term.assert_looks_like("*x*xxx|x*x*xx");
+    /// ```
+    pub fn fill_styled<F: FnMut(ColIndex, RowIndex) -> StyleModifier>(
+        &mut self,
+        c: GraphemeCluster,
+        mut style_at: F,
+    ) {
         let cluster_width = c.width();
-        let template = StyledGraphemeCluster::new(c, self.default_style);
-        let empty = StyledGraphemeCluster::new(GraphemeCluster::empty(), self.default_style);
-        let space = StyledGraphemeCluster::new(GraphemeCluster::space(), self.default_style);
         let w: i32 = self.get_width().into();
         let right_border = (w - (w % cluster_width as i32)) as usize;
-        for ((_, x), cell) in self.values.indexed_iter_mut() {
-            if x >= right_border.into() {
-                *cell = space.clone();
+        let default_style = self.default_style;
+        for ((y, x), cell) in self.values.indexed_iter_mut() {
+            let cluster = if x >= right_border {
+                GraphemeCluster::space()
             } else if x % cluster_width == 0 {
-                *cell = template.clone();
+                c.clone()
             } else {
-                *cell = empty.clone();
-            }
+                GraphemeCluster::empty()
+            };
+            let style = style_at(ColIndex::new(x as i32), RowIndex::new(y as i32));
+            *cell = StyledGraphemeCluster::new(cluster, style.apply(default_style));
         }
     }
 
@@ -287,6 +316,101 @@ impl<'w> Window<'w> {
         self.fill(GraphemeCluster::space());
     }
 
+    /// Fill the window with space characters, but modify the default style of each cell using the
+    /// style returned by `style_at` (see `fill_styled`).
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    /// let mut wb = WindowBuffer::new(Width::new(5).unwrap(), Height::new(5).unwrap());
+    /// let mut win = wb.as_window();
+    /// let height = win.get_height();
+    /// win.clear_styled(pattern::vertical_gradient((0, 0, 0), (0, 0, 255), height));
+    /// // wb now has a background that gets bluer from top to bottom.
+    /// ```
+    pub fn clear_styled<F: FnMut(ColIndex, RowIndex) -> StyleModifier>(&mut self, style_at: F) {
+        self.fill_styled(GraphemeCluster::space(), style_at);
+    }
+
+    /// Move the content of the window up by `n` rows. The top `n` rows are discarded and the
+    /// exposed rows at the bottom are cleared using the default style modified by `fill_style`.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    /// use unsegen::base::terminal::test::FakeTerminal;
+    ///
+    /// let mut term = FakeTerminal::from_str((2, 3), "a b c d e f").unwrap();
+    /// term.create_root_window().scroll_content_up(1, StyleModifier::new());
+    /// term.assert_looks_like("cd|ef|  ");
+    /// ```
+    pub fn scroll_content_up(&mut self, n: usize, fill_style: StyleModifier) {
+        self.shift_rows(n, true, fill_style);
+    }
+
+    /// Move the content of the window down by `n` rows. The bottom `n` rows are discarded and the
+    /// exposed rows at the top are cleared using the default style modified by `fill_style`.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    /// use unsegen::base::terminal::test::FakeTerminal;
+    ///
+    /// let mut term = FakeTerminal::from_str((3, 3), "a b c d e f g h i").unwrap();
+    /// term.create_root_window()
+    ///     .create_subwindow(ColIndex::new(1).., ..)
+    ///     .scroll_content_down(1, StyleModifier::new().bold(true));
+    /// term.assert_looks_like("a* ** *|dbc|gef");
+    /// ```
+    pub fn scroll_content_down(&mut self, n: usize, fill_style: StyleModifier) {
+        self.shift_rows(n, false, fill_style);
+    }
+
+    fn shift_rows(&mut self, n: usize, up: bool, fill_style: StyleModifier) {
+        let height: usize = self.get_height().into();
+        let n = n.min(height);
+        if n == 0 {
+            return;
+        }
+        let width: usize = self.get_width().into();
+        if let Some(cells) = self.values.as_slice_mut() {
+            // Fast path: All rows are stored contiguously (e.g., for a root window).
+            if up {
+                cells.rotate_left(n * width);
+            } else {
+                cells.rotate_right(n * width);
+            }
+        } else {
+            for i in 0..height - n {
+                // Rows have to be moved in the order in which they are overwritten.
+                let (src, dst) = if up {
+                    (i + n, i)
+                } else {
+                    let dst = height - 1 - i;
+                    (dst - n, dst)
+                };
+                let (mut first, mut second) =
+                    self.values.view_mut().split_at(Axis(0), src.max(dst));
+                if up {
+                    first
+                        .subview_mut(Axis(0), dst)
+                        .assign(&second.subview(Axis(0), 0));
+                } else {
+                    second
+                        .subview_mut(Axis(0), 0)
+                        .assign(&first.subview(Axis(0), src));
+                }
+            }
+        }
+        let exposed = if up { height - n..height } else { 0..n };
+        let mut exposed = self.create_subwindow(
+            ..,
+            RowIndex::new(exposed.start as i32)..RowIndex::new(exposed.end as i32),
+        );
+        exposed.modify_default_style(fill_style);
+        exposed.clear();
+    }
+
     /// Specify the new default style of the window. This style will be applied to all grapheme
     /// clusters written to the window.
     ///
@@ -392,7 +516,7 @@ impl ExtentEstimationWindow {
 
     /// Create an ExtentEstimationWindow with an unbounded width
     pub fn unbounded() -> Self {
-        Self::with_width(Width::new(UNBOUNDED_WIDTH).unwrap())
+        Self::with_width(Width::UNBOUNDED)
     }
 
     /// Get the width of the window required to display the contents written to the window.
@@ -416,7 +540,7 @@ impl CursorTarget for ExtentEstimationWindow {
         self.width
     }
     fn get_height(&self) -> Height {
-        Height::new(UNBOUNDED_HEIGHT).unwrap()
+        Height::UNBOUNDED
     }
     fn get_cell_mut(&mut self, x: ColIndex, y: RowIndex) -> Option<&mut StyledGraphemeCluster> {
         self.extent_x = max(self.extent_x, (x.diff_to_origin() + 1).positive_or_zero());
@@ -1,5 +1,5 @@
 //! Utility functions for unicode box characters
-use base::GraphemeCluster;
+use base::{rendering_profile, GraphemeCluster, RenderingProfile};
 
 /// Components of unicode box characters. A single character can contain up to 4 segments.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -54,10 +54,30 @@ impl LineCell {
         LineCell { components: 0 }
     }
 
-    /// Convert the cell to a grapheme cluster (always safe).
+    /// Convert the cell to a grapheme cluster (always safe) for the current `RenderingProfile`.
     pub fn to_grapheme_cluster(self) -> GraphemeCluster {
-        GraphemeCluster::try_from(CELL_TO_CHAR[self.components as usize])
-            .expect("CELL_TO_CHAR elements are single clusters")
+        self.to_grapheme_cluster_for(rendering_profile())
+    }
+
+    /// Convert the cell to a grapheme cluster (always safe). For `RenderingProfile::Ascii`, cells
+    /// with horizontal segments only are drawn as `-`, cells with vertical segments only as `|`
+    /// and all other (non-empty) cells as `+`.
+    pub fn to_grapheme_cluster_for(self, profile: RenderingProfile) -> GraphemeCluster {
+        let c = match profile {
+            RenderingProfile::Unicode => CELL_TO_CHAR[self.components as usize],
+            RenderingProfile::Ascii => {
+                let has = |segment: LineSegment| self.components & (segment.to_u8() * 0b11) != 0;
+                let horizontal = has(LineSegment::Left) || has(LineSegment::Right);
+                let vertical = has(LineSegment::Up) || has(LineSegment::Down);
+                match (horizontal, vertical) {
+                    (false, false) => ' ',
+                    (true, false) => '-',
+                    (false, true) => '|',
+                    (true, true) => '+',
+                }
+            }
+        };
+        GraphemeCluster::try_from(c).expect("box drawing characters are single clusters")
     }
 
     /// Set one of the four segments of the cell to the specified type.
@@ -137,3 +157,26 @@ const CELL_TO_CHAR: [char; 256] = [
     '╳', '╳', '╳', '╳',
     '╳', '╳', '╳', '╳',
 ];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_line_cell_ascii() {
+        let ascii = |cell: LineCell| cell.to_grapheme_cluster_for(RenderingProfile::Ascii);
+        let mut cell = LineCell::empty();
+        assert_eq!(ascii(cell).as_str(), " ");
+        cell.set(LineSegment::Left, LineType::Thick);
+        assert_eq!(ascii(cell).as_str(), "-");
+        cell.set(LineSegment::Down, LineType::Thin);
+        assert_eq!(ascii(cell).as_str(), "+");
+        cell.set(LineSegment::Left, LineType::None);
+        assert_eq!(ascii(cell).as_str(), "|");
+        assert_eq!(
+            cell.to_grapheme_cluster_for(RenderingProfile::Unicode)
+                .as_str(),
+            "╷"
+        );
+    }
+}
@@ -0,0 +1,451 @@
+//! A container that runs a command (e.g., a build or a test suite) and displays its output
+//! together with its status.
+//!
+//! # Example:
+//! ```no_run
+//! use unsegen::base::{RedrawScheduler, StyleModifier};
+//! use unsegen::container::CommandPane;
+//! use unsegen::input::Key;
+//! use std::process::Command;
+//!
+//! let scheduler = RedrawScheduler::with_max_fps(30);
+//! let mut command = Command::new("cargo");
+//! command.arg("build");
+//! let mut pane = CommandPane::new(command)
+//!     .restart_on(Key::F(5))
+//!     .notify(scheduler.dirty_flag());
+//! pane.start();
+//!
+//! loop {
+//!     pane.poll();
+//!     // Pass input to and draw the pane (e.g., using a ContainerManager) ...
+//! #   break;
+//! }
+//! ```
+use super::{Container, Events};
+use base::{Color, DirtyFlag, StyleModifier, Window};
+use input::{Event, Input, Key, OperationResult, ScrollBehavior, ToEvent};
+use std::fmt::Write as FmtWrite;
+use std::io::{BufRead, BufReader, Read};
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use widget::builtin::{LogViewer, TruncatedLabel};
+use widget::{text_width, Demand, Demand2D, RenderingHints, VLayout, Widget};
+
+/// The state of the command of a `CommandPane`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandStatus {
+    /// The command has not been started yet.
+    NotStarted,
+    /// The command is running.
+    Running,
+    /// The command exited with the given exit code.
+    Exited(i32),
+    /// The command was terminated by the given signal (e.g., using `CommandPane::kill`).
+    Killed(i32),
+    /// The command could not be started, e.g., because the program does not exist.
+    Failed(String),
+}
+
+impl ::std::fmt::Display for CommandStatus {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            CommandStatus::NotStarted => write!(f, "not started"),
+            CommandStatus::Running => write!(f, "running"),
+            CommandStatus::Exited(code) => write!(f, "exit {}", code),
+            CommandStatus::Killed(signal) => write!(f, "killed (signal {})", signal),
+            CommandStatus::Failed(ref msg) => write!(f, "failed: {}", msg),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Runs a command and displays its output (stdout and stderr, the latter with a different style)
+/// in a `LogViewer` below a header line showing the status of the command, the elapsed time and
+/// the command line.
+///
+/// The output of the command is read on background threads. Call `poll` regularly (e.g., in each
+/// iteration of the main loop) to transfer the output into the `LogViewer` and to check whether
+/// the command has exited. Since stdout and stderr are read separately, the order of lines of
+/// different streams may not be preserved exactly.
+///
+/// As a `Container`, the pane restarts the command (default: 'r') or kills it (default: ctrl-c)
+/// and scrolls the output using the arrow keys, home and end.
+///
+/// The command is killed when the pane is dropped.
+pub struct CommandPane {
+    command: Command,
+    child: Option<Child>,
+    output: Option<Receiver<(Stream, String)>>,
+    status: CommandStatus,
+    started: Option<Instant>,
+    finished: Option<Duration>,
+    log: LogViewer,
+    stderr_style: StyleModifier,
+    restart_on: Vec<Event>,
+    kill_on: Vec<Event>,
+    dirty: Option<DirtyFlag>,
+}
+
+impl CommandPane {
+    /// Create a pane for `command`, which is not started until `start` is called. The standard
+    /// streams of the command are replaced: stdin is closed and stdout and stderr are captured.
+    pub fn new(mut command: Command) -> Self {
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        CommandPane {
+            command,
+            child: None,
+            output: None,
+            status: CommandStatus::NotStarted,
+            started: None,
+            finished: None,
+            log: LogViewer::new(),
+            stderr_style: StyleModifier::new().fg_color(Color::Red),
+            restart_on: vec![Key::Char('r').to_event()],
+            kill_on: vec![Key::Ctrl('c').to_event()],
+            dirty: None,
+        }
+    }
+
+    /// Define the style of lines written to stderr (default: red).
+    pub fn stderr_style(mut self, style: StyleModifier) -> Self {
+        self.stderr_style = style;
+        self
+    }
+
+    /// Restart the command on the specified event (in addition to the default: 'r').
+    pub fn restart_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.restart_on.push(event.to_event());
+        self
+    }
+
+    /// Kill the command on the specified event (in addition to the default: ctrl-c).
+    pub fn kill_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.kill_on.push(event.to_event());
+        self
+    }
+
+    /// Mark the flag dirty whenever new output is available or the command exits.
+    pub fn notify(mut self, dirty: DirtyFlag) -> Self {
+        self.dirty = Some(dirty);
+        self
+    }
+
+    /// Start the command, killing the previous run if it is still running. The output of previous
+    /// runs is cleared.
+    pub fn start(&mut self) {
+        let _ = self.kill();
+        self.log.clear();
+        self.output = None;
+        self.started = Some(Instant::now());
+        self.finished = None;
+        let mut child = match self.command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                self.status = CommandStatus::Failed(e.to_string());
+                self.finished = Some(Duration::from_secs(0));
+                return;
+            }
+        };
+        let (sender, output) = channel();
+        if let Some(stdout) = child.stdout.take() {
+            self.read_in_background(stdout, Stream::Stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            self.read_in_background(stderr, Stream::Stderr, sender);
+        }
+        self.child = Some(child);
+        self.output = Some(output);
+        self.status = CommandStatus::Running;
+    }
+
+    fn read_in_background<R: Read + Send + 'static>(
+        &self,
+        stream: R,
+        kind: Stream,
+        sender: Sender<(Stream, String)>,
+    ) {
+        let dirty = self.dirty.clone();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stream);
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if line.last() == Some(&b'\n') {
+                            line.pop();
+                        }
+                        if line.last() == Some(&b'\r') {
+                            line.pop();
+                        }
+                        let text = String::from_utf8_lossy(&line).into_owned();
+                        if sender.send((kind, text)).is_err() {
+                            // The run has been replaced or the pane has been dropped.
+                            break;
+                        }
+                    }
+                }
+                if let Some(ref dirty) = dirty {
+                    dirty.mark_dirty();
+                }
+            }
+            // The stream is closed, so the command has (most likely) exited.
+            if let Some(ref dirty) = dirty {
+                dirty.mark_dirty();
+            }
+        });
+    }
+
+    /// Kill the command. Fails if it is not running.
+    pub fn kill(&mut self) -> OperationResult {
+        if self.status != CommandStatus::Running {
+            return Err(());
+        }
+        if let Some(ref mut child) = self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        self.poll();
+        Ok(())
+    }
+
+    /// Transfer the output that has been read since the last call into the `LogViewer` and check
+    /// whether the command has exited.
+    ///
+    /// Note that output may still arrive after the command has exited, e.g., if it has been
+    /// buffered or a child process of the command is still running.
+    pub fn poll(&mut self) {
+        if let Some(ref output) = self.output {
+            for (stream, text) in output.try_iter() {
+                self.log.set_line_style(match stream {
+                    Stream::Stdout => StyleModifier::new(),
+                    Stream::Stderr => self.stderr_style,
+                });
+                writeln!(self.log, "{}", text).expect("writing to LogViewer never fails");
+            }
+        }
+        let exit_status = match self.child {
+            Some(ref mut child) => child.try_wait().ok().and_then(|s| s),
+            None => None,
+        };
+        if let Some(exit_status) = exit_status {
+            self.child = None;
+            self.finished = Some(self.elapsed());
+            self.status = match (exit_status.code(), exit_status.signal()) {
+                (Some(code), _) => CommandStatus::Exited(code),
+                (None, Some(signal)) => CommandStatus::Killed(signal),
+                (None, None) => CommandStatus::Exited(-1),
+            };
+        }
+    }
+
+    /// Get the current status of the command (as of the last call to `poll`).
+    pub fn status(&self) -> &CommandStatus {
+        &self.status
+    }
+
+    /// The time since the command has been started or its total run time if it has finished.
+    pub fn elapsed(&self) -> Duration {
+        match (self.finished, self.started) {
+            (Some(duration), _) => duration,
+            (None, Some(start)) => start.elapsed(),
+            (None, None) => Duration::from_secs(0),
+        }
+    }
+
+    /// Get the `LogViewer` that displays the output of the command.
+    pub fn log(&self) -> &LogViewer {
+        &self.log
+    }
+
+    /// Get the `LogViewer` that displays the output of the command, e.g., to search or scroll.
+    pub fn log_mut(&mut self) -> &mut LogViewer {
+        &mut self.log
+    }
+
+    /// The command line, e.g., for display in the header.
+    fn command_line(&self) -> String {
+        let mut line = self.command.get_program().to_string_lossy().into_owned();
+        for arg in self.command.get_args() {
+            line.push(' ');
+            line.push_str(&arg.to_string_lossy());
+        }
+        line
+    }
+}
+
+impl Drop for CommandPane {
+    fn drop(&mut self) {
+        if let Some(ref mut child) = self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Format a duration like "4.2s" or "3m07s".
+fn format_elapsed(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{}.{}s", secs, duration.subsec_millis() / 100)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+impl<C: ?Sized> Container<C> for CommandPane {
+    fn input(&mut self, input: Input, _: &mut C) -> Option<Input> {
+        if self.restart_on.contains(&input.event) {
+            self.start();
+            None
+        } else if self.kill_on.contains(&input.event) && self.kill().is_ok() {
+            None
+        } else {
+            input
+                .chain(
+                    ScrollBehavior::new(&mut self.log)
+                        .backwards_on(Key::Up)
+                        .forwards_on(Key::Down)
+                        .to_beginning_on(Key::Home)
+                        .to_end_on(Key::End),
+                )
+                .finish()
+        }
+    }
+    fn as_widget<'a>(&'a self) -> Box<dyn Widget + 'a> {
+        Box::new(
+            VLayout::new()
+                .widget(CommandHeader {
+                    text: format!(
+                        "[{}] {} {}",
+                        self.status,
+                        format_elapsed(self.elapsed()),
+                        self.command_line()
+                    ),
+                })
+                .widget(self.log.as_widget()),
+        )
+    }
+    fn update(&mut self, _events: &Events, _context: &mut C) {
+        self.poll();
+    }
+}
+
+/// The header line of a `CommandPane`, which is shortened at the end (i.e., the command line is
+/// shortened first) if space is insufficient.
+struct CommandHeader {
+    text: String,
+}
+
+impl Widget for CommandHeader {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::at_least(text_width(&self.text)),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, window: Window, hints: RenderingHints) {
+        TruncatedLabel::new(&self.text).draw(window, hints);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+    use input::Scrollable;
+
+    fn shell(script: &str) -> CommandPane {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        CommandPane::new(command)
+    }
+
+    fn wait_for_lines(pane: &mut CommandPane, n: usize) {
+        let start = Instant::now();
+        while pane.log().scroll_position() < Some(n) && start.elapsed() < Duration::from_secs(5) {
+            pane.poll();
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn wait_until_finished(pane: &mut CommandPane) {
+        let start = Instant::now();
+        while *pane.status() == CommandStatus::Running && start.elapsed() < Duration::from_secs(5) {
+            pane.poll();
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_command_pane_output_and_status() {
+        let mut pane = shell("echo err >&2; exit 3").stderr_style(StyleModifier::new().bold(true));
+        assert_eq!(*pane.status(), CommandStatus::NotStarted);
+        pane.start();
+        wait_until_finished(&mut pane);
+        assert_eq!(*pane.status(), CommandStatus::Exited(3));
+        wait_for_lines(&mut pane, 1);
+
+        let mut term = FakeTerminal::with_size((3, 2));
+        pane.log()
+            .as_widget()
+            .draw(term.create_root_window(), RenderingHints::new());
+        term.assert_looks_like("*e**r**r*|   ");
+    }
+
+    #[test]
+    fn test_command_pane_kill_and_restart() {
+        let mut pane = shell("echo started; exec sleep 10");
+        let restart = Input::new(Key::Char('r'));
+        assert!(Container::<()>::input(&mut pane, restart, &mut ()).is_none());
+        assert_eq!(*pane.status(), CommandStatus::Running);
+
+        wait_for_lines(&mut pane, 1);
+        let kill = || Input::new(Key::Ctrl('c'));
+        assert!(Container::<()>::input(&mut pane, kill(), &mut ()).is_none());
+        assert_eq!(*pane.status(), CommandStatus::Killed(9));
+        assert!(Container::<()>::input(&mut pane, kill(), &mut ()).is_some());
+
+        // Killed before `sleep` finished, so the elapsed time has a single digit.
+        let elapsed = format_elapsed(pane.elapsed());
+        let mut term = FakeTerminal::with_size((27, 3));
+        Container::<()>::as_widget(&pane).draw(term.create_root_window(), RenderingHints::new());
+        term.assert_looks_like(&format!(
+            "[killed (signal 9)] {} s…|started{:20}|{:27}",
+            elapsed, "", ""
+        ));
+    }
+
+    #[test]
+    fn test_command_pane_failed_start() {
+        let mut pane = CommandPane::new(Command::new("/nonexistent/command"));
+        pane.start();
+        match *pane.status() {
+            CommandStatus::Failed(_) => {}
+            ref other => panic!("unexpected status: {:?}", other),
+        }
+        assert!(pane.kill().is_err());
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(4250)), "4.2s");
+        assert_eq!(format_elapsed(Duration::from_secs(187)), "3m07s");
+        assert_eq!(format_elapsed(Duration::from_secs(7320)), "2h02m");
+    }
+}
@@ -0,0 +1,220 @@
+//! Drag and drop of typed data between containers.
+//!
+//! A container starts a drag (usually when receiving a mouse press) using a `DragAndDrop` handle
+//! obtained from `ContainerManager::drag_and_drop`. While the drag is in progress,
+//! `ActiveContainerBehavior` routes mouse movement to the `ContainerManager`, which draws the
+//! label of the payload next to the mouse pointer. When the mouse button is released, the
+//! payload is passed to the `DropTarget` of the container under the pointer (see
+//! `Container::drop_target`). Pressing escape cancels the drag.
+//!
+//! # Example:
+//! ```
+//! use unsegen::base::terminal::test::FakeTerminal;
+//! use unsegen::base::{ColIndex, RowIndex, StyleModifier};
+//! use unsegen::container::*;
+//! use unsegen::input::*;
+//! use unsegen::widget::*;
+//! use std::path::PathBuf;
+//!
+//! struct Files(DragAndDrop);
+//! impl Container<()> for Files {
+//!     fn input(&mut self, input: Input, _: &mut ()) -> Option<Input> {
+//!         if let Event::Mouse(MouseEvent::Press(MouseButton::Left, _, _)) = input.event {
+//!             self.0.start(DragPayload::new(PathBuf::from("/tmp/a.txt"), "a.txt"));
+//!             None
+//!         } else {
+//!             Some(input)
+//!         }
+//!     }
+//!     fn as_widget<'a>(&'a self) -> Box<dyn Widget + 'a> {
+//!         Box::new("files")
+//!     }
+//! }
+//!
+//! struct Trash(Vec<PathBuf>);
+//! impl Container<()> for Trash {
+//!     fn input(&mut self, input: Input, _: &mut ()) -> Option<Input> {
+//!         Some(input)
+//!     }
+//!     fn as_widget<'a>(&'a self) -> Box<dyn Widget + 'a> {
+//!         Box::new("trash")
+//!     }
+//!     fn drop_target(&mut self) -> Option<&mut dyn DropTarget<()>> {
+//!         Some(self)
+//!     }
+//! }
+//! impl DropTarget<()> for Trash {
+//!     fn on_drop(&mut self, payload: &DragPayload, _: ColIndex, _: RowIndex, _: &mut ()) -> bool {
+//!         match payload.get::<PathBuf>() {
+//!             Some(path) => {
+//!                 self.0.push(path.clone());
+//!                 true
+//!             }
+//!             None => false,
+//!         }
+//!     }
+//! }
+//!
+//! struct App(Files, Trash);
+//! impl ContainerProvider for App {
+//!     type Context = ();
+//!     type Index = bool;
+//!     fn get<'a, 'b: 'a>(&'b self, index: &'a bool) -> &'b dyn Container<()> {
+//!         if *index { &self.0 } else { &self.1 }
+//!     }
+//!     fn get_mut<'a, 'b: 'a>(&'b mut self, index: &'a bool) -> &'b mut dyn Container<()> {
+//!         if *index { &mut self.0 } else { &mut self.1 }
+//!     }
+//!     const DEFAULT_CONTAINER: bool = true;
+//! }
+//!
+//! let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+//!     (Box::new(Leaf::new(true)), 0.5),
+//!     (Box::new(Leaf::new(false)), 0.5),
+//! ])));
+//! let mut app = App(Files(manager.drag_and_drop()), Trash(Vec::new()));
+//! let mut draw = |manager: &ContainerManager<App>, app: &mut App| {
+//!     let mut term = FakeTerminal::with_size((12, 2));
+//!     manager.draw(term.create_root_window(), app, StyleModifier::new(), RenderingHints::new());
+//!     term
+//! };
+//! draw(&manager, &mut app);
+//!
+//! let mut mouse = |manager: &mut ContainerManager<App>, app: &mut App, event| {
+//!     let input = Input::new(event);
+//!     input.chain(manager.active_container_behavior(app, &mut ())).finish()
+//! };
+//! assert!(mouse(&mut manager, &mut app, MouseEvent::Press(MouseButton::Left, 1, 1)).is_none());
+//! assert!(mouse(&mut manager, &mut app, MouseEvent::Hold(3, 2)).is_none());
+//! draw(&manager, &mut app).assert_looks_like("files┃trash│|  a.txt    │");
+//! assert!(mouse(&mut manager, &mut app, MouseEvent::Release(10, 2)).is_none());
+//! assert_eq!(app.1 .0, vec![PathBuf::from("/tmp/a.txt")]);
+//! assert!(!manager.drag_and_drop().is_dragging());
+//! ```
+use base::basic_types::*;
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// Data of any type that is carried by a drag, together with a label that is displayed next to
+/// the mouse pointer.
+pub struct DragPayload {
+    data: Box<dyn Any>,
+    label: String,
+}
+
+impl DragPayload {
+    /// Create a payload carrying `data`.
+    pub fn new<T: Any, S: Into<String>>(data: T, label: S) -> Self {
+        DragPayload {
+            data: Box::new(data),
+            label: label.into(),
+        }
+    }
+
+    /// Get the label of the payload.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Get the data, if it is of type `T`.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.data.downcast_ref::<T>()
+    }
+}
+
+impl fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DragPayload")
+            .field("label", &self.label)
+            .finish()
+    }
+}
+
+/// A container that can receive the payload of a drag, see `Container::drop_target`.
+pub trait DropTarget<C: ?Sized> {
+    /// Receive the payload of a drag that was released at (`x`, `y`) (relative to the area of
+    /// the container). Return whether the payload was accepted.
+    fn on_drop(&mut self, payload: &DragPayload, x: ColIndex, y: RowIndex, context: &mut C)
+        -> bool;
+}
+
+#[derive(Debug)]
+pub(super) struct Drag {
+    pub(super) payload: DragPayload,
+    position: Option<(ColIndex, RowIndex)>,
+}
+
+/// A handle to start (or cancel) a drag. Obtain it using `ContainerManager::drag_and_drop` and
+/// keep a clone in the containers that can start drags.
+#[derive(Clone, Debug)]
+pub struct DragAndDrop(Rc<RefCell<Option<Drag>>>);
+
+impl DragAndDrop {
+    pub(super) fn new() -> Self {
+        DragAndDrop(Rc::new(RefCell::new(None)))
+    }
+
+    /// Start dragging `payload`. Replaces any previous drag. The label of the payload is drawn as
+    /// soon as the mouse is moved.
+    pub fn start(&self, payload: DragPayload) {
+        *self.0.borrow_mut() = Some(Drag {
+            payload,
+            position: None,
+        });
+    }
+
+    /// Cancel the current drag (if any).
+    pub fn cancel(&self) {
+        *self.0.borrow_mut() = None;
+    }
+
+    /// Check whether a drag is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+
+    pub(super) fn move_to(&self, x: ColIndex, y: RowIndex) {
+        if let Some(ref mut drag) = *self.0.borrow_mut() {
+            drag.position = Some((x, y));
+        }
+    }
+
+    pub(super) fn take(&self) -> Option<Drag> {
+        self.0.borrow_mut().take()
+    }
+
+    pub(super) fn indicator(&self) -> Option<(ColIndex, RowIndex, String)> {
+        let drag = self.0.borrow();
+        let drag = drag.as_ref()?;
+        let (x, y) = drag.position?;
+        Some((x, y, drag.payload.label.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_drag_and_drop_handle() {
+        let dnd = DragAndDrop::new();
+        let clone = dnd.clone();
+        clone.start(DragPayload::new(42u32, "answer"));
+        assert!(dnd.is_dragging());
+        assert_eq!(dnd.indicator(), None);
+        dnd.move_to(ColIndex::new(1), RowIndex::new(2));
+        assert_eq!(
+            dnd.indicator(),
+            Some((ColIndex::new(1), RowIndex::new(2), "answer".to_owned()))
+        );
+        let drag = dnd.take().unwrap();
+        assert_eq!(drag.payload.get::<u32>(), Some(&42));
+        assert_eq!(drag.payload.get::<i32>(), None);
+        assert!(!clone.is_dragging());
+        clone.start(DragPayload::new((), ""));
+        clone.cancel();
+        assert!(!dnd.is_dragging());
+    }
+}
@@ -0,0 +1,77 @@
+//! A lightweight message bus for communication between containers.
+//!
+//! Containers post typed application events to an `EventBus` (which is usually part of the
+//! `ContainerProvider::Context`, so that it is available in `Container::input`). Before the next
+//! draw, the pending events are taken from the bus and passed to all containers of a
+//! `ContainerManager` using `ContainerManager::dispatch_events`, which calls `Container::update`.
+//!
+//! # Example:
+//! ```
+//! use unsegen::container::{EventBus, Events};
+//!
+//! struct SelectionChanged(usize);
+//!
+//! let mut bus = EventBus::new();
+//! bus.post(SelectionChanged(3));
+//! bus.post("some other event");
+//!
+//! let events: Events = bus.take();
+//! assert!(bus.is_empty());
+//! assert_eq!(events.len(), 2);
+//! assert_eq!(events.of::<SelectionChanged>().map(|e| e.0).collect::<Vec<_>>(), vec![3]);
+//! assert_eq!(events.of::<&str>().count(), 1);
+//! ```
+use std::any::Any;
+
+/// Collects events posted by containers until they are taken for dispatching.
+#[derive(Default)]
+pub struct EventBus {
+    pending: Vec<Box<dyn Any>>,
+}
+
+impl EventBus {
+    /// Create an empty bus.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Post an event of any type.
+    pub fn post<E: Any>(&mut self, event: E) {
+        self.pending.push(Box::new(event));
+    }
+
+    /// Check whether there are no pending events.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Take all pending events (in the order they were posted).
+    pub fn take(&mut self) -> Events {
+        Events {
+            events: ::std::mem::take(&mut self.pending),
+        }
+    }
+}
+
+/// A batch of events that have been taken from an `EventBus`.
+#[derive(Default)]
+pub struct Events {
+    events: Vec<Box<dyn Any>>,
+}
+
+impl Events {
+    /// Iterate over all events of type `E` (in the order they were posted).
+    pub fn of<E: Any>(&self) -> impl Iterator<Item = &E> {
+        self.events.iter().filter_map(|e| e.downcast_ref::<E>())
+    }
+
+    /// Get the number of events (of all types).
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Check whether there are no events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
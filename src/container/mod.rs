@@ -111,19 +111,41 @@
 //! }
 //! ```
 pub mod boxdrawing;
+pub mod command;
+pub mod dragdrop;
+pub mod events;
+pub mod overlay;
+pub mod tree;
+pub mod wizard;
+
+pub use self::command::*;
+pub use self::dragdrop::*;
+pub use self::events::*;
+pub use self::overlay::*;
+pub use self::tree::*;
+pub use self::wizard::*;
 
 use self::boxdrawing::{LineCell, LineSegment, LineType};
 use base::basic_types::*;
-use base::{CursorTarget, StyleModifier, Window};
-use input::{Behavior, Input, Navigatable, OperationResult};
-use std::cell::Cell;
+use base::{
+    rendering_profile, BoolModifyMode, Cursor, CursorTarget, GraphemeCluster, Rect, StyleModifier,
+    Window,
+};
+use input::{
+    Behavior, Event, Input, Key, MouseButton, MouseEvent, Navigatable, OperationResult, ToEvent,
+};
+use std::cell::{Cell, RefCell};
 use std::cmp::{max, min};
 use std::collections::btree_map;
 use std::collections::BTreeMap;
 use std::convert::From;
 use std::ops::Range;
+use std::rc::Rc;
+use util::{Easing, Lerp};
 use widget::layouts::layout_linearly;
-use widget::{ColDemand, Demand2D, RenderingHints, RowDemand, Widget};
+use widget::{
+    ColDemand, Demand, Demand2D, JumpMode, JumpResult, RenderingHints, RowDemand, Widget,
+};
 
 /// Extension to the widget trait to enable passing input to (active) widgets.
 /// The parameter C (i.e., the context) can be used to manipulate global application state.
@@ -132,6 +154,24 @@ pub trait Container<C: ?Sized> {
     fn input(&mut self, input: Input, context: &mut C) -> Option<Input>;
     /// Prepare for drawing to a window.
     fn as_widget<'a>(&'a self) -> Box<dyn Widget + 'a>;
+    /// React to events posted by other containers (see `ContainerManager::dispatch_events`).
+    /// (Default: ignore all events.)
+    fn update(&mut self, _events: &Events, _context: &mut C) {}
+    /// Called by `ContainerManager::draw` when the container has become the active one (e.g.,
+    /// to show a cursor or resume timers). (Default: do nothing.)
+    fn on_focus_gained(&mut self) {}
+    /// Called by `ContainerManager::draw` when the container is no longer the active one.
+    /// (Default: do nothing.)
+    fn on_focus_lost(&mut self) {}
+    /// Called by `ContainerManager::draw` (before drawing the container) when the container is
+    /// drawn for the first time or in a different area than before (e.g., to resize a pty).
+    /// (Default: do nothing.)
+    fn on_resize(&mut self, _area: Rectangle) {}
+    /// Get the container as a target for drag and drop (see `dragdrop`), if it accepts drops.
+    /// (Default: `None`.)
+    fn drop_target(&mut self) -> Option<&mut dyn DropTarget<C>> {
+        None
+    }
 }
 
 /// A ContainerProvider stores the individual components (`Container`s) of an application and
@@ -166,63 +206,156 @@ where
 }
 
 /// Pass input on to the currently active container.
+///
+/// Containers that are registered using `ContainerManager::add_background_input` receive the
+/// specified events before or after the active container.
+///
+/// If an overlay is shown (see `ContainerManager::push_overlay`), all input is passed to the
+/// topmost overlay instead. Input that it does not consume is returned, i.e., it is not passed to
+/// any other container.
+///
+/// Otherwise, if a container has grabbed the input (see `InputGrab`), all input is passed to it
+/// instead and consumed, even if the container does not consume it itself.
+///
+/// While a drag is in progress (see `DragAndDrop`), mouse movement and release as well as the
+/// escape key are handled by the `ContainerManager` instead. The same applies to all input while
+/// jump mode is active (see `ContainerManager::start_jump_mode`).
 impl<'a, 'b, 'c, 'd: 'a, C: ContainerProvider + 'a + 'b> Behavior
     for ActiveContainerBehavior<'a, 'b, 'c, 'd, C>
 {
     fn input(self, i: Input) -> Option<Input> {
-        i.chain(|i| {
-            self.provider
-                .get_mut(&self.manager.active)
-                .input(i, self.context)
-        })
-        .finish()
+        if self.manager.drag.is_dragging() {
+            match i.event {
+                Event::Mouse(MouseEvent::Hold(x, y)) => {
+                    let (x, y) = mouse_position(x, y);
+                    self.manager.drag.move_to(x, y);
+                    return None;
+                }
+                Event::Mouse(MouseEvent::Release(x, y)) => {
+                    let (x, y) = mouse_position(x, y);
+                    self.manager.drop_at(self.provider, x, y, self.context);
+                    return None;
+                }
+                Event::Key(Key::Esc) => {
+                    self.manager.drag.cancel();
+                    return None;
+                }
+                _ => {}
+            }
+        }
+        if self.manager.jump.is_active() {
+            if let JumpResult::Selected(index) = self.manager.jump.input(&i) {
+                self.manager.set_active(index);
+            }
+            return None;
+        }
+        if let Some((overlay, _)) = self.manager.overlays.last() {
+            return self.provider.get_mut(overlay).input(i, self.context);
+        }
+        if let Some(holder) = self.manager.grab.holder() {
+            let _ = self.provider.get_mut(&holder).input(i, self.context);
+            return None;
+        }
+        let manager = self.manager;
+        let provider = self.provider;
+        let context = self.context;
+        let i = manager.background_input(BackgroundOrder::BeforeActive, i, provider, context)?;
+        let i = provider.get_mut(&manager.active).input(i, context)?;
+        manager.background_input(BackgroundOrder::AfterActive, i, provider, context)
     }
 }
 
-/// A simple rectangle with integer coordinates. Nothing to see here.
-#[derive(Clone, Debug, PartialEq)]
-#[allow(missing_docs)]
-pub struct Rectangle {
-    pub x_range: Range<ColIndex>,
-    pub y_range: Range<RowIndex>,
+/// A `Behavior` which interprets mouse input for a `ContainerManager` (see
+/// `ContainerManager::mouse_behavior`).
+///
+/// Pressing the left mouse button on a separator between two weighted children of a split (see
+/// `ResizeHandle`) starts resizing them, which follows the mouse while it is held. These events
+/// are consumed. Pressing the left button inside a container activates the container, but the
+/// event is not consumed, so that it can be passed on to the container (e.g., using
+/// `active_container_behavior`).
+///
+/// Positions refer to the areas of the last draw, so the window of the `ContainerManager` is
+/// assumed to start at the top left corner of the terminal. While an overlay is shown or the input
+/// is grabbed (see `InputGrab`), presses are passed on unchanged, i.e., neither the active
+/// container is changed nor a split is resized.
+pub struct MouseContainerBehavior<'a, 'b: 'a, C: ContainerProvider + 'a> {
+    manager: &'a mut ContainerManager<'b, C>,
 }
 
-impl Rectangle {
-    /// Calculate the total number of columns occupied by the rectangle.
-    pub fn width(&self) -> Width {
-        (self.x_range.end - self.x_range.start)
-            .try_into_positive()
-            .expect("range invariant")
-    }
-    /// Calculate the total number of rows occupied by the rectangle.
-    pub fn height(&self) -> Height {
-        (self.y_range.end - self.y_range.start)
-            .try_into_positive()
-            .expect("range invariant")
-    }
-
-    fn slice_range_x(&self, range: Range<ColIndex>) -> Rectangle {
-        debug_assert!(
-            self.x_range.start <= range.start && range.end <= self.x_range.end,
-            "Invalid slice argument"
-        );
-        Rectangle {
-            x_range: range,
-            y_range: self.y_range.clone(),
+impl<'a, 'b: 'a, C: ContainerProvider + 'a> Behavior for MouseContainerBehavior<'a, 'b, C> {
+    fn input(self, i: Input) -> Option<Input> {
+        let manager = self.manager;
+        match i.event {
+            Event::Mouse(MouseEvent::Press(MouseButton::Left, x, y)) => {
+                if !manager.overlays.is_empty() || manager.grab.is_grabbed() {
+                    return Some(i);
+                }
+                let (x, y) = mouse_position(x, y);
+                let handle = manager
+                    .drawn_resize_handles
+                    .borrow()
+                    .iter()
+                    .find(|handle| handle.area.contains(x, y))
+                    .cloned();
+                if let Some(handle) = handle {
+                    manager.resizing = Some((handle, x, y));
+                    return None;
+                }
+                let target = manager
+                    .drawn_areas
+                    .borrow()
+                    .iter()
+                    .find(|(_, rect)| rect.contains(x, y))
+                    .map(|(index, _)| index.clone());
+                if let Some(index) = target {
+                    manager.set_active(index);
+                }
+                Some(i)
+            }
+            Event::Mouse(MouseEvent::Hold(x, y)) if manager.resizing.is_some() => {
+                let (x, y) = mouse_position(x, y);
+                let (ref handle, start_x, start_y) = *manager.resizing.as_ref().unwrap();
+                let offset = match handle.direction {
+                    SplitDirection::Horizontal => (x - start_x).raw_value(),
+                    SplitDirection::Vertical => (y - start_y).raw_value(),
+                };
+                let _ = manager.layout.resize_split(
+                    &handle.path,
+                    handle.index,
+                    handle.dragged_weights(offset),
+                );
+                None
+            }
+            Event::Mouse(MouseEvent::Release(..)) if manager.resizing.is_some() => {
+                manager.resizing = None;
+                None
+            }
+            _ => Some(i),
         }
     }
+}
 
-    fn slice_range_y(&self, range: Range<RowIndex>) -> Rectangle {
-        debug_assert!(
-            self.y_range.start <= range.start && range.end <= self.y_range.end,
-            "Invalid slice argument"
-        );
-        Rectangle {
-            x_range: self.x_range.clone(),
-            y_range: range,
-        }
-    }
+/// Specifies when a container receives input in the background (see
+/// `ContainerManager::add_background_input`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackgroundOrder {
+    /// Before the active container, i.e., the events are never passed to the active container
+    /// if the background container consumes them.
+    BeforeActive,
+    /// After the active container, i.e., only if the active container does not consume the
+    /// events.
+    AfterActive,
+}
 
+/// Convert the (1-based) position of a mouse event to a cell position.
+fn mouse_position(x: u16, y: u16) -> (ColIndex, RowIndex) {
+    (ColIndex::new(x as i32 - 1), RowIndex::new(y as i32 - 1))
+}
+
+/// A rectangle of cells of the terminal grid (see `Rect`).
+pub type Rectangle = Rect;
+
+impl Rectangle {
     fn slice_line_x(&self, x: ColIndex) -> HorizontalLine {
         debug_assert!(
             self.x_range.start <= x && x <= self.x_range.end,
@@ -245,29 +378,19 @@ impl Rectangle {
         }
     }
 
+    /// Check whether a line segment at (x, y) pointing in direction `dir` belongs to the border
+    /// of the rectangle (and not to a line leading away from it).
     fn is_near_border(&self, x: ColIndex, y: RowIndex, dir: LineSegment) -> bool {
-        let x_l = self.x_range.start - 1;
-        let x_r = self.x_range.end;
-        let y_l = self.y_range.start - 1;
-        let y_r = self.y_range.end;
-
-        let left = x_l == x;
-        let right = x_r == x;
-        let up = y_l == y;
-        let down = y_r == y;
-        if right && dir == LineSegment::Right
-            || left && dir == LineSegment::Left
-            || up && dir == LineSegment::Up
-            || down && dir == LineSegment::Down
-        {
-            false
-        } else {
-            (right || left) && (y_l <= y && y <= y_r) || (up || down) && (x_l <= x && x <= x_r)
-        }
+        let leads_away = x == self.x_range.end && dir == LineSegment::Right
+            || x == self.x_range.start - 1 && dir == LineSegment::Left
+            || y == self.y_range.start - 1 && dir == LineSegment::Up
+            || y == self.y_range.end && dir == LineSegment::Down;
+        !leads_away && self.is_on_border(x, y)
     }
 }
 
 /// A single line occupying a number of cells in a row.
+#[derive(Clone, Debug, PartialEq)]
 #[allow(missing_docs)]
 pub struct HorizontalLine {
     pub x: ColIndex,
@@ -275,6 +398,7 @@ pub struct HorizontalLine {
 }
 
 /// A single line occupying a number of cells in a column.
+#[derive(Clone, Debug, PartialEq)]
 #[allow(missing_docs)]
 pub struct VerticalLine {
     pub x_range: Range<ColIndex>,
@@ -282,6 +406,7 @@ pub struct VerticalLine {
 }
 
 /// An axis aligned line, either vertical or horizontal.
+#[derive(Clone, Debug, PartialEq)]
 #[allow(missing_docs)]
 pub enum Line {
     Horizontal(HorizontalLine),
@@ -300,29 +425,413 @@ impl From<VerticalLine> for Line {
     }
 }
 
+/// Something that draws the content of a custom separator between two containers (see
+/// `SplitSeparator::Custom`).
+///
+/// This is implemented for all `Fn(Window, RenderingHints)`.
+pub trait SeparatorRenderer {
+    /// Draw the separator to the given window, which covers exactly the separator area.
+    ///
+    /// The default style of the window has already been modified with the border style of the
+    /// `ContainerManager`. `hints.active` is only set if the separator touches the currently
+    /// active container.
+    fn draw(&self, window: Window, hints: RenderingHints);
+}
+
+impl<F: Fn(Window, RenderingHints)> SeparatorRenderer for F {
+    fn draw(&self, window: Window, hints: RenderingHints) {
+        self(window, hints)
+    }
+}
+
+/// Specifies how neighboring children of a `HSplit` or `VSplit` are visually separated.
+#[derive(Clone)]
+pub enum SplitSeparator {
+    /// A single line of unicode box drawing characters that is merged with adjacent lines. This
+    /// is the default.
+    Line,
+    /// An area of the given thickness (in columns for `HSplit`, in rows for `VSplit`) that is
+    /// drawn by the given renderer. A thickness of 0 places the children right next to each
+    /// other.
+    Custom {
+        #[allow(missing_docs)]
+        thickness: usize,
+        #[allow(missing_docs)]
+        renderer: Rc<dyn SeparatorRenderer>,
+    },
+}
+
+impl SplitSeparator {
+    /// Create a custom separator of the given thickness that is drawn using `renderer`.
+    pub fn custom<R: SeparatorRenderer + 'static>(thickness: usize, renderer: R) -> Self {
+        SplitSeparator::Custom {
+            thickness,
+            renderer: Rc::new(renderer),
+        }
+    }
+
+    fn thickness(&self) -> usize {
+        match self {
+            SplitSeparator::Line => 1,
+            SplitSeparator::Custom { thickness, .. } => *thickness,
+        }
+    }
+
+    /// Create the separator occupying the given area (that must have the thickness of the
+    /// separator in the split dimension).
+    fn create(&self, area: Rectangle, line: Line) -> Separator {
+        match self {
+            SplitSeparator::Line => Separator::Line(line),
+            SplitSeparator::Custom { renderer, .. } => Separator::Custom(area, renderer.clone()),
+        }
+    }
+}
+
+impl std::fmt::Debug for SplitSeparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SplitSeparator::Line => write!(f, "Line"),
+            SplitSeparator::Custom { thickness, .. } => write!(f, "Custom({})", thickness),
+        }
+    }
+}
+
+/// A separator in the result of a layouting operation for containers.
+#[derive(Clone)]
+pub enum Separator {
+    /// A line that will be drawn using unicode box drawing characters.
+    Line(Line),
+    /// An area that is drawn by a custom renderer.
+    Custom(Rectangle, Rc<dyn SeparatorRenderer>),
+    /// The placeholder bar of a collapsed child of a split. Navigating onto it expands the child
+    /// again.
+    Collapsed(Rectangle, CollapseToggle),
+}
+
+/// The separator between two neighboring weighted children of a `HSplit` or `VSplit` in the
+/// result of a layouting operation.
+///
+/// Dragging it (see `ContainerManager::mouse_behavior`) changes the weights of both children such
+/// that their sizes change by the dragged distance (if their demands permit), while the sum of
+/// their weights stays the same.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResizeHandle {
+    area: Rectangle,
+    direction: SplitDirection,
+    path: Vec<usize>,
+    index: usize,
+    sizes: (usize, usize),
+    weights: (f64, f64),
+}
+
+impl ResizeHandle {
+    /// The area of the separator.
+    pub fn area(&self) -> &Rectangle {
+        &self.area
+    }
+
+    /// The direction of the split, i.e., `Horizontal` if the separator is dragged horizontally.
+    pub fn direction(&self) -> SplitDirection {
+        self.direction
+    }
+
+    /// Calculate the weights of both children after moving the separator by `offset` cells
+    /// (to the right or down). Both children keep at least one cell.
+    fn dragged_weights(&self, offset: i32) -> (f64, f64) {
+        let total = self.sizes.0 + self.sizes.1;
+        if total < 2 {
+            return self.weights;
+        }
+        let before = (self.sizes.0 as i64 + i64::from(offset)).clamp(1, total as i64 - 1);
+        let sum = self.weights.0 + self.weights.1;
+        let sum = if sum > 0.0 { sum } else { 2.0 };
+        let first = sum * before as f64 / total as f64;
+        (first, sum - first)
+    }
+}
+
+/// Determines how much space a child of a `HSplit` or `VSplit` receives.
+///
+/// All children that are not `Weighted` are assigned their space first, in order. The remaining
+/// space is then distributed among the `Weighted` children according to their weights and
+/// demands.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SplitSize {
+    /// Share the remaining space with the other weighted children. This is the default.
+    #[default]
+    Weighted,
+    /// Exactly the given number of cells.
+    Fixed(usize),
+    /// The given percentage (0 to 100) of the space available to all children.
+    Percentage(f64),
+    /// The space demanded by the child: Its maximum demand if bounded, its minimum demand
+    /// otherwise.
+    Auto,
+}
+
+/// A shared flag that specifies whether a child of a `HSplit` or `VSplit` is collapsed.
+///
+/// Collapsed children only occupy a single row (`VSplit`) or column (`HSplit`) which is filled
+/// with a placeholder bar. Keep a clone of the toggle to collapse or expand the child at runtime.
+/// Navigating onto the placeholder bar (using `ContainerManager::navigatable`) expands the child
+/// again.
+#[derive(Clone, Debug, Default)]
+pub struct CollapseToggle(Rc<Cell<bool>>);
+
+impl CollapseToggle {
+    /// Create a new toggle with the specified initial state.
+    pub fn new(collapsed: bool) -> Self {
+        CollapseToggle(Rc::new(Cell::new(collapsed)))
+    }
+
+    /// Check whether the child is currently collapsed.
+    pub fn is_collapsed(&self) -> bool {
+        self.0.get()
+    }
+
+    /// Collapse or expand the child.
+    pub fn set_collapsed(&self, collapsed: bool) {
+        self.0.set(collapsed)
+    }
+
+    /// Collapse the child if it is expanded and vice versa.
+    pub fn toggle(&self) {
+        self.set_collapsed(!self.is_collapsed())
+    }
+}
+
+/// A shared handle that allows a container to grab all input of a `ContainerManager`, e.g., for
+/// modal dialogs or menus.
+///
+/// While the input is grabbed, `ActiveContainerBehavior` passes all input to the grabbing
+/// container, navigation between containers is not possible and the grabbing container is drawn
+/// as the active one. Obtain the handle using `ContainerManager::input_grab` and keep a clone
+/// (e.g., in the container itself) to release the grab once the interaction is finished. The
+/// previously active container is active again afterwards.
+#[derive(Debug)]
+pub struct InputGrab<I>(Rc<RefCell<Option<I>>>);
+
+impl<I> Clone for InputGrab<I> {
+    fn clone(&self) -> Self {
+        InputGrab(self.0.clone())
+    }
+}
+
+impl<I: Clone> InputGrab<I> {
+    fn new() -> Self {
+        InputGrab(Rc::new(RefCell::new(None)))
+    }
+
+    /// Let the container identified by `index` grab all input. Replaces any previous grab.
+    pub fn grab(&self, index: I) {
+        *self.0.borrow_mut() = Some(index);
+    }
+
+    /// Release the grab (if any).
+    pub fn release(&self) {
+        *self.0.borrow_mut() = None;
+    }
+
+    /// Check whether any container currently grabs the input.
+    pub fn is_grabbed(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+
+    /// Get the index of the container that currently grabs the input.
+    pub fn holder(&self) -> Option<I> {
+        self.0.borrow().clone()
+    }
+}
+
+/// Configuration of a single child of a `HSplit` or `VSplit`.
+#[derive(Clone, Debug, Default)]
+struct SplitChildConfig {
+    size: SplitSize,
+    collapse: Option<CollapseToggle>,
+}
+
+impl SplitChildConfig {
+    fn is_collapsed(&self) -> bool {
+        self.collapse
+            .as_ref()
+            .map(|c| c.is_collapsed())
+            .unwrap_or(false)
+    }
+
+    /// Check whether the child can be resized by dragging a neighboring separator.
+    fn is_resizable(&self) -> bool {
+        self.size == SplitSize::Weighted && !self.is_collapsed()
+    }
+
+    /// The demand of the child in the split dimension, given its actual demand.
+    fn demand<T: AxisDimension + Ord>(&self, demand: Demand<T>) -> Demand<T> {
+        if self.is_collapsed() {
+            return Demand::exact(1);
+        }
+        match self.size {
+            SplitSize::Fixed(size) => Demand::exact(size),
+            SplitSize::Weighted | SplitSize::Percentage(_) | SplitSize::Auto => demand,
+        }
+    }
+}
+
+/// Compute the space for all children of a split, honoring their sizes and collapse state before
+/// distributing the remaining space according to the weights.
+fn layout_split<T: AxisDimension + Ord + std::fmt::Debug + Clone>(
+    available_space: PositiveAxisDiff<T>,
+    separator_length: PositiveAxisDiff<T>,
+    demands: &[Demand<T>],
+    weights: &[f64],
+    configs: &[SplitChildConfig],
+) -> Box<[PositiveAxisDiff<T>]> {
+    let num_separators = demands.len().saturating_sub(1);
+    let children_space =
+        available_space.saturating_sub(separator_length.saturating_mul(num_separators));
+
+    let mut remaining = children_space.raw_value();
+    let mut assigned = vec![None; demands.len()];
+    for (i, config) in configs.iter().enumerate() {
+        let size = if config.is_collapsed() {
+            1
+        } else {
+            match config.size {
+                SplitSize::Weighted => continue,
+                SplitSize::Fixed(size) => size as i32,
+                SplitSize::Percentage(percentage) => {
+                    let fraction = percentage.clamp(0.0, 100.0) / 100.0;
+                    (children_space.raw_value() as f64 * fraction) as i32
+                }
+                SplitSize::Auto => demands[i].max.unwrap_or(demands[i].min).raw_value(),
+            }
+        };
+        let size = size.min(remaining);
+        remaining -= size;
+        assigned[i] = Some(PositiveAxisDiff::new_unchecked(size));
+    }
+
+    let weighted = (0..demands.len())
+        .filter(|&i| assigned[i].is_none())
+        .collect::<Vec<_>>();
+    let weighted_spaces = layout_linearly(
+        PositiveAxisDiff::new_unchecked(remaining),
+        PositiveAxisDiff::new_unchecked(0),
+        &weighted.iter().map(|&i| demands[i]).collect::<Vec<_>>(),
+        &weighted.iter().map(|&i| weights[i]).collect::<Vec<_>>(),
+    );
+    for (&i, &space) in weighted.iter().zip(weighted_spaces.iter()) {
+        assigned[i] = Some(space);
+    }
+    assigned
+        .into_iter()
+        .map(|a| a.expect("all children assigned"))
+        .collect()
+}
+
+/// Create the resize handle for the separator (occupying `area`) after the child at `index`, if
+/// the children on both sides of it can be resized.
+fn split_resize_handle<T: AxisDimension>(
+    area: Rectangle,
+    direction: SplitDirection,
+    index: usize,
+    spaces: &[PositiveAxisDiff<T>],
+    weights: &RefCell<Vec<f64>>,
+    configs: &[SplitChildConfig],
+) -> Option<ResizeHandle> {
+    if index + 1 >= configs.len()
+        || !configs[index].is_resizable()
+        || !configs[index + 1].is_resizable()
+    {
+        return None;
+    }
+    let weights = weights.borrow();
+    Some(ResizeHandle {
+        area,
+        direction,
+        path: Vec::new(),
+        index,
+        sizes: (
+            spaces[index].raw_value() as usize,
+            spaces[index + 1].raw_value() as usize,
+        ),
+        weights: (weights[index], weights[index + 1]),
+    })
+}
+
+/// Resize a split with the given children (see `Layout::resize_split`).
+fn resize_split_children<C: ContainerProvider>(
+    elms: &[Box<dyn Layout<C> + '_>],
+    weights: &RefCell<Vec<f64>>,
+    path: &[usize],
+    index: usize,
+    new_weights: (f64, f64),
+) -> OperationResult {
+    if let Some((&child, path)) = path.split_first() {
+        return elms
+            .get(child)
+            .ok_or(())?
+            .resize_split(path, index, new_weights);
+    }
+    let mut weights = weights.borrow_mut();
+    if index + 1 >= weights.len() {
+        return Err(());
+    }
+    weights[index] = new_weights.0;
+    weights[index + 1] = new_weights.1;
+    Ok(())
+}
+
+impl<L: Into<Line>> From<L> for Separator {
+    fn from(l: L) -> Self {
+        Separator::Line(l.into())
+    }
+}
+
 /// A Layouter managing screen real estate for multiple containers
 pub trait Layout<C: ContainerProvider>: std::fmt::Debug {
     /// Calculate the space demand required for all of the provided containers
     fn space_demand(&self, containers: &C) -> Demand2D;
+    /// Calculate the demand for rows if the containers are layed out in an area of the given
+    /// width (see `Widget::height_for_width`). By default, the height of `space_demand` is
+    /// returned.
+    fn height_for_width(&self, width: Width, containers: &C) -> RowDemand {
+        let _ = width;
+        self.space_demand(containers).height
+    }
     /// Specify how the provided containers should be layed out in the provided area, and how they
-    /// should be separated by lines.
+    /// should be separated by lines or other separators.
     ///
-    /// Note that the implementor is strictly required to enforce that returned windows and lines
-    /// DO NOT INTERSECT!
+    /// Note that the implementor is strictly required to enforce that returned windows and
+    /// separators DO NOT INTERSECT!
     fn layout(&self, available_area: Rectangle, containers: &C) -> LayoutOutput<C::Index>;
+
+    /// Get the indices of all containers in the layout, including those that are currently not
+    /// visible (e.g., because they are collapsed). This is used to pass events to all containers
+    /// (see `ContainerManager::dispatch_events`).
+    fn containers(&self) -> Vec<C::Index>;
+
+    /// Change the weights of the children at `index` and `index + 1` of the split that is reached
+    /// by following `path` (the positions of children, starting at this layout), e.g., when a
+    /// `ResizeHandle` is dragged. Fails if there is no such split, which is the default.
+    fn resize_split(&self, path: &[usize], index: usize, weights: (f64, f64)) -> OperationResult {
+        let _ = (path, index, weights);
+        Err(())
+    }
 }
 
 /// The result of a layouting operation for containers.
 ///
-/// Required invariant: None of the windows or lines mutually intersect!
+/// Required invariant: None of the windows or separators mutually intersect!
 pub struct LayoutOutput<I: Clone> {
     /// A mapping from a container index to the screen area where the container will be drawn.
     pub windows: Vec<(I, Rectangle)>,
-    /// A number of lines not directly associated with containers.
+    /// A number of separators (lines or custom drawn areas) not directly associated with
+    /// containers.
     ///
     /// (However, it is probably a good idea to use these to visually separate individual
     /// containers.)
-    pub separators: Vec<Line>,
+    pub separators: Vec<Separator>,
+    /// The separators between children of splits that can be dragged to resize the children.
+    pub resize_handles: Vec<ResizeHandle>,
 }
 
 impl<I: Clone + PartialEq> LayoutOutput<I> {
@@ -331,10 +840,11 @@ impl<I: Clone + PartialEq> LayoutOutput<I> {
         LayoutOutput {
             windows: Vec::new(),
             separators: Vec::new(),
+            resize_handles: Vec::new(),
         }
     }
 
-    /// Add all windows and lines from the provided output to the current.
+    /// Add all windows and separators from the provided output to the current.
     fn add_child(&mut self, child: LayoutOutput<I>) {
         for (index, window) in child.windows {
             //self.windows.push((index, region.transform_to_outside_rectangle(window)));
@@ -344,6 +854,16 @@ impl<I: Clone + PartialEq> LayoutOutput<I> {
             //self.separators.push(region.transform_to_outside_line(separator));
             self.separators.push(separator);
         }
+        self.resize_handles.extend(child.resize_handles);
+    }
+
+    /// Add the output of the child at `position` of a split, whose resize handles are relative to
+    /// the child.
+    fn add_split_child(&mut self, position: usize, mut child: LayoutOutput<I>) {
+        for handle in &mut child.resize_handles {
+            handle.path.insert(0, position);
+        }
+        self.add_child(child);
     }
 
     /// Retrieve the rectangle for the provided index
@@ -355,6 +875,31 @@ impl<I: Clone + PartialEq> LayoutOutput<I> {
     }
 }
 
+/// Check (in debug builds) that the windows of a layout are inside the available area and do not
+/// overlap, so that no container can draw over another one.
+fn debug_assert_valid_layout<I: std::fmt::Debug>(area: &Rectangle, windows: &[(I, Rectangle)]) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    for (i, (index, rect)) in windows.iter().enumerate() {
+        assert!(
+            rect.is_empty() || area.contains_rect(rect),
+            "Layout placed container {:?} at {:?} outside of {:?}",
+            index,
+            rect,
+            area
+        );
+        for (other_index, other_rect) in &windows[i + 1..] {
+            assert!(
+                rect.intersection(other_rect).is_none(),
+                "Layout placed containers {:?} and {:?} on top of each other",
+                index,
+                other_index
+            );
+        }
+    }
+}
+
 /// A `Leaf` in a `Layout`-tree.
 ///
 /// It simply refers to a container by its index.
@@ -384,6 +929,12 @@ impl<C: ContainerProvider> Layout<C> for Leaf<C> {
             .as_widget()
             .space_demand()
     }
+    fn height_for_width(&self, width: Width, containers: &C) -> RowDemand {
+        containers
+            .get(&self.container_index)
+            .as_widget()
+            .height_for_width(width)
+    }
     fn layout(&self, available_area: Rectangle, _: &C) -> LayoutOutput<C::Index> {
         let mut output = LayoutOutput::new();
         output
@@ -391,12 +942,17 @@ impl<C: ContainerProvider> Layout<C> for Leaf<C> {
             .push((self.container_index.clone(), available_area));
         output
     }
+    fn containers(&self) -> Vec<C::Index> {
+        vec![self.container_index.clone()]
+    }
 }
 
 /// A `Layout` laying out all children horizontally, separated by vertical lines.
 pub struct HSplit<'a, C: ContainerProvider> {
     elms: Vec<Box<dyn Layout<C> + 'a>>,
-    weights: Vec<f64>,
+    weights: RefCell<Vec<f64>>,
+    configs: Vec<SplitChildConfig>,
+    separator: SplitSeparator,
 }
 
 impl<'a, C: ContainerProvider> HSplit<'a, C> {
@@ -407,20 +963,64 @@ impl<'a, C: ContainerProvider> HSplit<'a, C> {
     pub fn new(elms: Vec<(Box<dyn Layout<C> + 'a>, f64)>) -> Self {
         let mut res = HSplit {
             elms: Vec::new(),
-            weights: Vec::new(),
+            weights: RefCell::new(Vec::new()),
+            configs: Vec::new(),
+            separator: SplitSeparator::Line,
         };
         for (e, w) in elms {
             res.elms.push(e);
-            res.weights.push(w);
+            res.weights.get_mut().push(w);
+            res.configs.push(SplitChildConfig::default());
         }
         res
     }
+
+    /// Specify how much space the child at position `index` receives. (Default:
+    /// `SplitSize::Weighted`)
+    ///
+    /// Panics if there is no child at position `index`.
+    pub fn size(mut self, index: usize, size: SplitSize) -> Self {
+        self.configs[index].size = size;
+        self
+    }
+
+    /// Make the child at position `index` collapsible using the given toggle.
+    ///
+    /// Panics if there is no child at position `index`.
+    pub fn collapsible(mut self, index: usize, toggle: CollapseToggle) -> Self {
+        self.configs[index].collapse = Some(toggle);
+        self
+    }
+
+    /// Specify how neighboring children are separated. (Default: `SplitSeparator::Line`)
+    pub fn separator(mut self, separator: SplitSeparator) -> Self {
+        self.separator = separator;
+        self
+    }
+}
+
+impl<'a, C: ContainerProvider> HSplit<'a, C> {
+    fn widths(&self, available_width: Width, containers: &C) -> Box<[Width]> {
+        let horizontal_demands: Vec<ColDemand> = self
+            .elms
+            .iter()
+            .map(|w| w.space_demand(containers).width)
+            .collect();
+        layout_split(
+            available_width,
+            Width::from(self.separator.thickness()),
+            horizontal_demands.as_slice(),
+            self.weights.borrow().as_slice(),
+            self.configs.as_slice(),
+        )
+    }
 }
 
 impl<'a, C: ContainerProvider> std::fmt::Debug for HSplit<'a, C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "(")?;
-        let mut objs = self.elms.iter().zip(self.weights.iter()).peekable();
+        let weights = self.weights.borrow();
+        let mut objs = self.elms.iter().zip(weights.iter()).peekable();
         loop {
             if let Some((e, w)) = objs.next() {
                 write!(f, "{}{:?}", w, e)?;
@@ -439,42 +1039,81 @@ impl<'a, C: ContainerProvider> Layout<C> for HSplit<'a, C> {
     fn space_demand(&self, containers: &C) -> Demand2D {
         let mut total_x = ColDemand::exact(0);
         let mut total_y = RowDemand::exact(0);
-        for e in self.elms.iter() {
+        for (e, config) in self.elms.iter().zip(self.configs.iter()) {
+            if config.is_collapsed() {
+                total_x += ColDemand::exact(1);
+                continue;
+            }
             let demand2d = e.space_demand(containers);
-            total_x = total_x + demand2d.width;
+            total_x += config.demand(demand2d.width);
             total_y = total_y.max(demand2d.height);
         }
-        total_x = total_x + ColDemand::exact(self.elms.len().checked_sub(1).unwrap_or(0));
+        total_x += ColDemand::exact(
+            Width::from(self.separator.thickness())
+                .saturating_mul(self.elms.len().saturating_sub(1)),
+        );
         Demand2D {
             width: total_x,
             height: total_y,
         }
     }
-    fn layout(&self, available_area: Rectangle, containers: &C) -> LayoutOutput<C::Index> {
-        let separator_length = Width::new(1).unwrap();
-        let horizontal_demands: Vec<ColDemand> = self
-            .elms
+    fn height_for_width(&self, width: Width, containers: &C) -> RowDemand {
+        self.elms
             .iter()
-            .map(|w| w.space_demand(containers).width)
-            .collect();
-        let assigned_spaces = layout_linearly(
-            available_area.width(),
-            separator_length,
-            horizontal_demands.as_slice(),
-            self.weights.as_slice(),
-        );
+            .zip(self.configs.iter())
+            .zip(self.widths(width, containers).iter())
+            .filter(|((_, config), _)| !config.is_collapsed())
+            .fold(RowDemand::exact(0), |total, ((e, _), &width)| {
+                total.max(e.height_for_width(width, containers))
+            })
+    }
+    fn containers(&self) -> Vec<C::Index> {
+        self.elms.iter().flat_map(|e| e.containers()).collect()
+    }
+    fn resize_split(&self, path: &[usize], index: usize, weights: (f64, f64)) -> OperationResult {
+        resize_split_children(&self.elms, &self.weights, path, index, weights)
+    }
+    fn layout(&self, available_area: Rectangle, containers: &C) -> LayoutOutput<C::Index> {
+        let separator_length = Width::from(self.separator.thickness());
+        let assigned_spaces = self.widths(available_area.width(), containers);
         let mut output = LayoutOutput::new();
         let mut p = available_area.x_range.start;
-        for (elm, space) in self.elms.iter().zip(assigned_spaces.into_iter()) {
+        for (i, ((elm, config), space)) in self
+            .elms
+            .iter()
+            .zip(self.configs.iter())
+            .zip(assigned_spaces.iter())
+            .enumerate()
+        {
             let elm_rect = available_area.slice_range_x(p..(p + *space));
-            output.add_child(elm.layout(elm_rect, containers));
+            match config.collapse {
+                Some(ref toggle) if toggle.is_collapsed() => {
+                    if *space > 0 {
+                        output
+                            .separators
+                            .push(Separator::Collapsed(elm_rect, toggle.clone()));
+                    }
+                }
+                _ => output.add_split_child(i, elm.layout(elm_rect, containers)),
+            }
             p += *space;
 
             if p < available_area.x_range.end {
-                output
-                    .separators
-                    .push(available_area.slice_line_x(p).into());
-                p += 1
+                let end = (p + separator_length).min(available_area.x_range.end);
+                let area = available_area.slice_range_x(p..end);
+                output.resize_handles.extend(split_resize_handle(
+                    area.clone(),
+                    SplitDirection::Horizontal,
+                    i,
+                    &assigned_spaces,
+                    &self.weights,
+                    &self.configs,
+                ));
+                output.separators.push(
+                    self.separator
+                        .create(area, available_area.slice_line_x(p).into()),
+                );
+                p = end;
             }
         }
         output
@@ -484,7 +1123,9 @@ impl<'a, C: ContainerProvider> Layout<C> for HSplit<'a, C> {
 /// A `Layout` laying out all children vertically, separated by Horizontal lines.
 pub struct VSplit<'a, C: ContainerProvider> {
     elms: Vec<Box<dyn Layout<C> + 'a>>,
-    weights: Vec<f64>,
+    weights: RefCell<Vec<f64>>,
+    configs: Vec<SplitChildConfig>,
+    separator: SplitSeparator,
 }
 
 impl<'a, C: ContainerProvider> VSplit<'a, C> {
@@ -495,20 +1136,47 @@ impl<'a, C: ContainerProvider> VSplit<'a, C> {
     pub fn new(elms: Vec<(Box<dyn Layout<C> + 'a>, f64)>) -> Self {
         let mut res = VSplit {
             elms: Vec::new(),
-            weights: Vec::new(),
+            weights: RefCell::new(Vec::new()),
+            configs: Vec::new(),
+            separator: SplitSeparator::Line,
         };
         for (e, w) in elms {
             res.elms.push(e);
-            res.weights.push(w);
+            res.weights.get_mut().push(w);
+            res.configs.push(SplitChildConfig::default());
         }
         res
     }
+
+    /// Specify how much space the child at position `index` receives. (Default:
+    /// `SplitSize::Weighted`)
+    ///
+    /// Panics if there is no child at position `index`.
+    pub fn size(mut self, index: usize, size: SplitSize) -> Self {
+        self.configs[index].size = size;
+        self
+    }
+
+    /// Make the child at position `index` collapsible using the given toggle.
+    ///
+    /// Panics if there is no child at position `index`.
+    pub fn collapsible(mut self, index: usize, toggle: CollapseToggle) -> Self {
+        self.configs[index].collapse = Some(toggle);
+        self
+    }
+
+    /// Specify how neighboring children are separated. (Default: `SplitSeparator::Line`)
+    pub fn separator(mut self, separator: SplitSeparator) -> Self {
+        self.separator = separator;
+        self
+    }
 }
 
 impl<'a, C: ContainerProvider> std::fmt::Debug for VSplit<'a, C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "(")?;
-        let mut objs = self.elms.iter().zip(self.weights.iter()).peekable();
+        let weights = self.weights.borrow();
+        let mut objs = self.elms.iter().zip(weights.iter()).peekable();
         loop {
             if let Some((e, w)) = objs.next() {
                 write!(f, "{}{:?}", w, e)?;
@@ -527,42 +1195,99 @@ impl<'a, C: ContainerProvider> Layout<C> for VSplit<'a, C> {
     fn space_demand(&self, containers: &C) -> Demand2D {
         let mut total_x = ColDemand::exact(0);
         let mut total_y = RowDemand::exact(0);
-        for e in self.elms.iter() {
+        for (e, config) in self.elms.iter().zip(self.configs.iter()) {
+            if config.is_collapsed() {
+                total_y += RowDemand::exact(1);
+                continue;
+            }
             let demand2d = e.space_demand(containers);
             total_x = total_x.max(demand2d.width);
-            total_y = total_y + demand2d.height;
+            total_y += config.demand(demand2d.height);
         }
-        total_y += RowDemand::exact(self.elms.len().checked_sub(1).unwrap_or(0));
+        total_y += RowDemand::exact(
+            Height::from(self.separator.thickness())
+                .saturating_mul(self.elms.len().saturating_sub(1)),
+        );
         Demand2D {
             width: total_x,
             height: total_y,
         }
     }
+    fn height_for_width(&self, width: Width, containers: &C) -> RowDemand {
+        let mut total = RowDemand::exact(0);
+        for (e, config) in self.elms.iter().zip(self.configs.iter()) {
+            total += if config.is_collapsed() {
+                RowDemand::exact(1)
+            } else {
+                config.demand(e.height_for_width(width, containers))
+            };
+        }
+        total
+            + RowDemand::exact(
+                Height::from(self.separator.thickness())
+                    .saturating_mul(self.elms.len().saturating_sub(1)),
+            )
+    }
+    fn containers(&self) -> Vec<C::Index> {
+        self.elms.iter().flat_map(|e| e.containers()).collect()
+    }
+    fn resize_split(&self, path: &[usize], index: usize, weights: (f64, f64)) -> OperationResult {
+        resize_split_children(&self.elms, &self.weights, path, index, weights)
+    }
+    /// The heights of the children are assigned according to their demand at the width of the
+    /// area (see `Layout::height_for_width`).
     fn layout(&self, available_area: Rectangle, containers: &C) -> LayoutOutput<C::Index> {
-        let separator_length = Height::new(1).unwrap();
+        let separator_length = Height::from(self.separator.thickness());
         let vertical_demands: Vec<RowDemand> = self
             .elms
             .iter()
-            .map(|w| w.space_demand(containers).height)
+            .map(|w| w.height_for_width(available_area.width(), containers))
             .collect();
-        let assigned_spaces = layout_linearly(
+        let assigned_spaces = layout_split(
             available_area.height(),
             separator_length,
             vertical_demands.as_slice(),
-            self.weights.as_slice(),
+            self.weights.borrow().as_slice(),
+            self.configs.as_slice(),
         );
         let mut output = LayoutOutput::new();
         let mut p = available_area.y_range.start;
-        for (elm, space) in self.elms.iter().zip(assigned_spaces.into_iter()) {
+        for (i, ((elm, config), space)) in self
+            .elms
+            .iter()
+            .zip(self.configs.iter())
+            .zip(assigned_spaces.iter())
+            .enumerate()
+        {
             let elm_rect = available_area.slice_range_y(p..(p + *space));
-            output.add_child(elm.layout(elm_rect, containers));
+            match config.collapse {
+                Some(ref toggle) if toggle.is_collapsed() => {
+                    if *space > 0 {
+                        output
+                            .separators
+                            .push(Separator::Collapsed(elm_rect, toggle.clone()));
+                    }
+                }
+                _ => output.add_split_child(i, elm.layout(elm_rect, containers)),
+            }
             p += *space;
 
             if p < available_area.y_range.end {
-                output
-                    .separators
-                    .push(available_area.slice_line_y(p).into());
-                p += 1
+                let end = (p + separator_length).min(available_area.y_range.end);
+                let area = available_area.slice_range_y(p..end);
+                output.resize_handles.extend(split_resize_handle(
+                    area.clone(),
+                    SplitDirection::Vertical,
+                    i,
+                    &assigned_spaces,
+                    &self.weights,
+                    &self.configs,
+                ));
+                output.separators.push(
+                    self.separator
+                        .create(area, available_area.slice_line_y(p).into()),
+                );
+                p = end;
             }
         }
         output
@@ -576,6 +1301,7 @@ pub struct NavigatableContainerManager<'a, 'b, 'd: 'a, C: ContainerProvider + 'a
     provider: &'b mut C,
 }
 
+#[derive(Clone, Copy)]
 enum MovementDirection {
     Up,
     Down,
@@ -583,28 +1309,65 @@ enum MovementDirection {
     Right,
 }
 
+enum NavigationTarget<'a, I> {
+    Container(&'a I),
+    Collapsed(&'a CollapseToggle),
+}
+
 fn raw_range<T: AxisDimension>(range: &Range<AxisIndex<T>>) -> Range<i32> {
     range.start.raw_value()..range.end.raw_value()
 }
 
 impl<'a, 'b, 'd: 'a, C: ContainerProvider + 'a + 'b> NavigatableContainerManager<'a, 'b, 'd, C> {
+    /// Like in tmux, navigating away from a zoomed container restores the original layout. If
+    /// there is nothing to navigate to, the zoom is kept.
+    ///
+    /// Navigation is not possible while a container grabs the input.
     fn move_to(&mut self, direction: MovementDirection) -> OperationResult {
+        if self.manager.grab.is_grabbed() {
+            return Err(());
+        }
+        let zoomed = self.manager.is_zoomed();
+        if zoomed {
+            self.manager.toggle_zoom();
+        }
+        let res = self.move_to_unzoomed(direction);
+        if res.is_err() && zoomed {
+            self.manager.toggle_zoom();
+        }
+        res
+    }
+
+    fn move_to_unzoomed(&mut self, direction: MovementDirection) -> OperationResult {
         let window_size = self.manager.last_window_size.get();
         let window_rect = Rectangle {
             x_range: 0.into()..window_size.0.from_origin(),
             y_range: 0.into()..window_size.1.from_origin(),
         };
-        let layout_result = self.manager.layout.layout(window_rect, self.provider);
+        let layout_result = self.manager.layout(window_rect, self.provider);
         let active_rect = layout_result
             .get_rect_with_index(self.manager.active.clone())
             .ok_or(())?;
-        let best = layout_result
+        let windows = layout_result
             .windows
             .iter()
-            .filter_map(|&(ref candidate_index, ref candidate_rect)| {
-                if *candidate_index == self.manager.active {
-                    return None;
-                }
+            .filter(|(index, _)| *index != self.manager.active)
+            .map(|(index, rect)| (NavigationTarget::Container(index), rect));
+        let placeholders =
+            layout_result
+                .separators
+                .iter()
+                .filter_map(|separator| match separator {
+                    Separator::Collapsed(rect, toggle) => {
+                        Some((NavigationTarget::Collapsed(toggle), rect))
+                    }
+                    _ => None,
+                });
+        let candidates = windows.chain(placeholders).collect::<Vec<_>>();
+        let best = candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &(ref candidate, candidate_rect))| {
                 let (smaller_adjacent, greater_adjacent, active_range, candidate_range) =
                     match direction {
                         MovementDirection::Up => (
@@ -632,23 +1395,44 @@ impl<'a, 'b, 'd: 'a, C: ContainerProvider + 'a + 'b> NavigatableContainerManager
                             raw_range(&candidate_rect.y_range),
                         ),
                     };
-                if smaller_adjacent < greater_adjacent && greater_adjacent - smaller_adjacent == 1 {
-                    // Rects are adjacent
-                    let overlap = min(active_range.end, candidate_range.end)
-                        .checked_sub(max(active_range.start, candidate_range.start))
-                        .unwrap_or(0);
-                    Some((overlap, candidate_index))
-                } else {
+                let shared = max(active_range.start, candidate_range.start)
+                    ..min(active_range.end, candidate_range.end);
+                if smaller_adjacent > greater_adjacent || shared.start >= shared.end {
+                    return None;
+                }
+                // Separators may have any thickness, so the candidate is a neighbor of the active
+                // container if only separators lie in the gap between the shared edges.
+                let gap = smaller_adjacent..greater_adjacent;
+                let (gap_x, gap_y) = match direction {
+                    MovementDirection::Up | MovementDirection::Down => (shared.clone(), gap),
+                    MovementDirection::Left | MovementDirection::Right => (gap, shared.clone()),
+                };
+                let intersects =
+                    |a: &Range<i32>, b: &Range<i32>| a.start < b.end && b.start < a.end;
+                let gap_is_blocked = candidates.iter().enumerate().any(|(j, &(_, other_rect))| {
+                    i != j
+                        && intersects(&gap_x, &raw_range(&other_rect.x_range))
+                        && intersects(&gap_y, &raw_range(&other_rect.y_range))
+                });
+                if gap_is_blocked {
                     None
+                } else {
+                    Some((shared.end - shared.start, candidate))
                 }
             })
             .max_by_key(|&(overlap, _)| overlap);
 
-        if let Some((_, index)) = best {
-            self.manager.active = index.clone();
-            Ok(())
-        } else {
-            Err(())
+        match best {
+            Some((_, &NavigationTarget::Container(index))) => {
+                self.manager.active = index.clone();
+                Ok(())
+            }
+            Some((_, &NavigationTarget::Collapsed(toggle))) => {
+                // Expand the collapsed child and move into it.
+                toggle.set_collapsed(false);
+                self.move_to(direction)
+            }
+            None => Err(()),
         }
     }
 }
@@ -704,13 +1488,67 @@ impl Iterator for LineCanvasIter {
     }
 }
 
-/// Stores the layout of containers and manages and has a concept of an active container.
-///
-/// In some sense this is the analogon of a "window manager" for containers.
+/// Rectangles of an animated transition between two layouts (see
+/// `ContainerManager::set_layout_transition`).
+struct LayoutTransition<I> {
+    area: Rectangle,
+    from: Vec<(I, Rectangle)>,
+    to: Vec<(I, Rectangle)>,
+    /// The transition is finished once `frame` reaches the number of frames of the transition.
+    frame: u32,
+}
+
+impl<I: Clone + PartialEq> LayoutTransition<I> {
+    fn windows(&self, frames: u32, easing: Easing) -> Vec<(I, Rectangle)> {
+        if self.frame >= frames {
+            return self.to.clone();
+        }
+        let t = easing.apply(f64::from(self.frame) / f64::from(frames));
+        self.to
+            .iter()
+            .map(|(index, to)| {
+                // Containers that were not visible before grow from the center of their area.
+                let from = self
+                    .from
+                    .iter()
+                    .find(|(i, _)| i == index)
+                    .map(|(_, r)| r.clone())
+                    .unwrap_or_else(|| {
+                        let x = to.x_range.start.lerp(&to.x_range.end, 0.5);
+                        let y = to.y_range.start.lerp(&to.y_range.end, 0.5);
+                        Rectangle {
+                            x_range: x..x,
+                            y_range: y..y,
+                        }
+                    });
+                (index.clone(), from.lerp(to, t))
+            })
+            .collect()
+    }
+}
+
+/// Stores the layout of containers and manages and has a concept of an active container.
+///
+/// In some sense this is the analogon of a "window manager" for containers.
 pub struct ContainerManager<'a, C: ContainerProvider> {
     layout: Box<dyn Layout<C> + 'a>,
+    zoomed: bool,
     active: C::Index,
+    grab: InputGrab<C::Index>,
     last_window_size: Cell<(Width, Height)>,
+    last_focused: RefCell<Option<C::Index>>,
+    last_areas: RefCell<Vec<(C::Index, Rectangle)>>,
+    transition_frames: u32,
+    transition_easing: Easing,
+    transition: RefCell<Option<LayoutTransition<C::Index>>>,
+    drag: DragAndDrop,
+    drawn_areas: RefCell<Vec<(C::Index, Rectangle)>>,
+    drawn_resize_handles: RefCell<Vec<ResizeHandle>>,
+    resizing: Option<(ResizeHandle, ColIndex, RowIndex)>,
+    background: Vec<(C::Index, Vec<Event>, BackgroundOrder)>,
+    jump: JumpMode<C::Index>,
+    debug_overlay: bool,
+    overlays: Vec<(C::Index, OverlayPlacement)>,
 }
 
 impl<'a, C: ContainerProvider> ContainerManager<'a, C> {
@@ -719,8 +1557,23 @@ impl<'a, C: ContainerProvider> ContainerManager<'a, C> {
     pub fn from_layout(layout_root: Box<dyn Layout<C> + 'a>) -> Self {
         ContainerManager {
             layout: layout_root,
+            zoomed: false,
             active: C::DEFAULT_CONTAINER.clone(),
+            grab: InputGrab::new(),
             last_window_size: Cell::new((Width::new(100).unwrap(), Height::new(100).unwrap())),
+            last_focused: RefCell::new(None),
+            last_areas: RefCell::new(Vec::new()),
+            transition_frames: 0,
+            transition_easing: Easing::Linear,
+            transition: RefCell::new(None),
+            drag: DragAndDrop::new(),
+            drawn_areas: RefCell::new(Vec::new()),
+            drawn_resize_handles: RefCell::new(Vec::new()),
+            resizing: None,
+            background: Vec::new(),
+            jump: JumpMode::new(),
+            debug_overlay: false,
+            overlays: Vec::new(),
         }
     }
 
@@ -728,15 +1581,459 @@ impl<'a, C: ContainerProvider> ContainerManager<'a, C> {
     /// active container to the default one.
     pub fn set_layout(&mut self, layout_root: Box<dyn Layout<C> + 'a>) {
         self.layout = layout_root;
+        self.zoomed = false;
         self.active = C::DEFAULT_CONTAINER.clone();
     }
 
-    /// Draw all containers and separating lines onto the provided window.
+    /// Split the area of the active container (see `LayoutTree::split`) and activate the
+    /// container `new`. `tree` should be (a clone of) the layout of the `ContainerManager`.
+    pub fn split_active(
+        &mut self,
+        tree: &LayoutTree<C::Index>,
+        direction: SplitDirection,
+        new: C::Index,
+    ) -> OperationResult {
+        tree.split(&self.active, direction, new.clone())?;
+        self.set_active(new);
+        Ok(())
+    }
+
+    /// Remove the active container from the layout (see `LayoutTree::close`) and activate the
+    /// container that takes its place. `tree` should be (a clone of) the layout of the
+    /// `ContainerManager`.
+    pub fn close_active(&mut self, tree: &LayoutTree<C::Index>) -> OperationResult {
+        let successor = tree.close(&self.active)?;
+        self.set_active(successor);
+        Ok(())
+    }
+
+    /// Zoom into the active container, i.e., temporarily lay out only a `Leaf` of the active
+    /// container instead of the whole layout. If already zoomed, restore the layout instead.
+    ///
+    /// Navigating to a different container (or activating one using `set_active`) also restores
+    /// the layout.
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = !self.zoomed;
+    }
+
+    /// Check whether the active container is currently zoomed (see `toggle_zoom`), e.g., to show
+    /// an indicator.
+    pub fn is_zoomed(&self) -> bool {
+        self.zoomed
+    }
+
+    /// Animate changes of the layout (e.g., when the layout is replaced or zoom is toggled) by
+    /// moving and resizing the containers over the specified number of frames. `0` (the default)
+    /// disables the animation.
+    ///
+    /// Each call to `advance_layout_transition` advances the animation by one frame, so call it
+    /// periodically (e.g., in `Application::tick`) and redraw as long as it returns `true`.
+    /// Separators are not drawn during the transition, containers that are removed from the
+    /// layout disappear immediately and containers only receive `on_resize` for their final area.
+    /// Changes of the size of the window are not animated.
     ///
-    /// Use `border_style` to change how the lines will be drawn.
+    /// # Examples:
+    /// ```
+    /// use unsegen::container::*;
+    /// use unsegen::util::Easing;
+    ///
+    /// fn animate_layout<C: ContainerProvider>(manager: &mut ContainerManager<C>) {
+    ///     manager.set_layout_transition(10);
+    ///     manager.set_layout_transition_easing(Easing::EaseInOut);
+    /// }
+    /// ```
+    pub fn set_layout_transition(&mut self, frames: u32) {
+        self.transition_frames = frames;
+    }
+
+    /// Specify how the containers accelerate and decelerate during a layout transition (see
+    /// `set_layout_transition`). (Default: `Easing::Linear`)
+    pub fn set_layout_transition_easing(&mut self, easing: Easing) {
+        self.transition_easing = easing;
+    }
+
+    /// Advance the layout transition (see `set_layout_transition`) by one frame. Returns whether
+    /// the transition is still in progress, i.e., whether the containers have to be redrawn.
+    pub fn advance_layout_transition(&mut self) -> bool {
+        let frames = self.transition_frames;
+        match self.transition.get_mut() {
+            Some(transition) if transition.frame < frames => {
+                transition.frame += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Get a handle to let a container grab all input (see `InputGrab`).
+    pub fn input_grab(&self) -> InputGrab<C::Index> {
+        self.grab.clone()
+    }
+
+    /// Let the container identified by `index` receive the specified events even if it is not
+    /// the active container, e.g., to toggle the follow mode of a log pane or to handle media
+    /// keys globally. Depending on `order`, the events are passed to the container before or
+    /// after the active container (see `ActiveContainerBehavior`). Events that the container
+    /// does not consume are passed on as usual.
+    ///
+    /// Background input is not delivered while a container grabs the input (see `InputGrab`) or
+    /// if the container is the active one (since it receives all input anyway).
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::container::*;
+    /// use unsegen::input::Key;
+    ///
+    /// // Let the log pane toggle its follow mode even if another container is active.
+    /// fn follow_log<C: ContainerProvider>(manager: &mut ContainerManager<C>, log: C::Index) {
+    ///     manager.add_background_input(log, &[Key::Char('F')], BackgroundOrder::BeforeActive);
+    /// }
+    /// ```
+    pub fn add_background_input<E: ToEvent + Clone>(
+        &mut self,
+        index: C::Index,
+        events: &[E],
+        order: BackgroundOrder,
+    ) {
+        let events = events.iter().map(|e| e.clone().to_event()).collect();
+        self.background.push((index, events, order));
+    }
+
+    /// Stop passing any events to the container identified by `index` in the background (see
+    /// `add_background_input`).
+    pub fn remove_background_input(&mut self, index: &C::Index) {
+        self.background.retain(|(i, _, _)| i != index);
+    }
+
+    /// Pass the input to the background containers registered for the input event and `order`
+    /// until it is consumed.
+    fn background_input(
+        &self,
+        order: BackgroundOrder,
+        input: Input,
+        provider: &mut C,
+        context: &mut C::Context,
+    ) -> Option<Input> {
+        let mut input = input;
+        for (index, events, o) in &self.background {
+            if *o == order && *index != self.active && events.contains(&input.event) {
+                input = provider.get_mut(index).input(input, context)?;
+            }
+        }
+        Some(input)
+    }
+
+    /// Get the areas of all containers (relative to the window) as of the last draw.
+    pub fn container_areas(&self) -> Vec<(C::Index, Rectangle)> {
+        self.drawn_areas.borrow().clone()
+    }
+
+    /// Get the area of the active container (see `active`) in the layout as of the last draw,
+    /// e.g., to prioritize its output (see `Terminal::prioritize`). Overlays are not part of the
+    /// layout, so this is `None` while an overlay is shown.
+    pub fn active_area(&self) -> Option<Rectangle> {
+        let active = self.active();
+        self.drawn_areas
+            .borrow()
+            .iter()
+            .find(|(index, _)| *index == active)
+            .map(|(_, rect)| rect.clone())
+    }
+
+    /// Show a label in the top left corner of every container of the last draw and activate the
+    /// container whose label is typed next (see `JumpMode`). Any other input cancels jump mode.
+    ///
+    /// The input has to be passed to the `ContainerManager` via `active_container_behavior`.
+    /// Fails if no containers have been drawn yet.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::container::*;
+    /// use unsegen::input::*;
+    ///
+    /// fn handle_input<C: ContainerProvider>(
+    ///     input: Input,
+    ///     manager: &mut ContainerManager<C>,
+    ///     provider: &mut C,
+    ///     context: &mut C::Context,
+    /// ) {
+    ///     input
+    ///         .chain((Key::Ctrl('j'), || drop(manager.start_jump_mode())))
+    ///         .chain(manager.active_container_behavior(provider, context));
+    /// }
+    /// ```
+    pub fn start_jump_mode(&mut self) -> OperationResult {
+        let targets = self
+            .drawn_areas
+            .borrow()
+            .iter()
+            .map(|(index, rect)| (index.clone(), rect.x_range.start, rect.y_range.start))
+            .collect::<Vec<_>>();
+        if targets.is_empty() {
+            return Err(());
+        }
+        self.jump.start(targets);
+        Ok(())
+    }
+
+    /// Check whether jump mode is active (see `start_jump_mode`).
+    pub fn is_jump_mode_active(&self) -> bool {
+        self.jump.is_active()
+    }
+
+    /// Change the labels of the jump mode (i.e., their alphabet and style, see `JumpMode`). This
+    /// cancels jump mode if it is active.
+    pub fn set_jump_mode(&mut self, jump: JumpMode<C::Index>) {
+        self.jump = jump;
+    }
+
+    /// Show (or hide) the area, the assigned size and the space demand of every container on top
+    /// of the containers, which is useful when developing layouts and widgets.
+    ///
+    /// The corners of each area are marked and the top row shows the index of the container and
+    /// its size, followed by the demand of its widget (`min-max`, or `min+` if unbounded).
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::container::*;
+    /// use unsegen::input::*;
+    ///
+    /// fn handle_input<C: ContainerProvider>(input: Input, manager: &mut ContainerManager<C>) {
+    ///     input.chain((Key::F(12), || manager.toggle_debug_overlay()));
+    /// }
+    /// ```
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        self.debug_overlay = enabled;
+    }
+
+    /// Show the debug overlay if it is hidden and vice versa (see `set_debug_overlay`).
+    pub fn toggle_debug_overlay(&mut self) {
+        self.debug_overlay = !self.debug_overlay;
+    }
+
+    /// Check whether the debug overlay is shown (see `set_debug_overlay`).
+    pub fn is_debug_overlay_enabled(&self) -> bool {
+        self.debug_overlay
+    }
+
+    fn draw_debug_overlay(
+        &self,
+        window: &mut Window,
+        provider: &C,
+        windows: &[(C::Index, Rectangle)],
+    ) {
+        fn format_demand<T: AxisDimension>(demand: Demand<T>) -> String {
+            match demand.max {
+                Some(max) if max == demand.min => format!("{}", max.raw_value()),
+                Some(max) => format!("{}-{}", demand.min.raw_value(), max.raw_value()),
+                None => format!("{}+", demand.min.raw_value()),
+            }
+        }
+        let style = StyleModifier::new().invert(BoolModifyMode::Toggle);
+        let profile = rendering_profile();
+        for (index, rect) in windows {
+            if rect.is_empty() {
+                continue;
+            }
+            let demand = provider.get(index).as_widget().space_demand();
+            let label = format!(
+                "{:?} {}x{} {}x{}",
+                index,
+                rect.width().raw_value(),
+                rect.height().raw_value(),
+                format_demand(demand.width),
+                format_demand(demand.height)
+            );
+            let mut area = window.create_subwindow(rect.x_range.clone(), rect.y_range.clone());
+            let (right, bottom) = (
+                (rect.width() - 1).from_origin(),
+                (rect.height() - 1).from_origin(),
+            );
+            let corners = [
+                (ColIndex::new(0), RowIndex::new(0), profile.select("┌", "+")),
+                (right, RowIndex::new(0), profile.select("┐", "+")),
+                (ColIndex::new(0), bottom, profile.select("└", "+")),
+                (right, bottom, profile.select("┘", "+")),
+            ];
+            Cursor::new(&mut area)
+                .position(ColIndex::new(1), RowIndex::new(0))
+                .style_modifier(style)
+                .write(&label);
+            for &(x, y, corner) in corners.iter() {
+                Cursor::new(&mut area)
+                    .position(x, y)
+                    .style_modifier(style)
+                    .write(corner);
+            }
+        }
+    }
+
+    /// Show the container `index` on top of all containers (and previously pushed overlays) in the
+    /// area specified by `placement`, e.g., to display a modal dialog.
+    ///
+    /// Overlays are drawn after the layout (their area is cleared beforehand). While any overlay is
+    /// shown, the topmost one is the active container and receives all input (see
+    /// `active_container_behavior`). The container should not be part of the layout, but receives
+    /// events (see `dispatch_events`) and lifecycle hooks (e.g., `Container::on_resize` for its
+    /// area) just like the containers of the layout.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::container::*;
+    ///
+    /// fn show_dialog<C: ContainerProvider>(manager: &mut ContainerManager<C>, dialog: C::Index) {
+    ///     manager.push_overlay(dialog, OverlayPlacement::CenteredFit);
+    /// }
+    /// ```
+    pub fn push_overlay(&mut self, index: C::Index, placement: OverlayPlacement) {
+        self.overlays.push((index, placement));
+    }
+
+    /// Hide the topmost overlay (see `push_overlay`) and return its index, if any.
+    pub fn pop_overlay(&mut self) -> Option<C::Index> {
+        self.overlays.pop().map(|(index, _)| index)
+    }
+
+    /// Hide the overlay of the container `index` (see `push_overlay`), if it is shown.
+    pub fn remove_overlay(&mut self, index: &C::Index) {
+        self.overlays.retain(|(i, _)| i != index);
+    }
+
+    /// Get the indices of all overlays (see `push_overlay`) from bottom to top.
+    pub fn overlays(&self) -> Vec<C::Index> {
+        self.overlays
+            .iter()
+            .map(|(index, _)| index.clone())
+            .collect()
+    }
+
+    /// Compute the areas of all overlays (from bottom to top) within `window_rect`.
+    fn overlay_areas(&self, window_rect: &Rectangle, provider: &C) -> Vec<(C::Index, Rectangle)> {
+        self.overlays
+            .iter()
+            .map(|(index, placement)| {
+                let demand = provider.get(index).as_widget().space_demand();
+                (index.clone(), placement.area(window_rect, demand))
+            })
+            .collect()
+    }
+
+    fn draw_overlays(
+        &self,
+        window: &mut Window,
+        provider: &mut C,
+        areas: &[(C::Index, Rectangle)],
+        hints: RenderingHints,
+    ) {
+        let top = areas.len().saturating_sub(1);
+        for (i, (index, rect)) in areas.iter().enumerate() {
+            let mut overlay_window =
+                window.create_subwindow(rect.x_range.clone(), rect.y_range.clone());
+            overlay_window.clear();
+            provider
+                .get(index)
+                .as_widget()
+                .draw(overlay_window, hints.active(hints.active && i == top));
+        }
+    }
+
+    /// Get a handle to start drags of data between containers (see `dragdrop`).
+    pub fn drag_and_drop(&self) -> DragAndDrop {
+        self.drag.clone()
+    }
+
+    /// Finish the current drag by passing its payload to the drop target (if any) under the
+    /// position (x, y) of the last draw. Returns whether the payload was accepted.
+    fn drop_at(
+        &self,
+        provider: &mut C,
+        x: ColIndex,
+        y: RowIndex,
+        context: &mut C::Context,
+    ) -> bool {
+        let drag = match self.drag.take() {
+            Some(drag) => drag,
+            None => return false,
+        };
+        let target = self
+            .drawn_areas
+            .borrow()
+            .iter()
+            .find(|(_, rect)| rect.contains(x, y))
+            .cloned();
+        let (index, rect) = match target {
+            Some(target) => target,
+            None => return false,
+        };
+        match provider.get_mut(&index).drop_target() {
+            Some(target) => target.on_drop(
+                &drag.payload,
+                (x - rect.x_range.start).from_origin(),
+                (y - rect.y_range.start).from_origin(),
+                context,
+            ),
+            None => false,
+        }
+    }
+
+    /// Draw the label of the payload of the current drag (if any) next to the mouse pointer.
+    fn draw_drag_indicator(&self, window: &mut Window) {
+        if let Some((x, y, label)) = self.drag.indicator() {
+            Cursor::new(window)
+                .position(x, y)
+                .style_modifier(StyleModifier::new().invert(BoolModifyMode::Toggle))
+                .write(&label);
+        }
+    }
+
+    /// Get the areas of the containers to draw, i.e., either the result of the layout or an
+    /// intermediate state of a running transition.
+    fn transition_windows(
+        &self,
+        area: &Rectangle,
+        windows: &[(C::Index, Rectangle)],
+    ) -> Option<Vec<(C::Index, Rectangle)>> {
+        let frames = self.transition_frames;
+        let mut transition = self.transition.borrow_mut();
+        let animate = match *transition {
+            Some(ref t) if t.to.as_slice() == windows => None,
+            Some(ref t) if frames > 0 && t.area == *area => {
+                Some(t.windows(frames, self.transition_easing))
+            }
+            _ => Some(Vec::new()),
+        };
+        if let Some(from) = animate {
+            *transition = Some(LayoutTransition {
+                area: area.clone(),
+                frame: if from.is_empty() { frames } else { 1 },
+                from,
+                to: windows.to_vec(),
+            });
+        }
+        transition
+            .as_ref()
+            .filter(|t| t.frame < frames)
+            .map(|t| t.windows(frames, self.transition_easing))
+    }
+
+    fn layout(&self, available_area: Rectangle, provider: &C) -> LayoutOutput<C::Index> {
+        if self.zoomed {
+            Leaf::<C>::new(self.active.clone()).layout(available_area, provider)
+        } else {
+            self.layout.layout(available_area, provider)
+        }
+    }
+
+    /// Draw all containers and separators onto the provided window.
+    ///
+    /// Use `border_style` to change how the lines (and custom separators) will be drawn.
     ///
     /// `hints` will be passed on to containers, with the exception that only the currently active
     /// container can have an `active` hint.
+    ///
+    /// Before drawing, the lifecycle hooks of containers (`Container::on_focus_gained`,
+    /// `on_focus_lost` and `on_resize`) are called if the active container or the areas of
+    /// containers have changed since the last draw.
     pub fn draw(
         &self,
         mut window: Window,
@@ -752,11 +2049,59 @@ impl<'a, C: ContainerProvider> ContainerManager<'a, C> {
             y_range: 0.into()..window.get_height().from_origin(),
         };
 
-        let layout_result = self.layout.layout(window_rect, provider);
-        let active_rect = layout_result.get_rect_with_index(self.active.clone());
+        let layout_result = self.layout(window_rect.clone(), provider);
+        debug_assert_valid_layout(&window_rect, &layout_result.windows);
+        let overlay_areas = self.overlay_areas(&window_rect, provider);
+        let active = self.active();
+        let active_rect = layout_result.get_rect_with_index(active.clone());
+
+        let previous = self.last_focused.replace(Some(active.clone()));
+        if previous.as_ref() != Some(&active) {
+            if let Some(previous) = previous {
+                provider.get_mut(&previous).on_focus_lost();
+            }
+            provider.get_mut(&active).on_focus_gained();
+        }
+        {
+            let mut last_areas = self.last_areas.borrow_mut();
+            for (index, rect) in layout_result.windows.iter().chain(overlay_areas.iter()) {
+                match last_areas.iter_mut().find(|(i, _)| i == index) {
+                    Some((_, last)) if last == rect => continue,
+                    Some((_, last)) => *last = rect.clone(),
+                    None => last_areas.push((index.clone(), rect.clone())),
+                }
+                provider.get_mut(index).on_resize(rect.clone());
+            }
+        }
+
+        *self.drawn_areas.borrow_mut() = layout_result.windows.clone();
+        *self.drawn_resize_handles.borrow_mut() = layout_result.resize_handles.clone();
+
+        let transition_windows = self.transition_windows(&window_rect, &layout_result.windows);
+        if let Some(windows) = transition_windows {
+            window.clear();
+            for (index, rect) in windows.iter().cloned() {
+                let hints = if index == active {
+                    hints
+                } else {
+                    hints.active(false)
+                };
+                provider
+                    .get_mut(&index)
+                    .as_widget()
+                    .draw(window.create_subwindow(rect.x_range, rect.y_range), hints);
+            }
+            self.draw_overlays(&mut window, provider, &overlay_areas, hints);
+            if self.debug_overlay {
+                self.draw_debug_overlay(&mut window, provider, &windows);
+            }
+            self.jump.draw(&mut window);
+            self.draw_drag_indicator(&mut window);
+            return;
+        }
 
         for (index, rect) in layout_result.windows {
-            let hints = if index == self.active {
+            let hints = if index == active {
                 hints
             } else {
                 hints.active(false)
@@ -781,7 +2126,23 @@ impl<'a, C: ContainerProvider> ContainerManager<'a, C> {
         };
 
         let mut line_canvas = LineCanvas::new();
-        for line in layout_result.separators {
+        let mut custom_separators = Vec::new();
+        for separator in layout_result.separators {
+            let line = match separator {
+                Separator::Line(line) => line,
+                Separator::Custom(rect, renderer) => {
+                    custom_separators.push((rect, renderer));
+                    continue;
+                }
+                Separator::Collapsed(rect, _) => {
+                    let mut placeholder = window.create_subwindow(rect.x_range, rect.y_range);
+                    placeholder.modify_default_style(border_style);
+                    placeholder.fill(
+                        GraphemeCluster::try_from(rendering_profile().select('·', '.')).unwrap(),
+                    );
+                    continue;
+                }
+            };
             match line {
                 Line::Horizontal(HorizontalLine { x, y_range }) => {
                     line_canvas.get_mut(x, y_range.start - 1).set(
@@ -824,6 +2185,43 @@ impl<'a, C: ContainerProvider> ContainerManager<'a, C> {
                 border_style.modify(&mut styled_cluster.style);
             }
         }
+
+        // Custom separators are drawn last so that they are not covered by the ends of adjacent
+        // lines.
+        for (rect, renderer) in custom_separators {
+            let active = active_rect
+                .as_ref()
+                .map(|active_rect| active_rect.touches(&rect))
+                .unwrap_or(false);
+            let mut separator_window = window.create_subwindow(rect.x_range, rect.y_range);
+            separator_window.modify_default_style(border_style);
+            separator_window.clear();
+            renderer.draw(separator_window, hints.active(hints.active && active));
+        }
+
+        self.draw_overlays(&mut window, provider, &overlay_areas, hints);
+        if self.debug_overlay {
+            let windows = self.drawn_areas.borrow();
+            self.draw_debug_overlay(&mut window, provider, &windows);
+        }
+        self.jump.draw(&mut window);
+        self.draw_drag_indicator(&mut window);
+    }
+
+    /// Pass `events` (usually taken from an `EventBus`) to all containers of the layout, all
+    /// overlays (see `push_overlay`) and the container that grabs the input (if any) by calling
+    /// `Container::update`. Call this once before drawing.
+    pub fn dispatch_events(&self, provider: &mut C, events: &Events, context: &mut C::Context) {
+        let mut indices = self.layout.containers();
+        indices.extend(self.overlays.iter().map(|(index, _)| index.clone()));
+        indices.extend(self.grab.holder());
+        let mut visited = Vec::new();
+        for index in indices {
+            if !visited.contains(&index) {
+                provider.get_mut(&index).update(events, context);
+                visited.push(index);
+            }
+        }
     }
 
     /// Allow the active container to be changed using a `NavigateBehavior`.
@@ -837,6 +2235,29 @@ impl<'a, C: ContainerProvider> ContainerManager<'a, C> {
         }
     }
 
+    /// Behavior that interprets mouse input (see `MouseContainerBehavior`), i.e., clicking a
+    /// container activates it and dragging a separator resizes the neighboring containers.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::container::*;
+    /// use unsegen::input::*;
+    ///
+    /// fn handle_input<C: ContainerProvider>(
+    ///     input: Input,
+    ///     manager: &mut ContainerManager<C>,
+    ///     provider: &mut C,
+    ///     context: &mut C::Context,
+    /// ) {
+    ///     input
+    ///         .chain(manager.mouse_behavior())
+    ///         .chain(manager.active_container_behavior(provider, context));
+    /// }
+    /// ```
+    pub fn mouse_behavior<'b>(&'b mut self) -> MouseContainerBehavior<'b, 'a, C> {
+        MouseContainerBehavior { manager: self }
+    }
+
     /// Behavior that passes all input to the currently active container.
     pub fn active_container_behavior<'b, 'c, 'd>(
         &'b mut self,
@@ -850,13 +2271,787 @@ impl<'a, C: ContainerProvider> ContainerManager<'a, C> {
         }
     }
 
-    /// Get the index of the currently active container.
+    /// Get the index of the currently active container, i.e., the topmost overlay (see
+    /// `push_overlay`) or the one that grabs the input (see `InputGrab`), if any.
     pub fn active(&self) -> C::Index {
-        self.active.clone()
+        self.overlays
+            .last()
+            .map(|(index, _)| index.clone())
+            .or_else(|| self.grab.holder())
+            .unwrap_or_else(|| self.active.clone())
     }
 
     /// Set the currently active container using its Index.
     pub fn set_active(&mut self, i: C::Index) {
+        if i != self.active {
+            self.zoomed = false;
+        }
         self.active = i;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::terminal::test::FakeTerminal;
+    use widget::WidgetExt;
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum Index {
+        A,
+        B,
+        C,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Hook {
+        FocusGained,
+        FocusLost,
+        Resize(Rectangle),
+    }
+
+    type HookLog = Rc<RefCell<Vec<(Index, Hook)>>>;
+
+    struct Counter {
+        index: Index,
+        updates: usize,
+        inputs: usize,
+        hooks: HookLog,
+    }
+
+    impl Counter {
+        fn hook(&self, hook: Hook) {
+            self.hooks.borrow_mut().push((self.index, hook));
+        }
+    }
+
+    impl Container<()> for Counter {
+        fn input(&mut self, input: Input, _: &mut ()) -> Option<Input> {
+            self.inputs += 1;
+            if input.matches(Key::Esc) {
+                Some(input)
+            } else {
+                None
+            }
+        }
+        fn as_widget<'a>(&'a self) -> Box<dyn Widget + 'a> {
+            Box::new("x".with_demand(|_| Demand2D {
+                width: ColDemand::at_least(1),
+                height: RowDemand::at_least(1),
+            }))
+        }
+        fn update(&mut self, _events: &Events, _context: &mut ()) {
+            self.updates += 1;
+        }
+        fn on_focus_gained(&mut self) {
+            self.hook(Hook::FocusGained);
+        }
+        fn on_focus_lost(&mut self) {
+            self.hook(Hook::FocusLost);
+        }
+        fn on_resize(&mut self, area: Rectangle) {
+            self.hook(Hook::Resize(area));
+        }
+    }
+
+    struct App(Counter, Counter, Counter);
+
+    impl App {
+        fn new() -> Self {
+            let hooks = HookLog::default();
+            let counter = |index| Counter {
+                index,
+                updates: 0,
+                inputs: 0,
+                hooks: hooks.clone(),
+            };
+            App(counter(Index::A), counter(Index::B), counter(Index::C))
+        }
+        /// Take the hooks that have been called on all containers (in order).
+        fn take_hooks(&self) -> Vec<(Index, Hook)> {
+            self.0.hooks.borrow_mut().drain(..).collect()
+        }
+        fn updates(&self) -> (usize, usize, usize) {
+            (self.0.updates, self.1.updates, self.2.updates)
+        }
+        fn inputs(&self) -> (usize, usize, usize) {
+            (self.0.inputs, self.1.inputs, self.2.inputs)
+        }
+    }
+
+    impl ContainerProvider for App {
+        type Context = ();
+        type Index = Index;
+        fn get<'a, 'b: 'a>(&'b self, index: &'a Index) -> &'b dyn Container<()> {
+            match index {
+                Index::A => &self.0,
+                Index::B => &self.1,
+                Index::C => &self.2,
+            }
+        }
+        fn get_mut<'a, 'b: 'a>(&'b mut self, index: &'a Index) -> &'b mut dyn Container<()> {
+            match index {
+                Index::A => &mut self.0,
+                Index::B => &mut self.1,
+                Index::C => &mut self.2,
+            }
+        }
+        const DEFAULT_CONTAINER: Index = Index::A;
+    }
+
+    fn leaf<'a>(index: Index) -> Box<dyn Layout<App> + 'a> {
+        Box::new(Leaf::new(index))
+    }
+
+    #[test]
+    fn test_dispatch_events() {
+        let mut app = App::new();
+        let manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+            (leaf(Index::A), 1.0),
+        ])));
+        let events = EventBus::new().take();
+
+        // Containers that appear more than once are only updated once.
+        manager.dispatch_events(&mut app, &events, &mut ());
+        assert_eq!(app.updates(), (1, 1, 0));
+
+        // The container grabbing the input is updated even if it is not part of the layout...
+        let grab = manager.input_grab();
+        grab.grab(Index::C);
+        manager.dispatch_events(&mut app, &events, &mut ());
+        assert_eq!(app.updates(), (2, 2, 1));
+
+        // ... but only once if it is.
+        grab.grab(Index::B);
+        manager.dispatch_events(&mut app, &events, &mut ());
+        assert_eq!(app.updates(), (3, 3, 1));
+
+        grab.release();
+        manager.dispatch_events(&mut app, &events, &mut ());
+        assert_eq!(app.updates(), (4, 4, 1));
+    }
+
+    #[test]
+    fn test_overlay_update_and_resize() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(Leaf::new(Index::A)));
+        manager.push_overlay(
+            Index::C,
+            OverlayPlacement::Centered(Width::new(2).unwrap(), Height::new(1).unwrap()),
+        );
+        manager.dispatch_events(&mut app, &EventBus::new().take(), &mut ());
+        assert_eq!(app.updates(), (1, 0, 1));
+
+        let area = |x: i32, y: i32, w: i32, h: i32| {
+            Rectangle::new(x.into()..(x + w).into(), y.into()..(y + h).into())
+        };
+        draw(&manager, &mut app, (4, 3));
+        assert_eq!(
+            app.take_hooks(),
+            vec![
+                (Index::C, Hook::FocusGained),
+                (Index::A, Hook::Resize(area(0, 0, 4, 3))),
+                (Index::C, Hook::Resize(area(1, 1, 2, 1))),
+            ]
+        );
+        draw(&manager, &mut app, (4, 3));
+        assert_eq!(app.take_hooks(), vec![]);
+        draw(&manager, &mut app, (6, 3));
+        assert_eq!(
+            app.take_hooks(),
+            vec![
+                (Index::A, Hook::Resize(area(0, 0, 6, 3))),
+                (Index::C, Hook::Resize(area(2, 1, 2, 1))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_hooks() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(
+            HSplit::new(vec![(leaf(Index::A), 1.0), (leaf(Index::B), 1.0)])
+                .size(0, SplitSize::Fixed(2)),
+        ));
+        let area = |x: i32, w: i32| Rectangle::new(x.into()..(x + w).into(), 0.into()..1.into());
+
+        draw(&manager, &mut app, (5, 1));
+        assert_eq!(
+            app.take_hooks(),
+            vec![
+                (Index::A, Hook::FocusGained),
+                (Index::A, Hook::Resize(area(0, 2))),
+                (Index::B, Hook::Resize(area(3, 2))),
+            ]
+        );
+        draw(&manager, &mut app, (5, 1));
+        assert_eq!(app.take_hooks(), vec![]);
+
+        manager.set_active(Index::B);
+        draw(&manager, &mut app, (5, 1));
+        assert_eq!(
+            app.take_hooks(),
+            vec![(Index::A, Hook::FocusLost), (Index::B, Hook::FocusGained)]
+        );
+        draw(&manager, &mut app, (5, 1));
+        assert_eq!(app.take_hooks(), vec![]);
+
+        // Only containers whose area changes are notified.
+        draw(&manager, &mut app, (7, 1));
+        assert_eq!(app.take_hooks(), vec![(Index::B, Hook::Resize(area(3, 4)))]);
+    }
+
+    #[test]
+    fn test_zoom() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+        ])));
+        draw(&manager, &mut app, (5, 1)).assert_looks_like("x ┃x ");
+        app.take_hooks();
+
+        // Only the active container is drawn while zoomed.
+        manager.toggle_zoom();
+        assert!(manager.is_zoomed());
+        draw(&manager, &mut app, (5, 1)).assert_looks_like("x    ");
+        assert_eq!(
+            app.take_hooks(),
+            vec![(
+                Index::A,
+                Hook::Resize(Rectangle::new(0.into()..5.into(), 0.into()..1.into()))
+            )]
+        );
+
+        // Toggling again restores the split.
+        manager.toggle_zoom();
+        assert!(!manager.is_zoomed());
+        draw(&manager, &mut app, (5, 1)).assert_looks_like("x ┃x ");
+    }
+
+    #[test]
+    fn test_zoom_and_navigation() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+        ])));
+        draw(&manager, &mut app, (5, 1));
+
+        // Failed navigation keeps the zoom.
+        manager.toggle_zoom();
+        assert!(manager.navigatable(&mut app).move_left().is_err());
+        assert!(manager.is_zoomed());
+        assert_eq!(manager.active(), Index::A);
+
+        // Successful navigation restores the layout.
+        manager.navigatable(&mut app).move_right().unwrap();
+        assert!(!manager.is_zoomed());
+        assert_eq!(manager.active(), Index::B);
+
+        // So does activating a different container, but not activating the same one.
+        manager.toggle_zoom();
+        manager.set_active(Index::B);
+        assert!(manager.is_zoomed());
+        manager.set_active(Index::A);
+        assert!(!manager.is_zoomed());
+        assert_eq!(manager.active(), Index::A);
+    }
+
+    #[test]
+    fn test_layout_transition() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+        ])));
+        manager.set_layout_transition(2);
+        manager.set_active(Index::B);
+        draw(&manager, &mut app, (8, 1)).assert_looks_like("x   ┃x  ");
+        assert!(!manager.advance_layout_transition());
+        app.take_hooks();
+
+        // B moves to the left over two frames, A disappears immediately.
+        manager.toggle_zoom();
+        draw(&manager, &mut app, (8, 1)).assert_looks_like("   x    ");
+        assert_eq!(
+            app.take_hooks(),
+            vec![(
+                Index::B,
+                Hook::Resize(Rectangle::new(0.into()..8.into(), 0.into()..1.into()))
+            )]
+        );
+        assert!(manager.advance_layout_transition());
+        draw(&manager, &mut app, (8, 1)).assert_looks_like("x       ");
+        assert!(!manager.advance_layout_transition());
+        assert_eq!(app.take_hooks(), vec![]);
+    }
+
+    #[test]
+    fn test_background_input() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+            (leaf(Index::C), 1.0),
+        ])));
+        manager.add_background_input(Index::B, &[Key::Char('b')], BackgroundOrder::BeforeActive);
+        manager.add_background_input(
+            Index::C,
+            &[Key::Char('c'), Key::Esc],
+            BackgroundOrder::AfterActive,
+        );
+        let mut send = |manager: &mut ContainerManager<App>, key| {
+            Input::new(key).chain(manager.active_container_behavior(&mut app, &mut ()));
+            app.inputs()
+        };
+
+        // B consumes its events before the active container receives them...
+        assert_eq!(send(&mut manager, Key::Char('b')), (0, 1, 0));
+        // ... but C only receives the events that the active container does not consume (i.e.,
+        // all but Esc).
+        assert_eq!(send(&mut manager, Key::Char('c')), (1, 1, 0));
+        assert_eq!(send(&mut manager, Key::Esc), (2, 1, 1));
+        assert_eq!(send(&mut manager, Key::Char('x')), (3, 1, 1));
+
+        // The active container receives all input anyway.
+        manager.set_active(Index::B);
+        assert_eq!(send(&mut manager, Key::Char('b')), (3, 2, 1));
+
+        manager.remove_background_input(&Index::B);
+        manager.set_active(Index::A);
+        assert_eq!(send(&mut manager, Key::Char('b')), (4, 2, 1));
+    }
+
+    #[test]
+    fn test_jump_mode() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+        ])));
+        assert!(manager.start_jump_mode().is_err());
+
+        draw(&manager, &mut app, (5, 1));
+        manager.start_jump_mode().unwrap();
+        assert!(manager.is_jump_mode_active());
+        draw(&manager, &mut app, (5, 1)).assert_looks_like("*a* ┃*b* ");
+
+        Input::new(Key::Char('b')).chain(manager.active_container_behavior(&mut app, &mut ()));
+        assert_eq!(manager.active(), Index::B);
+        assert!(!manager.is_jump_mode_active());
+        draw(&manager, &mut app, (5, 1)).assert_looks_like("x ┃x ");
+
+        // Any other input cancels jump mode (and is not passed on).
+        manager.start_jump_mode().unwrap();
+        Input::new(Key::Char('z')).chain(manager.active_container_behavior(&mut app, &mut ()));
+        assert_eq!(manager.active(), Index::B);
+        assert!(!manager.is_jump_mode_active());
+        assert_eq!(app.inputs(), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_debug_overlay() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(
+            VSplit::new(vec![(leaf(Index::A), 1.0), (leaf(Index::B), 1.0)])
+                .size(1, SplitSize::Fixed(1)),
+        ));
+        manager.toggle_debug_overlay();
+        assert!(manager.is_debug_overlay_enabled());
+        draw(&manager, &mut app, (14, 5)).assert_looks_like(
+            "┌A 14x3 1+x1+┐|              |└            ┘|━━━━━━━━━━━━━━|└B 14x1 1+x1+┘",
+        );
+
+        manager.set_debug_overlay(false);
+        draw(&manager, &mut app, (3, 5)).assert_looks_like("x  |   |   |━━━|x  ");
+    }
+
+    #[test]
+    fn test_overlays() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+        ])));
+        manager.push_overlay(Index::C, OverlayPlacement::CenteredFit);
+        assert_eq!(manager.active(), Index::C);
+        draw(&manager, &mut app, (5, 3)).assert_looks_like("x │x |  x  |  │  ");
+
+        // The topmost overlay receives all input.
+        Input::new(Key::Char('y')).chain(manager.active_container_behavior(&mut app, &mut ()));
+        assert_eq!(app.inputs(), (0, 0, 1));
+
+        assert_eq!(manager.pop_overlay(), Some(Index::C));
+        assert_eq!(manager.pop_overlay(), None);
+        assert_eq!(manager.active(), Index::A);
+        draw(&manager, &mut app, (5, 3)).assert_looks_like("x ┃x |  ┃  |  ┃  ");
+    }
+
+    fn press(manager: &mut ContainerManager<App>, x: u16) -> Option<Input> {
+        Input::new(MouseEvent::Press(MouseButton::Left, x, 1))
+            .chain(manager.mouse_behavior())
+            .finish()
+    }
+
+    fn drag(manager: &mut ContainerManager<App>, from: u16, to: u16) -> Option<Input> {
+        let res = press(manager, from);
+        Input::new(MouseEvent::Hold(to, 1)).chain(manager.mouse_behavior());
+        Input::new(MouseEvent::Release(to, 1)).chain(manager.mouse_behavior());
+        res
+    }
+
+    #[test]
+    fn test_mouse_activate_and_resize() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+        ])));
+        draw(&manager, &mut app, (7, 1)).assert_looks_like("x  ┃x  ");
+
+        // Mouse positions are 1-based. Clicks are passed on to the container.
+        assert!(press(&mut manager, 6).is_some());
+        assert_eq!(manager.active(), Index::B);
+
+        // Dragging the separator resizes the split without changing the active container.
+        assert!(drag(&mut manager, 4, 2).is_none());
+        assert_eq!(manager.active(), Index::B);
+        draw(&manager, &mut app, (7, 1)).assert_looks_like("x┃x    ");
+    }
+
+    #[test]
+    fn test_mouse_ignores_separators_under_overlays_and_grabs() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+        ])));
+        manager.push_overlay(
+            Index::C,
+            OverlayPlacement::Fixed(Rectangle::new(2.into()..5.into(), 0.into()..1.into())),
+        );
+        draw(&manager, &mut app, (7, 1));
+
+        // The press on the separator (at x = 3, i.e., 4 in 1-based mouse coordinates) is meant
+        // for the overlay above it.
+        assert!(drag(&mut manager, 4, 2).is_some());
+        assert_eq!(manager.pop_overlay(), Some(Index::C));
+        draw(&manager, &mut app, (7, 1)).assert_looks_like("x  ┃x  ");
+
+        manager.input_grab().grab(Index::A);
+        assert!(drag(&mut manager, 4, 2).is_some());
+        manager.input_grab().release();
+        draw(&manager, &mut app, (7, 1)).assert_looks_like("x  ┃x  ");
+
+        assert!(drag(&mut manager, 4, 2).is_none());
+        draw(&manager, &mut app, (7, 1)).assert_looks_like("x┃x    ");
+    }
+
+    fn draw(manager: &ContainerManager<App>, app: &mut App, size: (u32, u32)) -> FakeTerminal {
+        let mut term = FakeTerminal::with_size(size);
+        manager.draw(
+            term.create_root_window(),
+            app,
+            StyleModifier::new(),
+            RenderingHints::default(),
+        );
+        term
+    }
+
+    fn hash_separator(thickness: usize) -> SplitSeparator {
+        SplitSeparator::custom(thickness, |mut window: Window, _| {
+            window.fill(GraphemeCluster::try_from('#').unwrap())
+        })
+    }
+
+    #[test]
+    fn test_split_separator_layout() {
+        let area = Rectangle {
+            x_range: 0.into()..10.into(),
+            y_range: 0.into()..2.into(),
+        };
+        let app = App::new();
+        let split = HSplit::new(vec![(leaf(Index::A), 1.0), (leaf(Index::B), 1.0)])
+            .separator(hash_separator(2));
+        let output = split.layout(area.clone(), &app);
+        assert_eq!(
+            output.windows,
+            vec![
+                (Index::A, area.slice_range_x(0.into()..4.into())),
+                (Index::B, area.slice_range_x(6.into()..10.into())),
+            ]
+        );
+        match output.separators.as_slice() {
+            [Separator::Custom(rect, _)] => {
+                assert_eq!(*rect, area.slice_range_x(4.into()..6.into()))
+            }
+            _ => panic!("expected a single custom separator"),
+        }
+
+        let split = VSplit::new(vec![(leaf(Index::A), 1.0), (leaf(Index::B), 1.0)])
+            .separator(hash_separator(0));
+        let output = split.layout(area.clone(), &app);
+        assert_eq!(
+            output.windows,
+            vec![
+                (Index::A, area.slice_range_y(0.into()..1.into())),
+                (Index::B, area.slice_range_y(1.into()..2.into())),
+            ]
+        );
+
+        let split = HSplit::new(vec![(leaf(Index::A), 1.0), (leaf(Index::B), 1.0)]);
+        match split.layout(area.clone(), &app).separators.as_slice() {
+            [Separator::Line(line)] => assert_eq!(*line, area.slice_line_x(5.into()).into()),
+            _ => panic!("expected a single line separator"),
+        }
+    }
+
+    #[test]
+    fn test_split_separator_demand() {
+        let app = App::new();
+        let split = HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+            (leaf(Index::C), 1.0),
+        ])
+        .separator(hash_separator(3));
+        let leaf_demand = Leaf::<App>::new(Index::A).space_demand(&app).width;
+        assert_eq!(
+            split.space_demand(&app).width,
+            leaf_demand + leaf_demand + leaf_demand + ColDemand::exact(6)
+        );
+    }
+
+    #[test]
+    fn test_draw_custom_separator() {
+        let mut app = App::new();
+        let manager = ContainerManager::<App>::from_layout(Box::new(
+            HSplit::new(vec![(leaf(Index::A), 1.0), (leaf(Index::B), 1.0)])
+                .separator(hash_separator(2)),
+        ));
+        draw(&manager, &mut app, (6, 1)).assert_looks_like("x ##x ");
+    }
+
+    #[test]
+    fn test_navigate_across_thick_separator() {
+        // A | B (separated by 3 columns)
+        // -----
+        // C | D
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(VSplit::new(vec![
+            (
+                Box::new(
+                    HSplit::new(vec![(leaf(Index::A), 1.0), (leaf(Index::B), 1.0)])
+                        .separator(hash_separator(3)),
+                ),
+                1.0,
+            ),
+            (
+                Box::new(HSplit::new(vec![
+                    (leaf(Index::C), 1.0),
+                    (leaf(Index::C), 1.0),
+                ])),
+                1.0,
+            ),
+        ])));
+        draw(&manager, &mut app, (11, 3));
+
+        // The right half of the bottom row is closer to A than B, but does not touch A.
+        manager.navigatable(&mut app).move_right().unwrap();
+        assert_eq!(manager.active(), Index::B);
+        manager.navigatable(&mut app).move_left().unwrap();
+        assert_eq!(manager.active(), Index::A);
+        assert!(manager.navigatable(&mut app).move_up().is_err());
+        assert_eq!(manager.active(), Index::A);
+    }
+
+    #[test]
+    fn test_navigate_to_touching_neighbors_only() {
+        let mut app = App::new();
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(HSplit::new(vec![
+            (leaf(Index::A), 1.0),
+            (leaf(Index::B), 1.0),
+            (leaf(Index::C), 1.0),
+        ])));
+        draw(&manager, &mut app, (8, 2));
+
+        // C overlaps A just as much as B, but B lies in between.
+        manager.navigatable(&mut app).move_right().unwrap();
+        assert_eq!(manager.active(), Index::B);
+        manager.navigatable(&mut app).move_right().unwrap();
+        assert_eq!(manager.active(), Index::C);
+        assert!(manager.navigatable(&mut app).move_right().is_err());
+        assert!(manager.navigatable(&mut app).move_down().is_err());
+        manager.navigatable(&mut app).move_left().unwrap();
+        assert_eq!(manager.active(), Index::B);
+    }
+
+    fn split_config(size: SplitSize, collapsed: Option<bool>) -> SplitChildConfig {
+        SplitChildConfig {
+            size,
+            collapse: collapsed.map(CollapseToggle::new),
+        }
+    }
+
+    fn split(available: i32, demands: &[ColDemand], configs: &[SplitChildConfig]) -> Vec<i32> {
+        layout_split(
+            Width::new(available).unwrap(),
+            Width::new(1).unwrap(),
+            demands,
+            &vec![1.0; demands.len()],
+            configs,
+        )
+        .iter()
+        .map(|w| w.raw_value())
+        .collect()
+    }
+
+    #[test]
+    fn test_layout_split_sizes() {
+        let any = ColDemand::at_least(0);
+        let weighted = split_config(SplitSize::Weighted, None);
+
+        assert_eq!(
+            split(
+                20,
+                &[any, any, any],
+                &[
+                    split_config(SplitSize::Fixed(5), None),
+                    weighted.clone(),
+                    weighted.clone()
+                ]
+            ),
+            vec![5, 7, 6]
+        );
+        assert_eq!(
+            split(
+                21,
+                &[any, any],
+                &[
+                    split_config(SplitSize::Percentage(25.0), None),
+                    weighted.clone()
+                ]
+            ),
+            vec![5, 15]
+        );
+        assert_eq!(
+            split(
+                10,
+                &[any, any],
+                &[
+                    split_config(SplitSize::Percentage(150.0), None),
+                    weighted.clone()
+                ]
+            ),
+            vec![9, 0]
+        );
+        assert_eq!(
+            split(
+                20,
+                &[ColDemand::from_to(2, 4), ColDemand::at_least(3), any],
+                &[
+                    split_config(SplitSize::Auto, None),
+                    split_config(SplitSize::Auto, None),
+                    weighted.clone()
+                ]
+            ),
+            vec![4, 3, 11]
+        );
+    }
+
+    #[test]
+    fn test_layout_split_collapsed() {
+        let any = ColDemand::at_least(0);
+        let weighted = split_config(SplitSize::Weighted, None);
+        assert_eq!(
+            split(
+                12,
+                &[any, any, any],
+                &[
+                    split_config(SplitSize::Fixed(5), Some(true)),
+                    split_config(SplitSize::Weighted, Some(false)),
+                    weighted.clone()
+                ]
+            ),
+            vec![1, 5, 4]
+        );
+    }
+
+    #[test]
+    fn test_layout_split_overflow() {
+        let any = ColDemand::at_least(0);
+        let weighted = split_config(SplitSize::Weighted, None);
+
+        // Sized children are assigned space from left to right as long as there is some left.
+        assert_eq!(
+            split(
+                8,
+                &[any, any, any],
+                &[
+                    split_config(SplitSize::Fixed(5), None),
+                    split_config(SplitSize::Fixed(5), None),
+                    weighted.clone()
+                ]
+            ),
+            vec![5, 1, 0]
+        );
+        assert_eq!(
+            split(
+                3,
+                &[any, any, any],
+                &[
+                    split_config(SplitSize::Auto, None),
+                    split_config(SplitSize::Weighted, Some(true)),
+                    weighted.clone()
+                ]
+            ),
+            vec![0, 1, 0]
+        );
+        assert_eq!(
+            split(
+                1,
+                &[any, any, any],
+                &[weighted.clone(), weighted.clone(), weighted.clone()]
+            ),
+            vec![0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_collapsed_child() {
+        let mut app = App::new();
+        let toggle = CollapseToggle::new(true);
+        let mut manager = ContainerManager::<App>::from_layout(Box::new(
+            HSplit::new(vec![(leaf(Index::A), 1.0), (leaf(Index::B), 1.0)])
+                .collapsible(1, toggle.clone()),
+        ));
+        let area = Rectangle {
+            x_range: 0.into()..8.into(),
+            y_range: 0.into()..1.into(),
+        };
+        let output = manager.layout.layout(area.clone(), &app);
+        assert_eq!(
+            output.windows,
+            vec![(Index::A, area.slice_range_x(0.into()..6.into()))]
+        );
+        match output.separators.as_slice() {
+            [Separator::Line(_), Separator::Collapsed(rect, _)] => {
+                assert_eq!(*rect, area.slice_range_x(7.into()..8.into()))
+            }
+            _ => panic!("expected a line and a placeholder"),
+        }
+        assert_eq!(manager.layout.containers(), vec![Index::A, Index::B]);
+
+        // Navigating onto the placeholder expands the child and activates it.
+        draw(&manager, &mut app, (8, 1));
+        manager.navigatable(&mut app).move_right().unwrap();
+        assert!(!toggle.is_collapsed());
+        assert_eq!(manager.active(), Index::B);
+
+        toggle.toggle();
+        assert!(toggle.is_collapsed());
+    }
+}
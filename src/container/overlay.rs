@@ -0,0 +1,119 @@
+//! Placement of floating containers (e.g., dialogs or popups) on top of the layout of a
+//! `ContainerManager` (see `ContainerManager::push_overlay`).
+use super::Rectangle;
+use base::basic_types::*;
+use widget::Demand2D;
+
+/// A corner of the window that an overlay can be anchored to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlayAnchor {
+    /// The top left corner of the window.
+    TopLeft,
+    /// The top right corner of the window.
+    TopRight,
+    /// The bottom left corner of the window.
+    BottomLeft,
+    /// The bottom right corner of the window.
+    BottomRight,
+}
+
+/// Where an overlay is placed in the window of the `ContainerManager`.
+///
+/// Overlays never exceed the window, i.e., they are shrunk (or moved, if centered or anchored) if
+/// the window is too small.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OverlayPlacement {
+    /// Occupy a fixed area of the window.
+    Fixed(Rectangle),
+    /// Center an area with the given size in the window.
+    Centered(Width, Height),
+    /// Center an area in the window whose size is the maximum demand of the widget of the
+    /// container (or its minimum demand, if the maximum is unbounded).
+    CenteredFit,
+    /// Place an area with the given size in a corner of the window.
+    Anchored(OverlayAnchor, Width, Height),
+}
+
+impl OverlayPlacement {
+    /// Calculate the area of the overlay in `window` for the (current) `demand` of its widget.
+    pub fn area(&self, window: &Rectangle, demand: Demand2D) -> Rectangle {
+        let (anchor, width, height) = match *self {
+            OverlayPlacement::Fixed(ref rect) => {
+                return rect.intersection(window).unwrap_or_else(|| {
+                    Rectangle::new(
+                        window.x_range.start..window.x_range.start,
+                        window.y_range.start..window.y_range.start,
+                    )
+                });
+            }
+            OverlayPlacement::Centered(width, height) => (None, width, height),
+            OverlayPlacement::CenteredFit => (
+                None,
+                demand.width.max.unwrap_or(demand.width.min),
+                demand.height.max.unwrap_or(demand.height.min),
+            ),
+            OverlayPlacement::Anchored(anchor, width, height) => (Some(anchor), width, height),
+        };
+        let width = width.min(window.width());
+        let height = height.min(window.height());
+        let (x, y) = match anchor {
+            None => (
+                window.x_range.start + (window.width() - width) / 2,
+                window.y_range.start + (window.height() - height) / 2,
+            ),
+            Some(OverlayAnchor::TopLeft) => (window.x_range.start, window.y_range.start),
+            Some(OverlayAnchor::TopRight) => (window.x_range.end - width, window.y_range.start),
+            Some(OverlayAnchor::BottomLeft) => (window.x_range.start, window.y_range.end - height),
+            Some(OverlayAnchor::BottomRight) => {
+                (window.x_range.end - width, window.y_range.end - height)
+            }
+        };
+        Rectangle::new(x..x + width, y..y + height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use widget::Demand;
+
+    fn rect(x: (i32, i32), y: (i32, i32)) -> Rectangle {
+        Rectangle::new(
+            ColIndex::new(x.0)..ColIndex::new(x.1),
+            RowIndex::new(y.0)..RowIndex::new(y.1),
+        )
+    }
+
+    #[test]
+    fn test_overlay_placement_area() {
+        let window = rect((0, 10), (0, 5));
+        let demand = Demand2D {
+            width: Demand::from_to(2, 4),
+            height: Demand::at_least(1),
+        };
+        let w = |w| Width::new(w).unwrap();
+        let h = |h| Height::new(h).unwrap();
+        let area = |placement: OverlayPlacement| placement.area(&window, demand);
+        assert_eq!(
+            area(OverlayPlacement::Centered(w(4), h(3))),
+            rect((3, 7), (1, 4))
+        );
+        assert_eq!(
+            area(OverlayPlacement::Centered(w(20), h(1))),
+            rect((0, 10), (2, 3))
+        );
+        assert_eq!(area(OverlayPlacement::CenteredFit), rect((3, 7), (2, 3)));
+        assert_eq!(
+            area(OverlayPlacement::Anchored(
+                OverlayAnchor::BottomRight,
+                w(3),
+                h(2)
+            )),
+            rect((7, 10), (3, 5))
+        );
+        assert_eq!(
+            area(OverlayPlacement::Fixed(rect((8, 12), (-1, 2)))),
+            rect((8, 10), (0, 2))
+        );
+    }
+}
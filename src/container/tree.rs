@@ -0,0 +1,491 @@
+//! A layout of containers that can be modified at runtime, similar to a tiling window manager.
+//!
+//! A `LayoutTree` is a shared handle: Pass a clone to `ContainerManager::from_layout` and keep
+//! another one to split, close, swap or rotate containers later on (see also
+//! `ContainerManager::split_active` and `ContainerManager::close_active`).
+//!
+//! # Examples:
+//! ```
+//! use unsegen::container::*;
+//!
+//! let tree = LayoutTree::new('a');
+//! tree.split(&'a', SplitDirection::Horizontal, 'b').unwrap();
+//! tree.split(&'b', SplitDirection::Vertical, 'c').unwrap();
+//! assert_eq!(format!("{:?}", tree), "(1*'a'|1*(1*'b'-1*'c'))");
+//!
+//! tree.rotate(&'b').unwrap();
+//! assert_eq!(tree.leaves(), vec!['a', 'c', 'b']);
+//!
+//! // Closing a container returns the container that takes its place:
+//! assert_eq!(tree.close(&'a'), Ok('c'));
+//! assert_eq!(format!("{:?}", tree), "(1*'c'-1*'b')");
+//! ```
+use super::{ContainerProvider, HSplit, Layout, LayoutOutput, Leaf, Rectangle, VSplit};
+use base::basic_types::*;
+use input::OperationResult;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use widget::{Demand2D, RowDemand};
+
+/// The direction in which the children of a split in a `LayoutTree` are arranged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// Children are placed from left to right (like in a `HSplit`).
+    Horizontal,
+    /// Children are placed from top to bottom (like in a `VSplit`).
+    Vertical,
+}
+
+/// A handle to a node of a `LayoutTree`.
+///
+/// Handles stay valid until the node is removed from the tree (e.g., because the container was
+/// closed or a split was left with a single child). Handles of removed nodes never refer to nodes
+/// that are inserted later on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: usize,
+    generation: usize,
+}
+
+/// The content of a node of a `LayoutTree` (see `LayoutTree::node`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutNode<I> {
+    /// A container.
+    Leaf(I),
+    /// A split with its children (and their weights) in order.
+    Split(SplitDirection, Vec<(NodeId, f64)>),
+}
+
+struct Node<I> {
+    parent: Option<NodeId>,
+    content: LayoutNode<I>,
+}
+
+/// A slot for a node. The generation is incremented whenever the node is removed, so that the
+/// slot can be reused without stale `NodeId`s referring to the new node.
+struct Slot<I> {
+    generation: usize,
+    node: Option<Node<I>>,
+}
+
+struct Nodes<I> {
+    slots: Vec<Slot<I>>,
+    root: NodeId,
+}
+
+impl<I: Clone + PartialEq> Nodes<I> {
+    fn lookup(&self, id: NodeId) -> Option<&Node<I>> {
+        self.slots
+            .get(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_ref())
+    }
+
+    fn get(&self, id: NodeId) -> &Node<I> {
+        self.lookup(id).expect("valid node id")
+    }
+
+    fn get_mut(&mut self, id: NodeId) -> &mut Node<I> {
+        let slot = &mut self.slots[id.index];
+        assert_eq!(slot.generation, id.generation, "valid node id");
+        slot.node.as_mut().expect("valid node id")
+    }
+
+    fn insert(&mut self, node: Node<I>) -> NodeId {
+        let index = match self.slots.iter().position(|slot| slot.node.is_none()) {
+            Some(free) => free,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node: None,
+                });
+                self.slots.len() - 1
+            }
+        };
+        let slot = &mut self.slots[index];
+        slot.node = Some(node);
+        NodeId {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    fn remove(&mut self, id: NodeId) {
+        let slot = &mut self.slots[id.index];
+        slot.node = None;
+        slot.generation += 1;
+    }
+
+    fn find(&self, index: &I) -> Option<NodeId> {
+        self.slots
+            .iter()
+            .enumerate()
+            .find(|(_, slot)| match slot.node {
+                Some(Node {
+                    content: LayoutNode::Leaf(ref i),
+                    ..
+                }) => i == index,
+                _ => false,
+            })
+            .map(|(i, slot)| NodeId {
+                index: i,
+                generation: slot.generation,
+            })
+    }
+
+    fn children_mut(&mut self, id: NodeId) -> &mut Vec<(NodeId, f64)> {
+        match self.get_mut(id).content {
+            LayoutNode::Split(_, ref mut children) => children,
+            LayoutNode::Leaf(_) => panic!("Parent is not a split"),
+        }
+    }
+
+    fn position_in_parent(&self, id: NodeId) -> Option<(NodeId, usize)> {
+        let parent = self.get(id).parent?;
+        match self.get(parent).content {
+            LayoutNode::Split(_, ref children) => children
+                .iter()
+                .position(|(child, _)| *child == id)
+                .map(|pos| (parent, pos)),
+            LayoutNode::Leaf(_) => None,
+        }
+    }
+
+    /// Put `new` at the place of `old` in the tree (or make it the root).
+    fn replace(&mut self, old: NodeId, new: NodeId) {
+        let position = self.position_in_parent(old);
+        self.get_mut(new).parent = position.map(|(parent, _)| parent);
+        match position {
+            Some((parent, pos)) => self.children_mut(parent)[pos].0 = new,
+            None => self.root = new,
+        }
+    }
+
+    fn first_leaf(&self, id: NodeId) -> I {
+        match self.get(id).content {
+            LayoutNode::Leaf(ref index) => index.clone(),
+            LayoutNode::Split(_, ref children) => self.first_leaf(children[0].0),
+        }
+    }
+
+    fn leaves(&self, id: NodeId, leaves: &mut Vec<I>) {
+        match self.get(id).content {
+            LayoutNode::Leaf(ref index) => leaves.push(index.clone()),
+            LayoutNode::Split(_, ref children) => {
+                for (child, _) in children {
+                    self.leaves(*child, leaves);
+                }
+            }
+        }
+    }
+
+    fn build<'a, C: ContainerProvider<Index = I> + 'a>(&self, id: NodeId) -> Box<dyn Layout<C> + 'a>
+    where
+        I: 'a,
+    {
+        match self.get(id).content {
+            LayoutNode::Leaf(ref index) => Box::new(Leaf::new(index.clone())),
+            LayoutNode::Split(direction, ref children) => {
+                let children = children
+                    .iter()
+                    .map(|&(child, weight)| (self.build(child), weight))
+                    .collect();
+                match direction {
+                    SplitDirection::Horizontal => Box::new(HSplit::new(children)),
+                    SplitDirection::Vertical => Box::new(VSplit::new(children)),
+                }
+            }
+        }
+    }
+
+    fn fmt(&self, id: NodeId, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    where
+        I: fmt::Debug,
+    {
+        match self.get(id).content {
+            LayoutNode::Leaf(ref index) => write!(f, "{:?}", index),
+            LayoutNode::Split(direction, ref children) => {
+                let separator = match direction {
+                    SplitDirection::Horizontal => "|",
+                    SplitDirection::Vertical => "-",
+                };
+                write!(f, "(")?;
+                for (i, &(child, weight)) in children.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", separator)?;
+                    }
+                    write!(f, "{}*", weight)?;
+                    self.fmt(child, f)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// A shared, modifiable tree of horizontal and vertical splits with containers as leaves.
+///
+/// All operations refer to containers by their index and fail if the container is not part of
+/// the tree. Each container should appear at most once.
+pub struct LayoutTree<I>(Rc<RefCell<Nodes<I>>>);
+
+impl<I> Clone for LayoutTree<I> {
+    fn clone(&self) -> Self {
+        LayoutTree(self.0.clone())
+    }
+}
+
+impl<I: Clone + PartialEq> LayoutTree<I> {
+    /// Create a tree that only consists of the container `root`.
+    pub fn new(root: I) -> Self {
+        LayoutTree(Rc::new(RefCell::new(Nodes {
+            slots: vec![Slot {
+                generation: 0,
+                node: Some(Node {
+                    parent: None,
+                    content: LayoutNode::Leaf(root),
+                }),
+            }],
+            root: NodeId {
+                index: 0,
+                generation: 0,
+            },
+        })))
+    }
+
+    /// Get the root node of the tree.
+    pub fn root(&self) -> NodeId {
+        self.0.borrow().root
+    }
+
+    /// Get the node of the container `index`.
+    pub fn find(&self, index: &I) -> Option<NodeId> {
+        self.0.borrow().find(index)
+    }
+
+    /// Get the content of a node. Returns `None` if the node has been removed.
+    pub fn node(&self, id: NodeId) -> Option<LayoutNode<I>> {
+        self.0.borrow().lookup(id).map(|node| node.content.clone())
+    }
+
+    /// Get the split that contains the node. Returns `None` for the root (or a removed node).
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.0.borrow().lookup(id).and_then(|node| node.parent)
+    }
+
+    /// Get all containers of the tree, from left to right and top to bottom.
+    pub fn leaves(&self) -> Vec<I> {
+        let nodes = self.0.borrow();
+        let mut leaves = Vec::new();
+        nodes.leaves(nodes.root, &mut leaves);
+        leaves
+    }
+
+    /// Split the area of the container `index` in two and place the container `new` right of
+    /// (or below) it.
+    ///
+    /// If the container already is part of a split in the same direction, `new` is added to that
+    /// split instead of creating a nested one. Either way, both containers share the space that
+    /// was assigned to `index` before.
+    pub fn split(&self, index: &I, direction: SplitDirection, new: I) -> OperationResult {
+        let mut nodes = self.0.borrow_mut();
+        let leaf = nodes.find(index).ok_or(())?;
+        let position = nodes.position_in_parent(leaf);
+        let parent_direction = position.map(|(parent, _)| match nodes.get(parent).content {
+            LayoutNode::Split(direction, _) => direction,
+            LayoutNode::Leaf(_) => unreachable!("Parent is not a split"),
+        });
+        match position {
+            Some((parent, pos)) if parent_direction == Some(direction) => {
+                let new = nodes.insert(Node {
+                    parent: Some(parent),
+                    content: LayoutNode::Leaf(new),
+                });
+                let children = nodes.children_mut(parent);
+                let weight = children[pos].1 / 2.0;
+                children[pos].1 = weight;
+                children.insert(pos + 1, (new, weight));
+            }
+            _ => {
+                let split = nodes.insert(Node {
+                    parent: None,
+                    content: LayoutNode::Split(direction, Vec::new()),
+                });
+                nodes.replace(leaf, split);
+                let new = nodes.insert(Node {
+                    parent: Some(split),
+                    content: LayoutNode::Leaf(new),
+                });
+                nodes.get_mut(leaf).parent = Some(split);
+                *nodes.children_mut(split) = vec![(leaf, 1.0), (new, 1.0)];
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the container `index` from the tree and return the container that takes its place
+    /// (e.g., to activate it). Splits that are left with a single child are replaced by that
+    /// child.
+    ///
+    /// Fails if `index` is the only container of the tree.
+    pub fn close(&self, index: &I) -> Result<I, ()> {
+        let mut nodes = self.0.borrow_mut();
+        let leaf = nodes.find(index).ok_or(())?;
+        let (parent, pos) = nodes.position_in_parent(leaf).ok_or(())?;
+        nodes.remove(leaf);
+        let children = nodes.children_mut(parent);
+        children.remove(pos);
+        let successor = children[pos.min(children.len() - 1)].0;
+        if children.len() == 1 {
+            let child = children[0].0;
+            nodes.replace(parent, child);
+            nodes.remove(parent);
+        }
+        Ok(nodes.first_leaf(successor))
+    }
+
+    /// Exchange the positions of the containers `a` and `b`.
+    pub fn swap(&self, a: &I, b: &I) -> OperationResult {
+        let mut nodes = self.0.borrow_mut();
+        let a_node = nodes.find(a).ok_or(())?;
+        let b_node = nodes.find(b).ok_or(())?;
+        nodes.get_mut(a_node).content = LayoutNode::Leaf(b.clone());
+        nodes.get_mut(b_node).content = LayoutNode::Leaf(a.clone());
+        Ok(())
+    }
+
+    /// Rotate the children of the split that contains the container `index` by one position,
+    /// i.e., the last child becomes the first one. Fails if `index` is the only container.
+    pub fn rotate(&self, index: &I) -> OperationResult {
+        let mut nodes = self.0.borrow_mut();
+        let leaf = nodes.find(index).ok_or(())?;
+        let (parent, _) = nodes.position_in_parent(leaf).ok_or(())?;
+        nodes.children_mut(parent).rotate_right(1);
+        Ok(())
+    }
+
+    /// Change the direction of the split that contains the container `index`. Fails if `index`
+    /// is the only container.
+    pub fn set_direction(&self, index: &I, direction: SplitDirection) -> OperationResult {
+        let mut nodes = self.0.borrow_mut();
+        let leaf = nodes.find(index).ok_or(())?;
+        let (parent, _) = nodes.position_in_parent(leaf).ok_or(())?;
+        if let LayoutNode::Split(ref mut d, _) = nodes.get_mut(parent).content {
+            *d = direction;
+        }
+        Ok(())
+    }
+
+    fn build<'a, C: ContainerProvider<Index = I> + 'a>(&self) -> Box<dyn Layout<C> + 'a>
+    where
+        I: 'a,
+    {
+        let nodes = self.0.borrow();
+        nodes.build(nodes.root)
+    }
+}
+
+/// Splits are written as `(w*child|w*child)` (or with `-` for vertical splits), where `w` is the
+/// weight of the child.
+impl<I: Clone + PartialEq + fmt::Debug> fmt::Debug for LayoutTree<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nodes = self.0.borrow();
+        nodes.fmt(nodes.root, f)
+    }
+}
+
+/// The tree is converted into `HSplit`s, `VSplit`s and `Leaf`s (with default settings) for each
+/// operation. Dragging a `ResizeHandle` changes the weights of the tree.
+impl<C: ContainerProvider> Layout<C> for LayoutTree<C::Index> {
+    fn space_demand(&self, containers: &C) -> Demand2D {
+        self.build::<C>().space_demand(containers)
+    }
+    fn height_for_width(&self, width: Width, containers: &C) -> RowDemand {
+        self.build::<C>().height_for_width(width, containers)
+    }
+    fn layout(&self, available_area: Rectangle, containers: &C) -> LayoutOutput<C::Index> {
+        self.build::<C>().layout(available_area, containers)
+    }
+    fn containers(&self) -> Vec<C::Index> {
+        self.leaves()
+    }
+    fn resize_split(&self, path: &[usize], index: usize, weights: (f64, f64)) -> OperationResult {
+        let mut nodes = self.0.borrow_mut();
+        let mut id = nodes.root;
+        for &position in path {
+            id = match nodes.get(id).content {
+                LayoutNode::Split(_, ref children) => children.get(position).ok_or(())?.0,
+                LayoutNode::Leaf(_) => return Err(()),
+            };
+        }
+        match nodes.get_mut(id).content {
+            LayoutNode::Split(_, ref mut children) if index + 1 < children.len() => {
+                children[index].1 = weights.0;
+                children[index + 1].1 = weights.1;
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_layout_tree_split_and_close() {
+        let tree = LayoutTree::new(0);
+        assert!(tree.close(&0).is_err());
+        assert!(tree.split(&5, SplitDirection::Vertical, 1).is_err());
+
+        tree.split(&0, SplitDirection::Vertical, 1).unwrap();
+        tree.split(&0, SplitDirection::Vertical, 2).unwrap();
+        assert_eq!(format!("{:?}", tree), "(0.5*0-0.5*2-1*1)");
+        let split = tree.parent(tree.find(&2).unwrap()).unwrap();
+        assert_eq!(split, tree.root());
+        match tree.node(split) {
+            Some(LayoutNode::Split(SplitDirection::Vertical, children)) => {
+                let weights = children.iter().map(|(_, w)| *w).collect::<Vec<_>>();
+                assert_eq!(weights, vec![0.5, 0.5, 1.0]);
+            }
+            other => panic!("unexpected node {:?}", other),
+        }
+
+        tree.split(&2, SplitDirection::Horizontal, 3).unwrap();
+        assert_eq!(format!("{:?}", tree), "(0.5*0-0.5*(1*2|1*3)-1*1)");
+        tree.swap(&0, &3).unwrap();
+        tree.set_direction(&0, SplitDirection::Vertical).unwrap();
+        assert_eq!(format!("{:?}", tree), "(0.5*3-0.5*(1*2-1*0)-1*1)");
+
+        assert_eq!(tree.close(&1), Ok(2));
+        assert_eq!(tree.close(&3), Ok(2));
+        assert_eq!(format!("{:?}", tree), "(1*2-1*0)");
+        assert_eq!(tree.close(&2), Ok(0));
+        assert_eq!(tree.leaves(), vec![0]);
+        assert_eq!(tree.parent(tree.root()), None);
+        assert!(tree.node(split).is_none());
+    }
+
+    #[test]
+    fn test_layout_tree_stale_node_ids() {
+        let tree = LayoutTree::new(0);
+        tree.split(&0, SplitDirection::Horizontal, 1).unwrap();
+        let split = tree.root();
+        let closed = tree.find(&1).unwrap();
+        assert_eq!(tree.close(&1), Ok(0));
+        assert!(tree.node(closed).is_none());
+        assert!(tree.node(split).is_none());
+
+        // The freed slots are reused by the new nodes, but the old ids stay invalid.
+        tree.split(&0, SplitDirection::Vertical, 2).unwrap();
+        assert_eq!(tree.leaves(), vec![0, 2]);
+        assert!(tree.node(closed).is_none());
+        assert!(tree.node(split).is_none());
+        assert_eq!(tree.parent(closed), None);
+        assert_ne!(tree.find(&2), Some(closed));
+        assert_eq!(tree.node(tree.find(&2).unwrap()), Some(LayoutNode::Leaf(2)));
+        assert_eq!(tree.parent(tree.find(&2).unwrap()), Some(tree.root()));
+    }
+}
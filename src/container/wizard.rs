@@ -0,0 +1,359 @@
+//! A container that guides the user through a sequence of steps, e.g., in installers or setup
+//! flows.
+//!
+//! # Example:
+//! ```
+//! use unsegen::base::terminal::test::FakeTerminal;
+//! use unsegen::container::*;
+//! use unsegen::input::*;
+//! use unsegen::widget::builtin::LineEdit;
+//! use unsegen::widget::*;
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! #[derive(Default, Debug, PartialEq)]
+//! struct Settings {
+//!     name: String,
+//! }
+//!
+//! struct NameStep(LineEdit);
+//! impl Container<()> for NameStep {
+//!     fn input(&mut self, input: Input, _: &mut ()) -> Option<Input> {
+//!         input.chain(EditBehavior::new(&mut self.0)).finish()
+//!     }
+//!     fn as_widget<'a>(&'a self) -> Box<dyn Widget + 'a> {
+//!         Box::new(self.0.as_widget())
+//!     }
+//! }
+//! impl WizardStep<(), Settings> for NameStep {
+//!     fn title(&self) -> &str {
+//!         "Name"
+//!     }
+//!     fn validate(&self) -> Result<(), String> {
+//!         if self.0.get().is_empty() {
+//!             Err("Enter a name".to_owned())
+//!         } else {
+//!             Ok(())
+//!         }
+//!     }
+//!     fn collect(&self, result: &mut Settings) {
+//!         result.name = self.0.get().to_owned();
+//!     }
+//! }
+//!
+//! let finished = Rc::new(RefCell::new(None));
+//! let finished_clone = finished.clone();
+//! let mut wizard = Wizard::new(move |settings: Settings, _: &mut ()| {
+//!     *finished_clone.borrow_mut() = Some(settings)
+//! })
+//! .step(NameStep(LineEdit::new()));
+//!
+//! let mut input = |wizard: &mut Wizard<(), Settings>, event: Key| {
+//!     let input = Input::new(event);
+//!     wizard.input(input, &mut ())
+//! };
+//! input(&mut wizard, Key::Char('\n'));
+//! let mut term = FakeTerminal::with_size((12, 3));
+//! wizard.as_widget().draw(term.create_root_window(), RenderingHints::new());
+//! term.assert_looks_like("*●**N**a**m**e*       |            |Enter a name");
+//!
+//! input(&mut wizard, Key::Char('a'));
+//! input(&mut wizard, Key::Char('\n'));
+//! assert_eq!(*finished.borrow(), Some(Settings { name: "a".to_owned() }));
+//! assert!(wizard.is_finished());
+//! ```
+use super::{Container, Events};
+use base::{rendering_profile, Cursor, StyleModifier, Window};
+use input::{Event, Input, Key, OperationResult, ToEvent};
+use widget::{text_width, Demand, Demand2D, RenderingHints, VLayout, Widget};
+
+/// A single step of a `Wizard`.
+///
+/// `C` is the context of the container and `R` is the result of the wizard, to which each step
+/// contributes when the wizard is finished.
+pub trait WizardStep<C: ?Sized, R>: Container<C> {
+    /// The title of the step that is displayed in the `StepIndicator`.
+    fn title(&self) -> &str;
+    /// Check whether the wizard can proceed to the next step. The error message is displayed
+    /// below the step. (Default: always valid.)
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+    /// Store the input of the step in the result of the wizard.
+    fn collect(&self, result: &mut R);
+}
+
+type FinishCallback<'a, C, R> = Box<dyn FnMut(R, &mut C) + 'a>;
+
+/// A container that displays one of several steps at a time together with a `StepIndicator` above
+/// and the result of the last validation below.
+///
+/// Input that is not consumed by the current step can be used to navigate to the next step
+/// (default: enter) or the previous step (default: escape). Proceeding from the last step
+/// finishes the wizard: The result (starting from `R::default()`) is collected from all steps and
+/// passed to the callback specified in `new`.
+pub struct Wizard<'a, C: ?Sized, R> {
+    steps: Vec<Box<dyn WizardStep<C, R> + 'a>>,
+    current: usize,
+    error: Option<String>,
+    finished: bool,
+    on_finish: FinishCallback<'a, C, R>,
+    next_on: Vec<Event>,
+    back_on: Vec<Event>,
+}
+
+impl<'a, C: ?Sized, R: Default> Wizard<'a, C, R> {
+    /// Create a wizard without steps that calls `on_finish` with the collected result once it is
+    /// finished. Add steps using `step`.
+    pub fn new<F: FnMut(R, &mut C) + 'a>(on_finish: F) -> Self {
+        Wizard {
+            steps: Vec::new(),
+            current: 0,
+            error: None,
+            finished: false,
+            on_finish: Box::new(on_finish),
+            next_on: vec![Key::Char('\n').to_event()],
+            back_on: vec![Key::Esc.to_event()],
+        }
+    }
+
+    /// Add a step after all previously added steps.
+    pub fn step<S: WizardStep<C, R> + 'a>(mut self, step: S) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Navigate to the next step on the specified event (in addition to the default: enter).
+    pub fn next_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.next_on.push(event.to_event());
+        self
+    }
+
+    /// Navigate to the previous step on the specified event (in addition to the default: escape).
+    pub fn back_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.back_on.push(event.to_event());
+        self
+    }
+
+    /// Get the index of the current step.
+    pub fn current_step(&self) -> usize {
+        self.current
+    }
+
+    /// Check whether the wizard has been finished (see `next`).
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Validate the current step and proceed to the next one or finish the wizard if the current
+    /// step is the last one. Fails if the validation fails or the wizard is already finished.
+    pub fn next(&mut self, context: &mut C) -> OperationResult {
+        if self.finished || self.steps.is_empty() {
+            return Err(());
+        }
+        if let Err(msg) = self.steps[self.current].validate() {
+            self.error = Some(msg);
+            return Err(());
+        }
+        self.error = None;
+        if self.current + 1 < self.steps.len() {
+            self.switch_to(self.current + 1);
+        } else {
+            let mut result = R::default();
+            for step in &self.steps {
+                step.collect(&mut result);
+            }
+            self.finished = true;
+            (self.on_finish)(result, context);
+        }
+        Ok(())
+    }
+
+    /// Return to the previous step. Fails if the current step is the first one.
+    pub fn back(&mut self) -> OperationResult {
+        if self.current == 0 {
+            return Err(());
+        }
+        self.error = None;
+        self.finished = false;
+        self.switch_to(self.current - 1);
+        Ok(())
+    }
+
+    fn switch_to(&mut self, index: usize) {
+        self.steps[self.current].on_focus_lost();
+        self.current = index;
+        self.steps[self.current].on_focus_gained();
+    }
+}
+
+impl<'a, C: ?Sized, R: Default> Container<C> for Wizard<'a, C, R> {
+    fn input(&mut self, input: Input, context: &mut C) -> Option<Input> {
+        let input = match self.steps.get_mut(self.current) {
+            Some(step) if !self.finished => step.input(input, context)?,
+            _ => input,
+        };
+        if self.next_on.contains(&input.event) {
+            let _ = self.next(context);
+            None
+        } else if self.back_on.contains(&input.event) && self.back().is_ok() {
+            None
+        } else {
+            Some(input)
+        }
+    }
+    fn as_widget<'b>(&'b self) -> Box<dyn Widget + 'b> {
+        let mut layout = VLayout::new().widget(StepIndicator::new(
+            self.steps.iter().map(|s| s.title()).collect(),
+            self.current,
+        ));
+        if let Some(step) = self.steps.get(self.current) {
+            layout = layout.widget(step.as_widget());
+        }
+        if let Some(ref error) = self.error {
+            layout = layout.widget(error.as_str());
+        }
+        Box::new(layout)
+    }
+    fn update(&mut self, events: &Events, context: &mut C) {
+        for step in &mut self.steps {
+            step.update(events, context);
+        }
+    }
+    fn on_focus_gained(&mut self) {
+        if let Some(step) = self.steps.get_mut(self.current) {
+            step.on_focus_gained();
+        }
+    }
+    fn on_focus_lost(&mut self) {
+        if let Some(step) = self.steps.get_mut(self.current) {
+            step.on_focus_lost();
+        }
+    }
+}
+
+/// A single line showing the titles of a sequence of steps, marking completed steps ('✓'), the
+/// current step ('●', bold) and upcoming steps ('○'). With the ASCII `RenderingProfile`, the
+/// markers are 'x', '>' and 'o'.
+pub struct StepIndicator<'a> {
+    titles: Vec<&'a str>,
+    current: usize,
+}
+
+impl<'a> StepIndicator<'a> {
+    /// Create an indicator for the steps with the given titles where the step with index
+    /// `current` is the current one.
+    pub fn new(titles: Vec<&'a str>, current: usize) -> Self {
+        StepIndicator { titles, current }
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (String, bool)> + '_ {
+        self.titles.iter().enumerate().map(move |(i, title)| {
+            let profile = rendering_profile();
+            let marker = if i < self.current {
+                profile.select('✓', 'x')
+            } else if i == self.current {
+                profile.select('●', '>')
+            } else {
+                profile.select('○', 'o')
+            };
+            (format!("{}{}", marker, title), i == self.current)
+        })
+    }
+}
+
+impl<'a> Widget for StepIndicator<'a> {
+    fn space_demand(&self) -> Demand2D {
+        let width = self
+            .entries()
+            .map(|(e, _)| text_width(&e).raw_value() as usize)
+            .sum::<usize>()
+            + 2 * self.titles.len().saturating_sub(1);
+        Demand2D {
+            width: Demand::at_least(width),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let mut cursor = Cursor::new(&mut window);
+        for (i, (entry, current)) in self.entries().enumerate() {
+            if i > 0 {
+                cursor.write("  ");
+            }
+            cursor.set_style_modifier(StyleModifier::new().bold(current));
+            cursor.write(&entry);
+            cursor.set_style_modifier(StyleModifier::new());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    struct Step(&'static str, bool);
+    impl Container<Vec<String>> for Step {
+        fn input(&mut self, input: Input, _: &mut Vec<String>) -> Option<Input> {
+            if input.matches(Key::Char('x')) {
+                self.1 = true;
+                None
+            } else {
+                Some(input)
+            }
+        }
+        fn as_widget<'a>(&'a self) -> Box<dyn Widget + 'a> {
+            Box::new(self.0)
+        }
+    }
+    impl WizardStep<Vec<String>, String> for Step {
+        fn title(&self) -> &str {
+            self.0
+        }
+        fn validate(&self) -> Result<(), String> {
+            if self.1 {
+                Ok(())
+            } else {
+                Err("x!".to_owned())
+            }
+        }
+        fn collect(&self, result: &mut String) {
+            result.push_str(self.0);
+        }
+    }
+
+    fn key(wizard: &mut Wizard<Vec<String>, String>, key: Key, ctx: &mut Vec<String>) -> bool {
+        let input = Input::new(key);
+        wizard.input(input, ctx).is_none()
+    }
+
+    #[test]
+    fn test_wizard_navigation() {
+        let mut wizard = Wizard::new(|r: String, ctx: &mut Vec<String>| ctx.push(r))
+            .step(Step("a", true))
+            .step(Step("b", false))
+            .next_on(Key::Right);
+        let mut results = Vec::new();
+        assert!(!key(&mut wizard, Key::Esc, &mut results));
+        assert!(key(&mut wizard, Key::Right, &mut results));
+        assert_eq!(wizard.current_step(), 1);
+        assert!(key(&mut wizard, Key::Char('\n'), &mut results));
+        assert_eq!(wizard.current_step(), 1);
+        assert!(results.is_empty());
+
+        let mut term = FakeTerminal::with_size((6, 3));
+        wizard
+            .as_widget()
+            .draw(term.create_root_window(), RenderingHints::new());
+        term.assert_looks_like("✓a  *●**b*|b     |x!    ");
+
+        assert!(key(&mut wizard, Key::Esc, &mut results));
+        assert_eq!(wizard.current_step(), 0);
+        assert!(key(&mut wizard, Key::Char('\n'), &mut results));
+        assert!(key(&mut wizard, Key::Char('x'), &mut results));
+        assert!(key(&mut wizard, Key::Char('\n'), &mut results));
+        assert!(wizard.is_finished());
+        assert_eq!(results, vec!["ab".to_owned()]);
+        assert!(wizard.next(&mut results).is_err());
+    }
+}
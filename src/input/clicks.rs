@@ -0,0 +1,130 @@
+//! Detection of double, triple, ... clicks from consecutive mouse button presses.
+use super::{Event, Input, MouseButton, MouseEvent};
+use std::time::{Duration, Instant};
+
+/// A mouse button press together with the number of consecutive presses (1 for a single click, 2
+/// for a double click, ...).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Click {
+    /// The pressed button.
+    pub button: MouseButton,
+    /// Column of the press (1-based, as reported by the terminal).
+    pub x: u16,
+    /// Row of the press (1-based, as reported by the terminal).
+    pub y: u16,
+    /// The number of consecutive presses of the same button at (roughly) the same position.
+    pub count: usize,
+}
+
+/// Counts consecutive presses of a mouse button to detect double and triple clicks.
+///
+/// Presses are consecutive if they are of the same button, happen within a maximum interval
+/// (`max_interval`) and at a maximum distance (`max_distance`) of the previous press. Any other
+/// input (e.g., a key press) in between resets the count.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+/// use std::time::{Duration, Instant};
+///
+/// let press = Input::new(MouseEvent::Press(MouseButton::Left, 5, 3));
+/// let start = Instant::now();
+/// let mut counter = ClickCounter::new();
+///
+/// assert_eq!(counter.click_at(&press, start).unwrap().count, 1);
+/// let double_click = counter.click_at(&press, start + Duration::from_millis(200)).unwrap();
+/// assert_eq!(double_click.count, 2);
+/// // Too late for a triple click:
+/// assert_eq!(counter.click_at(&press, start + Duration::from_secs(1)).unwrap().count, 1);
+///
+/// // `click` uses the time at which the input was read:
+/// let mut late_press = press.clone();
+/// late_press.time += Duration::from_secs(5);
+/// assert_eq!(counter.click(&late_press).unwrap().count, 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ClickCounter {
+    max_interval: Duration,
+    max_distance: u16,
+    last: Option<(Instant, Click)>,
+}
+
+impl Default for ClickCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClickCounter {
+    /// Create a counter with a maximum interval of 400ms and a maximum distance of 0 (i.e.,
+    /// consecutive presses have to be on the same cell).
+    pub fn new() -> Self {
+        ClickCounter {
+            max_interval: Duration::from_millis(400),
+            max_distance: 0,
+            last: None,
+        }
+    }
+
+    /// Set the maximum time between consecutive presses (default: 400ms).
+    pub fn max_interval(mut self, interval: Duration) -> Self {
+        self.max_interval = interval;
+        self
+    }
+
+    /// Set the maximum distance (in cells, in either direction) between consecutive presses
+    /// (default: 0).
+    pub fn max_distance(mut self, distance: u16) -> Self {
+        self.max_distance = distance;
+        self
+    }
+
+    /// Register the input (received at `input.time`) and return the click if it is a mouse
+    /// button press.
+    ///
+    /// Call this for every input (not only for mouse events) so that the count is reset properly.
+    pub fn click(&mut self, input: &Input) -> Option<Click> {
+        self.click_at(input, input.time)
+    }
+
+    /// Register the input (received at the specified time) and return the click if it is a mouse
+    /// button press.
+    pub fn click_at(&mut self, input: &Input, time: Instant) -> Option<Click> {
+        let (button, x, y) = match input.event {
+            Event::Mouse(MouseEvent::Press(button, x, y)) => (button, x, y),
+            Event::Mouse(_) => return None,
+            _ => {
+                self.last = None;
+                return None;
+            }
+        };
+        let count = match self.last {
+            Some((last_time, ref last))
+                if last.button == button
+                    && time.saturating_duration_since(last_time) <= self.max_interval
+                    && distance(last.x, x) <= self.max_distance
+                    && distance(last.y, y) <= self.max_distance =>
+            {
+                last.count + 1
+            }
+            _ => 1,
+        };
+        let click = Click {
+            button,
+            x,
+            y,
+            count,
+        };
+        self.last = Some((time, click));
+        Some(click)
+    }
+
+    /// Forget the previous press, so that the next press is counted as a single click.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+fn distance(a: u16, b: u16) -> u16 {
+    a.max(b) - a.min(b)
+}
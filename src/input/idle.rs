@@ -0,0 +1,84 @@
+//! Tracking of the time since the last input, e.g., to dim the interface or to show a screensaver
+//! after a period of inactivity.
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A handle to query how long the user has been idle, i.e., how much time has passed since the
+/// most recent input.
+///
+/// `InputIter` keeps its tracker (see `InputIter::idle_tracker`) up to date with every input it
+/// reads. Since the iterator blocks while waiting for input, it is usually moved to a separate
+/// thread. Trackers can be cloned and sent to other threads, so the idle time can still be queried
+/// from the main thread.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+/// use std::time::Duration;
+///
+/// let mut inputs = Input::read_all(&b"ab"[..]);
+/// let tracker = inputs.idle_tracker();
+/// let first = inputs.next().unwrap().unwrap();
+///
+/// assert_eq!(tracker.last_input(), first.time);
+/// assert!(tracker.idle_time() < Duration::from_secs(60));
+/// ```
+#[derive(Clone, Debug)]
+pub struct IdleTracker {
+    last_input: Arc<Mutex<Instant>>,
+}
+
+impl Default for IdleTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdleTracker {
+    /// Create a tracker that counts the idle time from now on.
+    pub fn new() -> Self {
+        IdleTracker {
+            last_input: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Register an input at the specified time. Inputs that are older than the most recent one
+    /// are ignored.
+    pub fn register_input(&self, time: Instant) {
+        let mut last_input = self.last_input.lock().expect("lock last input");
+        *last_input = time.max(*last_input);
+    }
+
+    /// Get the time of the most recent input (or the creation of the tracker if there was none).
+    pub fn last_input(&self) -> Instant {
+        *self.last_input.lock().expect("lock last input")
+    }
+
+    /// Get the time that has passed since the most recent input.
+    pub fn idle_time(&self) -> Duration {
+        self.last_input().elapsed()
+    }
+
+    /// Check whether at least `duration` has passed since the most recent input.
+    pub fn is_idle_for(&self, duration: Duration) -> bool {
+        self.idle_time() >= duration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_idle_tracker() {
+        let tracker = IdleTracker::new();
+        let shared = tracker.clone();
+        let later = Instant::now() + Duration::from_secs(3600);
+        shared.register_input(later);
+        shared.register_input(later - Duration::from_secs(1));
+        assert_eq!(tracker.last_input(), later);
+        assert_eq!(tracker.idle_time(), Duration::from_secs(0));
+        assert!(!tracker.is_idle_for(Duration::from_millis(1)));
+        assert!(tracker.is_idle_for(Duration::from_secs(0)));
+    }
+}
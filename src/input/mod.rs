@@ -68,11 +68,23 @@
 //! }
 //! ```
 
+pub mod clicks;
+pub mod idle;
+pub mod normalize;
+pub mod script;
+pub mod scrollsync;
+
+pub use self::clicks::*;
+pub use self::idle::*;
+pub use self::normalize::*;
+pub use self::script::*;
+pub use self::scrollsync::*;
 use std::collections::HashSet;
 pub use termion::event::{Event, Key, MouseButton, MouseEvent};
 use termion::input::{EventsAndRaw, TermReadEventsAndRaw};
 
 use std::io;
+use std::time::Instant;
 
 /// A structure corresponding to a single input event, e.g., a single keystroke or mouse event.
 ///
@@ -80,14 +92,28 @@ use std::io;
 /// available, as well. This is useful if the user wants to pass the input on to some other
 /// terminal-like abstraction under certain circumstances (e.g., when writing a terminal
 /// multiplexer).
-#[derive(Eq, PartialEq, Clone, Debug)]
+///
+/// Inputs also carry the (monotonic) time at which they were read, e.g., for detecting double
+/// clicks (see `ClickCounter`) or key repeats. The time is ignored when comparing inputs.
+#[derive(Clone, Debug)]
 #[allow(missing_docs)]
 pub struct Input {
     pub event: Event,
     pub raw: Vec<u8>,
+    pub time: Instant,
 }
 
 impl Input {
+    /// Create an input of the specified event without raw bytes that happened now. This is
+    /// mostly useful for tests and for synthesizing inputs.
+    pub fn new<T: ToEvent>(event: T) -> Self {
+        Input {
+            event: event.to_event(),
+            raw: Vec::new(),
+            time: Instant::now(),
+        }
+    }
+
     /// Create an iterator that reads from the provided argument and converts the read bytes into
     /// a stream of `Input`s.
     ///
@@ -95,6 +121,7 @@ impl Input {
     pub fn read_all<R: io::Read>(read: R) -> InputIter<R> {
         InputIter {
             inner: read.events_and_raw(),
+            idle: IdleTracker::new(),
         }
     }
 
@@ -110,19 +137,38 @@ impl Input {
     }
 }
 
-/// An iterator of `Input` events.
+impl PartialEq for Input {
+    fn eq(&self, other: &Self) -> bool {
+        self.event == other.event && self.raw == other.raw
+    }
+}
+
+impl Eq for Input {}
+
+/// An iterator of `Input` events, which are timestamped as soon as they are read.
 pub struct InputIter<R: io::Read> {
     inner: EventsAndRaw<R>,
+    idle: IdleTracker,
+}
+
+impl<R: io::Read> InputIter<R> {
+    /// Get a tracker of the time since the last input read by this iterator, which can be used
+    /// even after the iterator has been moved to another thread.
+    pub fn idle_tracker(&self) -> IdleTracker {
+        self.idle.clone()
+    }
 }
 
 impl<R: io::Read> Iterator for InputIter<R> {
     type Item = Result<Input, io::Error>;
 
     fn next(&mut self) -> Option<Result<Input, io::Error>> {
+        let idle = &self.idle;
         self.inner.next().map(|tuple| {
-            tuple.map(|(event, raw)| Input {
-                event: event,
-                raw: raw,
+            tuple.map(|(event, raw)| {
+                let time = Instant::now();
+                idle.register_input(time);
+                Input { event, raw, time }
             })
         })
     }
@@ -139,10 +185,7 @@ impl<R: io::Read> Iterator for InputIter<R> {
 /// let mut triggered_second = false;
 /// let mut triggered_third = false;
 ///
-/// let input = Input {
-///     event: Event::Key(Key::Char('g')),
-///     raw: Vec::new(), //Incorrect, but does not matter for this example.
-/// };
+/// let input = Input::new(Key::Char('g'));
 ///
 /// let res = input
 ///     .chain((Key::Char('f'), || triggered_first = true)) // does not match, passes event on
@@ -338,6 +381,46 @@ fn pass_on_if_err(res: OperationResult, input: Input) -> Option<Input> {
     }
 }
 
+// DisabledBehavior -----------------------------------------------
+
+/// Guard for another behavior that swallows all input while disabled, e.g., while a pane is
+/// greyed out (see `widget::Disabled`) during a long running operation. While enabled, input is
+/// passed on to the guarded behavior.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+///
+/// let mut triggered = false;
+/// let input = Input::new(Key::Char('f'));
+/// let res = input
+///     .chain(DisabledBehavior::new(true, (Key::Char('f'), || triggered = true)))
+///     .finish();
+/// assert!(res.is_none());
+/// assert!(!triggered);
+/// ```
+pub struct DisabledBehavior<B: Behavior> {
+    disabled: bool,
+    behavior: B,
+}
+
+impl<B: Behavior> DisabledBehavior<B> {
+    /// Guard `behavior`, which only receives input if `disabled` is false.
+    pub fn new(disabled: bool, behavior: B) -> Self {
+        DisabledBehavior { disabled, behavior }
+    }
+}
+
+impl<B: Behavior> Behavior for DisabledBehavior<B> {
+    fn input(self, input: Input) -> Option<Input> {
+        if self.disabled {
+            None
+        } else {
+            self.behavior.input(input)
+        }
+    }
+}
+
 // ScrollableBehavior -----------------------------------------------
 
 /// Collection of triggers for functions of something `Scrollable` implementing `Behavior`.
@@ -424,6 +507,73 @@ pub trait Scrollable {
             Ok(())
         }
     }
+    /// The current position (e.g., the index of the current line), if it is known. This is used
+    /// for synchronized scrolling with a line mapping (see `ScrollSync`).
+    fn scroll_position(&self) -> Option<usize> {
+        None
+    }
+    /// Scroll to the specified position (see `scroll_position`). By default, this scrolls to the
+    /// beginning and then `position` times forwards.
+    fn scroll_to(&mut self, position: usize) -> OperationResult {
+        let _ = self.scroll_to_beginning();
+        for _ in 0..position {
+            self.scroll_forwards()?;
+        }
+        Ok(())
+    }
+}
+
+// HorizontalScrollBehavior ------------------------------------------
+
+/// Collection of triggers for functions of something `HorizontalScrollable` implementing
+/// `Behavior`.
+pub struct HorizontalScrollBehavior<'a, S: HorizontalScrollable + 'a> {
+    scrollable: &'a mut S,
+    left_on: EventSet,
+    right_on: EventSet,
+}
+
+impl<'a, S: HorizontalScrollable> HorizontalScrollBehavior<'a, S> {
+    /// Create the behavior to act on the provided `HorizontalScrollable`. Add triggers using other
+    /// functions!
+    pub fn new(scrollable: &'a mut S) -> Self {
+        HorizontalScrollBehavior {
+            scrollable,
+            left_on: EventSet::new(),
+            right_on: EventSet::new(),
+        }
+    }
+    /// Make the behavior trigger the `scroll_left` function on the provided event.
+    pub fn left_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.left_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `scroll_right` function on the provided event.
+    pub fn right_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.right_on.insert(event);
+        self
+    }
+}
+
+impl<'a, S: HorizontalScrollable> Behavior for HorizontalScrollBehavior<'a, S> {
+    fn input(self, input: Input) -> Option<Input> {
+        if self.left_on.contains(&input.event) {
+            pass_on_if_err(self.scrollable.scroll_left(), input)
+        } else if self.right_on.contains(&input.event) {
+            pass_on_if_err(self.scrollable.scroll_right(), input)
+        } else {
+            Some(input)
+        }
+    }
+}
+
+/// Something with content wider than its window (e.g., unwrapped lines) that can be panned
+/// horizontally. Use in conjunction with `HorizontalScrollBehavior` to manipulate when input
+/// arrives.
+#[allow(missing_docs)]
+pub trait HorizontalScrollable {
+    fn scroll_left(&mut self) -> OperationResult;
+    fn scroll_right(&mut self) -> OperationResult;
 }
 
 // WriteBehavior ------------------------------------------
@@ -551,6 +701,17 @@ pub struct EditBehavior<'a, E: Editable + 'a> {
     clear_on: EventSet,
     go_to_beginning_of_line_on: EventSet,
     go_to_end_of_line_on: EventSet,
+    duplicate_lines_on: EventSet,
+    move_lines_up_on: EventSet,
+    move_lines_down_on: EventSet,
+    join_lines_on: EventSet,
+    toggle_comment_on: EventSet,
+    undo_on: EventSet,
+    redo_on: EventSet,
+    kill_to_end_of_line_on: EventSet,
+    kill_to_beginning_of_line_on: EventSet,
+    kill_word_backwards_on: EventSet,
+    yank_on: EventSet,
 }
 
 impl<'a, E: Editable> EditBehavior<'a, E> {
@@ -567,6 +728,17 @@ impl<'a, E: Editable> EditBehavior<'a, E> {
             clear_on: EventSet::new(),
             go_to_beginning_of_line_on: EventSet::new(),
             go_to_end_of_line_on: EventSet::new(),
+            duplicate_lines_on: EventSet::new(),
+            move_lines_up_on: EventSet::new(),
+            move_lines_down_on: EventSet::new(),
+            join_lines_on: EventSet::new(),
+            toggle_comment_on: EventSet::new(),
+            undo_on: EventSet::new(),
+            redo_on: EventSet::new(),
+            kill_to_end_of_line_on: EventSet::new(),
+            kill_to_beginning_of_line_on: EventSet::new(),
+            kill_word_backwards_on: EventSet::new(),
+            yank_on: EventSet::new(),
         }
     }
 
@@ -631,6 +803,77 @@ impl<'a, E: Editable> EditBehavior<'a, E> {
         self.go_to_end_of_line_on.insert(event);
         self
     }
+    /// Make the behavior trigger the `duplicate_lines` function on the provided event.
+    pub fn duplicate_lines_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.duplicate_lines_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `move_lines_up` function on the provided event.
+    ///
+    /// A typical candidate for `event` would be `Key::AltUp`.
+    pub fn move_lines_up_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.move_lines_up_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `move_lines_down` function on the provided event.
+    ///
+    /// A typical candidate for `event` would be `Key::AltDown`.
+    pub fn move_lines_down_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.move_lines_down_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `join_lines` function on the provided event.
+    pub fn join_lines_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.join_lines_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `toggle_comment` function on the provided event.
+    pub fn toggle_comment_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.toggle_comment_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `undo` function on the provided event.
+    ///
+    /// A typical candidate for `event` would be `Key::Ctrl('z')`.
+    pub fn undo_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.undo_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `redo` function on the provided event.
+    ///
+    /// A typical candidate for `event` would be `Key::Ctrl('y')`.
+    pub fn redo_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.redo_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `kill_to_end_of_line` function on the provided event.
+    ///
+    /// A typical candidate for `event` would be `Key::Ctrl('k')`.
+    pub fn kill_to_end_of_line_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.kill_to_end_of_line_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `kill_to_beginning_of_line` function on the provided event.
+    ///
+    /// A typical candidate for `event` would be `Key::Ctrl('u')`.
+    pub fn kill_to_beginning_of_line_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.kill_to_beginning_of_line_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `kill_word_backwards` function on the provided event.
+    ///
+    /// A typical candidate for `event` would be `Key::Ctrl('w')`.
+    pub fn kill_word_backwards_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.kill_word_backwards_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `yank` function on the provided event.
+    ///
+    /// A typical candidate for `event` would be `Key::Ctrl('y')`.
+    pub fn yank_on<T: ToEvent>(mut self, event: T) -> Self {
+        self.yank_on.insert(event);
+        self
+    }
 }
 
 impl<'a, E: Editable> Behavior for EditBehavior<'a, E> {
@@ -653,6 +896,28 @@ impl<'a, E: Editable> Behavior for EditBehavior<'a, E> {
             pass_on_if_err(self.editable.go_to_beginning_of_line(), input)
         } else if self.go_to_end_of_line_on.contains(&input.event) {
             pass_on_if_err(self.editable.go_to_end_of_line(), input)
+        } else if self.duplicate_lines_on.contains(&input.event) {
+            pass_on_if_err(self.editable.duplicate_lines(), input)
+        } else if self.move_lines_up_on.contains(&input.event) {
+            pass_on_if_err(self.editable.move_lines_up(), input)
+        } else if self.move_lines_down_on.contains(&input.event) {
+            pass_on_if_err(self.editable.move_lines_down(), input)
+        } else if self.join_lines_on.contains(&input.event) {
+            pass_on_if_err(self.editable.join_lines(), input)
+        } else if self.toggle_comment_on.contains(&input.event) {
+            pass_on_if_err(self.editable.toggle_comment(), input)
+        } else if self.undo_on.contains(&input.event) {
+            pass_on_if_err(self.editable.undo(), input)
+        } else if self.redo_on.contains(&input.event) {
+            pass_on_if_err(self.editable.redo(), input)
+        } else if self.kill_to_end_of_line_on.contains(&input.event) {
+            pass_on_if_err(self.editable.kill_to_end_of_line(), input)
+        } else if self.kill_to_beginning_of_line_on.contains(&input.event) {
+            pass_on_if_err(self.editable.kill_to_beginning_of_line(), input)
+        } else if self.kill_word_backwards_on.contains(&input.event) {
+            pass_on_if_err(self.editable.kill_word_backwards(), input)
+        } else if self.yank_on.contains(&input.event) {
+            pass_on_if_err(self.editable.yank(), input)
         } else if let Event::Key(Key::Char(c)) = input.event {
             pass_on_if_err(self.editable.write(c), input)
         } else {
@@ -673,4 +938,162 @@ pub trait Editable: Navigatable + Writable {
     fn go_to_end_of_line(&mut self) -> OperationResult;
     /// Remove all content.
     fn clear(&mut self) -> OperationResult;
+
+    /// Insert a copy of the current line(s) below them. Not supported by default.
+    fn duplicate_lines(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Swap the current line(s) with the line above. Not supported by default.
+    fn move_lines_up(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Swap the current line(s) with the line below. Not supported by default.
+    fn move_lines_down(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Join the current line(s) with the following line. Not supported by default.
+    fn join_lines(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Comment out or uncomment the current line(s). Not supported by default.
+    fn toggle_comment(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Revert the most recent modification. Not supported by default.
+    fn undo(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Apply the most recently reverted modification again. Not supported by default.
+    fn redo(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Remove the content from the cursor to the end of the line and store it in the kill ring
+    /// (see `util::KillRing`). Not supported by default.
+    fn kill_to_end_of_line(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Remove the content from the beginning of the line to the cursor and store it in the kill
+    /// ring. Not supported by default.
+    fn kill_to_beginning_of_line(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Remove the whitespace-delimited word before the cursor (and any whitespace between it and
+    /// the cursor) and store it in the kill ring. Not supported by default.
+    fn kill_word_backwards(&mut self) -> OperationResult {
+        Err(())
+    }
+    /// Insert the most recently killed content at the cursor. Not supported by default.
+    fn yank(&mut self) -> OperationResult {
+        Err(())
+    }
+}
+
+// SearchBehavior ----------------------------------------------
+
+/// Collection of triggers for functions of something `Searchable` implementing `Behavior`.
+///
+/// Entering the search pattern is up to the application (e.g., using a `LineEdit` that is shown
+/// after pressing '/'). Once the pattern is known, pass it using `start_on`.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+/// use unsegen::widget::builtin::LogViewer;
+/// use std::fmt::Write;
+///
+/// let mut log = LogViewer::new();
+/// writeln!(log, "foo\nbar\nfoo\nbaz").unwrap();
+///
+/// let search = |log: &mut LogViewer, key| {
+///     Input::new(Event::Key(key))
+///         .chain(
+///             SearchBehavior::new(log)
+///                 .start_on(Key::Char('\n'), "foo")
+///                 .next_on(Key::Char('n'))
+///                 .prev_on(Key::Char('N'))
+///                 .clear_on(Key::Esc),
+///         )
+///         .finish()
+/// };
+/// assert!(search(&mut log, Key::Char('\n')).is_none());
+/// assert!(search(&mut log, Key::Char('n')).is_none());
+/// assert!(search(&mut log, Key::Esc).is_none());
+/// assert!(search(&mut log, Key::Esc).is_some()); // Highlights are already cleared
+/// ```
+pub struct SearchBehavior<'a, S: Searchable + 'a> {
+    searchable: &'a mut S,
+    pattern: String,
+    start_on: EventSet,
+    next_on: EventSet,
+    prev_on: EventSet,
+    clear_on: EventSet,
+}
+
+impl<'a, S: Searchable> SearchBehavior<'a, S> {
+    /// Create the behavior to act on the provided `Searchable`. Add triggers using other functions!
+    pub fn new(searchable: &'a mut S) -> Self {
+        SearchBehavior {
+            searchable,
+            pattern: String::new(),
+            start_on: EventSet::new(),
+            next_on: EventSet::new(),
+            prev_on: EventSet::new(),
+            clear_on: EventSet::new(),
+        }
+    }
+    /// Make the behavior trigger the `start_search` function with `pattern` on the provided event.
+    pub fn start_on<E: ToEvent>(mut self, event: E, pattern: &str) -> Self {
+        self.start_on.insert(event);
+        self.pattern = pattern.to_owned();
+        self
+    }
+    /// Make the behavior trigger the `next_match` function on the provided event.
+    pub fn next_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.next_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `prev_match` function on the provided event.
+    pub fn prev_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.prev_on.insert(event);
+        self
+    }
+    /// Make the behavior trigger the `clear_highlights` function on the provided event.
+    pub fn clear_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.clear_on.insert(event);
+        self
+    }
+}
+
+impl<'a, S: Searchable> Behavior for SearchBehavior<'a, S> {
+    fn input(self, input: Input) -> Option<Input> {
+        if self.start_on.contains(&input.event) {
+            pass_on_if_err(self.searchable.start_search(&self.pattern), input)
+        } else if self.next_on.contains(&input.event) {
+            pass_on_if_err(self.searchable.next_match(), input)
+        } else if self.prev_on.contains(&input.event) {
+            pass_on_if_err(self.searchable.prev_match(), input)
+        } else if self.clear_on.contains(&input.event) {
+            pass_on_if_err(self.searchable.clear_highlights(), input)
+        } else {
+            Some(input)
+        }
+    }
+}
+
+/// Something that can be searched for (plain text) matches. Use in conjunction with
+/// `SearchBehavior` to manipulate when input arrives.
+///
+/// Searching wraps around at the beginning and end of the content. Matches of the current search
+/// are highlighted until `clear_highlights` is called. Moving to another match highlights them
+/// again.
+pub trait Searchable {
+    /// Search for `pattern` and move to the match closest to the current position. Fails if the
+    /// pattern is empty or there is no match.
+    fn start_search(&mut self, pattern: &str) -> OperationResult;
+    /// Move to the next match of the current search.
+    fn next_match(&mut self) -> OperationResult;
+    /// Move to the previous match of the current search.
+    fn prev_match(&mut self) -> OperationResult;
+    /// Stop highlighting matches of the current search. Fails if there is nothing to clear.
+    fn clear_highlights(&mut self) -> OperationResult;
 }
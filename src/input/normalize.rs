@@ -0,0 +1,233 @@
+//! Table-driven normalization of input events, e.g., for dead keys of international keyboard
+//! layouts.
+use super::{Event, Input, Key, ToEvent};
+use std::collections::VecDeque;
+
+/// Maps (sequences of) input events to canonical events, so that applications do not have to
+/// special-case the output of specific keyboard layouts or terminals.
+///
+/// Mappings can be added individually (`map`), for dead keys (`dead_key`, `latin_dead_keys`) or
+/// for escape-prefixed characters sent by some terminals instead of `Key::Alt`
+/// (`esc_prefix_as_alt`).
+///
+/// Note that if an event is the beginning of a mapped sequence, it is only emitted (either
+/// mapped or as is) once the following event has been read.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+/// use std::time::Instant;
+///
+/// let normalizer = InputNormalizer::new()
+///     .latin_dead_keys()
+///     .map(&[Key::Char('\u{7f}')], Key::Backspace);
+///
+/// let inputs = "´e\u{7f}´x".chars().map(|c| Input {
+///     event: Event::Key(Key::Char(c)),
+///     raw: c.to_string().into_bytes(),
+///     time: Instant::now(),
+/// });
+/// let events = normalizer
+///     .normalize(inputs)
+///     .map(|i| i.event)
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     events,
+///     vec![
+///         Event::Key(Key::Char('é')),
+///         Event::Key(Key::Backspace),
+///         Event::Key(Key::Char('´')),
+///         Event::Key(Key::Char('x')),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct InputNormalizer {
+    mappings: Vec<(Vec<Event>, Event)>,
+    esc_prefix_as_alt: bool,
+}
+
+enum Lookup {
+    Complete(Event),
+    Prefix,
+    CompleteAndPrefix(Event),
+    NoMatch,
+}
+
+impl InputNormalizer {
+    /// Create a normalizer without any mappings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map the sequence of events to the single event `result`. Mappings that are added later
+    /// take precedence over earlier ones.
+    pub fn map<E: ToEvent + Clone, R: ToEvent>(mut self, sequence: &[E], result: R) -> Self {
+        let sequence = sequence
+            .iter()
+            .cloned()
+            .map(ToEvent::to_event)
+            .collect::<Vec<_>>();
+        assert!(!sequence.is_empty(), "Cannot map empty sequence");
+        self.mappings.insert(0, (sequence, result.to_event()));
+        self
+    }
+
+    /// Map each of the `bases` following the `dead` key to the corresponding character of
+    /// `composed`.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::input::*;
+    ///
+    /// let normalizer = InputNormalizer::new().dead_key('^', "aeAE", "âêÂÊ");
+    /// ```
+    pub fn dead_key(mut self, dead: char, bases: &str, composed: &str) -> Self {
+        assert_eq!(
+            bases.chars().count(),
+            composed.chars().count(),
+            "Number of base and composed characters must match"
+        );
+        for (base, composed) in bases.chars().zip(composed.chars()) {
+            self = self.map(&[Key::Char(dead), Key::Char(base)], Key::Char(composed));
+        }
+        self
+    }
+
+    /// Add mappings for common dead keys of latin keyboard layouts (acute, grave, circumflex,
+    /// diaeresis and tilde).
+    pub fn latin_dead_keys(self) -> Self {
+        self.dead_key('´', "aeiouyAEIOUY", "áéíóúýÁÉÍÓÚÝ")
+            .dead_key('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ")
+            .dead_key('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ")
+            .dead_key('¨', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ")
+            .dead_key('~', "anoANO", "ãñõÃÑÕ")
+    }
+
+    /// Treat `Key::Esc` followed by a character as `Key::Alt` of that character (default: false).
+    ///
+    /// Note that this means that a single press of the escape key will only be emitted once the
+    /// following key has been pressed.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::input::*;
+    ///
+    /// let inputs = vec![Key::Esc, Key::Char('x'), Key::Esc, Key::Up]
+    ///     .into_iter()
+    ///     .map(Input::new);
+    /// let events = InputNormalizer::new()
+    ///     .esc_prefix_as_alt(true)
+    ///     .normalize(inputs)
+    ///     .map(|i| i.event)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     events,
+    ///     vec![
+    ///         Event::Key(Key::Alt('x')),
+    ///         Event::Key(Key::Esc),
+    ///         Event::Key(Key::Up),
+    ///     ]
+    /// );
+    /// ```
+    pub fn esc_prefix_as_alt(mut self, enable: bool) -> Self {
+        self.esc_prefix_as_alt = enable;
+        self
+    }
+
+    /// Normalize the events of the given stream of inputs. The raw bytes of inputs that are
+    /// combined are concatenated.
+    pub fn normalize<I: Iterator<Item = Input>>(self, inputs: I) -> NormalizedInputs<I> {
+        NormalizedInputs {
+            normalizer: self,
+            inner: inputs,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn lookup<'a>(&self, events: impl Iterator<Item = &'a Event> + Clone) -> Lookup {
+        let len = events.clone().count();
+        if self.esc_prefix_as_alt {
+            let mut it = events.clone();
+            if let Some(Event::Key(Key::Esc)) = it.next() {
+                match (it.next(), len) {
+                    (None, _) => return Lookup::Prefix,
+                    (Some(Event::Key(Key::Char(c))), 2) => {
+                        return Lookup::Complete(Event::Key(Key::Alt(*c)))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let mut complete = None;
+        let mut prefix = false;
+        for (sequence, result) in &self.mappings {
+            if sequence.len() < len || !sequence.iter().zip(events.clone()).all(|(a, b)| a == b) {
+                continue;
+            }
+            if sequence.len() == len {
+                complete = complete.or_else(|| Some(result.clone()));
+            } else {
+                prefix = true;
+            }
+        }
+        match (complete, prefix) {
+            (Some(event), false) => Lookup::Complete(event),
+            (Some(event), true) => Lookup::CompleteAndPrefix(event),
+            (None, true) => Lookup::Prefix,
+            (None, false) => Lookup::NoMatch,
+        }
+    }
+}
+
+/// An iterator of normalized `Input`s (see `InputNormalizer::normalize`).
+pub struct NormalizedInputs<I: Iterator<Item = Input>> {
+    normalizer: InputNormalizer,
+    inner: I,
+    pending: VecDeque<Input>,
+}
+
+impl<I: Iterator<Item = Input>> NormalizedInputs<I> {
+    fn lookup_pending(&self, n: usize) -> Lookup {
+        self.normalizer
+            .lookup(self.pending.iter().take(n).map(|i| &i.event))
+    }
+
+    /// Emit the longest mapped sequence at the beginning of the pending inputs or, if there is
+    /// none, the first pending input as is.
+    fn emit_longest_match(&mut self) -> Option<Input> {
+        for n in (1..=self.pending.len()).rev() {
+            match self.lookup_pending(n) {
+                Lookup::Complete(event) | Lookup::CompleteAndPrefix(event) => {
+                    // The sequence is complete when its last input has been received.
+                    let time = self.pending[n - 1].time;
+                    let raw = self.pending.drain(..n).flat_map(|i| i.raw).collect();
+                    return Some(Input { event, raw, time });
+                }
+                Lookup::Prefix | Lookup::NoMatch => {}
+            }
+        }
+        self.pending.pop_front()
+    }
+}
+
+impl<I: Iterator<Item = Input>> Iterator for NormalizedInputs<I> {
+    type Item = Input;
+
+    fn next(&mut self) -> Option<Input> {
+        loop {
+            if !self.pending.is_empty() {
+                match self.lookup_pending(self.pending.len()) {
+                    Lookup::Complete(_) | Lookup::NoMatch => return self.emit_longest_match(),
+                    Lookup::Prefix | Lookup::CompleteAndPrefix(_) => {}
+                }
+            }
+            match self.inner.next() {
+                Some(input) => self.pending.push_back(input),
+                None => return self.emit_longest_match(),
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Scripted input: Key descriptions for tests and timed replay of recorded input.
+use super::{Event, Input, Key};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Convert a description of key presses into a sequence of `Input`s.
+///
+/// Characters stand for themselves, special keys are written in angle brackets using a vim-like
+/// notation: `<Left>`, `<Right>`, `<Up>`, `<Down>`, `<Home>`, `<End>`, `<PageUp>`, `<PageDown>`,
+/// `<BS>`, `<Del>`, `<Ins>`, `<Esc>`, `<Enter>` (i.e., '\n'), `<Tab>`, `<BackTab>`, `<Space>`,
+/// `<lt>` (i.e., '<'), `<F1>` to `<F12>` as well as `<C-x>` (Ctrl) and `<M-x>`/`<A-x>` (Alt) for
+/// any character `x`.
+///
+/// The raw bytes of the inputs are only filled in for characters.
+///
+/// # Panics
+///
+/// Panics if the description contains an unknown or unterminated special key, since it is meant to
+/// be used in tests.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+///
+/// let events = keys("a<C-x><Left><lt>")
+///     .into_iter()
+///     .map(|i| i.event)
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     events,
+///     vec![
+///         Event::Key(Key::Char('a')),
+///         Event::Key(Key::Ctrl('x')),
+///         Event::Key(Key::Left),
+///         Event::Key(Key::Char('<')),
+///     ]
+/// );
+/// ```
+pub fn keys(description: &str) -> Vec<Input> {
+    let mut inputs = Vec::new();
+    let mut chars = description.chars();
+    while let Some(c) = chars.next() {
+        let key = if c == '<' {
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('>') => break,
+                    Some(c) => name.push(c),
+                    None => panic!("Unterminated key description: <{}", name),
+                }
+            }
+            special_key(&name).unwrap_or_else(|| panic!("Invalid key description: <{}>", name))
+        } else {
+            Key::Char(c)
+        };
+        let raw = match key {
+            Key::Char(c) => c.to_string().into_bytes(),
+            _ => Vec::new(),
+        };
+        inputs.push(Input {
+            event: Event::Key(key),
+            raw,
+            time: Instant::now(),
+        });
+    }
+    inputs
+}
+
+fn special_key(name: &str) -> Option<Key> {
+    let single_char = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    };
+    Some(match name {
+        "Left" => Key::Left,
+        "Right" => Key::Right,
+        "Up" => Key::Up,
+        "Down" => Key::Down,
+        "Home" => Key::Home,
+        "End" => Key::End,
+        "PageUp" => Key::PageUp,
+        "PageDown" => Key::PageDown,
+        "BS" => Key::Backspace,
+        "Del" => Key::Delete,
+        "Ins" => Key::Insert,
+        "Esc" => Key::Esc,
+        "Enter" => Key::Char('\n'),
+        "Tab" => Key::Char('\t'),
+        "BackTab" => Key::BackTab,
+        "Space" => Key::Char(' '),
+        "lt" => Key::Char('<'),
+        _ => {
+            if let Some(c) = name.strip_prefix("C-").and_then(single_char) {
+                Key::Ctrl(c)
+            } else if let Some(c) = name
+                .strip_prefix("M-")
+                .or_else(|| name.strip_prefix("A-"))
+                .and_then(single_char)
+            {
+                Key::Alt(c)
+            } else {
+                match name.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => Key::F(n),
+                    _ => return None,
+                }
+            }
+        }
+    })
+}
+
+/// Replays inputs with their original delays (e.g., recorded from a user session), scaled by a
+/// speed factor. Inputs are timestamped again when they are emitted.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+/// use std::time::Duration;
+///
+/// let recorded = keys("abc")
+///     .into_iter()
+///     .map(|i| (Duration::from_millis(100), i))
+///     .collect::<Vec<_>>();
+///
+/// // Replay without any delays, e.g., in a test:
+/// let replayed = Replay::new(recorded.clone()).instant().collect::<Vec<_>>();
+/// assert_eq!(replayed, keys("abc"));
+///
+/// // Replay at ten times the original speed (i.e., with 10ms between inputs):
+/// assert_eq!(Replay::new(recorded).speed(10.0).count(), 3);
+/// ```
+pub struct Replay {
+    inputs: ::std::vec::IntoIter<(Duration, Input)>,
+    speed: f64,
+}
+
+impl Replay {
+    /// Create a replay of the inputs, each with the delay before it is emitted.
+    pub fn new(inputs: Vec<(Duration, Input)>) -> Self {
+        Replay {
+            inputs: inputs.into_iter(),
+            speed: 1.0,
+        }
+    }
+
+    /// Scale the replay speed: 2.0 replays twice as fast, 0.5 at half the original speed.
+    /// Non-positive factors are ignored.
+    pub fn speed(mut self, factor: f64) -> Self {
+        if factor > 0.0 {
+            self.speed = factor;
+        }
+        self
+    }
+
+    /// Emit all inputs without any delay.
+    pub fn instant(mut self) -> Self {
+        self.speed = f64::INFINITY;
+        self
+    }
+}
+
+impl Iterator for Replay {
+    type Item = Input;
+
+    fn next(&mut self) -> Option<Input> {
+        let (delay, mut input) = self.inputs.next()?;
+        if self.speed.is_finite() {
+            thread::sleep(delay.div_f64(self.speed));
+        }
+        input.time = Instant::now();
+        Some(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        let events = keys("<lt>><Space><C-a><M-b><F12>")
+            .into_iter()
+            .map(|i| i.event)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                Event::Key(Key::Char('<')),
+                Event::Key(Key::Char('>')),
+                Event::Key(Key::Char(' ')),
+                Event::Key(Key::Ctrl('a')),
+                Event::Key(Key::Alt('b')),
+                Event::Key(Key::F(12)),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unterminated key description: <Left")]
+    fn test_keys_unterminated() {
+        keys("a<Left");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid key description: <F13>")]
+    fn test_keys_invalid() {
+        keys("<F13>");
+    }
+}
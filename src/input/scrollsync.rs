@@ -0,0 +1,214 @@
+//! Synchronized scrolling of multiple `Scrollable`s, e.g., the panes of a side-by-side diff.
+use super::{OperationResult, Scrollable};
+
+type LineMapping<'a> = Box<dyn Fn(usize, usize) -> Option<usize> + 'a>;
+
+/// Links two or more `Scrollable`s so that scroll operations applied to one of them (the leader)
+/// are mirrored to the others.
+///
+/// By default, the followers are scrolled using the same operations as the leader. If a line
+/// mapping is specified (see `line_mapping`), the followers are instead scrolled to the position
+/// corresponding to the new position of the leader, which requires the leader to support
+/// `Scrollable::scroll_position`.
+///
+/// `ScrollSync` is itself `Scrollable`, so it can be used in a `ScrollBehavior`. It is meant to be
+/// created on the fly in the input handling code.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+///
+/// struct Lines(usize, usize);
+/// impl Scrollable for Lines {
+///     fn scroll_backwards(&mut self) -> OperationResult {
+///         self.scroll_to(self.0.checked_sub(1).ok_or(())?)
+///     }
+///     fn scroll_forwards(&mut self) -> OperationResult {
+///         self.scroll_to(self.0 + 1)
+///     }
+///     fn scroll_position(&self) -> Option<usize> {
+///         Some(self.0)
+///     }
+///     fn scroll_to(&mut self, position: usize) -> OperationResult {
+///         if position < self.1 {
+///             self.0 = position;
+///             Ok(())
+///         } else {
+///             Err(())
+///         }
+///     }
+/// }
+///
+/// let mut source = Lines(0, 10);
+/// let mut assembly = Lines(0, 30);
+/// let input = Input::new(Key::Down);
+/// input.chain(
+///     ScrollBehavior::new(
+///         &mut ScrollSync::new()
+///             .pane(&mut source)
+///             .pane(&mut assembly)
+///             // Every line of source code corresponds to three lines of assembly:
+///             .line_mapping(|_pane, line| Some(line * 3)),
+///     )
+///     .forwards_on(Key::Down),
+/// );
+/// assert_eq!(source.0, 1);
+/// assert_eq!(assembly.0, 3);
+/// ```
+pub struct ScrollSync<'a> {
+    panes: Vec<&'a mut dyn Scrollable>,
+    leader: usize,
+    mapping: Option<LineMapping<'a>>,
+}
+
+impl<'a> Default for ScrollSync<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ScrollSync<'a> {
+    /// Create a synchronization without any panes. Add panes using `pane`.
+    pub fn new() -> Self {
+        ScrollSync {
+            panes: Vec::new(),
+            leader: 0,
+            mapping: None,
+        }
+    }
+
+    /// Add a pane. Panes are indexed in the order they are added, starting at 0.
+    pub fn pane<S: Scrollable>(mut self, pane: &'a mut S) -> Self {
+        self.panes.push(pane);
+        self
+    }
+
+    /// Specify the index of the pane that scroll operations are applied to, e.g., the one that
+    /// has focus (default: 0).
+    pub fn leader(mut self, index: usize) -> Self {
+        self.leader = index;
+        self
+    }
+
+    /// Map the position of the leader to the position of other panes. `mapping` is called with
+    /// the index of a following pane and the position of the leader and returns the new position
+    /// of the following pane or `None` if the pane should not be moved.
+    pub fn line_mapping<F: Fn(usize, usize) -> Option<usize> + 'a>(mut self, mapping: F) -> Self {
+        self.mapping = Some(Box::new(mapping));
+        self
+    }
+
+    fn apply<F: Fn(&mut dyn Scrollable) -> OperationResult>(&mut self, op: F) -> OperationResult {
+        let leader = self.leader;
+        op(*self.panes.get_mut(leader).ok_or(())?)?;
+        let position = self.panes[leader].scroll_position();
+        for (i, pane) in self.panes.iter_mut().enumerate() {
+            if i == leader {
+                continue;
+            }
+            // Followers may not be able to scroll as far as the leader, so errors are ignored.
+            let _ = match (&self.mapping, position) {
+                (Some(mapping), Some(position)) => match mapping(i, position) {
+                    Some(target) => pane.scroll_to(target),
+                    None => Ok(()),
+                },
+                _ => op(*pane),
+            };
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Scrollable for ScrollSync<'a> {
+    fn scroll_backwards(&mut self) -> OperationResult {
+        self.apply(|s| s.scroll_backwards())
+    }
+    fn scroll_forwards(&mut self) -> OperationResult {
+        self.apply(|s| s.scroll_forwards())
+    }
+    fn scroll_to_beginning(&mut self) -> OperationResult {
+        self.apply(|s| s.scroll_to_beginning())
+    }
+    fn scroll_to_end(&mut self) -> OperationResult {
+        self.apply(|s| s.scroll_to_end())
+    }
+    fn scroll_position(&self) -> Option<usize> {
+        self.panes.get(self.leader)?.scroll_position()
+    }
+    fn scroll_to(&mut self, position: usize) -> OperationResult {
+        self.apply(|s| s.scroll_to(position))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Steps(i32);
+    impl Scrollable for Steps {
+        fn scroll_backwards(&mut self) -> OperationResult {
+            self.0 -= 1;
+            Ok(())
+        }
+        fn scroll_forwards(&mut self) -> OperationResult {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_scroll_sync_mirror() {
+        let (mut a, mut b, mut c) = (Steps(0), Steps(5), Steps(0));
+        {
+            let mut sync = ScrollSync::new().pane(&mut a).pane(&mut b).pane(&mut c);
+            sync.scroll_forwards().unwrap();
+            sync.scroll_forwards().unwrap();
+            sync.scroll_backwards().unwrap();
+        }
+        assert_eq!((a.0, b.0, c.0), (1, 6, 1));
+        {
+            let mut sync = ScrollSync::new().pane(&mut a).pane(&mut b).leader(1);
+            sync.scroll_backwards().unwrap();
+        }
+        assert_eq!((a.0, b.0), (0, 5));
+        assert!(ScrollSync::new().leader(1).scroll_forwards().is_err());
+    }
+
+    #[test]
+    fn test_scroll_sync_mapping() {
+        use std::fmt::Write;
+        use widget::builtin::LogViewer;
+
+        let mut left = LogViewer::new();
+        let mut right = LogViewer::new();
+        write!(left, "0\n1\n2\n3\n4").unwrap();
+        write!(right, "0\n1\n2\n3\n4\n5\n6\n7").unwrap();
+        let mapping = |_: usize, line: usize| if line == 1 { None } else { Some(line + 2) };
+        {
+            let mut sync = ScrollSync::new()
+                .pane(&mut left)
+                .pane(&mut right)
+                .line_mapping(mapping);
+            sync.scroll_to_beginning().unwrap();
+        }
+        assert_eq!(left.scroll_position(), Some(0));
+        assert_eq!(right.scroll_position(), Some(2));
+        {
+            let mut sync = ScrollSync::new()
+                .pane(&mut left)
+                .pane(&mut right)
+                .line_mapping(mapping);
+            sync.scroll_forwards().unwrap();
+        }
+        assert_eq!(right.scroll_position(), Some(2));
+        {
+            let mut sync = ScrollSync::new()
+                .pane(&mut left)
+                .pane(&mut right)
+                .line_mapping(mapping);
+            sync.scroll_forwards().unwrap();
+        }
+        assert_eq!(left.scroll_position(), Some(2));
+        assert_eq!(right.scroll_position(), Some(4));
+    }
+}
@@ -11,6 +11,8 @@ extern crate termion;
 extern crate unicode_segmentation;
 extern crate unicode_width;
 
+#[deny(missing_docs)]
+pub mod app;
 #[deny(missing_docs)]
 pub mod base;
 #[deny(missing_docs)]
@@ -18,4 +20,6 @@ pub mod container;
 #[deny(missing_docs)]
 pub mod input;
 #[deny(missing_docs)]
+pub mod util;
+#[deny(missing_docs)]
 pub mod widget;
@@ -0,0 +1,283 @@
+//! Timing of animations that are advanced in discrete ticks (e.g., in `Application::tick` or
+//! once per frame).
+//!
+//! A `Transition` interpolates between two values of any type that implements `Lerp` (numbers,
+//! coordinates, rectangles and colors) and shapes the progress using an `Easing` function.
+//! `ContainerManager::set_layout_transition` uses this to animate layout changes.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::util::*;
+//!
+//! let mut progress = Transition::new(0.0, 1.0, 4).easing(Easing::EaseOut);
+//! let mut values = vec![progress.value()];
+//! while progress.tick() {
+//!     values.push(progress.value());
+//! }
+//! assert_eq!(values, vec![0.0, 0.4375, 0.75, 0.9375, 1.0]);
+//!
+//! // Smooth out jumps of the displayed value when the target changes:
+//! progress.retarget(0.5);
+//! progress.tick();
+//! assert_eq!(progress.value(), 0.78125);
+//! ```
+use base::basic_types::*;
+use base::{Color, Rect};
+
+/// A function that maps the linear progress `t` in `[0, 1]` of an animation to the progress of
+/// the animated value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Start slowly and accelerate (quadratic).
+    EaseIn,
+    /// Start quickly and decelerate (quadratic).
+    EaseOut,
+    /// Accelerate in the first half and decelerate in the second half (quadratic).
+    EaseInOut,
+    /// Start slowly and accelerate (cubic).
+    CubicIn,
+    /// Start quickly and decelerate (cubic).
+    CubicOut,
+    /// Accelerate in the first half and decelerate in the second half (cubic).
+    CubicInOut,
+}
+
+impl Easing {
+    /// Map `t` (which is clamped to `[0, 1]`) to the progress of the value. The result is `0` for
+    /// `t = 0` and `1` for `t = 1`.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - 2.0 * (1.0 - t) * (1.0 - t)
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - 4.0 * (1.0 - t).powi(3)
+                }
+            }
+        }
+    }
+}
+
+/// Values that can be interpolated linearly.
+pub trait Lerp: Clone {
+    /// Calculate the value that is `t` (in `[0, 1]`) of the way from `self` to `to`.
+    fn lerp(&self, to: &Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        self + (to - self) * t as f32
+    }
+}
+
+/// Rounds to the nearest integer.
+impl Lerp for i32 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        f64::from(*self).lerp(&f64::from(*to), t).round() as i32
+    }
+}
+
+/// Rounds to the nearest integer.
+impl Lerp for u8 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        f64::from(*self).lerp(&f64::from(*to), t).round() as u8
+    }
+}
+
+impl<D: AxisDimension> Lerp for AxisIndex<D> {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        AxisIndex::new(self.raw_value().lerp(&to.raw_value(), t))
+    }
+}
+
+impl<D: AxisDimension> Lerp for PositiveAxisDiff<D> {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        PositiveAxisDiff::new_unchecked(self.raw_value().lerp(&to.raw_value(), t))
+    }
+}
+
+/// Interpolates the start and end of both ranges.
+impl<X: AxisDimension, Y: AxisDimension> Lerp for Rect<X, Y> {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        Rect::new(
+            self.x_range.start.lerp(&to.x_range.start, t)
+                ..self.x_range.end.lerp(&to.x_range.end, t),
+            self.y_range.start.lerp(&to.y_range.start, t)
+                ..self.y_range.end.lerp(&to.y_range.end, t),
+        )
+    }
+}
+
+/// Rgb colors are mixed. All other colors cannot be mixed, so they switch to `to` halfway.
+impl Lerp for Color {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        match (*self, *to) {
+            (
+                Color::Rgb { r, g, b },
+                Color::Rgb {
+                    r: r2,
+                    g: g2,
+                    b: b2,
+                },
+            ) => Color::Rgb {
+                r: r.lerp(&r2, t),
+                g: g.lerp(&g2, t),
+                b: b.lerp(&b2, t),
+            },
+            _ if t < 0.5 => *self,
+            _ => *to,
+        }
+    }
+}
+
+/// An animated change of a value from one state to another over a fixed number of ticks.
+#[derive(Clone, Debug)]
+pub struct Transition<T: Lerp> {
+    from: T,
+    to: T,
+    ticks: u32,
+    tick: u32,
+    easing: Easing,
+}
+
+impl<T: Lerp> Transition<T> {
+    /// Create a transition from `from` to `to` that finishes after `ticks` calls to `tick`. The
+    /// transition is finished immediately if `ticks` is `0`.
+    pub fn new(from: T, to: T, ticks: u32) -> Self {
+        Transition {
+            from,
+            to,
+            ticks,
+            tick: 0,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Create a finished transition that stays at `value`, e.g., to be retargeted later on.
+    pub fn constant(value: T) -> Self {
+        Self::new(value.clone(), value, 0)
+    }
+
+    /// Specify the easing function of the transition (default: linear).
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Advance the transition by one tick. Returns whether the value changed, i.e., whether the
+    /// transition had not been finished before.
+    pub fn tick(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.tick += 1;
+        true
+    }
+
+    /// Check whether the transition has reached its target.
+    pub fn is_finished(&self) -> bool {
+        self.tick >= self.ticks
+    }
+
+    /// Get the linear progress of the transition in `[0, 1]`, i.e., before applying the easing
+    /// function.
+    pub fn progress(&self) -> f64 {
+        if self.is_finished() {
+            1.0
+        } else {
+            f64::from(self.tick) / f64::from(self.ticks)
+        }
+    }
+
+    /// Get the current value of the transition.
+    pub fn value(&self) -> T {
+        if self.is_finished() {
+            return self.to.clone();
+        }
+        self.from.lerp(&self.to, self.easing.apply(self.progress()))
+    }
+
+    /// Get the value that the transition ends at.
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+
+    /// Start a new transition (with the same number of ticks and easing) from the current value
+    /// to `to`, e.g., to smoothly follow a value that changes while the transition is running.
+    pub fn retarget(&mut self, to: T) {
+        self.from = self.value();
+        self.to = to;
+        self.tick = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_easing_bounds() {
+        let easings = [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::CubicIn,
+            Easing::CubicOut,
+            Easing::CubicInOut,
+        ];
+        for easing in easings.iter() {
+            assert_eq!(easing.apply(-1.0), 0.0);
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::CubicInOut.apply(0.25), 0.0625);
+    }
+
+    #[test]
+    fn test_transition_lerp() {
+        let red = Color::Rgb { r: 255, g: 0, b: 0 };
+        let blue = Color::Rgb { r: 0, g: 0, b: 255 };
+        let mut fade = Transition::new(red, blue, 2);
+        fade.tick();
+        assert_eq!(
+            fade.value(),
+            Color::Rgb {
+                r: 128,
+                g: 0,
+                b: 128
+            }
+        );
+        assert_eq!(Color::Red.lerp(&Color::Blue, 0.4), Color::Red);
+
+        let mut position = Transition::new(ColIndex::new(0), ColIndex::new(10), 0);
+        assert!(position.is_finished());
+        assert!(!position.tick());
+        assert_eq!(position.value(), ColIndex::new(10));
+        assert_eq!(Transition::constant(3).value(), 3);
+    }
+}
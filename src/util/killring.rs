@@ -0,0 +1,122 @@
+//! A store of killed (i.e., cut) text for readline-style editing (see
+//! `Editable::kill_to_end_of_line` and `Editable::yank`).
+//!
+//! Every `LineEdit` and `TextEdit` has its own `KillRing` by default. Share a ring between
+//! widgets (e.g., between all input fields of a form) to yank text that was killed in another
+//! widget.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::input::Editable;
+//! use unsegen::util::KillRing;
+//! use unsegen::widget::builtin::LineEdit;
+//!
+//! let ring = KillRing::new();
+//! let mut first = LineEdit::new();
+//! first.set_kill_ring(ring.clone());
+//! first.set("hello world");
+//! first.kill_word_backwards().unwrap();
+//! assert_eq!(first.get(), "hello ");
+//!
+//! let mut second = LineEdit::new();
+//! second.set_kill_ring(ring.clone());
+//! second.yank().unwrap();
+//! assert_eq!(second.get(), "world");
+//! assert_eq!(ring.latest(), Some("world".to_owned()));
+//! ```
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// The default number of killed texts that a `KillRing` keeps.
+const DEFAULT_CAPACITY: usize = 16;
+
+/// A shared handle to a list of the most recently killed texts.
+///
+/// Clones refer to the same list. Rings can be sent to other threads, so widgets that use them
+/// can be, too.
+#[derive(Clone, Debug)]
+pub struct KillRing {
+    entries: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl Default for KillRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KillRing {
+    /// Create an empty ring that keeps the 16 most recently killed texts.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Create an empty ring that keeps the `capacity` most recently killed texts (at least one).
+    pub fn with_capacity(capacity: usize) -> Self {
+        KillRing {
+            entries: Arc::new(Mutex::new(VecDeque::new())),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Store a killed text as the latest one. Empty texts are ignored.
+    pub fn kill(&self, text: impl Into<String>) {
+        let text = text.into();
+        if text.is_empty() {
+            return;
+        }
+        let mut entries = self.entries.lock().expect("lock kill ring");
+        entries.push_front(text);
+        entries.truncate(self.capacity);
+    }
+
+    /// Get the most recently killed text, i.e., the text that is inserted by `yank`.
+    pub fn latest(&self) -> Option<String> {
+        self.entries
+            .lock()
+            .expect("lock kill ring")
+            .front()
+            .cloned()
+    }
+
+    /// Make the previously killed text the latest one (moving the latest one to the end), e.g.,
+    /// to replace the yanked text by an older one (like `M-y` in readline).
+    pub fn rotate(&self) {
+        let mut entries = self.entries.lock().expect("lock kill ring");
+        if let Some(latest) = entries.pop_front() {
+            entries.push_back(latest);
+        }
+    }
+
+    /// Get the number of stored texts.
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("lock kill ring").len()
+    }
+
+    /// Check whether no text has been killed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_kill_ring() {
+        let ring = KillRing::with_capacity(2);
+        assert_eq!(ring.latest(), None);
+        ring.kill("a");
+        ring.kill("");
+        ring.kill("b");
+        ring.kill("c");
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.latest(), Some("c".to_owned()));
+        ring.rotate();
+        assert_eq!(ring.clone().latest(), Some("b".to_owned()));
+        ring.rotate();
+        assert_eq!(ring.latest(), Some("c".to_owned()));
+    }
+}
@@ -0,0 +1,117 @@
+//! Incremental updates from successive snapshots of a text, e.g., the output of a command that is
+//! polled periodically (like `watch`).
+//!
+//! Instead of clearing and refilling a widget on every poll (which resets its scroll position),
+//! compare each snapshot to the previous one using a `LineDiffer` and apply only the resulting
+//! `LineDiff` (see `LogViewer::apply_diff`).
+//!
+//! # Examples:
+//! ```
+//! use unsegen::util::*;
+//!
+//! let mut differ = LineDiffer::new();
+//! let diff = differ.update("a\nb\n");
+//! assert_eq!(diff.appended, vec!["a", "b"]);
+//!
+//! let diff = differ.update("a\nB\nc\n");
+//! assert_eq!(diff.changed, vec![(1, "B".to_owned())]);
+//! assert_eq!(diff.appended, vec!["c"]);
+//!
+//! let diff = differ.update("a\n");
+//! assert_eq!(diff.removed, 2);
+//! ```
+
+/// The difference between two snapshots of a text, line by line.
+///
+/// Lines are compared by position: Applying the diff to the lines of the old snapshot means
+/// replacing the `changed` lines, removing `removed` lines from the end and finally appending the
+/// `appended` lines.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineDiff {
+    /// The lines (index and new content) that exist in both snapshots but differ.
+    pub changed: Vec<(usize, String)>,
+    /// The number of lines at the end of the old snapshot that do not exist in the new one.
+    pub removed: usize,
+    /// The lines at the end of the new snapshot that do not exist in the old one.
+    pub appended: Vec<String>,
+}
+
+impl LineDiff {
+    /// Compare two snapshots, each given as its lines.
+    pub fn between<S: AsRef<str>>(old: &[S], new: &[S]) -> Self {
+        let common = old.len().min(new.len());
+        LineDiff {
+            changed: (0..common)
+                .filter(|&i| old[i].as_ref() != new[i].as_ref())
+                .map(|i| (i, new[i].as_ref().to_owned()))
+                .collect(),
+            removed: old.len() - common,
+            appended: new[common..]
+                .iter()
+                .map(|l| l.as_ref().to_owned())
+                .collect(),
+        }
+    }
+
+    /// Check whether both snapshots are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed == 0 && self.appended.is_empty()
+    }
+
+    /// Apply the diff to the lines of the old snapshot, which turns them into the lines of the new
+    /// one.
+    pub fn apply(&self, lines: &mut Vec<String>) {
+        for (i, line) in &self.changed {
+            lines[*i] = line.clone();
+        }
+        let remaining = lines.len().saturating_sub(self.removed);
+        lines.truncate(remaining);
+        lines.extend(self.appended.iter().cloned());
+    }
+}
+
+/// Remembers the last snapshot of a text to compute the `LineDiff` to the next one.
+///
+/// Snapshots are split into lines using `str::lines`, i.e., a final line break is optional.
+#[derive(Clone, Debug, Default)]
+pub struct LineDiffer {
+    lines: Vec<String>,
+}
+
+impl LineDiffer {
+    /// Create a differ whose previous snapshot is empty, i.e., the first update appends all lines.
+    pub fn new() -> Self {
+        LineDiffer { lines: Vec::new() }
+    }
+
+    /// Compare `snapshot` to the previous snapshot and remember it for the next update.
+    pub fn update(&mut self, snapshot: &str) -> LineDiff {
+        let lines = snapshot.lines().map(str::to_owned).collect::<Vec<_>>();
+        let diff = LineDiff::between(&self.lines, &lines);
+        self.lines = lines;
+        diff
+    }
+
+    /// Get the lines of the previous snapshot.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_line_diff_apply() {
+        let snapshots = ["", "a\nb", "a\nb\n", "x\nb\nc\r\nd", "x", "y\nz\n"];
+        let mut differ = LineDiffer::new();
+        let mut lines = Vec::new();
+        for snapshot in snapshots.iter() {
+            differ.update(snapshot).apply(&mut lines);
+            assert_eq!(lines, snapshot.lines().collect::<Vec<_>>());
+            assert_eq!(lines, differ.lines());
+        }
+        assert!(differ.update("y\nz").is_empty());
+    }
+}
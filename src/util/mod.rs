@@ -0,0 +1,15 @@
+//! Utilities that are not specific to terminals, but are used by widgets and are useful for
+//! applications built on top of them.
+pub mod animation;
+pub mod killring;
+pub mod linediff;
+pub mod search;
+pub mod storage;
+pub mod undo;
+
+pub use self::animation::*;
+pub use self::killring::*;
+pub use self::linediff::*;
+pub use self::search::*;
+pub use self::storage::*;
+pub use self::undo::*;
@@ -0,0 +1,333 @@
+//! Matching of search patterns in text that is shared by all widgets with search features (e.g.,
+//! `LogViewer`, `TextEdit` and the history search of `PromptLine`), so that they behave
+//! consistently for non-ASCII content.
+//!
+//! Apart from exact matching, the text and the pattern are compared grapheme cluster by grapheme
+//! cluster, optionally ignoring case and diacritics (see `MatchMode`). `MatchMode::fuzzy_match`
+//! additionally matches patterns whose clusters occur in order, but not necessarily next to each
+//! other, e.g., for filtering lists.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::util::MatchMode;
+//!
+//! let mode = MatchMode::exact().ignore_case(true).ignore_diacritics(true);
+//! assert_eq!(mode.find_all("Crème brûlée, creme", "CREME"), vec![0..6, 17..22]);
+//!
+//! let fuzzy = mode.fuzzy_match("open_file_dialog", "ofd").unwrap();
+//! assert_eq!(fuzzy.positions, vec![0..1, 5..6, 10..11]);
+//! assert!(fuzzy.score > mode.fuzzy_match("wolf_and_dog", "ofd").unwrap().score);
+//! ```
+use std::borrow::Cow;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Precomposed latin letters (Latin-1 Supplement and Latin Extended-A) and their base letters.
+const LETTERS_WITH_DIACRITICS: &[(&str, char)] = &[
+    ("ÀÁÂÃÄÅĀĂĄ", 'A'),
+    ("àáâãäåāăą", 'a'),
+    ("ÇĆĈĊČ", 'C'),
+    ("çćĉċč", 'c'),
+    ("ĎĐ", 'D'),
+    ("ďđ", 'd'),
+    ("ÈÉÊËĒĔĖĘĚ", 'E'),
+    ("èéêëēĕėęě", 'e'),
+    ("ĜĞĠĢ", 'G'),
+    ("ĝğġģ", 'g'),
+    ("ĤĦ", 'H'),
+    ("ĥħ", 'h'),
+    ("ÌÍÎÏĨĪĬĮİ", 'I'),
+    ("ìíîïĩīĭįı", 'i'),
+    ("Ĵ", 'J'),
+    ("ĵ", 'j'),
+    ("Ķ", 'K'),
+    ("ķ", 'k'),
+    ("ĹĻĽĿŁ", 'L'),
+    ("ĺļľŀł", 'l'),
+    ("ÑŃŅŇ", 'N'),
+    ("ñńņňŉ", 'n'),
+    ("ÒÓÔÕÖØŌŎŐ", 'O'),
+    ("òóôõöøōŏő", 'o'),
+    ("ŔŖŘ", 'R'),
+    ("ŕŗř", 'r'),
+    ("ŚŜŞŠ", 'S'),
+    ("śŝşš", 's'),
+    ("ŢŤŦ", 'T'),
+    ("ţťŧ", 't'),
+    ("ÙÚÛÜŨŪŬŮŰŲ", 'U'),
+    ("ùúûüũūŭůűų", 'u'),
+    ("Ŵ", 'W'),
+    ("ŵ", 'w'),
+    ("ÝŶŸ", 'Y'),
+    ("ýÿŷ", 'y'),
+    ("ŹŻŽ", 'Z'),
+    ("źżž", 'z'),
+];
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F)
+}
+
+fn base_letter(c: char) -> char {
+    LETTERS_WITH_DIACRITICS
+        .iter()
+        .find(|(letters, _)| letters.contains(c))
+        .map(|&(_, base)| base)
+        .unwrap_or(c)
+}
+
+/// Determines how a search pattern is compared to text.
+///
+/// The default is exact matching, i.e., matching substrings like `str::match_indices`. If case or
+/// diacritics are ignored, the text and the pattern are compared grapheme cluster by grapheme
+/// cluster instead, so a pattern never matches only a part of a cluster (e.g., "e" does not
+/// match the first half of "e\u{301}").
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MatchMode {
+    ignore_case: bool,
+    ignore_diacritics: bool,
+}
+
+impl MatchMode {
+    /// Match exactly (the default).
+    pub fn exact() -> Self {
+        Self::default()
+    }
+
+    /// Specify whether upper and lower case letters are considered equal (using the unicode
+    /// lowercase mapping).
+    pub fn ignore_case(mut self, ignore: bool) -> Self {
+        self.ignore_case = ignore;
+        self
+    }
+
+    /// Ignore case only if `pattern` does not contain upper case letters (like "smartcase" in
+    /// vim).
+    pub fn smart_case(self, pattern: &str) -> Self {
+        self.ignore_case(!pattern.chars().any(char::is_uppercase))
+    }
+
+    /// Specify whether letters with diacritics are considered equal to their base letters, e.g.,
+    /// "é" to "e". Combining marks are ignored and precomposed latin letters are replaced by their
+    /// base letters.
+    pub fn ignore_diacritics(mut self, ignore: bool) -> Self {
+        self.ignore_diacritics = ignore;
+        self
+    }
+
+    fn is_exact(&self) -> bool {
+        !self.ignore_case && !self.ignore_diacritics
+    }
+
+    /// Transform a grapheme cluster into the form in which it is compared.
+    fn fold<'a>(&self, cluster: &'a str) -> Cow<'a, str> {
+        if self.is_exact() {
+            return Cow::Borrowed(cluster);
+        }
+        let mut folded = String::with_capacity(cluster.len());
+        for c in cluster.chars() {
+            let c = if self.ignore_diacritics {
+                if is_combining_mark(c) {
+                    continue;
+                }
+                base_letter(c)
+            } else {
+                c
+            };
+            if self.ignore_case {
+                folded.extend(c.to_lowercase());
+            } else {
+                folded.push(c);
+            }
+        }
+        Cow::Owned(folded)
+    }
+
+    fn folded_clusters<'a>(&self, text: &'a str) -> Vec<(Range<usize>, Cow<'a, str>)> {
+        text.grapheme_indices(true)
+            .map(|(i, g)| (i..i + g.len(), self.fold(g)))
+            .collect()
+    }
+
+    /// Find all non-overlapping matches of `pattern` in `text` (as byte ranges), from left to
+    /// right. An empty pattern does not match.
+    pub fn find_all(&self, text: &str, pattern: &str) -> Vec<Range<usize>> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+        if self.is_exact() {
+            return text
+                .match_indices(pattern)
+                .map(|(i, m)| i..i + m.len())
+                .collect();
+        }
+        let pattern = self
+            .folded_clusters(pattern)
+            .into_iter()
+            .map(|(_, g)| g)
+            .collect::<Vec<_>>();
+        let clusters = self.folded_clusters(text);
+        let mut matches = Vec::new();
+        let mut i = 0;
+        while i + pattern.len() <= clusters.len() {
+            let candidate = &clusters[i..i + pattern.len()];
+            if candidate
+                .iter()
+                .zip(pattern.iter())
+                .all(|((_, g), p)| g == p)
+            {
+                matches.push(candidate[0].0.start..candidate[pattern.len() - 1].0.end);
+                i += pattern.len();
+            } else {
+                i += 1;
+            }
+        }
+        matches
+    }
+
+    /// Check whether `pattern` occurs in `text`.
+    pub fn contains(&self, text: &str, pattern: &str) -> bool {
+        if self.is_exact() {
+            !pattern.is_empty() && text.contains(pattern)
+        } else {
+            !self.find_all(text, pattern).is_empty()
+        }
+    }
+
+    /// Check whether the grapheme clusters of `pattern` occur in `text` in the same order (but not
+    /// necessarily next to each other) and rate the match. An empty pattern matches every text
+    /// with a score of 0.
+    ///
+    /// Matches score higher if the matched clusters are consecutive or at the beginning of
+    /// words, and lower the more clusters lie between the first and the last matched cluster.
+    pub fn fuzzy_match(&self, text: &str, pattern: &str) -> Option<FuzzyMatch> {
+        let pattern = self
+            .folded_clusters(pattern)
+            .into_iter()
+            .map(|(_, g)| g)
+            .collect::<Vec<_>>();
+        if pattern.is_empty() {
+            return Some(FuzzyMatch {
+                score: 0,
+                positions: Vec::new(),
+            });
+        }
+        let clusters = self.folded_clusters(text);
+
+        // Find the end of the first match, then the closest start before it.
+        let mut remaining = pattern.iter().peekable();
+        let end = clusters.iter().position(|(_, g)| {
+            if remaining.peek() == Some(&g) {
+                remaining.next();
+            }
+            remaining.peek().is_none()
+        })?;
+        let mut remaining = pattern.iter().rev().peekable();
+        let start = (0..=end).rev().find(|&i| {
+            if remaining.peek() == Some(&&clusters[i].1) {
+                remaining.next();
+            }
+            remaining.peek().is_none()
+        })?;
+
+        let mut remaining = pattern.iter().peekable();
+        let mut matched = Vec::with_capacity(pattern.len());
+        for (i, (_, g)) in clusters.iter().enumerate().take(end + 1).skip(start) {
+            if remaining.peek() == Some(&g) {
+                remaining.next();
+                matched.push(i);
+            }
+        }
+
+        let is_word_char = |i: usize| {
+            text[clusters[i].0.clone()]
+                .chars()
+                .next()
+                .is_some_and(char::is_alphanumeric)
+        };
+        let mut score = -((end + 1 - start - matched.len()) as i64);
+        let mut positions: Vec<Range<usize>> = Vec::new();
+        for (n, &i) in matched.iter().enumerate() {
+            score += 16;
+            if n > 0 && matched[n - 1] + 1 == i {
+                score += 8;
+            }
+            if is_word_char(i) && (i == 0 || !is_word_char(i - 1)) {
+                score += 8;
+            }
+            let range = clusters[i].0.clone();
+            match positions.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => positions.push(range),
+            }
+        }
+        Some(FuzzyMatch { score, positions })
+    }
+}
+
+/// The result of `MatchMode::fuzzy_match`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// The rating of the match: Higher is better. Use it to sort candidates, the absolute value
+    /// has no meaning.
+    pub score: i64,
+    /// The byte ranges of the matched parts of the text (e.g., for highlighting), where adjacent
+    /// matched clusters are merged.
+    pub positions: Vec<Range<usize>>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_all() {
+        let exact = MatchMode::exact();
+        assert_eq!(exact.find_all("aXaxa", "a"), vec![0..1, 2..3, 4..5]);
+        assert_eq!(exact.find_all("abc", ""), vec![]);
+        assert!(!exact.contains("Über", "über"));
+
+        let caseless = MatchMode::exact().ignore_case(true);
+        assert_eq!(caseless.find_all("ÜBER über", "über"), vec![0..5, 6..11]);
+        assert_eq!(caseless.find_all("aaaa", "aa"), vec![0..2, 2..4]);
+        assert!(!caseless.contains("e\u{301}", "e"));
+
+        let plain = MatchMode::exact().ignore_diacritics(true);
+        assert_eq!(
+            plain.find_all("e\u{301}tude Étude étude", "etude"),
+            vec![0..7, 15..21]
+        );
+        assert!(!plain.contains("Étude", "etude"));
+        assert!(plain.smart_case("etude").contains("Étude", "etude"));
+        assert!(!plain.smart_case("Etude").contains("étude", "Etude"));
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let mode = MatchMode::exact();
+        assert_eq!(mode.fuzzy_match("abc", "").unwrap().score, 0);
+        assert_eq!(mode.fuzzy_match("abc", "ca"), None);
+        assert_eq!(
+            mode.fuzzy_match("a_abc", "abc").unwrap().positions,
+            vec![2..5]
+        );
+        let consecutive = mode.fuzzy_match("xabcx", "abc").unwrap().score;
+        let spread = mode.fuzzy_match("xaxbxcx", "abc").unwrap().score;
+        let word_starts = mode.fuzzy_match("x a b c", "abc").unwrap().score;
+        assert!(consecutive > spread);
+        assert!(word_starts > spread);
+        assert!(
+            mode.fuzzy_match("abc_x", "abc").unwrap().score > consecutive,
+            "word start bonus"
+        );
+        assert_eq!(
+            MatchMode::exact()
+                .ignore_case(true)
+                .fuzzy_match("Ärger", "äg")
+                .unwrap()
+                .positions,
+            vec![0..2, 3..4]
+        );
+    }
+}
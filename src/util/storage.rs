@@ -0,0 +1,122 @@
+//! Persistence of small pieces of user context (e.g., the history of a `PromptLine`) across
+//! restarts of an application.
+//!
+//! Widgets that support persistence take a `Storage` and a name under which their state is saved
+//! as a blob. Applications usually use a `FileStorage` in a directory of their choice and
+//! `MemoryStorage` in tests.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::util::*;
+//! use unsegen::widget::builtin::PromptLine;
+//!
+//! let mut storage = MemoryStorage::new();
+//! let mut prompt = PromptLine::with_prompt("> ".to_owned());
+//! prompt.line.set("ls");
+//! prompt.finish_line();
+//! prompt.save_history(&mut storage, "commands").unwrap();
+//!
+//! let mut restarted = PromptLine::with_prompt("> ".to_owned());
+//! restarted.load_history(&storage, "commands").unwrap();
+//! assert_eq!(restarted.previous_line(1), Some("ls"));
+//! ```
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// A store of named blobs.
+pub trait Storage {
+    /// Load the blob with the given name. Returns `None` if nothing has been saved under that
+    /// name yet.
+    fn load(&self, name: &str) -> io::Result<Option<Vec<u8>>>;
+    /// Save `data` under the given name, replacing the previous blob (if any).
+    fn save(&mut self, name: &str, data: &[u8]) -> io::Result<()>;
+}
+
+/// Stores each blob in a file (named like the blob) in a directory, which is created when the
+/// first blob is saved.
+///
+/// Names must be valid file names, i.e., they must not be empty or contain path separators.
+#[derive(Clone, Debug)]
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    /// Store blobs in `dir`, e.g., a subdirectory of `$XDG_STATE_HOME`.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        FileStorage { dir: dir.into() }
+    }
+
+    fn path(&self, name: &str) -> io::Result<PathBuf> {
+        if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid storage name: {:?}", name),
+            ));
+        }
+        Ok(self.dir.join(name))
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path(name)?) {
+            Ok(data) => Ok(Some(data)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The blob is written to a temporary file first, so that a crash while saving does not
+    /// destroy the previous blob.
+    fn save(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let path = self.path(name)?;
+        fs::create_dir_all(&self.dir)?;
+        let tmp = self.dir.join(format!(".{}.tmp", name));
+        fs::write(&tmp, data)?;
+        fs::rename(&tmp, &path)
+    }
+}
+
+/// Keeps all blobs in memory, e.g., for tests.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    blobs: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryStorage {
+    /// Create an empty storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.blobs.get(name).cloned())
+    }
+
+    fn save(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        self.blobs.insert(name.to_owned(), data.to_vec());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_file_storage() {
+        let dir = ::std::env::temp_dir().join(format!("unsegen-storage-{}", ::std::process::id()));
+        let mut storage = FileStorage::new(&dir);
+        assert_eq!(storage.load("history").unwrap(), None);
+        storage.save("history", b"foo").unwrap();
+        storage.save("history", b"bar").unwrap();
+        assert_eq!(storage.load("history").unwrap(), Some(b"bar".to_vec()));
+        assert!(storage.save("../history", b"").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,197 @@
+//! A generic undo/redo history based on reversible commands.
+//!
+//! Modifications of some state `T` are expressed as `Command`s, which can be applied to and
+//! reverted from the state. An `UndoHistory` keeps a bounded number of applied commands so that
+//! they can be undone (and redone) later on. `Table` and `TextEdit` use this to provide undo of
+//! row edits and text modifications, but applications can use it for their own state as well,
+//! e.g., to offer undo of destructive actions.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::util::*;
+//!
+//! struct Push(i32);
+//! impl Command<Vec<i32>> for Push {
+//!     fn apply(&mut self, target: &mut Vec<i32>) {
+//!         target.push(self.0);
+//!     }
+//!     fn revert(&mut self, target: &mut Vec<i32>) {
+//!         target.pop();
+//!     }
+//! }
+//!
+//! let mut numbers = Vec::new();
+//! let mut history = UndoHistory::new(2);
+//! history.execute(Push(1), &mut numbers);
+//! history.execute(Push(2), &mut numbers);
+//! history.execute(Push(3), &mut numbers);
+//! assert_eq!(numbers, vec![1, 2, 3]);
+//!
+//! history.undo(&mut numbers).unwrap();
+//! history.undo(&mut numbers).unwrap();
+//! // Only the last two commands are kept:
+//! assert!(history.undo(&mut numbers).is_err());
+//! assert_eq!(numbers, vec![1]);
+//!
+//! history.redo(&mut numbers).unwrap();
+//! assert_eq!(numbers, vec![1, 2]);
+//! ```
+use input::OperationResult;
+use std::collections::VecDeque;
+
+/// A reversible modification of a target of type `T`.
+///
+/// `revert` is only called after `apply` (and vice versa), with the target in the state right
+/// after the previous call.
+pub trait Command<T: ?Sized> {
+    /// Apply the modification to `target`.
+    fn apply(&mut self, target: &mut T);
+    /// Undo the modification of `target`.
+    fn revert(&mut self, target: &mut T);
+}
+
+/// A sequence of commands that is applied (and reverted) as a single command.
+impl<T: ?Sized> Command<T> for Vec<Box<dyn Command<T>>> {
+    fn apply(&mut self, target: &mut T) {
+        for command in self.iter_mut() {
+            command.apply(target);
+        }
+    }
+    fn revert(&mut self, target: &mut T) {
+        for command in self.iter_mut().rev() {
+            command.revert(target);
+        }
+    }
+}
+
+/// A bounded history of applied commands that can be undone and redone.
+///
+/// Executing (or recording) a new command discards all commands that have been undone, i.e., the
+/// history is linear. If the capacity is exceeded, the oldest commands are dropped.
+pub struct UndoHistory<T: ?Sized> {
+    done: VecDeque<Box<dyn Command<T>>>,
+    undone: Vec<Box<dyn Command<T>>>,
+    capacity: usize,
+}
+
+impl<T: ?Sized> UndoHistory<T> {
+    /// Create an empty history that keeps at most `capacity` commands.
+    pub fn new(capacity: usize) -> Self {
+        UndoHistory {
+            done: VecDeque::new(),
+            undone: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Change the maximum number of commands that are kept. Drops the oldest commands if
+    /// necessary.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.truncate();
+    }
+
+    /// Apply `command` to `target` and add it to the history.
+    pub fn execute<C: Command<T> + 'static>(&mut self, mut command: C, target: &mut T) {
+        command.apply(target);
+        self.record(command);
+    }
+
+    /// Add a command to the history that has already been applied to the target.
+    pub fn record<C: Command<T> + 'static>(&mut self, command: C) {
+        self.undone.clear();
+        self.done.push_back(Box::new(command));
+        self.truncate();
+    }
+
+    /// Revert the most recently applied command. Fails if there is nothing to undo.
+    pub fn undo(&mut self, target: &mut T) -> OperationResult {
+        let mut command = self.done.pop_back().ok_or(())?;
+        command.revert(target);
+        self.undone.push(command);
+        Ok(())
+    }
+
+    /// Apply the most recently undone command again. Fails if there is nothing to redo.
+    pub fn redo(&mut self, target: &mut T) -> OperationResult {
+        let mut command = self.undone.pop().ok_or(())?;
+        command.apply(target);
+        self.done.push_back(command);
+        Ok(())
+    }
+
+    /// Check whether there is a command that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Check whether there is a command that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Discard all commands, e.g., because the target was modified in a way that is not tracked
+    /// by the history.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+
+    fn truncate(&mut self) {
+        while self.done.len() > self.capacity {
+            self.done.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Add(i32);
+    impl Command<i32> for Add {
+        fn apply(&mut self, target: &mut i32) {
+            *target += self.0;
+        }
+        fn revert(&mut self, target: &mut i32) {
+            *target -= self.0;
+        }
+    }
+
+    #[test]
+    fn test_undo_history() {
+        let mut value = 0;
+        let mut history = UndoHistory::new(10);
+        assert!(history.undo(&mut value).is_err());
+        history.execute(Add(1), &mut value);
+        history.execute(Add(10), &mut value);
+        history.undo(&mut value).unwrap();
+        assert_eq!(value, 1);
+        assert!(history.can_redo());
+        history.execute(Add(100), &mut value);
+        assert!(!history.can_redo());
+        assert!(history.redo(&mut value).is_err());
+        history.undo(&mut value).unwrap();
+        history.undo(&mut value).unwrap();
+        assert_eq!(value, 0);
+        assert!(!history.can_undo());
+        history.redo(&mut value).unwrap();
+        assert_eq!(value, 1);
+    }
+
+    #[test]
+    fn test_undo_history_capacity_and_groups() {
+        let mut value = 0;
+        let mut history = UndoHistory::new(3);
+        for _ in 0..5 {
+            history.execute(Add(1), &mut value);
+        }
+        history.set_capacity(1);
+        let group: Vec<Box<dyn Command<i32>>> = vec![Box::new(Add(2)), Box::new(Add(3))];
+        history.execute(group, &mut value);
+        assert_eq!(value, 10);
+        history.undo(&mut value).unwrap();
+        assert_eq!(value, 5);
+        assert!(history.undo(&mut value).is_err());
+    }
+}
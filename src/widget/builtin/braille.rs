@@ -0,0 +1,132 @@
+//! A canvas with a resolution of 2x4 "pixels" per cell using unicode braille characters.
+use base::basic_types::*;
+use base::{Cursor, Window};
+use widget::{Demand, Demand2D, RenderingHints, Widget};
+
+const BRAILLE_BASE: u32 = 0x2800;
+// Bit of the braille dot at (x, y) within a cell, indexed by [y][x].
+const DOT_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// A monochrome pixel canvas that is drawn using braille characters, i.e., every cell of the
+/// terminal displays 2 (horizontal) x 4 (vertical) pixels.
+///
+/// Pixel (0, 0) is the top left pixel. Pixels outside of the canvas are silently ignored.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::*;
+/// use unsegen::widget::*;
+/// use unsegen::widget::builtin::BrailleCanvas;
+///
+/// let mut canvas = BrailleCanvas::new(Width::new(2).unwrap(), Height::new(1).unwrap());
+/// canvas.line(0, 3, 3, 0);
+///
+/// let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((2, 1));
+/// canvas.draw(term.create_root_window(), RenderingHints::new());
+/// term.assert_looks_like("⡠⠊");
+/// ```
+#[derive(Clone, Debug)]
+pub struct BrailleCanvas {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    /// Create an empty canvas that covers the specified number of cells.
+    pub fn new(width: Width, height: Height) -> Self {
+        let width = width.raw_value() as usize;
+        let height = height.raw_value() as usize;
+        BrailleCanvas {
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    /// The number of pixels in horizontal direction.
+    pub fn pixel_width(&self) -> usize {
+        self.width * 2
+    }
+
+    /// The number of pixels in vertical direction.
+    pub fn pixel_height(&self) -> usize {
+        self.height * 4
+    }
+
+    /// Set the pixel at (x, y).
+    pub fn set(&mut self, x: i32, y: i32) {
+        if x < 0 || y < 0 || x as usize >= self.pixel_width() || y as usize >= self.pixel_height() {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        self.cells[(y / 4) * self.width + x / 2] |= DOT_BITS[y % 4][x % 2];
+    }
+
+    /// Check whether the pixel at (x, y) is set.
+    pub fn is_set(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x as usize >= self.pixel_width() || y as usize >= self.pixel_height() {
+            return false;
+        }
+        let (x, y) = (x as usize, y as usize);
+        self.cells[(y / 4) * self.width + x / 2] & DOT_BITS[y % 4][x % 2] != 0
+    }
+
+    /// Set all pixels on the line from (x0, y0) to (x1, y1) (inclusive).
+    pub fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
+        // Bresenham's line algorithm
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let (mut x, mut y) = (x0, y0);
+        let mut err = dx + dy;
+        loop {
+            self.set(x, y);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Clear all pixels.
+    pub fn clear(&mut self) {
+        for cell in &mut self.cells {
+            *cell = 0;
+        }
+    }
+}
+
+impl Widget for BrailleCanvas {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::exact(self.width),
+            height: Demand::exact(self.height),
+        }
+    }
+    /// Only cells with at least one pixel set are drawn. All other cells are left untouched.
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let mut cursor = Cursor::new(&mut window);
+        for (i, bits) in self.cells.iter().enumerate() {
+            if *bits == 0 {
+                continue;
+            }
+            let c = ::std::char::from_u32(BRAILLE_BASE + u32::from(*bits))
+                .expect("valid braille character");
+            cursor.move_to(
+                ColIndex::new((i % self.width) as i32),
+                RowIndex::new((i / self.width) as i32),
+            );
+            cursor.write(&c.to_string());
+        }
+    }
+}
@@ -0,0 +1,396 @@
+//! Ready-made widgets for common types of cells in a `Table`.
+//!
+//! The widgets borrow or copy the data to display, so they can be returned directly from the
+//! `access` function of a `Column`. Text that should be shortened with an ellipsis instead of
+//! being clipped can be displayed using `TruncatedLabel`.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::base::terminal::test::FakeTerminal;
+//! use unsegen::base::Width;
+//! use unsegen::widget::builtin::*;
+//! use unsegen::widget::*;
+//!
+//! struct Download {
+//!     name: String,
+//!     size: u64,
+//!     progress: f64,
+//!     rates: Vec<f64>,
+//! }
+//!
+//! impl TableRow for Download {
+//!     type BehaviorContext = ();
+//!     const COLUMNS: &'static [Column<Self>] = &[
+//!         Column {
+//!             access: |r| Box::new(WrappedTextCell::new(&r.name, Width::new(6).unwrap())),
+//!             behavior: |_, i, _| Some(i),
+//!         },
+//!         Column {
+//!             access: |r| Box::new(NumberCell::new(r.size as f64)),
+//!             behavior: |_, i, _| Some(i),
+//!         },
+//!         Column {
+//!             access: |r| Box::new(ProgressCell::new(r.progress).percentage(false)),
+//!             behavior: |_, i, _| Some(i),
+//!         },
+//!         Column {
+//!             access: |r| Box::new(SparklineCell::new(&r.rates)),
+//!             behavior: |_, i, _| Some(i),
+//!         },
+//!     ];
+//! }
+//!
+//! let mut table = Table::new();
+//! table.rows_mut().push(Download {
+//!     name: "foo bar baz".to_owned(),
+//!     size: 1234567,
+//!     progress: 0.5,
+//!     rates: vec![1.0, 2.0, 3.0],
+//! });
+//!
+//! let mut term = FakeTerminal::with_size((22, 3));
+//! table
+//!     .as_widget()
+//!     .col_separation(SeparatingStyle::Draw(unsegen::base::GraphemeCluster::try_from('|').unwrap()))
+//!     .draw(term.create_root_window(), RenderingHints::new());
+//! // The row is three lines high to fit the wrapped name:
+//! term.assert_looks_like(concat!(
+//!     "foo |1,234,567|█▌ |▁▅█|",
+//!     "bar |         |   |   |",
+//!     "baz |         |   |   "
+//! ));
+//! ```
+use base::basic_types::*;
+use base::{
+    rendering_profile, BreakPolicy, Cursor, CursorTarget, ExtentEstimationWindow, GraphemeCluster,
+    RenderingProfile, Window, WrappingMode,
+};
+use widget::{text_width, Demand, Demand2D, RenderingHints, RowDemand, Widget};
+
+/// Format `value` with `precision` decimal places and the integer digits grouped in threes by
+/// `separator` (if any).
+///
+/// # Examples:
+/// ```
+/// use unsegen::widget::builtin::format_number;
+///
+/// assert_eq!(format_number(1234567.891, 2, Some(',')), "1,234,567.89");
+/// assert_eq!(format_number(-1234.0, 0, Some('\'')), "-1'234");
+/// assert_eq!(format_number(999.0, 0, None), "999");
+/// ```
+pub fn format_number(value: f64, precision: usize, separator: Option<char>) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let formatted = format!("{:.*}", precision, value.abs());
+    let (integer, fraction) = match formatted.find('.') {
+        Some(pos) => formatted.split_at(pos),
+        None => (formatted.as_str(), ""),
+    };
+    let mut res = String::new();
+    if value < 0.0 && formatted.chars().any(|c| c != '0' && c != '.') {
+        res.push('-');
+    }
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            if let Some(separator) = separator {
+                res.push(separator);
+            }
+        }
+        res.push(digit);
+    }
+    res.push_str(fraction);
+    res
+}
+
+/// A right-aligned number with thousands separators (see `format_number`).
+///
+/// If the number does not fit into the window, the cell is filled with `#`.
+pub struct NumberCell {
+    value: f64,
+    precision: usize,
+    separator: Option<char>,
+}
+
+impl NumberCell {
+    /// Display `value` without decimal places and with `,` as the thousands separator.
+    pub fn new(value: f64) -> Self {
+        NumberCell {
+            value,
+            precision: 0,
+            separator: Some(','),
+        }
+    }
+
+    /// Specify the number of decimal places (default: 0).
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Specify the thousands separator or disable grouping using `None` (default: `,`).
+    pub fn separator(mut self, separator: Option<char>) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    fn text(&self) -> String {
+        format_number(self.value, self.precision, self.separator)
+    }
+}
+
+impl Widget for NumberCell {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::exact(text_width(&self.text())),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let text = self.text();
+        let width = text_width(&text);
+        if width > window.get_width() {
+            window.fill(GraphemeCluster::try_from('#').unwrap());
+            return;
+        }
+        let start = (window.get_width() - width).from_origin();
+        let mut cursor = Cursor::new(&mut window);
+        cursor.move_to_x(start);
+        cursor.write(&text);
+    }
+}
+
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const ASCII_SPARK_LEVELS: [char; 8] = ['_', '.', '-', '~', '=', '+', '*', '#'];
+
+/// A horizontal bar that fills the cell according to a fraction in `[0, 1]`, optionally followed
+/// by the percentage.
+///
+/// The bar is drawn with a resolution of an eighth of a cell (or of whole cells using `#` if the
+/// `RenderingProfile` is ASCII).
+pub struct ProgressCell {
+    fraction: f64,
+    percentage: bool,
+}
+
+impl ProgressCell {
+    /// Display `fraction` (which is clamped to `[0, 1]`).
+    pub fn new(fraction: f64) -> Self {
+        ProgressCell {
+            fraction: if fraction.is_nan() {
+                0.0
+            } else {
+                fraction.clamp(0.0, 1.0)
+            },
+            percentage: true,
+        }
+    }
+
+    /// Specify whether the percentage is displayed right of the bar (default: true).
+    pub fn percentage(mut self, show: bool) -> Self {
+        self.percentage = show;
+        self
+    }
+
+    fn bar(&self, bar_width: usize, profile: RenderingProfile) -> String {
+        match profile {
+            RenderingProfile::Unicode => {
+                let eighths = (self.fraction * (bar_width * 8) as f64).round() as usize;
+                let mut bar = "█".repeat(eighths / 8);
+                let partial = eighths % 8;
+                if partial > 0 {
+                    bar.push(PARTIAL_BLOCKS[partial - 1]);
+                }
+                bar
+            }
+            RenderingProfile::Ascii => {
+                "#".repeat((self.fraction * bar_width as f64).round() as usize)
+            }
+        }
+    }
+
+    fn label(&self) -> String {
+        if self.percentage {
+            format!(" {:>3}%", (self.fraction * 100.0).round())
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl Widget for ProgressCell {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::at_least(text_width(&self.label()) + 1),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let label = self.label();
+        let bar_width = (window.get_width() - text_width(&label))
+            .positive_or_zero()
+            .raw_value() as usize;
+        let bar = self.bar(bar_width, rendering_profile());
+        let mut cursor = Cursor::new(&mut window);
+        cursor.write(&bar);
+        cursor.move_to_x(ColIndex::new(bar_width as i32));
+        cursor.write(&label);
+    }
+}
+
+/// A small line chart of values using block characters of eight different heights.
+///
+/// If there are more values than columns in the window, only the last values are displayed.
+pub struct SparklineCell<'a> {
+    values: &'a [f64],
+    range: Option<(f64, f64)>,
+}
+
+impl<'a> SparklineCell<'a> {
+    /// Display `values` scaled to the minimum and maximum of all values.
+    pub fn new(values: &'a [f64]) -> Self {
+        SparklineCell {
+            values,
+            range: None,
+        }
+    }
+
+    /// Use a fixed value range instead of the minimum and maximum of the values. Values outside
+    /// of the range are clamped.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+}
+
+impl<'a> Widget for SparklineCell<'a> {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::from_to(1, self.values.len().max(1)),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let width = window.get_width().raw_value() as usize;
+        let values = &self.values[self.values.len().saturating_sub(width)..];
+        let (min, max) = self.range.unwrap_or_else(|| {
+            self.values
+                .iter()
+                .filter(|v| !v.is_nan())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+                    (min.min(v), max.max(v))
+                })
+        });
+        let levels = rendering_profile().select(&SPARK_LEVELS, &ASCII_SPARK_LEVELS);
+        let line = values
+            .iter()
+            .map(|&v| {
+                if v.is_nan() {
+                    ' '
+                } else if max > min {
+                    let rel = ((v - min) / (max - min)).clamp(0.0, 1.0);
+                    levels[(rel * 7.0).round() as usize]
+                } else {
+                    levels[0]
+                }
+            })
+            .collect::<String>();
+        Cursor::new(&mut window).write(&line);
+    }
+}
+
+/// Text that is wrapped (preferably at whitespace and punctuation) at a fixed width, so that the
+/// height of the row grows with the length of the text.
+pub struct WrappedTextCell<'a> {
+    text: &'a str,
+    width: Width,
+}
+
+impl<'a> WrappedTextCell<'a> {
+    /// Display `text` wrapped at `width` columns.
+    pub fn new(text: &'a str, width: Width) -> Self {
+        WrappedTextCell { text, width }
+    }
+
+    fn write_to<T: CursorTarget>(&self, cursor: Cursor<T>) {
+        cursor
+            .wrapping_mode(WrappingMode::Wrap)
+            .break_policy(BreakPolicy::AtPunctuation)
+            .write(self.text);
+    }
+}
+
+impl<'a> Widget for WrappedTextCell<'a> {
+    fn space_demand(&self) -> Demand2D {
+        let mut window = ExtentEstimationWindow::with_width(self.width);
+        self.write_to(Cursor::new(&mut window));
+        Demand2D {
+            width: Demand::exact(window.extent_x()),
+            height: Demand::exact(window.extent_y()),
+        }
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        let mut window = ExtentEstimationWindow::with_width(width.min(self.width));
+        self.write_to(Cursor::new(&mut window));
+        Demand::exact(window.extent_y())
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        self.write_to(Cursor::new(&mut window));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    fn draw(size: (u32, u32), widget: impl Widget) -> FakeTerminal {
+        let mut term = FakeTerminal::with_size(size);
+        widget.draw(term.create_root_window(), RenderingHints::new());
+        term
+    }
+
+    #[test]
+    fn test_number_cell() {
+        draw((8, 1), NumberCell::new(12345.678).precision(1)).assert_looks_like("12,345.7");
+        draw((10, 1), NumberCell::new(-0.001).precision(2)).assert_looks_like("      0.00");
+        draw((4, 1), NumberCell::new(12345.0)).assert_looks_like("####");
+        assert_eq!(format_number(123456.0, 0, Some(',')), "123,456");
+        assert_eq!(format_number(f64::NAN, 2, Some(',')), "NaN");
+    }
+
+    #[test]
+    fn test_progress_cell() {
+        draw((10, 1), ProgressCell::new(0.5)).assert_looks_like("██▌    50%");
+        draw((4, 1), ProgressCell::new(1.5).percentage(false)).assert_looks_like("████");
+        draw((4, 1), ProgressCell::new(0.1).percentage(false)).assert_looks_like("▍   ");
+    }
+
+    #[test]
+    fn test_progress_cell_ascii() {
+        let bar =
+            |fraction: f64, width| ProgressCell::new(fraction).bar(width, RenderingProfile::Ascii);
+        assert_eq!(bar(0.5, 5), "###");
+        assert_eq!(bar(0.1, 4), "");
+        assert_eq!(bar(1.0, 4), "####");
+    }
+
+    #[test]
+    fn test_sparkline_cell() {
+        let values = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        draw((8, 1), SparklineCell::new(&values)).assert_looks_like("▁▂▃▄▅▆▇█");
+        draw((3, 1), SparklineCell::new(&values)).assert_looks_like("▆▇█");
+        draw((3, 1), SparklineCell::new(&values[..3]).range(0.0, 14.0)).assert_looks_like("▁▂▂");
+        draw((2, 1), SparklineCell::new(&[1.0, 1.0])).assert_looks_like("▁▁");
+    }
+
+    #[test]
+    fn test_wrapped_text_cell() {
+        let cell = WrappedTextCell::new("foo bar, baz", Width::new(5).unwrap());
+        let demand = cell.space_demand();
+        assert_eq!(demand.width, Demand::exact(5));
+        assert_eq!(demand.height, Demand::exact(3));
+        draw((5, 3), cell).assert_looks_like("foo  |bar, |baz  ");
+    }
+}
@@ -0,0 +1,557 @@
+//! Widgets for choosing colors and previewing styles, e.g., for runtime theme configuration.
+use base::basic_types::*;
+use base::{BoolModifyMode, Color, Cursor, Style, StyleModifier, Window};
+use input::{Editable, Navigatable, OperationResult, Writable};
+use widget::builtin::NumberInput;
+use widget::{text_width, Demand, Demand2D, RenderingHints, Widget};
+
+const NAMED_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::LightBlack,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::LightWhite,
+];
+const NAMED_COLUMNS: usize = 8;
+const NAMED_SWATCH_WIDTH: i32 = 3;
+
+const CUBE_COLUMNS: usize = 36;
+const CUBE_ROWS: usize = 6;
+const NUM_GRAYS: usize = 24;
+const FIRST_CUBE_INDEX: usize = 16;
+const FIRST_GRAY_INDEX: usize = 232;
+
+const CHANNEL_NAMES: [&str; 3] = ["R", "G", "B"];
+
+/// The ways a color can be chosen in a `ColorPicker`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorPickerMode {
+    /// One of the 16 named colors (`Color::Black`, ..., `Color::LightWhite`).
+    Named,
+    /// One of the 6x6x6 color cube or the 24 grayscale values of the 256 color palette.
+    Palette,
+    /// Arbitrary RGB values.
+    Rgb,
+}
+
+impl ColorPickerMode {
+    fn name(self) -> &'static str {
+        match self {
+            ColorPickerMode::Named => "Named",
+            ColorPickerMode::Palette => "Palette",
+            ColorPickerMode::Rgb => "RGB",
+        }
+    }
+
+    fn all() -> [ColorPickerMode; 3] {
+        [
+            ColorPickerMode::Named,
+            ColorPickerMode::Palette,
+            ColorPickerMode::Rgb,
+        ]
+    }
+}
+
+/// Choose a color from the named colors, the 256 color palette or by entering RGB values.
+///
+/// In `Named` and `Palette` mode, swatches are selected via `Navigatable`. In `Rgb` mode, up/down
+/// selects the channel, left/right decrements/increments its value and values can also be entered
+/// as text (`Writable`/`Editable`).
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::Color;
+/// use unsegen::input::{Navigatable, Writable};
+/// use unsegen::widget::builtin::{ColorPicker, ColorPickerMode};
+///
+/// let mut picker = ColorPicker::new();
+/// picker.move_right().unwrap();
+/// picker.move_down().unwrap();
+/// assert_eq!(picker.color(), Color::LightRed);
+///
+/// picker.set_mode(ColorPickerMode::Rgb);
+/// picker.move_down().unwrap(); // Select green channel
+/// picker.write('7').unwrap();
+/// assert_eq!(picker.color(), Color::Rgb { r: 0, g: 7, b: 0 });
+/// ```
+pub struct ColorPicker {
+    mode: ColorPickerMode,
+    named: usize,
+    palette: (usize, usize),
+    rgb: [NumberInput; 3],
+    channel: usize,
+}
+
+impl ColorPicker {
+    /// Create a color picker in `Named` mode with black selected.
+    pub fn new() -> Self {
+        let channel = || NumberInput::integer(0).min(0.0).max(255.0);
+        ColorPicker {
+            mode: ColorPickerMode::Named,
+            named: 0,
+            palette: (0, 0),
+            rgb: [channel(), channel(), channel()],
+            channel: 0,
+        }
+    }
+
+    /// Get the current mode.
+    pub fn mode(&self) -> ColorPickerMode {
+        self.mode
+    }
+
+    /// Change the mode. The selection within each mode is preserved.
+    pub fn set_mode(&mut self, mode: ColorPickerMode) {
+        self.mode = mode;
+    }
+
+    /// Switch to the next mode (wrapping around after `Rgb`).
+    pub fn next_mode(&mut self) {
+        self.mode = match self.mode {
+            ColorPickerMode::Named => ColorPickerMode::Palette,
+            ColorPickerMode::Palette => ColorPickerMode::Rgb,
+            ColorPickerMode::Rgb => ColorPickerMode::Named,
+        };
+    }
+
+    /// Get the currently chosen color.
+    pub fn color(&self) -> Color {
+        match self.mode {
+            ColorPickerMode::Named => NAMED_COLORS[self.named],
+            ColorPickerMode::Palette => Color::Ansi(palette_index(self.palette) as u8),
+            ColorPickerMode::Rgb => {
+                let channel = |i: usize| self.rgb[i].value() as u8;
+                Color::Rgb {
+                    r: channel(0),
+                    g: channel(1),
+                    b: channel(2),
+                }
+            }
+        }
+    }
+
+    /// Select the specified color, switching to the mode that is appropriate for it. Fails (and
+    /// leaves the picker unchanged) for `Color::Default` and semantic colors.
+    pub fn set_color(&mut self, color: Color) -> OperationResult {
+        match color {
+            Color::Default | Color::Semantic(_) => return Err(()),
+            Color::Rgb { r, g, b } => {
+                for (input, v) in self.rgb.iter_mut().zip(&[r, g, b]) {
+                    input.set_value(f64::from(*v));
+                }
+                self.mode = ColorPickerMode::Rgb;
+            }
+            Color::Ansi(i) if (i as usize) < FIRST_CUBE_INDEX => {
+                self.named = i as usize;
+                self.mode = ColorPickerMode::Named;
+            }
+            Color::Ansi(i) => {
+                let i = i as usize;
+                self.palette = if i < FIRST_GRAY_INDEX {
+                    let i = i - FIRST_CUBE_INDEX;
+                    (i / CUBE_COLUMNS, i % CUBE_COLUMNS)
+                } else {
+                    (CUBE_ROWS, i - FIRST_GRAY_INDEX)
+                };
+                self.mode = ColorPickerMode::Palette;
+            }
+            named => {
+                self.named = NAMED_COLORS
+                    .iter()
+                    .position(|c| *c == named)
+                    .expect("all remaining colors are named");
+                self.mode = ColorPickerMode::Named;
+            }
+        }
+        Ok(())
+    }
+
+    fn move_selection(&mut self, d_row: isize, d_col: isize) -> OperationResult {
+        let (row, col, num_rows) = match self.mode {
+            ColorPickerMode::Named => (self.named / NAMED_COLUMNS, self.named % NAMED_COLUMNS, 2),
+            ColorPickerMode::Palette => (self.palette.0, self.palette.1, CUBE_ROWS + 1),
+            ColorPickerMode::Rgb => unreachable!("Rgb mode has no swatches"),
+        };
+        let row = row as isize + d_row;
+        let col = col as isize + d_col;
+        if row < 0 || row >= num_rows as isize || col < 0 {
+            return Err(());
+        }
+        let (row, col) = (row as usize, col as usize);
+        match self.mode {
+            ColorPickerMode::Named if col < NAMED_COLUMNS => {
+                self.named = row * NAMED_COLUMNS + col;
+                Ok(())
+            }
+            ColorPickerMode::Palette if col < palette_row_len(row) => {
+                self.palette = (row, col);
+                Ok(())
+            }
+            ColorPickerMode::Palette if d_row != 0 => {
+                // Moving between the cube and the (shorter) grayscale row
+                self.palette = (row, palette_row_len(row) - 1);
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn current_channel(&mut self) -> Result<&mut NumberInput, ()> {
+        if self.mode == ColorPickerMode::Rgb {
+            Ok(&mut self.rgb[self.channel])
+        } else {
+            Err(())
+        }
+    }
+
+    /// Prepare for drawing as a `Widget`.
+    pub fn as_widget<'a>(&'a self) -> ColorPickerWidget<'a> {
+        ColorPickerWidget {
+            picker: self,
+            mode_style: StyleModifier::new().invert(BoolModifyMode::Toggle),
+            named_selection: "[ ]",
+            palette_selection: "X",
+        }
+    }
+}
+
+impl Default for ColorPicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn palette_row_len(row: usize) -> usize {
+    if row < CUBE_ROWS {
+        CUBE_COLUMNS
+    } else {
+        NUM_GRAYS
+    }
+}
+
+fn palette_index((row, col): (usize, usize)) -> usize {
+    if row < CUBE_ROWS {
+        FIRST_CUBE_INDEX + row * CUBE_COLUMNS + col
+    } else {
+        FIRST_GRAY_INDEX + col
+    }
+}
+
+impl Navigatable for ColorPicker {
+    fn move_up(&mut self) -> OperationResult {
+        if self.mode == ColorPickerMode::Rgb {
+            if self.channel > 0 {
+                self.channel -= 1;
+                Ok(())
+            } else {
+                Err(())
+            }
+        } else {
+            self.move_selection(-1, 0)
+        }
+    }
+    fn move_down(&mut self) -> OperationResult {
+        if self.mode == ColorPickerMode::Rgb {
+            if self.channel < 2 {
+                self.channel += 1;
+                Ok(())
+            } else {
+                Err(())
+            }
+        } else {
+            self.move_selection(1, 0)
+        }
+    }
+    fn move_left(&mut self) -> OperationResult {
+        if self.mode == ColorPickerMode::Rgb {
+            self.current_channel()?.decrement()
+        } else {
+            self.move_selection(0, -1)
+        }
+    }
+    fn move_right(&mut self) -> OperationResult {
+        if self.mode == ColorPickerMode::Rgb {
+            self.current_channel()?.increment()
+        } else {
+            self.move_selection(0, 1)
+        }
+    }
+}
+
+/// Values can only be written in `Rgb` mode.
+impl Writable for ColorPicker {
+    fn write(&mut self, c: char) -> OperationResult {
+        self.current_channel()?.write(c)
+    }
+}
+
+/// Values can only be edited in `Rgb` mode.
+impl Editable for ColorPicker {
+    fn delete_forwards(&mut self) -> OperationResult {
+        self.current_channel()?.delete_forwards()
+    }
+    fn delete_backwards(&mut self) -> OperationResult {
+        self.current_channel()?.delete_backwards()
+    }
+    fn go_to_beginning_of_line(&mut self) -> OperationResult {
+        self.current_channel()?.go_to_beginning_of_line()
+    }
+    fn go_to_end_of_line(&mut self) -> OperationResult {
+        self.current_channel()?.go_to_end_of_line()
+    }
+    fn clear(&mut self) -> OperationResult {
+        self.current_channel()?.clear()
+    }
+}
+
+/// A `Widget` representing a `ColorPicker`.
+///
+/// The first line lists the modes, followed by the swatches or RGB values of the current mode.
+pub struct ColorPickerWidget<'a> {
+    picker: &'a ColorPicker,
+    mode_style: StyleModifier,
+    named_selection: &'a str,
+    palette_selection: &'a str,
+}
+
+impl<'a> ColorPickerWidget<'a> {
+    /// Define the style that the name of the current mode will be drawn with.
+    pub fn mode(mut self, style: StyleModifier) -> Self {
+        self.mode_style = style;
+        self
+    }
+
+    /// Define the text that marks the selected swatch in `Named` mode (at most 3 cells wide).
+    pub fn named_selection(mut self, marker: &'a str) -> Self {
+        self.named_selection = marker;
+        self
+    }
+
+    /// Define the text that marks the selected swatch in `Palette` mode (1 cell wide).
+    pub fn palette_selection(mut self, marker: &'a str) -> Self {
+        self.palette_selection = marker;
+        self
+    }
+
+    fn draw_swatch(cursor: &mut Cursor, color: Color, content: &str, width: i32) {
+        let mut cursor = cursor.save().style_modifier();
+        cursor.apply_style_modifier(StyleModifier::new().bg_color(color));
+        let padding = (width - text_width(content).raw_value()).max(0);
+        cursor.write(&" ".repeat((padding / 2) as usize));
+        cursor.write(content);
+        cursor.write(&" ".repeat((padding - padding / 2) as usize));
+    }
+}
+
+impl<'a> Widget for ColorPickerWidget<'a> {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::exact(CUBE_COLUMNS),
+            height: Demand::exact(1 + CUBE_ROWS + 1),
+        }
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        let picker = self.picker;
+        {
+            let mut cursor = Cursor::new(&mut window);
+            for (i, mode) in ColorPickerMode::all().iter().enumerate() {
+                if i > 0 {
+                    cursor.write(" ");
+                }
+                let mut cursor = cursor.save().style_modifier();
+                if *mode == picker.mode {
+                    cursor.apply_style_modifier(self.mode_style);
+                }
+                cursor.write(mode.name());
+            }
+
+            match picker.mode {
+                ColorPickerMode::Named => {
+                    for (i, color) in NAMED_COLORS.iter().enumerate() {
+                        if i % NAMED_COLUMNS == 0 {
+                            cursor.move_to(ColIndex::new(0), RowIndex::new(1 + i as i32 / 8));
+                        }
+                        let content = if i == picker.named {
+                            self.named_selection
+                        } else {
+                            ""
+                        };
+                        Self::draw_swatch(&mut cursor, *color, content, NAMED_SWATCH_WIDTH);
+                    }
+                }
+                ColorPickerMode::Palette => {
+                    for row in 0..=CUBE_ROWS {
+                        cursor.move_to(ColIndex::new(0), RowIndex::new(1 + row as i32));
+                        for col in 0..palette_row_len(row) {
+                            let color = Color::Ansi(palette_index((row, col)) as u8);
+                            let content = if (row, col) == picker.palette {
+                                self.palette_selection
+                            } else {
+                                ""
+                            };
+                            Self::draw_swatch(&mut cursor, color, content, 1);
+                        }
+                    }
+                }
+                ColorPickerMode::Rgb => {}
+            }
+        }
+
+        if picker.mode == ColorPickerMode::Rgb {
+            let width = window.get_width().from_origin();
+            let height = window.get_height().from_origin();
+            for (i, input) in picker.rgb.iter().enumerate() {
+                let row = RowIndex::new(1 + i as i32);
+                if row >= height {
+                    break;
+                }
+                let mut line = window.create_subwindow(.., row..row + 1);
+                Cursor::new(&mut line).write(CHANNEL_NAMES[i]);
+                let start = ColIndex::new(2).min(width);
+                input.as_widget().draw(
+                    line.create_subwindow(start.., ..),
+                    hints.active(hints.active && i == picker.channel),
+                );
+            }
+            let row = RowIndex::new(1 + CHANNEL_NAMES.len() as i32);
+            if row < height {
+                let mut line = window.create_subwindow(.., row..row + 1);
+                line.modify_default_style(StyleModifier::new().bg_color(picker.color()));
+                line.clear();
+            }
+        }
+    }
+}
+
+/// A widget that renders sample text with a given `Style`, e.g., to preview theme settings.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::{Color, StyleModifier};
+/// use unsegen::widget::builtin::StylePreview;
+///
+/// let style = StyleModifier::new().fg_color(Color::Red).bold(true).apply_to_default();
+/// let preview = StylePreview::new(style).text("Error: file not found");
+/// ```
+pub struct StylePreview<'a> {
+    style: Style,
+    text: &'a str,
+}
+
+impl<'a> StylePreview<'a> {
+    /// Create a preview of `style` with a default sample text.
+    pub fn new(style: Style) -> Self {
+        StylePreview {
+            style,
+            text: "Sample text",
+        }
+    }
+
+    /// Define the sample text.
+    pub fn text(mut self, text: &'a str) -> Self {
+        self.text = text;
+        self
+    }
+}
+
+impl<'a> Widget for StylePreview<'a> {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::exact(text_width(self.text)),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        window.set_default_style(self.style);
+        window.clear();
+        Cursor::new(&mut window).write(self.text);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    fn test_color_picker(window_dim: (u32, u32), picker: &ColorPicker, after: &str) {
+        let mut term = FakeTerminal::with_size(window_dim);
+        picker
+            .as_widget()
+            .mode(StyleModifier::new().bold(true))
+            .draw(term.create_root_window(), RenderingHints::default());
+        term.assert_looks_like(after);
+    }
+
+    #[test]
+    fn test_color_picker_palette() {
+        let mut picker = ColorPicker::new();
+        picker.set_mode(ColorPickerMode::Palette);
+        assert_eq!(picker.color(), Color::Ansi(16));
+        assert!(picker.move_left().is_err());
+        for _ in 0..CUBE_ROWS {
+            picker.move_down().unwrap();
+        }
+        assert_eq!(picker.color(), Color::Ansi(232));
+        assert!(picker.move_down().is_err());
+
+        picker.set_color(Color::Ansi(231)).unwrap();
+        assert_eq!(picker.mode(), ColorPickerMode::Palette);
+        picker.move_down().unwrap();
+        assert_eq!(picker.color(), Color::Ansi(255));
+        picker.move_up().unwrap();
+        assert_eq!(picker.color(), Color::Ansi(16 + 5 * 36 + 23));
+
+        picker.set_color(Color::LightCyan).unwrap();
+        assert_eq!(picker.mode(), ColorPickerMode::Named);
+        assert!(picker.write('1').is_err());
+        assert!(picker.set_color(Color::Default).is_err());
+    }
+
+    #[test]
+    fn test_color_picker_draw() {
+        let mut picker = ColorPicker::new();
+        picker.move_right().unwrap();
+        test_color_picker(
+            (24, 3),
+            &picker,
+            &[
+                "*N**a**m**e**d* Palette RGB       ",
+                "   [ ]                  ",
+                "                        ",
+            ]
+            .join("|"),
+        );
+
+        picker
+            .set_color(Color::Rgb {
+                r: 1,
+                g: 22,
+                b: 255,
+            })
+            .unwrap();
+        picker.move_down().unwrap();
+        test_color_picker(
+            (12, 5),
+            &picker,
+            &[
+                "Named Palett",
+                "R 1         ",
+                "G 22        ",
+                "B 255       ",
+                "            ",
+            ]
+            .join("|"),
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Formatting of plain data (text, numbers and dates) in the cells of a `Table`.
+//!
+//! Instead of writing a formatting widget for every column, describe the formatting of a column
+//! once as a (constant) `DataColumn` and create the cells from the data of the row using
+//! `DataColumn::cell`.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::base::terminal::test::FakeTerminal;
+//! use unsegen::widget::builtin::*;
+//! use unsegen::widget::*;
+//!
+//! struct Measurement {
+//!     sensor: String,
+//!     value: f64,
+//!     date: Date,
+//! }
+//!
+//! const SENSOR: DataColumn = DataColumn::new().align(Alignment::Center);
+//! const VALUE: DataColumn = DataColumn::new().precision(1).unit(" °C");
+//! const DATE: DataColumn = DataColumn::new().date_format("%d.%m.");
+//!
+//! impl TableRow for Measurement {
+//!     type BehaviorContext = ();
+//!     const COLUMNS: &'static [Column<Self>] = &[
+//!         Column {
+//!             access: |r| Box::new(SENSOR.cell(&r.sensor)),
+//!             behavior: |_, i, _| Some(i),
+//!         },
+//!         Column {
+//!             access: |r| Box::new(VALUE.cell(r.value)),
+//!             behavior: |_, i, _| Some(i),
+//!         },
+//!         Column {
+//!             access: |r| Box::new(DATE.cell(r.date)),
+//!             behavior: |_, i, _| Some(i),
+//!         },
+//!     ];
+//! }
+//!
+//! let mut table = Table::new();
+//! {
+//!     let mut rows = table.rows_mut();
+//!     rows.push(Measurement {
+//!         sensor: "a".to_owned(),
+//!         value: 21.34,
+//!         date: Date::new(2020, 5, 3).unwrap(),
+//!     });
+//!     rows.push(Measurement {
+//!         sensor: "ccc".to_owned(),
+//!         value: -4.0,
+//!         date: Date::new(2020, 12, 24).unwrap(),
+//!     });
+//! }
+//!
+//! let mut term = FakeTerminal::with_size((16, 2));
+//! table.as_widget().draw(term.create_root_window(), RenderingHints::new());
+//! term.assert_looks_like(concat!(
+//!     " a 21.3 °C03.05.|",
+//!     "ccc-4.0 °C24.12."
+//! ));
+//! ```
+use super::cells::format_number;
+use super::datepicker::Date;
+use base::basic_types::*;
+use base::{Cursor, GraphemeCluster, Window};
+use widget::{text_width, Demand, Demand2D, RenderingHints, Widget};
+
+/// The horizontal alignment of the content of a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// Align at the left border of the cell.
+    Left,
+    /// Align at the right border of the cell.
+    Right,
+    /// Center within the cell (rounding towards the left).
+    Center,
+}
+
+/// Plain data that can be displayed in a cell using a `DataColumn`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellData<'a> {
+    /// Text, which is displayed as is.
+    Text(&'a str),
+    /// A number, which is displayed with the precision, separator and unit of the column.
+    Number(f64),
+    /// A date, which is displayed according to the date format of the column.
+    Date(Date),
+    /// Missing data, which is displayed as an empty cell.
+    Empty,
+}
+
+impl<'a> From<&'a str> for CellData<'a> {
+    fn from(text: &'a str) -> Self {
+        CellData::Text(text)
+    }
+}
+
+impl<'a> From<&'a String> for CellData<'a> {
+    fn from(text: &'a String) -> Self {
+        CellData::Text(text)
+    }
+}
+
+impl<'a> From<Date> for CellData<'a> {
+    fn from(date: Date) -> Self {
+        CellData::Date(date)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> From<$t> for CellData<'a> {
+                fn from(value: $t) -> Self {
+                    CellData::Number(value as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(f64, f32, i64, i32, i16, i8, u64, u32, u16, u8, usize, isize);
+
+impl<'a, T: Into<CellData<'a>>> From<Option<T>> for CellData<'a> {
+    fn from(data: Option<T>) -> Self {
+        data.map(Into::into).unwrap_or(CellData::Empty)
+    }
+}
+
+/// Format `date` according to `format`, in which `%Y` (year), `%m` (month), `%d` (day), `%a`
+/// (short weekday name) and `%%` are replaced.
+///
+/// # Examples:
+/// ```
+/// use unsegen::widget::builtin::{format_date, Date};
+///
+/// let date = Date::new(2020, 2, 3).unwrap();
+/// assert_eq!(format_date(date, "%a %d/%m/%Y"), "Mo 03/02/2020");
+/// ```
+pub fn format_date(date: Date, format: &str) -> String {
+    let mut res = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => res.push_str(&format!("{:04}", date.year())),
+            Some('m') => res.push_str(&format!("{:02}", date.month())),
+            Some('d') => res.push_str(&format!("{:02}", date.day())),
+            Some('a') => res.push_str(date.weekday().short_name()),
+            Some('%') => res.push('%'),
+            Some(other) => {
+                res.push('%');
+                res.push(other);
+            }
+            None => res.push('%'),
+        }
+    }
+    res
+}
+
+/// The formatting of a column of plain data in a `Table`.
+///
+/// All methods are `const`, so the formatting can be defined once as a constant and used in the
+/// `access` function of the `Column` (see the module documentation).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DataColumn {
+    alignment: Option<Alignment>,
+    precision: usize,
+    separator: Option<char>,
+    unit: &'static str,
+    date_format: &'static str,
+}
+
+impl Default for DataColumn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataColumn {
+    /// Create the default formatting: numbers are right-aligned, have no decimal places and `,`
+    /// as thousands separator; dates are formatted as `%Y-%m-%d`; everything else is left-aligned.
+    pub const fn new() -> Self {
+        DataColumn {
+            alignment: None,
+            precision: 0,
+            separator: Some(','),
+            unit: "",
+            date_format: "%Y-%m-%d",
+        }
+    }
+
+    /// Align all cells of the column the same way (default: numbers right, everything else left).
+    pub const fn align(mut self, alignment: Alignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    /// Specify the number of decimal places of numbers (default: 0).
+    pub const fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Specify the thousands separator of numbers or disable grouping using `None` (default: `,`).
+    pub const fn separator(mut self, separator: Option<char>) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Specify a unit that is appended to numbers, including any space, e.g., `" MB"` (default:
+    /// none).
+    pub const fn unit(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Specify the format of dates (see `format_date`, default: `%Y-%m-%d`).
+    pub const fn date_format(mut self, format: &'static str) -> Self {
+        self.date_format = format;
+        self
+    }
+
+    /// Format `data` as text according to this column.
+    pub fn format(&self, data: CellData) -> String {
+        match data {
+            CellData::Text(text) => text.to_owned(),
+            CellData::Number(value) => {
+                format_number(value, self.precision, self.separator) + self.unit
+            }
+            CellData::Date(date) => format_date(date, self.date_format),
+            CellData::Empty => String::new(),
+        }
+    }
+
+    /// Create a widget displaying `data` formatted according to this column.
+    pub fn cell<'a, D: Into<CellData<'a>>>(&self, data: D) -> DataCell {
+        let data = data.into();
+        let is_number = matches!(data, CellData::Number(_));
+        DataCell {
+            text: self.format(data),
+            alignment: self.alignment.unwrap_or(if is_number {
+                Alignment::Right
+            } else {
+                Alignment::Left
+            }),
+            is_number,
+        }
+    }
+}
+
+/// A single line of formatted data, created by `DataColumn::cell`.
+///
+/// If the text does not fit into the window, numbers are replaced by `#` (like in a `NumberCell`)
+/// and anything else is clipped.
+pub struct DataCell {
+    text: String,
+    alignment: Alignment,
+    is_number: bool,
+}
+
+impl DataCell {
+    /// Get the formatted text of the cell.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Widget for DataCell {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::exact(text_width(&self.text)),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let width = text_width(&self.text);
+        if width > window.get_width() && self.is_number {
+            window.fill(GraphemeCluster::try_from('#').unwrap());
+            return;
+        }
+        let space = (window.get_width() - width).positive_or_zero().raw_value();
+        let start = match self.alignment {
+            Alignment::Left => 0,
+            Alignment::Right => space,
+            Alignment::Center => space / 2,
+        };
+        let mut cursor = Cursor::new(&mut window);
+        cursor.move_to_x(ColIndex::new(start as i32));
+        cursor.write(&self.text);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    fn draw(size: (u32, u32), widget: impl Widget) -> FakeTerminal {
+        let mut term = FakeTerminal::with_size(size);
+        widget.draw(term.create_root_window(), RenderingHints::new());
+        term
+    }
+
+    #[test]
+    fn test_data_column_format() {
+        let col = DataColumn::new()
+            .precision(2)
+            .separator(Some('\''))
+            .unit("B");
+        assert_eq!(col.format(1234.5.into()), "1'234.50B");
+        assert_eq!(col.format("abc".into()), "abc");
+        assert_eq!(col.format(None::<u32>.into()), "");
+        let date = Date::new(1999, 12, 31).unwrap();
+        assert_eq!(col.format(date.into()), "1999-12-31");
+        assert_eq!(format_date(date, "%Y%% %q%"), "1999% %q%");
+    }
+
+    #[test]
+    fn test_data_cell_alignment() {
+        let col = DataColumn::new();
+        draw((5, 1), col.cell(42u8)).assert_looks_like("   42");
+        draw((5, 1), col.cell("ab")).assert_looks_like("ab   ");
+        draw((5, 1), col.align(Alignment::Center).cell("ab")).assert_looks_like(" ab  ");
+        draw((5, 1), col.align(Alignment::Left).cell(-1)).assert_looks_like("-1   ");
+        draw((3, 1), col.cell(1234)).assert_looks_like("###");
+        draw((3, 1), col.align(Alignment::Right).cell("abcd")).assert_looks_like("abc");
+    }
+}
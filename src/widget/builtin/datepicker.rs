@@ -0,0 +1,462 @@
+//! A calendar widget for selecting a date.
+use base::basic_types::*;
+use base::{BoolModifyMode, Color, Cursor, StyleModifier, Window};
+use input::{Navigatable, OperationResult};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use widget::{Demand, Demand2D, RenderingHints, Widget};
+
+/// A day of the week.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    fn from_index(index: i64) -> Self {
+        match index.rem_euclid(7) {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    /// The number of days since the previous monday, i.e., 0 for monday and 6 for sunday.
+    pub fn index(self) -> i64 {
+        self as i64
+    }
+
+    /// Two letter abbreviation of the (english) name of the day.
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Weekday::Monday => "Mo",
+            Weekday::Tuesday => "Tu",
+            Weekday::Wednesday => "We",
+            Weekday::Thursday => "Th",
+            Weekday::Friday => "Fr",
+            Weekday::Saturday => "Sa",
+            Weekday::Sunday => "Su",
+        }
+    }
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// A date of the (proleptic) gregorian calendar.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Create a date from year, month (1-12) and day (1-31). Returns `None` if the date does not
+    /// exist.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::Date;
+    ///
+    /// assert!(Date::new(2024, 2, 29).is_some());
+    /// assert!(Date::new(2023, 2, 29).is_none());
+    /// assert!(Date::new(2023, 13, 1).is_none());
+    /// ```
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    /// Get the current date (in UTC).
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Self::from_days_since_epoch(secs.div_euclid(86400))
+    }
+
+    /// Get the year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Get the month (1-12).
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    /// Get the day of the month (1-31).
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Get the day of the week.
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a thursday.
+        Weekday::from_index(self.days_since_epoch() + Weekday::Thursday.index())
+    }
+
+    /// Get the date `days` days after (or before, if negative) this one.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::Date;
+    ///
+    /// let d = Date::new(2024, 2, 28).unwrap();
+    /// assert_eq!(d.add_days(2), Date::new(2024, 3, 1).unwrap());
+    /// assert_eq!(d.add_days(-59), Date::new(2023, 12, 31).unwrap());
+    /// ```
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days_since_epoch(self.days_since_epoch() + days)
+    }
+
+    /// Get the date `months` months after (or before, if negative) this one. The day is clamped to
+    /// the length of the resulting month.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::Date;
+    ///
+    /// let d = Date::new(2024, 1, 31).unwrap();
+    /// assert_eq!(d.add_months(1), Date::new(2024, 2, 29).unwrap());
+    /// assert_eq!(d.add_months(-13), Date::new(2022, 12, 31).unwrap());
+    /// ```
+    pub fn add_months(&self, months: i32) -> Self {
+        let total = self.year * 12 + (self.month as i32 - 1) + months;
+        let year = total.div_euclid(12);
+        let month = total.rem_euclid(12) as u32 + 1;
+        Date {
+            year,
+            month,
+            day: self.day.min(days_in_month(year, month)),
+        }
+    }
+
+    fn first_of_month(&self) -> Self {
+        Date { day: 1, ..*self }
+    }
+
+    // See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    fn days_since_epoch(&self) -> i64 {
+        let y = i64::from(self.year) - if self.month <= 2 { 1 } else { 0 };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = i64::from(self.month);
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400) as i32 + if month <= 2 { 1 } else { 0 };
+        Date { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+const NUM_WEEKS: i32 = 6;
+const CELL_WIDTH: i32 = 3;
+
+/// A month calendar in which a date can be selected.
+///
+/// The selected day can be changed via `Navigatable` (left/right: previous/next day, up/down:
+/// previous/next week) and paged through by month or year.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::Navigatable;
+/// use unsegen::widget::builtin::{Date, DatePicker};
+///
+/// let mut picker = DatePicker::new(Date::new(2024, 1, 31).unwrap());
+/// picker.move_down().unwrap();
+/// assert_eq!(picker.selected(), Date::new(2024, 2, 7).unwrap());
+/// picker.next_year();
+/// assert_eq!(picker.selected(), Date::new(2025, 2, 7).unwrap());
+/// ```
+pub struct DatePicker {
+    selected: Date,
+    today: Date,
+    first_day_of_week: Weekday,
+}
+
+impl DatePicker {
+    /// Create a date picker with `selected` as the initially selected date. Weeks start on monday
+    /// by default.
+    pub fn new(selected: Date) -> Self {
+        DatePicker {
+            selected,
+            today: Date::today(),
+            first_day_of_week: Weekday::Monday,
+        }
+    }
+
+    /// Set the day that is displayed in the first column of the calendar.
+    pub fn first_day_of_week(mut self, day: Weekday) -> Self {
+        self.first_day_of_week = day;
+        self
+    }
+
+    /// Override the date that is highlighted as today (default: `Date::today()`).
+    pub fn set_today(&mut self, today: Date) {
+        self.today = today;
+    }
+
+    /// Get the currently selected date.
+    pub fn selected(&self) -> Date {
+        self.selected
+    }
+
+    /// Select the specified date.
+    pub fn select(&mut self, date: Date) {
+        self.selected = date;
+    }
+
+    /// Select the same day in the next month (or the last day of the next month if it is shorter).
+    pub fn next_month(&mut self) {
+        self.selected = self.selected.add_months(1);
+    }
+
+    /// Select the same day in the previous month (or the last day of the previous month if it is
+    /// shorter).
+    pub fn prev_month(&mut self) {
+        self.selected = self.selected.add_months(-1);
+    }
+
+    /// Select the same day in the next year.
+    pub fn next_year(&mut self) {
+        self.selected = self.selected.add_months(12);
+    }
+
+    /// Select the same day in the previous year.
+    pub fn prev_year(&mut self) {
+        self.selected = self.selected.add_months(-12);
+    }
+
+    /// The first day that is displayed, i.e., the first day of the week containing the first day
+    /// of the selected month.
+    fn first_displayed_day(&self) -> Date {
+        let first = self.selected.first_of_month();
+        let offset = (first.weekday().index() - self.first_day_of_week.index()).rem_euclid(7);
+        first.add_days(-offset)
+    }
+
+    /// Prepare for drawing as a `Widget`.
+    pub fn as_widget<'a>(&'a self) -> DatePickerWidget<'a> {
+        DatePickerWidget {
+            picker: self,
+            header_style: StyleModifier::new().bold(true),
+            today_style: StyleModifier::new().underline(true),
+            selected_style: StyleModifier::new().invert(BoolModifyMode::Toggle),
+            out_of_month_style: StyleModifier::new().fg_color(Color::LightBlack),
+        }
+    }
+}
+
+impl Navigatable for DatePicker {
+    fn move_up(&mut self) -> OperationResult {
+        self.selected = self.selected.add_days(-7);
+        Ok(())
+    }
+    fn move_down(&mut self) -> OperationResult {
+        self.selected = self.selected.add_days(7);
+        Ok(())
+    }
+    fn move_left(&mut self) -> OperationResult {
+        self.selected = self.selected.add_days(-1);
+        Ok(())
+    }
+    fn move_right(&mut self) -> OperationResult {
+        self.selected = self.selected.add_days(1);
+        Ok(())
+    }
+}
+
+/// A `Widget` representing a `DatePicker` as a grid of the days of the selected month.
+///
+/// It allows for customization of the styles of the header and special days.
+pub struct DatePickerWidget<'a> {
+    picker: &'a DatePicker,
+    header_style: StyleModifier,
+    today_style: StyleModifier,
+    selected_style: StyleModifier,
+    out_of_month_style: StyleModifier,
+}
+
+impl<'a> DatePickerWidget<'a> {
+    /// Define the style of the month/year title and the weekday names.
+    pub fn header(mut self, style: StyleModifier) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Define the style of the current day.
+    pub fn today(mut self, style: StyleModifier) -> Self {
+        self.today_style = style;
+        self
+    }
+
+    /// Define the style of the selected day. It is only applied if the widget is active.
+    pub fn selected(mut self, style: StyleModifier) -> Self {
+        self.selected_style = style;
+        self
+    }
+
+    /// Define the style of days that belong to the previous or next month.
+    pub fn out_of_month(mut self, style: StyleModifier) -> Self {
+        self.out_of_month_style = style;
+        self
+    }
+}
+
+impl<'a> Widget for DatePickerWidget<'a> {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::exact(7 * CELL_WIDTH as usize - 1),
+            height: Demand::exact(2 + NUM_WEEKS as usize),
+        }
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        let picker = self.picker;
+        let selected = picker.selected;
+        let mut cursor = Cursor::new(&mut window);
+
+        {
+            let mut cursor = cursor.save().style_modifier();
+            cursor.apply_style_modifier(self.header_style);
+            let title = format!(
+                "{} {}",
+                MONTH_NAMES[selected.month as usize - 1],
+                selected.year
+            );
+            let padding = (7 * CELL_WIDTH - 1 - title.len() as i32).max(0) / 2;
+            cursor.move_to(ColIndex::new(padding), RowIndex::new(0));
+            cursor.write(&title);
+            for i in 0..7 {
+                let day = Weekday::from_index(picker.first_day_of_week.index() + i);
+                cursor.move_to(ColIndex::new(i as i32 * CELL_WIDTH), RowIndex::new(1));
+                cursor.write(day.short_name());
+            }
+        }
+
+        let first = picker.first_displayed_day();
+        for week in 0..NUM_WEEKS {
+            for weekday in 0..7 {
+                let date = first.add_days(i64::from(week * 7 + weekday));
+                let mut style = StyleModifier::new();
+                if date.month != selected.month {
+                    style = self.out_of_month_style.on_top_of(style);
+                }
+                if date == picker.today {
+                    style = self.today_style.on_top_of(style);
+                }
+                if date == selected && hints.active {
+                    style = self.selected_style.on_top_of(style);
+                }
+                cursor.move_to(ColIndex::new(weekday * CELL_WIDTH), RowIndex::new(2 + week));
+                let mut cursor = cursor.save().style_modifier();
+                cursor.apply_style_modifier(style);
+                cursor.write(&format!("{:>2}", date.day));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    #[test]
+    fn test_date_conversion() {
+        for &days in &[-719468, -1, 0, 59, 10957, 19782, 2932896] {
+            assert_eq!(Date::from_days_since_epoch(days).days_since_epoch(), days);
+        }
+        assert_eq!(
+            Date::from_days_since_epoch(0),
+            Date::new(1970, 1, 1).unwrap()
+        );
+        assert_eq!(Date::new(2024, 3, 1).unwrap().weekday(), Weekday::Friday);
+        assert_eq!(Date::new(2000, 1, 1).unwrap().to_string(), "2000-01-01");
+    }
+
+    #[test]
+    fn test_date_picker_draw() {
+        let mut picker =
+            DatePicker::new(Date::new(2024, 2, 14).unwrap()).first_day_of_week(Weekday::Sunday);
+        picker.set_today(Date::new(2000, 1, 1).unwrap());
+        let mut term = FakeTerminal::with_size((20, 8));
+        picker
+            .as_widget()
+            .header(StyleModifier::new())
+            .selected(StyleModifier::new().bold(true))
+            .draw(term.create_root_window(), RenderingHints::default());
+        term.assert_looks_like(
+            &[
+                "   February 2024    ",
+                "Su Mo Tu We Th Fr Sa",
+                "28 29 30 31  1  2  3",
+                " 4  5  6  7  8  9 10",
+                "11 12 13 *1**4* 15 16 17",
+                "18 19 20 21 22 23 24",
+                "25 26 27 28 29  1  2",
+                " 3  4  5  6  7  8  9",
+            ]
+            .join("|"),
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Line numbers next to a text widget.
+//!
+//! A `Gutter` either displays fixed line numbers (see `Gutter::new`) or follows a widget that
+//! implements `VisibleLines` (see `Gutter::synced`). In the latter case, place the gutter and the
+//! widget next to each other in an `HLayout`: Both are drawn with the same height, so the gutter
+//! can determine which lines the widget displays.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::base::terminal::test::FakeTerminal;
+//! use unsegen::widget::builtin::*;
+//! use unsegen::widget::*;
+//! use std::fmt::Write;
+//!
+//! let mut log = LogViewer::new();
+//! write!(log, "a\nb\nc\nd").unwrap();
+//!
+//! let mut term = FakeTerminal::with_size((4, 3));
+//! HLayout::new()
+//!     .widget(Gutter::synced(&log))
+//!     .widget(log.as_widget())
+//!     .draw(term.create_root_window(), RenderingHints::new());
+//! // The number of the current line is bold by default:
+//! term.assert_looks_like("2 b |3 c |*4* d ");
+//! ```
+use base::basic_types::*;
+use base::{Cursor, StyleModifier, Window};
+use std::ops::Range;
+use widget::{Demand, Demand2D, RenderingHints, Widget};
+
+/// A widget that displays lines of text, so that a `Gutter` can display the matching line numbers
+/// (see `Gutter::synced`).
+///
+/// Lines are indexed starting at 0. Line numbers assume that each line occupies a single row,
+/// i.e., wrapped lines are numbered as if they were not wrapped.
+pub trait VisibleLines {
+    /// Get the range of lines that are visible if the widget is drawn to a window with `height`
+    /// rows and the row the first of these lines is drawn to.
+    fn visible_lines(&self, height: Height) -> (RowIndex, Range<usize>);
+    /// Get the total number of lines, which determines the width of the gutter.
+    fn num_lines(&self) -> usize;
+    /// Get the line that relative line numbers are counted from, e.g., the line of the cursor.
+    fn current_line(&self) -> usize;
+}
+
+/// How line numbers are displayed in a `Gutter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineNumbering {
+    /// Number all lines starting at 1.
+    Absolute,
+    /// Display the absolute number of the current line and the distance to the current line for
+    /// all other lines.
+    Relative,
+}
+
+enum Lines<'a> {
+    Fixed {
+        first: usize,
+        num_lines: usize,
+        current: usize,
+    },
+    Synced(&'a dyn VisibleLines),
+}
+
+/// Right-aligned line numbers, followed by a single space.
+///
+/// The gutter demands enough width for the largest line number, so that its width does not
+/// change while scrolling.
+pub struct Gutter<'a> {
+    lines: Lines<'a>,
+    numbering: LineNumbering,
+    style: StyleModifier,
+    current_line_style: StyleModifier,
+}
+
+impl<'a> Gutter<'a> {
+    /// Number the rows of the window starting at line `first_line` (0-based) of an adjacent text
+    /// widget with `num_lines` lines.
+    pub fn new(first_line: usize, num_lines: usize) -> Self {
+        Self::with_lines(Lines::Fixed {
+            first: first_line,
+            num_lines,
+            current: first_line,
+        })
+    }
+
+    /// Follow the lines that are displayed by `widget` when drawn to a window of the same height.
+    pub fn synced<V: VisibleLines>(widget: &'a V) -> Self {
+        Self::with_lines(Lines::Synced(widget))
+    }
+
+    fn with_lines(lines: Lines<'a>) -> Self {
+        Gutter {
+            lines,
+            numbering: LineNumbering::Absolute,
+            style: StyleModifier::new(),
+            current_line_style: StyleModifier::new().bold(true),
+        }
+    }
+
+    /// Specify how line numbers are displayed (default: absolute).
+    pub fn numbering(mut self, numbering: LineNumbering) -> Self {
+        self.numbering = numbering;
+        self
+    }
+
+    /// Specify the current line for fixed line numbers (default: the first line). This only has
+    /// an effect for gutters created using `new`.
+    pub fn current_line(mut self, line: usize) -> Self {
+        if let Lines::Fixed {
+            ref mut current, ..
+        } = self.lines
+        {
+            *current = line;
+        }
+        self
+    }
+
+    /// Specify the style of the line numbers (default: no change).
+    pub fn style(mut self, style: StyleModifier) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Specify the style of the number of the current line (default: bold).
+    pub fn current_line_style(mut self, style: StyleModifier) -> Self {
+        self.current_line_style = style;
+        self
+    }
+
+    fn num_lines(&self) -> usize {
+        match self.lines {
+            Lines::Fixed { num_lines, .. } => num_lines,
+            Lines::Synced(widget) => widget.num_lines(),
+        }
+    }
+
+    fn number_width(&self) -> usize {
+        self.num_lines().max(1).to_string().len()
+    }
+}
+
+impl<'a> Widget for Gutter<'a> {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::exact(self.number_width() + 1),
+            height: Demand::at_least(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let height = window.get_height();
+        let (first_row, lines, current) = match self.lines {
+            Lines::Fixed {
+                first,
+                num_lines,
+                current,
+            } => {
+                let end = (first + height.raw_value() as usize).min(num_lines);
+                (RowIndex::new(0), first..end.max(first), current)
+            }
+            Lines::Synced(widget) => {
+                let (row, lines) = widget.visible_lines(height);
+                (row, lines, widget.current_line())
+            }
+        };
+        let width = self.number_width();
+        let mut cursor = Cursor::new(&mut window).position(ColIndex::new(0), first_row);
+        for line in lines {
+            let number = match self.numbering {
+                LineNumbering::Relative if line != current => {
+                    (line as isize - current as isize).unsigned_abs()
+                }
+                _ => line + 1,
+            };
+            cursor.set_style_modifier(if line == current {
+                self.current_line_style.on_top_of(self.style)
+            } else {
+                self.style
+            });
+            cursor.write(&format!("{:>1$}", number, width));
+            cursor.wrap_line();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+    use widget::builtin::TextEdit;
+    use widget::HLayout;
+
+    fn draw(size: (u32, u32), widget: impl Widget) -> FakeTerminal {
+        let mut term = FakeTerminal::with_size(size);
+        widget.draw(term.create_root_window(), RenderingHints::new());
+        term
+    }
+
+    #[test]
+    fn test_gutter_fixed() {
+        let plain = StyleModifier::new();
+        draw((4, 3), Gutter::new(8, 11).current_line_style(plain))
+            .assert_looks_like(" 9  |10  |11  ");
+        draw(
+            (3, 4),
+            Gutter::new(2, 5)
+                .current_line(3)
+                .numbering(LineNumbering::Relative),
+        )
+        .assert_looks_like("1  |*4*  |1  |   ");
+    }
+
+    #[test]
+    fn test_gutter_synced_text_edit() {
+        let mut edit = TextEdit::new();
+        edit.set("a\nb\nc\nd\ne");
+        let term = draw(
+            (4, 3),
+            HLayout::new()
+                .widget(Gutter::synced(&edit).current_line_style(StyleModifier::new()))
+                .widget(edit.as_widget()),
+        );
+        // The cursor is at the end of the text, one line of context would be below the cursor.
+        term.assert_looks_like("3 c |4 d |5 e ");
+    }
+
+    #[test]
+    fn test_gutter_synced_log_viewer_bottom_aligned() {
+        use std::fmt::Write;
+        let mut log = ::widget::builtin::LogViewer::new();
+        write!(log, "a\nb").unwrap();
+        let term = draw(
+            (4, 3),
+            HLayout::new()
+                .widget(Gutter::synced(&log).current_line_style(StyleModifier::new()))
+                .widget(log.as_widget()),
+        );
+        term.assert_looks_like("    |1 a |2 b ");
+    }
+}
@@ -0,0 +1,377 @@
+//! A matrix of values displayed as colored cells.
+use base::basic_types::*;
+use base::{BoolModifyMode, Color, Cursor, StyleModifier, Window};
+use input::{Navigatable, OperationResult};
+use std::cell::Cell;
+use widget::{text_width, Demand, Demand2D, RenderingHints, Widget};
+
+/// Maps values to colors.
+///
+/// The value range (either fixed or the minimum and maximum of all values) is divided into
+/// equally sized buckets, one per color.
+#[derive(Clone, Debug)]
+pub struct ColorScale {
+    colors: Vec<Color>,
+    range: Option<(f64, f64)>,
+}
+
+impl ColorScale {
+    /// Create a scale from colors for ascending values.
+    ///
+    /// Panics if `colors` is empty.
+    pub fn new(colors: Vec<Color>) -> Self {
+        assert!(!colors.is_empty(), "ColorScale requires at least one color");
+        ColorScale {
+            colors,
+            range: None,
+        }
+    }
+
+    /// A scale of the 24 gray values of the 256 color palette from dark to light.
+    pub fn grayscale() -> Self {
+        Self::new((0..24).map(Color::ansi_grayscale).collect())
+    }
+
+    /// A scale from cold (blue) to hot (red) using named colors.
+    pub fn heat() -> Self {
+        Self::new(vec![
+            Color::Blue,
+            Color::Cyan,
+            Color::Green,
+            Color::Yellow,
+            Color::Red,
+        ])
+    }
+
+    /// Use a fixed value range instead of the minimum and maximum of all values. Values outside
+    /// of the range are mapped to the first or last color.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Get the color for `value`, given the value range of the data (which is ignored if a fixed
+    /// range has been specified).
+    pub fn color(&self, value: f64, data_range: (f64, f64)) -> Color {
+        let (min, max) = self.range.unwrap_or(data_range);
+        let rel = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let index = (rel * self.colors.len() as f64) as usize;
+        self.colors[index.min(self.colors.len() - 1)]
+    }
+}
+
+/// A 2D matrix of values with a cursor that can be moved from cell to cell (via `Navigatable`).
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::Navigatable;
+/// use unsegen::widget::builtin::Heatmap;
+///
+/// let mut heatmap = Heatmap::new(2, 3);
+/// heatmap.set(1, 2, 5.0);
+/// heatmap.move_down().unwrap();
+/// heatmap.move_right().unwrap();
+/// heatmap.move_right().unwrap();
+/// assert!(heatmap.move_right().is_err());
+/// assert_eq!(heatmap.cursor(), (1, 2));
+/// assert_eq!(heatmap.selected_value(), Some(5.0));
+/// ```
+pub struct Heatmap {
+    values: Vec<f64>,
+    num_rows: usize,
+    num_cols: usize,
+    cursor: (usize, usize),
+    scroll: Cell<(usize, usize)>,
+}
+
+impl Heatmap {
+    /// Create a matrix of the specified size filled with zeros.
+    pub fn new(num_rows: usize, num_cols: usize) -> Self {
+        Heatmap {
+            values: vec![0.0; num_rows * num_cols],
+            num_rows,
+            num_cols,
+            cursor: (0, 0),
+            scroll: Cell::new((0, 0)),
+        }
+    }
+
+    /// Replace all values with those of `rows`. The cursor is moved into the new matrix if
+    /// necessary.
+    ///
+    /// Panics if not all rows have the same length.
+    pub fn set_rows(&mut self, rows: Vec<Vec<f64>>) {
+        let num_cols = rows.first().map(Vec::len).unwrap_or(0);
+        assert!(
+            rows.iter().all(|r| r.len() == num_cols),
+            "All rows must have the same length"
+        );
+        self.num_rows = rows.len();
+        self.num_cols = num_cols;
+        self.values = rows.into_iter().flatten().collect();
+        self.cursor = (
+            self.cursor.0.min(self.num_rows.saturating_sub(1)),
+            self.cursor.1.min(self.num_cols.saturating_sub(1)),
+        );
+    }
+
+    /// Get the number of rows.
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Get the number of columns.
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    /// Get the value at the specified position.
+    pub fn get(&self, row: usize, col: usize) -> Option<f64> {
+        if row < self.num_rows && col < self.num_cols {
+            Some(self.values[row * self.num_cols + col])
+        } else {
+            None
+        }
+    }
+
+    /// Set the value at the specified position. Fails if the position is out of bounds.
+    pub fn set(&mut self, row: usize, col: usize, value: f64) -> OperationResult {
+        if row < self.num_rows && col < self.num_cols {
+            self.values[row * self.num_cols + col] = value;
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Get the position (row, column) of the cursor.
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    /// Move the cursor to the specified position. Fails if the position is out of bounds.
+    pub fn set_cursor(&mut self, row: usize, col: usize) -> OperationResult {
+        if row < self.num_rows && col < self.num_cols {
+            self.cursor = (row, col);
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Get the value under the cursor (if the matrix is not empty).
+    pub fn selected_value(&self) -> Option<f64> {
+        self.get(self.cursor.0, self.cursor.1)
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        self.values
+            .iter()
+            .fold(None, |range: Option<(f64, f64)>, v| {
+                Some(range.map_or((*v, *v), |(min, max)| (min.min(*v), max.max(*v))))
+            })
+            .unwrap_or((0.0, 0.0))
+    }
+
+    /// Prepare for drawing as a `Widget`.
+    pub fn as_widget<'a>(&'a self) -> HeatmapWidget<'a> {
+        HeatmapWidget {
+            heatmap: self,
+            scale: ColorScale::heat(),
+            cell_width: 2,
+            glyph: None,
+            readout: None,
+            cursor_style: StyleModifier::new().invert(BoolModifyMode::Toggle),
+        }
+    }
+}
+
+impl Navigatable for Heatmap {
+    fn move_up(&mut self) -> OperationResult {
+        let (row, col) = self.cursor;
+        self.set_cursor(row.checked_sub(1).ok_or(())?, col)
+    }
+    fn move_down(&mut self) -> OperationResult {
+        let (row, col) = self.cursor;
+        self.set_cursor(row + 1, col)
+    }
+    fn move_left(&mut self) -> OperationResult {
+        let (row, col) = self.cursor;
+        self.set_cursor(row, col.checked_sub(1).ok_or(())?)
+    }
+    fn move_right(&mut self) -> OperationResult {
+        let (row, col) = self.cursor;
+        self.set_cursor(row, col + 1)
+    }
+}
+
+/// Text that is shown for a cell (given row, column and value).
+pub type CellText<'a> = Box<dyn Fn(usize, usize, f64) -> String + 'a>;
+
+/// A `Widget` representing a `Heatmap`.
+///
+/// If the matrix does not fit into the window, only the part around the cursor is shown.
+pub struct HeatmapWidget<'a> {
+    heatmap: &'a Heatmap,
+    scale: ColorScale,
+    cell_width: usize,
+    glyph: Option<CellText<'a>>,
+    readout: Option<CellText<'a>>,
+    cursor_style: StyleModifier,
+}
+
+impl<'a> HeatmapWidget<'a> {
+    /// Define how values are mapped to colors (default: `ColorScale::heat()`).
+    pub fn scale(mut self, scale: ColorScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Define the number of columns per cell (default: 2).
+    ///
+    /// Panics if `width` is zero.
+    pub fn cell_width(mut self, width: usize) -> Self {
+        assert!(width > 0, "Cells must be at least one column wide");
+        self.cell_width = width;
+        self
+    }
+
+    /// Display text (e.g., a symbol or the value itself) in each cell.
+    pub fn glyph(mut self, glyph: impl Fn(usize, usize, f64) -> String + 'a) -> Self {
+        self.glyph = Some(Box::new(glyph));
+        self
+    }
+
+    /// Display a description of the cell under the cursor in the last line of the window.
+    pub fn readout(mut self, readout: impl Fn(usize, usize, f64) -> String + 'a) -> Self {
+        self.readout = Some(Box::new(readout));
+        self
+    }
+
+    /// Define the style of the cell under the cursor when the widget is active.
+    pub fn cursor(mut self, style: StyleModifier) -> Self {
+        self.cursor_style = style;
+        self
+    }
+}
+
+/// Adjust the first visible index so that `cursor` is visible, moving as little as possible.
+fn scroll_to(first: usize, cursor: usize, visible: usize, total: usize) -> usize {
+    if visible == 0 {
+        return first;
+    }
+    let first = if cursor < first {
+        cursor
+    } else if cursor >= first + visible {
+        cursor + 1 - visible
+    } else {
+        first
+    };
+    first.min(total.saturating_sub(visible))
+}
+
+impl<'a> Widget for HeatmapWidget<'a> {
+    fn space_demand(&self) -> Demand2D {
+        let readout_height = if self.readout.is_some() { 1 } else { 0 };
+        Demand2D {
+            width: Demand::at_least(self.heatmap.num_cols * self.cell_width),
+            height: Demand::at_least(self.heatmap.num_rows + readout_height),
+        }
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        let heatmap = self.heatmap;
+        let mut height = window.get_height().raw_value() as usize;
+        let width = window.get_width().raw_value() as usize;
+        if height == 0 {
+            return;
+        }
+
+        let (row, col) = heatmap.cursor;
+        let mut cursor = Cursor::new(&mut window);
+        if let (Some(readout), Some(value)) = (&self.readout, heatmap.selected_value()) {
+            height -= 1;
+            cursor.move_to(ColIndex::new(0), RowIndex::new(height as i32));
+            cursor.write(&readout(row, col, value));
+        }
+
+        let visible_rows = height;
+        let visible_cols = width / self.cell_width;
+        let (first_row, first_col) = heatmap.scroll.get();
+        let first_row = scroll_to(first_row, row, visible_rows, heatmap.num_rows);
+        let first_col = scroll_to(first_col, col, visible_cols, heatmap.num_cols);
+        heatmap.scroll.set((first_row, first_col));
+
+        let data_range = heatmap.value_range();
+        let last_row = (first_row + visible_rows).min(heatmap.num_rows);
+        let last_col = (first_col + visible_cols).min(heatmap.num_cols);
+        for r in first_row..last_row {
+            for c in first_col..last_col {
+                let value = heatmap.values[r * heatmap.num_cols + c];
+                let mut style = StyleModifier::new().bg_color(self.scale.color(value, data_range));
+                if (r, c) == heatmap.cursor && hints.active {
+                    style = self.cursor_style.on_top_of(style);
+                }
+                let text = self
+                    .glyph
+                    .as_ref()
+                    .map(|g| g(r, c, value))
+                    .unwrap_or_default();
+                let padding = self
+                    .cell_width
+                    .saturating_sub(text_width(&text).raw_value() as usize);
+
+                cursor.move_to(
+                    ColIndex::new(((c - first_col) * self.cell_width) as i32),
+                    RowIndex::new((r - first_row) as i32),
+                );
+                let mut cursor = cursor.save().style_modifier();
+                cursor.apply_style_modifier(style);
+                cursor.write(&text);
+                cursor.write(&" ".repeat(padding));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    fn test_heatmap(window_dim: (u32, u32), heatmap: &Heatmap, after: &str) {
+        let mut term = FakeTerminal::with_size(window_dim);
+        heatmap
+            .as_widget()
+            .cell_width(1)
+            .glyph(|_, _, v| v.to_string())
+            .readout(|r, c, v| format!("{},{}:{}", r, c, v))
+            .cursor(StyleModifier::new().bold(true))
+            .draw(
+                term.create_root_window(),
+                RenderingHints::new().active(true),
+            );
+        term.assert_looks_like(after);
+    }
+
+    #[test]
+    fn test_heatmap_scrolling() {
+        let mut heatmap = Heatmap::new(0, 0);
+        heatmap.set_rows(
+            (0..4)
+                .map(|r| (0..4).map(|c| (r + c) as f64).collect())
+                .collect(),
+        );
+        test_heatmap((5, 3), &heatmap, "*0*123 |1234 |0,0:0");
+
+        heatmap.set_cursor(3, 3).unwrap();
+        test_heatmap((5, 3), &heatmap, "2345 |345*6* |3,3:6");
+
+        heatmap.move_up().unwrap();
+        heatmap.move_up().unwrap();
+        test_heatmap((5, 3), &heatmap, "123*4* |2345 |1,3:4");
+    }
+}
@@ -0,0 +1,359 @@
+//! Capture key presses for interactive rebinding of keys.
+use base::{Color, Cursor, StyleModifier, Window};
+use input::{Behavior, Event, Input, Key, ToEvent};
+use widget::{text_width, Demand, Demand2D, RenderingHints, Widget};
+
+/// Format a key in a human readable way, e.g., "Ctrl-x", "Alt-a", "Up" or "F5".
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::Key;
+/// use unsegen::widget::builtin::format_key;
+///
+/// assert_eq!(format_key(Key::Ctrl('s')), "Ctrl-s");
+/// assert_eq!(format_key(Key::Char('\n')), "Enter");
+/// assert_eq!(format_key(Key::F(5)), "F5");
+/// ```
+pub fn format_key(key: Key) -> String {
+    let named = |c: char| match c {
+        '\n' => Some("Enter"),
+        '\t' => Some("Tab"),
+        ' ' => Some("Space"),
+        _ => None,
+    };
+    let char_name = |c: char| named(c).map(str::to_owned).unwrap_or_else(|| c.to_string());
+    match key {
+        Key::Backspace => "Backspace".to_owned(),
+        Key::Left => "Left".to_owned(),
+        Key::Right => "Right".to_owned(),
+        Key::Up => "Up".to_owned(),
+        Key::Down => "Down".to_owned(),
+        Key::Home => "Home".to_owned(),
+        Key::End => "End".to_owned(),
+        Key::PageUp => "PageUp".to_owned(),
+        Key::PageDown => "PageDown".to_owned(),
+        Key::BackTab => "Shift-Tab".to_owned(),
+        Key::Delete => "Delete".to_owned(),
+        Key::Insert => "Insert".to_owned(),
+        Key::F(n) => format!("F{}", n),
+        Key::Char(c) => char_name(c),
+        Key::Alt(c) => format!("Alt-{}", char_name(c)),
+        Key::Ctrl(c) => format!("Ctrl-{}", char_name(c)),
+        Key::Null => "Null".to_owned(),
+        Key::Esc => "Esc".to_owned(),
+        _ => "Unknown".to_owned(),
+    }
+}
+
+/// Format a sequence of keys (i.e., a chord) by joining the formatted keys with spaces, e.g.,
+/// "Ctrl-x Ctrl-s".
+pub fn format_keys(keys: &[Key]) -> String {
+    keys.iter()
+        .map(|k| format_key(*k))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The state of a `KeyCapture`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CaptureState {
+    /// No capture has been started (or the last result has been taken).
+    Idle,
+    /// Keys are currently being captured.
+    Capturing,
+    /// A complete binding has been captured.
+    Captured(Vec<Key>),
+    /// The capture has been cancelled by the user.
+    Cancelled,
+}
+
+/// Grabs the next key (or chord of keys) verbatim, e.g., to let users rebind keys interactively.
+///
+/// While capturing, the `capture_behavior` consumes all key inputs, including those that would
+/// otherwise be handled by the application. The cancel key (`Esc` by default) aborts the capture
+/// if pressed as the first key of a binding. Captured bindings are checked for conflicts with
+/// existing bindings that have been registered using `existing_binding`.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::*;
+/// use unsegen::widget::builtin::*;
+///
+/// let mut capture = KeyCapture::new()
+///     .chord_length(2)
+///     .existing_binding(&[Key::Ctrl('x'), Key::Ctrl('s')], "save");
+///
+/// capture.start();
+/// for input in Input::read_all(&b"\x18\x13"[..]) {
+///     input.unwrap().chain(capture.capture_behavior());
+/// }
+/// assert_eq!(capture.formatted_binding().unwrap(), "Ctrl-x Ctrl-s");
+/// assert_eq!(capture.conflicts(), vec!["save"]);
+/// assert_eq!(capture.take_binding(), Some(vec![Key::Ctrl('x'), Key::Ctrl('s')]));
+/// ```
+pub struct KeyCapture {
+    state: CaptureState,
+    pending: Vec<Key>,
+    chord_length: usize,
+    cancel: Key,
+    existing: Vec<(Vec<Key>, String)>,
+}
+
+impl KeyCapture {
+    /// Create an idle `KeyCapture` that captures single keys and is cancelled by `Esc`.
+    pub fn new() -> Self {
+        KeyCapture {
+            state: CaptureState::Idle,
+            pending: Vec::new(),
+            chord_length: 1,
+            cancel: Key::Esc,
+            existing: Vec::new(),
+        }
+    }
+
+    /// Set the maximum number of keys of a binding. A capture is complete once that many keys
+    /// have been pressed or `finish` is called.
+    ///
+    /// Panics if `length` is zero.
+    pub fn chord_length(mut self, length: usize) -> Self {
+        assert!(length > 0, "Chords must consist of at least one key");
+        self.chord_length = length;
+        self
+    }
+
+    /// Set the key that cancels the capture (default: `Esc`).
+    pub fn cancel_key(mut self, key: Key) -> Self {
+        self.cancel = key;
+        self
+    }
+
+    /// Register an existing binding (described by `name`) to detect conflicts with.
+    pub fn existing_binding(mut self, keys: &[Key], name: impl Into<String>) -> Self {
+        self.existing.push((keys.to_vec(), name.into()));
+        self
+    }
+
+    /// Start capturing, discarding any previous result.
+    pub fn start(&mut self) {
+        self.pending.clear();
+        self.state = CaptureState::Capturing;
+    }
+
+    /// Complete the capture of a chord early, i.e., before `chord_length` keys have been pressed.
+    /// Does nothing if no keys have been captured yet.
+    pub fn finish(&mut self) {
+        if self.state == CaptureState::Capturing && !self.pending.is_empty() {
+            self.state = CaptureState::Captured(::std::mem::take(&mut self.pending));
+        }
+    }
+
+    /// Abort the current capture.
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+        self.state = CaptureState::Cancelled;
+    }
+
+    /// Check whether keys are currently being captured.
+    pub fn is_capturing(&self) -> bool {
+        self.state == CaptureState::Capturing
+    }
+
+    /// Get the current state.
+    pub fn state(&self) -> &CaptureState {
+        &self.state
+    }
+
+    /// Get the captured binding, if any.
+    pub fn binding(&self) -> Option<&[Key]> {
+        if let CaptureState::Captured(ref keys) = self.state {
+            Some(keys)
+        } else {
+            None
+        }
+    }
+
+    /// Get the captured binding formatted using `format_keys`.
+    pub fn formatted_binding(&self) -> Option<String> {
+        self.binding().map(format_keys)
+    }
+
+    /// Take the captured binding (if any), returning to `Idle` state.
+    pub fn take_binding(&mut self) -> Option<Vec<Key>> {
+        match ::std::mem::replace(&mut self.state, CaptureState::Idle) {
+            CaptureState::Captured(keys) => Some(keys),
+            other => {
+                self.state = other;
+                None
+            }
+        }
+    }
+
+    /// Get the names of existing bindings that conflict with the captured one, i.e., that are
+    /// equal or one of which is a prefix of the other.
+    pub fn conflicts(&self) -> Vec<&str> {
+        let binding = match self.binding() {
+            Some(binding) => binding,
+            None => return Vec::new(),
+        };
+        self.existing
+            .iter()
+            .filter(|(keys, _)| keys.starts_with(binding) || binding.starts_with(keys))
+            .map(|(_, name)| name.as_str())
+            .collect()
+    }
+
+    /// Create a `Behavior` that consumes all key inputs while capturing.
+    pub fn capture_behavior(&mut self) -> KeyCaptureBehavior<'_> {
+        KeyCaptureBehavior { capture: self }
+    }
+
+    /// Prepare for drawing as a `Widget`.
+    pub fn as_widget<'a>(&'a self) -> KeyCaptureWidget<'a> {
+        KeyCaptureWidget {
+            capture: self,
+            prompt: "Press a key...",
+            conflict_style: StyleModifier::new().fg_color(Color::Red),
+        }
+    }
+}
+
+impl Default for KeyCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Captures keys for a `KeyCapture`. See `KeyCapture::capture_behavior`.
+pub struct KeyCaptureBehavior<'a> {
+    capture: &'a mut KeyCapture,
+}
+
+impl<'a> Behavior for KeyCaptureBehavior<'a> {
+    fn input(self, input: Input) -> Option<Input> {
+        let key = match input.event {
+            Event::Key(key) if self.capture.is_capturing() => key,
+            _ => return Some(input),
+        };
+        if self.capture.pending.is_empty() && key.to_event() == self.capture.cancel.to_event() {
+            self.capture.cancel();
+        } else {
+            self.capture.pending.push(key);
+            if self.capture.pending.len() >= self.capture.chord_length {
+                self.capture.finish();
+            }
+        }
+        None
+    }
+}
+
+/// A `Widget` representing a `KeyCapture`.
+///
+/// It shows a prompt while capturing and the captured binding (along with conflicts) afterwards.
+pub struct KeyCaptureWidget<'a> {
+    capture: &'a KeyCapture,
+    prompt: &'a str,
+    conflict_style: StyleModifier,
+}
+
+impl<'a> KeyCaptureWidget<'a> {
+    /// Define the text that is shown while waiting for the first key.
+    pub fn prompt(mut self, prompt: &'a str) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    /// Define the style of the conflict warning.
+    pub fn conflict(mut self, style: StyleModifier) -> Self {
+        self.conflict_style = style;
+        self
+    }
+
+    fn text(&self) -> (String, String) {
+        match self.capture.state {
+            CaptureState::Idle | CaptureState::Cancelled => (String::new(), String::new()),
+            CaptureState::Capturing if self.capture.pending.is_empty() => {
+                (self.prompt.to_owned(), String::new())
+            }
+            CaptureState::Capturing => (
+                format!("{} ...", format_keys(&self.capture.pending)),
+                String::new(),
+            ),
+            CaptureState::Captured(ref keys) => {
+                let conflicts = self.capture.conflicts();
+                let warning = if conflicts.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (conflicts with: {})", conflicts.join(", "))
+                };
+                (format_keys(keys), warning)
+            }
+        }
+    }
+}
+
+impl<'a> Widget for KeyCaptureWidget<'a> {
+    fn space_demand(&self) -> Demand2D {
+        let (binding, warning) = self.text();
+        Demand2D {
+            width: Demand::at_least(text_width(&binding) + text_width(&warning)),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let (binding, warning) = self.text();
+        let mut cursor = Cursor::new(&mut window);
+        cursor.write(&binding);
+        let mut cursor = cursor.save().style_modifier();
+        cursor.apply_style_modifier(self.conflict_style);
+        cursor.write(&warning);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    fn key(k: Key) -> Input {
+        Input::new(k)
+    }
+
+    fn test_key_capture_draw(capture: &KeyCapture, after: &str) {
+        let mut term = FakeTerminal::with_size((30, 1));
+        capture
+            .as_widget()
+            .conflict(StyleModifier::new())
+            .draw(term.create_root_window(), RenderingHints::default());
+        term.assert_looks_like(after);
+    }
+
+    #[test]
+    fn test_key_capture() {
+        let mut capture = KeyCapture::new()
+            .chord_length(2)
+            .existing_binding(&[Key::Char('q')], "quit");
+        assert!(capture.capture_behavior().input(key(Key::Esc)).is_some());
+
+        capture.start();
+        test_key_capture_draw(&capture, "Press a key...                ");
+        assert!(capture.capture_behavior().input(key(Key::Esc)).is_none());
+        assert_eq!(*capture.state(), CaptureState::Cancelled);
+
+        capture.start();
+        assert!(capture
+            .capture_behavior()
+            .input(key(Key::Alt('q')))
+            .is_none());
+        test_key_capture_draw(&capture, "Alt-q ...                     ");
+        assert!(capture.capture_behavior().input(key(Key::Esc)).is_none());
+        assert_eq!(capture.binding(), Some(&[Key::Alt('q'), Key::Esc][..]));
+        assert!(capture.conflicts().is_empty());
+
+        capture.start();
+        capture.capture_behavior().input(key(Key::Char('q')));
+        capture.finish();
+        test_key_capture_draw(&capture, "q (conflicts with: quit)      ");
+        assert_eq!(capture.take_binding(), Some(vec![Key::Char('q')]));
+        assert_eq!(*capture.state(), CaptureState::Idle);
+    }
+}
@@ -1,8 +1,12 @@
 //! A user-editable line of text.
 use base::basic_types::*;
-use base::{BoolModifyMode, Cursor, StyleModifier, Window};
+use base::{
+    is_reordered, visual_neighbor, BidiMode, BoolModifyMode, Cursor, CursorMovement, StyleModifier,
+    Window,
+};
 use input::{Editable, Navigatable, OperationResult, Writable};
 use unicode_segmentation::UnicodeSegmentation;
+use util::KillRing;
 use widget::{
     count_grapheme_clusters, text_width, Blink, Demand, Demand2D, RenderingHints, Widget,
 };
@@ -14,6 +18,9 @@ use widget::{
 pub struct LineEdit {
     text: String,
     cursor_pos: usize,
+    bidi: BidiMode,
+    cursor_movement: CursorMovement,
+    kill_ring: KillRing,
 }
 
 impl LineEdit {
@@ -22,7 +29,62 @@ impl LineEdit {
         LineEdit {
             text: String::new(),
             cursor_pos: 0,
+            bidi: BidiMode::Disabled,
+            cursor_movement: CursorMovement::Logical,
+            kill_ring: KillRing::new(),
+        }
+    }
+
+    /// Set how the content is reordered for display if it contains right-to-left text (default:
+    /// `BidiMode::Disabled`).
+    pub fn set_bidi(&mut self, mode: BidiMode) {
+        self.bidi = mode;
+    }
+
+    /// Use the given kill ring (e.g., one that is shared with other widgets) instead of the one
+    /// that the `LineEdit` was created with for `Editable::kill_to_end_of_line` etc.
+    pub fn set_kill_ring(&mut self, ring: KillRing) {
+        self.kill_ring = ring;
+    }
+
+    /// Get (a handle to) the kill ring of the `LineEdit`.
+    pub fn kill_ring(&self) -> KillRing {
+        self.kill_ring.clone()
+    }
+
+    /// Set whether moving the cursor left or right (using `Navigatable`) follows the logical or
+    /// the visual order of reordered content (default: `CursorMovement::Logical`).
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::{BidiMode, CursorMovement};
+    /// use unsegen::input::Navigatable;
+    /// use unsegen::widget::builtin::LineEdit;
+    ///
+    /// let mut l = LineEdit::new();
+    /// l.set("ab אבג"); // Displayed as "ab גבא"
+    /// l.set_bidi(BidiMode::Auto);
+    /// l.set_cursor_movement(CursorMovement::Visual);
+    /// l.set_cursor_pos(1).unwrap();
+    /// l.move_right().unwrap();
+    /// l.move_right().unwrap();
+    /// assert_eq!(&l.get()[l.cursor_pos()..], "ג");
+    /// ```
+    pub fn set_cursor_movement(&mut self, movement: CursorMovement) {
+        self.cursor_movement = movement;
+    }
+
+    /// Move the cursor to the visually adjacent cluster if the content is reordered and the
+    /// cursor moves visually.
+    fn move_cursor_visually(&mut self, right: bool) -> Option<OperationResult> {
+        if self.cursor_movement != CursorMovement::Visual || !is_reordered(&self.text, self.bidi) {
+            return None;
         }
+        Some(
+            visual_neighbor(&self.text, self.bidi, self.cursor_pos(), right)
+                .ok_or(())
+                .and_then(|pos| self.set_cursor_pos(pos)),
+        )
     }
 
     /// Get the current content.
@@ -165,10 +227,12 @@ impl Navigatable for LineEdit {
         Err(())
     }
     fn move_left(&mut self) -> OperationResult {
-        self.move_cursor_left()
+        self.move_cursor_visually(false)
+            .unwrap_or_else(|| self.move_cursor_left())
     }
     fn move_right(&mut self) -> OperationResult {
-        self.move_cursor_right()
+        self.move_cursor_visually(true)
+            .unwrap_or_else(|| self.move_cursor_right())
     }
 }
 
@@ -217,6 +281,60 @@ impl Editable for LineEdit {
             Ok(())
         }
     }
+    fn kill_to_end_of_line(&mut self) -> OperationResult {
+        let pos = self.cursor_pos();
+        if pos == self.text.len() {
+            return Err(());
+        }
+        self.kill_ring.kill(self.text.split_off(pos));
+        Ok(())
+    }
+    fn kill_to_beginning_of_line(&mut self) -> OperationResult {
+        let pos = self.cursor_pos();
+        if pos == 0 {
+            return Err(());
+        }
+        self.kill_ring
+            .kill(self.text.drain(..pos).collect::<String>());
+        self.cursor_pos = 0;
+        Ok(())
+    }
+    fn kill_word_backwards(&mut self) -> OperationResult {
+        let pos = self.cursor_pos();
+        let start = word_begin_before(&self.text[..pos]).ok_or(())?;
+        self.kill_ring
+            .kill(self.text.drain(start..pos).collect::<String>());
+        self.set_cursor_pos(start)
+    }
+    fn yank(&mut self) -> OperationResult {
+        let text = self.kill_ring.latest().ok_or(())?;
+        let end = self.cursor_pos() + text.len();
+        self.insert(&text);
+        self.cursor_pos = count_grapheme_clusters(&self.text[..end]);
+        Ok(())
+    }
+}
+
+/// Find the byte position of the beginning of the last whitespace-delimited word in `text` (which
+/// may be followed by whitespace). Returns `None` for an empty text.
+pub(super) fn word_begin_before(text: &str) -> Option<usize> {
+    let is_space = |g: &str| g.chars().all(char::is_whitespace);
+    let mut clusters = text.grapheme_indices(true).rev().peekable();
+    let mut begin = None;
+    while let Some(&(i, g)) = clusters.peek() {
+        if !is_space(g) {
+            break;
+        }
+        begin = Some(i);
+        clusters.next();
+    }
+    for (i, g) in clusters {
+        if is_space(g) {
+            break;
+        }
+        begin = Some(i);
+    }
+    begin
 }
 
 /// A `Widget` representing a `LineEdit`
@@ -283,31 +401,20 @@ impl<'a> Widget for LineEditWidget<'a> {
             (false, _) => self.cursor_style_inactive,
         };
 
-        let mut cursor = Cursor::new(&mut window).position(draw_cursor_start_pos, RowIndex::new(0));
-        if let Some(cursor_pos_offset) = maybe_cursor_pos_offset {
-            let (until_cursor, from_cursor) = self.lineedit.text.split_at(cursor_pos_offset);
-            cursor.write(until_cursor);
-            if let Some(after_cursor_offset) = maybe_after_cursor_offset {
-                let (cursor_str, after_cursor) =
-                    from_cursor.split_at(after_cursor_offset - cursor_pos_offset);
-                {
-                    let mut cursor = cursor.save().style_modifier();
-                    cursor.apply_style_modifier(cursor_style);
-                    cursor.write(cursor_str);
-                }
-                cursor.write(after_cursor);
+        let mut cursor = Cursor::new(&mut window)
+            .position(draw_cursor_start_pos, RowIndex::new(0))
+            .bidi(self.lineedit.bidi);
+        cursor.write_styled(&self.lineedit.text, |offset| {
+            if Some(offset) == maybe_cursor_pos_offset {
+                cursor_style
             } else {
-                let mut cursor = cursor.save().style_modifier();
-                cursor.apply_style_modifier(cursor_style);
-                cursor.write(from_cursor);
-            }
-        } else {
-            cursor.write(&self.lineedit.text);
-            {
-                let mut cursor = cursor.save().style_modifier();
-                cursor.apply_style_modifier(cursor_style);
-                cursor.write(" ");
+                StyleModifier::new()
             }
+        });
+        if maybe_cursor_pos_offset.is_none() {
+            let mut cursor = cursor.save().style_modifier();
+            cursor.apply_style_modifier(cursor_style);
+            cursor.write(" ");
         }
     }
 }
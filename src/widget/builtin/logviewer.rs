@@ -1,54 +1,566 @@
 //! A scrollable, append-only buffer of lines.
 use base::basic_types::*;
-use base::{Cursor, Window, WrappingMode};
-use input::{OperationResult, Scrollable};
+use base::{
+    decode_escaped, sanitizer, text_extent, BoolModifyMode, Cursor, StyleModifier, Window,
+    WrappingMode,
+};
+use input::{HorizontalScrollable, OperationResult, Scrollable, Searchable};
+use std::borrow::Cow;
+use std::cmp::max;
 use std::fmt;
 use std::ops::Range;
-use widget::{Demand, Demand2D, RenderingHints, Widget};
+use util::{LineDiff, MatchMode};
+use widget::builtin::VisibleLines;
+use widget::{Demand, Demand2D, RenderingHints, StylingRules, Widget};
+
+/// A range of lines that can be collapsed into a summary line (see `LogViewer::add_fold`).
+struct Fold {
+    lines: Range<usize>,
+    collapsed: bool,
+}
+
+struct LogLine {
+    text: String,
+    repetitions: usize,
+    style: StyleModifier,
+}
+
+impl LogLine {
+    fn new(style: StyleModifier) -> Self {
+        LogLine {
+            text: String::new(),
+            repetitions: 1,
+            style,
+        }
+    }
+
+    fn display(&self) -> Cow<'_, str> {
+        if self.repetitions > 1 {
+            Cow::Owned(format!("{} x{}", self.text, self.repetitions))
+        } else {
+            Cow::Borrowed(&self.text)
+        }
+    }
+}
 
 /// A scrollable, append-only buffer of lines.
+///
+/// Optionally, consecutive identical lines can be collapsed into a single line with a repeat
+/// counter (see `deduplicate_lines`). Ranges of lines (e.g., stack traces) can be marked as folds
+/// and collapsed into a single summary line (see `add_fold`).
+///
+/// Long lines are wrapped by default. Alternatively, they can be truncated at the right border of
+/// the window and panned using `HorizontalScrollable` (see `set_wrapping_mode`), which is useful
+/// for wide content such as tables or source code.
+///
+/// By default, control characters (and invalid UTF-8 written using `write_bytes`) are displayed
+/// as visible escapes, so that the output of untrusted subprocesses cannot corrupt the terminal
+/// (see `LogViewerWidget::sanitize`).
+///
+/// # Examples:
+/// ```
+/// use std::fmt::Write;
+/// use unsegen::widget::builtin::LogViewer;
+///
+/// let mut log = LogViewer::new().deduplicate_lines(true);
+/// write!(log, "connecting...\nconnecting...\nconnecting...\ndone\n").unwrap();
+/// // Displayed as:
+/// // connecting... x3
+/// // done
+/// ```
 pub struct LogViewer {
-    storage: Vec<String>, // Invariant: always holds at least one line, does not contain newlines
+    storage: Vec<LogLine>, // Invariant: always holds at least one line, does not contain newlines
     scrollback_position: Option<LineIndex>,
     scroll_step: usize,
+    deduplicate: bool,
+    search: Option<String>,
+    search_mode: MatchMode,
+    highlight_matches: bool,
+    selection_anchor: Option<LineIndex>,
+    folds: Vec<Fold>, // Invariant: sorted, non-overlapping and within storage
+    wrapping: WrappingMode,
+    horizontal_offset: usize, // Invariant: 0 if wrapping
+    horizontal_scroll_step: usize,
+    line_style: StyleModifier,
 }
 
 impl LogViewer {
     /// Create an empty `LogViewer`. Add lines by writing to the viewer as `std::io::Write`.
     pub fn new() -> Self {
         let mut storage = Vec::new();
-        storage.push(String::new()); //Fullfil invariant (at least one line)
+        storage.push(LogLine::new(StyleModifier::new())); //Fullfil invariant (at least one line)
         LogViewer {
             storage: storage,
             scrollback_position: None,
             scroll_step: 1,
+            deduplicate: false,
+            search: None,
+            search_mode: MatchMode::exact(),
+            highlight_matches: false,
+            selection_anchor: None,
+            folds: Vec::new(),
+            wrapping: WrappingMode::Wrap,
+            horizontal_offset: 0,
+            horizontal_scroll_step: 8,
+            line_style: StyleModifier::new(),
         }
     }
 
+    /// Collapse consecutive identical lines into a single line followed by a repeat counter
+    /// ("xN"). Only lines that are written after enabling deduplication are affected.
+    pub fn deduplicate_lines(mut self, enable: bool) -> Self {
+        self.deduplicate = enable;
+        self
+    }
+
+    /// Specify how search patterns are matched, e.g., ignoring case (default: exactly).
+    pub fn search_mode(mut self, mode: MatchMode) -> Self {
+        self.search_mode = mode;
+        self
+    }
+
+    /// Specify by how many columns `scroll_left` and `scroll_right` pan the view (default: 8).
+    pub fn horizontal_scroll_step(mut self, columns: usize) -> Self {
+        self.horizontal_scroll_step = max(1, columns);
+        self
+    }
+
+    /// Wrap long lines (`WrappingMode::Wrap`, the default) or truncate them at the right border of
+    /// the window (`WrappingMode::NoWrap`), in which case the view can be panned horizontally
+    /// using `scroll_left` and `scroll_right`. Switching to wrapping resets the horizontal offset.
+    ///
+    /// # Examples:
+    /// ```
+    /// use std::fmt::Write;
+    /// use unsegen::base::WrappingMode;
+    /// use unsegen::input::{HorizontalScrollBehavior, Input, Key};
+    /// use unsegen::widget::builtin::LogViewer;
+    ///
+    /// let mut log = LogViewer::new().horizontal_scroll_step(4);
+    /// write!(log, "a rather long line").unwrap();
+    /// log.set_wrapping_mode(WrappingMode::NoWrap);
+    ///
+    /// Input::new(Key::Right)
+    ///     .chain(
+    ///         HorizontalScrollBehavior::new(&mut log)
+    ///             .left_on(Key::Left)
+    ///             .right_on(Key::Right),
+    ///     )
+    ///     .finish();
+    /// assert_eq!(log.horizontal_offset(), 4);
+    /// ```
+    pub fn set_wrapping_mode(&mut self, mode: WrappingMode) {
+        self.wrapping = mode;
+        if mode == WrappingMode::Wrap {
+            self.horizontal_offset = 0;
+        }
+    }
+
+    /// Switch between wrapping and truncating long lines (see `set_wrapping_mode`).
+    pub fn toggle_wrapping(&mut self) {
+        let mode = match self.wrapping {
+            WrappingMode::Wrap => WrappingMode::NoWrap,
+            WrappingMode::NoWrap => WrappingMode::Wrap,
+        };
+        self.set_wrapping_mode(mode);
+    }
+
+    /// Whether long lines are currently wrapped or truncated (see `set_wrapping_mode`).
+    pub fn get_wrapping_mode(&self) -> WrappingMode {
+        self.wrapping
+    }
+
+    /// The number of columns the view is scrolled to the right (always 0 while wrapping).
+    pub fn horizontal_offset(&self) -> usize {
+        self.horizontal_offset
+    }
+
+    /// The width of the widest visible line (ignoring the escapes of sanitized characters).
+    fn max_line_width(&self) -> usize {
+        let tab_width = Width::new(4).unwrap();
+        let mut max_width = 0;
+        let mut line = Some(0);
+        while let Some(l) = line {
+            let text = self.display_line(l);
+            let (_, end) = text_extent(&text, Width::UNBOUNDED, tab_width, WrappingMode::NoWrap);
+            max_width = max(max_width, end.raw_value() as usize);
+            line = self.next_visible_line(l);
+        }
+        max_width
+    }
+
+    /// Display the lines that are started from now on (including the active line if it is still
+    /// empty) using `style`, e.g., to distinguish the output of different sources.
+    pub fn set_line_style(&mut self, style: StyleModifier) {
+        self.line_style = style;
+        let active = self
+            .storage
+            .last_mut()
+            .expect("Invariant: At least one line");
+        if active.text.is_empty() {
+            active.style = style;
+        }
+    }
+
+    /// Remove all lines and folds and stop scrolling back, e.g., before displaying the output of
+    /// a new run of a command.
+    pub fn clear(&mut self) {
+        self.storage.clear();
+        self.storage.push(LogLine::new(self.line_style)); //Fullfil invariant (at least one line)
+        self.scrollback_position = None;
+        self.selection_anchor = None;
+        self.highlight_matches = false;
+        self.folds.clear();
+        self.horizontal_offset = 0;
+    }
+
     fn num_lines_stored(&self) -> usize {
         self.storage.len() // Per invariant: no newlines in storage
     }
 
+    /// The (visible) line at the bottom of the view.
     fn current_line_index(&self) -> LineIndex {
-        self.scrollback_position.unwrap_or(LineIndex::new(
-            self.num_lines_stored().checked_sub(1).unwrap_or(0),
-        ))
+        let line = self
+            .scrollback_position
+            .unwrap_or(LineIndex::new(
+                self.num_lines_stored().checked_sub(1).unwrap_or(0),
+            ))
+            .raw_value();
+        LineIndex::new(self.visible_line(line))
+    }
+
+    /// Mark `lines` (e.g., the lines of a stack trace) as a fold, which is displayed as its first
+    /// line followed by the number of hidden lines if it is `collapsed`. Fails if the range has
+    /// less than two lines, exceeds the stored lines or overlaps another fold.
+    ///
+    /// Folds are displayed and scrolled over like a single line while they are collapsed. A
+    /// synced `Gutter` numbers lines as if all folds were expanded, though.
+    ///
+    /// # Examples:
+    /// ```
+    /// use std::fmt::Write;
+    /// use unsegen::input::{Input, Key, Scrollable};
+    /// use unsegen::widget::builtin::LogViewer;
+    ///
+    /// let mut log = LogViewer::new();
+    /// write!(log, "error: oops\n  at main.rs:3\n  at lib.rs:7\ndone").unwrap();
+    /// log.add_fold(0..3, true).unwrap();
+    /// // Displayed as:
+    /// // error: oops [+2 lines]
+    /// // done
+    ///
+    /// // Expand and collapse the fold of the current line with a key:
+    /// log.scroll_to(0).unwrap();
+    /// Input::new(Key::Char('z')).chain((Key::Char('z'), || {
+    ///     let _ = log.toggle_fold();
+    /// }));
+    /// assert!(!log.is_fold_collapsed(1));
+    /// ```
+    pub fn add_fold(&mut self, lines: Range<usize>, collapsed: bool) -> OperationResult {
+        if lines.end < lines.start + 2
+            || lines.end > self.num_lines_stored()
+            || self
+                .folds
+                .iter()
+                .any(|f| f.lines.start < lines.end && lines.start < f.lines.end)
+        {
+            return Err(());
+        }
+        let index = self
+            .folds
+            .iter()
+            .position(|f| f.lines.start > lines.start)
+            .unwrap_or(self.folds.len());
+        let start = lines.start;
+        self.folds.insert(
+            index,
+            Fold {
+                lines,
+                collapsed: false,
+            },
+        );
+        if collapsed {
+            self.set_fold_collapsed(start, true)?;
+        }
+        Ok(())
+    }
+
+    /// Remove all folds, i.e., display all lines again.
+    pub fn remove_folds(&mut self) {
+        self.folds.clear();
+    }
+
+    fn fold_index(&self, line: usize) -> Option<usize> {
+        self.folds.iter().position(|f| f.lines.contains(&line))
+    }
+
+    /// Check whether `line` is part of a collapsed fold.
+    pub fn is_fold_collapsed(&self, line: usize) -> bool {
+        self.fold_index(line)
+            .is_some_and(|i| self.folds[i].collapsed)
+    }
+
+    /// Collapse or expand the fold that contains `line`. Fails if there is no such fold or if it
+    /// already is in the requested state.
+    pub fn set_fold_collapsed(&mut self, line: usize, collapsed: bool) -> OperationResult {
+        let index = self.fold_index(line).ok_or(())?;
+        if self.folds[index].collapsed == collapsed {
+            return Err(());
+        }
+        self.folds[index].collapsed = collapsed;
+        if collapsed {
+            self.move_to_visible_line();
+        }
+        Ok(())
+    }
+
+    /// Collapse or expand the fold that contains the current line.
+    pub fn toggle_fold(&mut self) -> OperationResult {
+        let current = self.current_line_index().raw_value();
+        let collapsed = self.is_fold_collapsed(current);
+        self.set_fold_collapsed(current, !collapsed)
+    }
+
+    /// Collapse or expand all folds.
+    pub fn set_all_folds_collapsed(&mut self, collapsed: bool) {
+        for fold in self.folds.iter_mut() {
+            fold.collapsed = collapsed;
+        }
+        if collapsed {
+            self.move_to_visible_line();
+        }
+    }
+
+    /// Stay on the summary line if the current line has been hidden by collapsing its fold.
+    fn move_to_visible_line(&mut self) {
+        if self.scrollback_position.is_some() {
+            self.scrollback_position = Some(self.current_line_index());
+        }
+    }
+
+    /// The first line of the collapsed fold that hides `line`, or `line` itself if it is visible.
+    fn visible_line(&self, line: usize) -> usize {
+        match self.fold_index(line).map(|i| &self.folds[i]) {
+            Some(fold) if fold.collapsed => fold.lines.start,
+            _ => line,
+        }
+    }
+
+    /// The visible line after `line` (if any).
+    fn next_visible_line(&self, line: usize) -> Option<usize> {
+        let next = match self.fold_index(line).map(|i| &self.folds[i]) {
+            Some(fold) if fold.collapsed => fold.lines.end,
+            _ => line + 1,
+        };
+        Some(next).filter(|&l| l < self.num_lines_stored())
+    }
+
+    /// The visible line before `line` (if any).
+    fn previous_visible_line(&self, line: usize) -> Option<usize> {
+        line.checked_sub(1).map(|l| self.visible_line(l))
+    }
+
+    /// The text that is displayed for the visible `line`.
+    fn display_line(&self, line: usize) -> Cow<'_, str> {
+        match self.fold_index(line).map(|i| &self.folds[i]) {
+            Some(fold) if fold.collapsed => Cow::Owned(format!(
+                "{} [+{} lines]",
+                self.storage[line].display(),
+                fold.lines.len() - 1
+            )),
+            _ => self.storage[line].display(),
+        }
     }
 
     /// Note: Do not insert newlines into the string using this
     fn active_line_mut(&mut self) -> &mut String {
-        self.storage
+        &mut self
+            .storage
             .last_mut()
             .expect("Invariant: At least one line")
+            .text
+    }
+
+    /// Complete the active line and start a new one, or (if deduplicating) increase the counter
+    /// of the previous line if it is identical to the active line.
+    fn finish_active_line(&mut self) {
+        let num_lines = self.num_lines_stored();
+        if self.deduplicate && num_lines >= 2 {
+            let (previous, active) = self.storage.split_at_mut(num_lines - 1);
+            let previous = previous.last_mut().expect("num_lines >= 2");
+            let active = &mut active[0];
+            if previous.text == active.text && previous.style == active.style {
+                previous.repetitions += 1;
+                active.text.clear();
+                return;
+            }
+        }
+        self.storage.push(LogLine::new(self.line_style));
     }
 
-    fn view(&self, range: Range<LineIndex>) -> &[String] {
-        &self.storage[range.start.raw_value()..range.end.raw_value()]
+    /// Scroll to the first line containing the pattern of the current search, starting at
+    /// (but excluding) the current line and wrapping around at the beginning/end. A collapsed
+    /// fold containing the line is expanded.
+    fn jump_to_match(&mut self, forwards: bool) -> OperationResult {
+        let pattern = self.search.as_ref().ok_or(())?;
+        let num_lines = self.num_lines_stored();
+        let current = self.current_line_index().raw_value();
+        let found = (1..=num_lines)
+            .map(|offset| {
+                if forwards {
+                    (current + offset) % num_lines
+                } else {
+                    (current + num_lines - offset) % num_lines
+                }
+            })
+            .find(|&i| self.search_mode.contains(&self.storage[i].text, pattern))
+            .ok_or(())?;
+        let _ = self.set_fold_collapsed(found, false);
+        self.scrollback_position = Some(LineIndex::new(found));
+        self.highlight_matches = true;
+        Ok(())
+    }
+
+    /// Start selecting lines at the current line. The selection ranges from this line to the
+    /// current line, so scrolling (or `extend_selection`) extends the selection.
+    pub fn start_selection(&mut self) {
+        self.selection_anchor = Some(self.current_line_index());
+    }
+
+    /// Extend the selection (starting it if necessary) by moving the current line by `lines`
+    /// (e.g., `1` for a line or the height of the window for a page). Negative values move
+    /// backwards.
+    pub fn extend_selection(&mut self, lines: isize) -> OperationResult {
+        let current = self.current_line_index();
+        if self.selection_anchor.is_none() {
+            self.selection_anchor = Some(current);
+        }
+        let last = self.num_lines_stored() as isize - 1;
+        let target = (current.raw_value() as isize + lines).max(0).min(last) as usize;
+        if target == current.raw_value() {
+            return Err(());
+        }
+        self.scroll_to(target)
+    }
+
+    /// Select the lines that are visible in a window of the specified height (ignoring wrapped
+    /// lines), i.e., the current line and the `height - 1` (visible) lines above it.
+    pub fn select_visible(&mut self, height: Height) {
+        let first = self.first_visible_line(height);
+        self.selection_anchor = Some(LineIndex::new(first));
+    }
+
+    /// The first line that is displayed in a window of the specified height (ignoring wrapped
+    /// lines).
+    fn first_visible_line(&self, height: Height) -> usize {
+        let mut first = self.current_line_index().raw_value();
+        for _ in 1..height.raw_value() {
+            match self.previous_visible_line(first) {
+                Some(line) => first = line,
+                None => break,
+            }
+        }
+        first
+    }
+
+    /// Remove the selection (if any).
+    pub fn clear_selection(&mut self) -> OperationResult {
+        self.selection_anchor.take().map(|_| ()).ok_or(())
+    }
+
+    fn selection_range(&self) -> Option<Range<usize>> {
+        let anchor = self.selection_anchor?.raw_value();
+        let current = self.current_line_index().raw_value();
+        Some(anchor.min(current)..anchor.max(current) + 1)
+    }
+
+    /// Get the selected lines separated by newlines, e.g., to copy them to the clipboard using
+    /// `Terminal::set_clipboard`.
+    pub fn selected_text(&self) -> Option<String> {
+        let range = self.selection_range()?;
+        Some(
+            self.storage[range]
+                .iter()
+                .map(|line| line.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    /// Append raw bytes (e.g., the output of a subprocess) to the log. Invalid UTF-8 is kept and
+    /// displayed as escapes (see `base::decode_escaped`).
+    ///
+    /// Note that invalid sequences are escaped per call, so multibyte characters must not be
+    /// split across calls.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        let text = decode_escaped(bytes);
+        fmt::Write::write_str(self, &text).expect("Writing to LogViewer never fails");
+    }
+
+    /// Update the log incrementally from successive snapshots of a text (see `util::linediff`),
+    /// e.g., to display the output of a periodically polled command.
+    ///
+    /// Line `i` of the snapshots corresponds to stored line `i`, so a log that is updated this way
+    /// should not be written to otherwise (and should not deduplicate lines). The scroll position
+    /// and the selection are kept (unless the lines are removed), so a log that follows the end
+    /// keeps following it and a log that has been scrolled back does not jump.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::input::Scrollable;
+    /// use unsegen::util::LineDiffer;
+    /// use unsegen::widget::builtin::LogViewer;
+    ///
+    /// let mut log = LogViewer::new();
+    /// let mut differ = LineDiffer::new();
+    /// log.apply_diff(&differ.update("a\nb\nc"));
+    /// log.scroll_to(1).unwrap();
+    ///
+    /// log.apply_diff(&differ.update("a\nB\nc\nd"));
+    /// assert_eq!(log.scroll_position(), Some(1));
+    /// ```
+    pub fn apply_diff(&mut self, diff: &LineDiff) {
+        let num_complete_lines = self.num_lines_stored() - 1;
+        for (i, text) in &diff.changed {
+            if *i < num_complete_lines {
+                self.storage[*i] = LogLine {
+                    text: text.clone(),
+                    repetitions: 1,
+                    style: self.line_style,
+                };
+            }
+        }
+        self.storage
+            .truncate(num_complete_lines.saturating_sub(diff.removed));
+        self.storage.push(LogLine::new(self.line_style)); //Fullfil invariant (at least one line)
+        let num_lines = self.num_lines_stored();
+        for fold in self.folds.iter_mut() {
+            fold.lines.end = fold.lines.end.min(num_lines);
+        }
+        self.folds.retain(|f| f.lines.len() >= 2);
+
+        let last = LineIndex::new(self.num_lines_stored() - 1);
+        if self.scrollback_position.is_some_and(|p| p >= last) {
+            self.scrollback_position = None;
+        }
+        if self.selection_anchor.is_some_and(|a| a > last) {
+            self.selection_anchor = Some(last);
+        }
+        for line in &diff.appended {
+            self.active_line_mut().push_str(line);
+            self.finish_active_line();
+        }
     }
 
     /// Prepare for drawing as a `Widget`.
-    pub fn as_widget<'a>(&'a self) -> impl Widget + 'a {
-        LogViewerWidget { inner: self }
+    pub fn as_widget<'a>(&'a self) -> LogViewerWidget<'a> {
+        LogViewerWidget {
+            inner: self,
+            styling: None,
+            search_highlight: StyleModifier::new().invert(BoolModifyMode::Toggle),
+            sanitize: Some(StyleModifier::new().invert(BoolModifyMode::Toggle)),
+            selection_style: StyleModifier::new().invert(BoolModifyMode::Toggle),
+        }
     }
 }
 
@@ -60,7 +572,7 @@ impl fmt::Write for LogViewer {
             let mut line: String = s.drain(..(newline_offset + 1)).collect();
             line.pop(); //Remove the \n
             self.active_line_mut().push_str(&line);
-            self.storage.push(String::new());
+            self.finish_active_line();
         }
         self.active_line_mut().push_str(&s);
         Ok(())
@@ -69,32 +581,34 @@ impl fmt::Write for LogViewer {
 
 impl Scrollable for LogViewer {
     fn scroll_forwards(&mut self) -> OperationResult {
-        let current = self.current_line_index();
-        let candidate = current + self.scroll_step;
-        self.scrollback_position = if candidate.raw_value() < self.num_lines_stored() {
-            Some(candidate)
-        } else {
-            None
-        };
-        if self.scrollback_position.is_some() {
-            Ok(())
-        } else {
-            Err(())
+        let mut line = self.current_line_index().raw_value();
+        for _ in 0..self.scroll_step {
+            match self.next_visible_line(line) {
+                Some(next) => line = next,
+                None => {
+                    self.scrollback_position = None;
+                    return Err(());
+                }
+            }
         }
+        self.scrollback_position = Some(LineIndex::new(line));
+        Ok(())
     }
     fn scroll_backwards(&mut self) -> OperationResult {
-        let current = self.current_line_index();
-        let op_res = if current.raw_value() != 0 {
+        let current = self.current_line_index().raw_value();
+        let mut line = current;
+        for _ in 0..self.scroll_step {
+            match self.previous_visible_line(line) {
+                Some(previous) => line = previous,
+                None => break,
+            }
+        }
+        self.scrollback_position = Some(LineIndex::new(line));
+        if current != 0 {
             Ok(())
         } else {
             Err(())
-        };
-        self.scrollback_position = Some(
-            current
-                .checked_sub(self.scroll_step)
-                .unwrap_or(LineIndex::new(0)),
-        );
-        op_res
+        }
     }
     fn scroll_to_beginning(&mut self) -> OperationResult {
         if Some(LineIndex::new(0)) == self.scrollback_position {
@@ -112,10 +626,146 @@ impl Scrollable for LogViewer {
             Ok(())
         }
     }
+    fn scroll_position(&self) -> Option<usize> {
+        Some(self.current_line_index().raw_value())
+    }
+    fn scroll_to(&mut self, position: usize) -> OperationResult {
+        let last = self.num_lines_stored() - 1;
+        self.scrollback_position = if position < last {
+            Some(LineIndex::new(position))
+        } else {
+            None
+        };
+        self.move_to_visible_line();
+        if position <= last {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl HorizontalScrollable for LogViewer {
+    fn scroll_left(&mut self) -> OperationResult {
+        if self.horizontal_offset == 0 {
+            Err(())
+        } else {
+            self.horizontal_offset = self
+                .horizontal_offset
+                .saturating_sub(self.horizontal_scroll_step);
+            Ok(())
+        }
+    }
+    /// Fails while wrapping lines or if no line would remain (partially) visible.
+    fn scroll_right(&mut self) -> OperationResult {
+        let offset = self.horizontal_offset + self.horizontal_scroll_step;
+        if self.wrapping == WrappingMode::Wrap || offset >= self.max_line_width() {
+            Err(())
+        } else {
+            self.horizontal_offset = offset;
+            Ok(())
+        }
+    }
 }
 
-struct LogViewerWidget<'a> {
+impl VisibleLines for LogViewer {
+    fn visible_lines(&self, height: Height) -> (RowIndex, Range<usize>) {
+        // Lines are drawn from the bottom of the window upwards.
+        let end = self.current_line_index().raw_value() + 1;
+        if height == 0 {
+            return (RowIndex::new(0), end..end);
+        }
+        let start = self.first_visible_line(height);
+        let mut num_rows = 1;
+        let mut line = start;
+        while let Some(next) = self.next_visible_line(line).filter(|&l| l < end) {
+            line = next;
+            num_rows += 1;
+        }
+        let first_row = height.raw_value() as usize - num_rows;
+        (RowIndex::new(first_row as i32), start..end)
+    }
+    fn num_lines(&self) -> usize {
+        self.num_lines_stored()
+    }
+    fn current_line(&self) -> usize {
+        self.current_line_index().raw_value()
+    }
+}
+
+/// Search line by line. `start_search` scrolls to the most recent line containing the pattern,
+/// starting at the current line.
+impl Searchable for LogViewer {
+    fn start_search(&mut self, pattern: &str) -> OperationResult {
+        if pattern.is_empty() {
+            self.search = None;
+            return Err(());
+        }
+        self.search = Some(pattern.to_owned());
+        // Start at the line below the current one so that the current line is checked first.
+        let current = self.current_line_index().raw_value();
+        let below = (current + 1) % self.num_lines_stored();
+        let previous_position = self.scrollback_position;
+        self.scrollback_position = Some(LineIndex::new(below));
+        let res = self.jump_to_match(false);
+        if res.is_err() {
+            self.scrollback_position = previous_position;
+        }
+        res
+    }
+    fn next_match(&mut self) -> OperationResult {
+        self.jump_to_match(true)
+    }
+    fn prev_match(&mut self) -> OperationResult {
+        self.jump_to_match(false)
+    }
+    fn clear_highlights(&mut self) -> OperationResult {
+        if self.highlight_matches {
+            self.highlight_matches = false;
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+/// A `Widget` representing a `LogViewer`
+///
+/// It allows for styling the lines using `StylingRules`.
+pub struct LogViewerWidget<'a> {
     inner: &'a LogViewer,
+    styling: Option<&'a StylingRules>,
+    search_highlight: StyleModifier,
+    sanitize: Option<StyleModifier>,
+    selection_style: StyleModifier,
+}
+
+impl<'a> LogViewerWidget<'a> {
+    /// Style the displayed lines according to the specified rules.
+    pub fn styling_rules(mut self, rules: &'a StylingRules) -> Self {
+        self.styling = Some(rules);
+        self
+    }
+
+    /// Define the style of matches of the current search (default: inverted).
+    pub fn search_highlight(mut self, style: StyleModifier) -> Self {
+        self.search_highlight = style;
+        self
+    }
+
+    /// Define the style of selected lines (default: inverted).
+    pub fn selection_style(mut self, style: StyleModifier) -> Self {
+        self.selection_style = style;
+        self
+    }
+
+    /// Display control characters and invalid UTF-8 as visible escapes with the specified style
+    /// (see `base::sanitizer`) or write them to the terminal unmodified using `None`
+    /// (default: inverted).
+    pub fn sanitize(mut self, style: Option<StyleModifier>) -> Self {
+        self.sanitize = style;
+        self
+    }
 }
 
 impl<'a> Widget for LogViewerWidget<'a> {
@@ -136,17 +786,268 @@ impl<'a> Widget for LogViewerWidget<'a> {
         // self.scroll_step = ::std::cmp::max(1, height.checked_sub(1).unwrap_or(1));
 
         let y_start = height - 1;
+        let x_start = ColIndex::new(-(self.inner.horizontal_offset as i32));
         let mut cursor = Cursor::new(&mut window)
-            .position(ColIndex::new(0), y_start.from_origin())
-            .wrapping_mode(WrappingMode::Wrap);
-        let end_line = self.inner.current_line_index();
-        let start_line =
-            LineIndex::new(end_line.raw_value().checked_sub(height.into()).unwrap_or(0));
-        for line in self.inner.view(start_line..(end_line + 1)).iter().rev() {
+            .position(x_start, y_start.from_origin())
+            .line_start_column(x_start)
+            .wrapping_mode(self.inner.wrapping);
+        if let Some(style) = self.sanitize {
+            cursor = cursor.shaper(sanitizer(style));
+        }
+        // The visible lines from the bottom of the window upwards (one more than fits, since
+        // the top one may be wrapped).
+        let mut lines = Vec::new();
+        let mut line = Some(self.inner.current_line_index().raw_value());
+        while let Some(l) = line.filter(|_| lines.len() <= height.raw_value() as usize) {
+            lines.push(l);
+            line = self.inner.previous_visible_line(l);
+        }
+        let search = self
+            .inner
+            .search
+            .as_ref()
+            .filter(|_| self.inner.highlight_matches);
+        let no_rules = StylingRules::new();
+        let selection = self.inner.selection_range();
+        for index in lines {
+            let selected = selection.as_ref().is_some_and(|s| s.contains(&index));
+            let line_style = self.inner.storage[index].style;
+            cursor.set_style_modifier(if selected {
+                self.selection_style.on_top_of(line_style)
+            } else {
+                line_style
+            });
+            let line = self.inner.display_line(index);
             let num_auto_wraps = cursor.num_expected_wraps(&line) as i32;
             cursor.move_by(ColDiff::new(0), RowDiff::new(-num_auto_wraps));
-            cursor.writeln(&line);
+            if let Some(pattern) = search {
+                let matches = self
+                    .inner
+                    .search_mode
+                    .find_all(&line, pattern)
+                    .into_iter()
+                    .map(|m| (m, self.search_highlight))
+                    .collect();
+                self.styling
+                    .unwrap_or(&no_rules)
+                    .write_with_spans(&mut cursor, &line, matches);
+            } else if let Some(rules) = self.styling {
+                rules.write(&mut cursor, &line);
+            } else {
+                cursor.write(&line);
+            }
+            if selected {
+                cursor.fill_and_wrap_line();
+            } else {
+                cursor.wrap_line();
+            }
             cursor.move_by(ColDiff::new(0), RowDiff::new(-num_auto_wraps) - 2);
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+    use std::fmt::Write;
+
+    fn test_log_viewer(window_dim: (u32, u32), log: &LogViewer, after: &str) {
+        let mut term = FakeTerminal::with_size(window_dim);
+        log.as_widget()
+            .draw(term.create_root_window(), RenderingHints::default());
+        term.assert_looks_like(after);
+    }
+
+    #[test]
+    fn test_log_viewer_horizontal_scroll() {
+        let mut log = LogViewer::new().horizontal_scroll_step(2);
+        write!(log, "abcdefg\nhi\n沐xyz").unwrap();
+        test_log_viewer((4, 3), &log, "hi  |沐xy|z   ");
+        assert!(log.scroll_right().is_err());
+
+        log.toggle_wrapping();
+        assert_eq!(log.get_wrapping_mode(), WrappingMode::NoWrap);
+        test_log_viewer((4, 3), &log, "abcd|hi  |沐xy");
+        assert!(log.scroll_left().is_err());
+        log.scroll_right().unwrap();
+        test_log_viewer((4, 3), &log, "cdef|    |xyz ");
+        log.scroll_right().unwrap();
+        log.scroll_right().unwrap();
+        test_log_viewer((4, 3), &log, "g   |    |    ");
+        assert!(log.scroll_right().is_err());
+        log.scroll_left().unwrap();
+        test_log_viewer((4, 3), &log, "efg |    |z   ");
+
+        // A wide cluster cut by the left border is blanked.
+        let mut log = LogViewer::new().horizontal_scroll_step(1);
+        log.set_wrapping_mode(WrappingMode::NoWrap);
+        write!(log, "沐xyz").unwrap();
+        log.scroll_right().unwrap();
+        test_log_viewer((4, 1), &log, " xyz");
+
+        log.toggle_wrapping();
+        assert_eq!(log.horizontal_offset(), 0);
+        test_log_viewer((4, 2), &log, "沐xy|z   ");
+        test_log_viewer((4, 1), &log, "z   ");
+    }
+
+    #[test]
+    fn test_log_viewer_apply_diff() {
+        use util::LineDiffer;
+        let mut log = LogViewer::new();
+        let mut differ = LineDiffer::new();
+        log.apply_diff(&differ.update("a\nb\nc\nd\n"));
+        test_log_viewer((2, 2), &log, "d |  ");
+        log.apply_diff(&differ.update("a\nb\nC\nd\ne"));
+        test_log_viewer((2, 2), &log, "e |  ");
+
+        log.scroll_to(2).unwrap();
+        log.apply_diff(&differ.update("a\nx\nC\nd\ne\nf"));
+        test_log_viewer((2, 2), &log, "x |C ");
+        log.apply_diff(&differ.update("y"));
+        test_log_viewer((2, 2), &log, "y |  ");
+        assert_eq!(log.scroll_position(), Some(1));
+    }
+
+    #[test]
+    fn test_log_viewer_deduplicate() {
+        let mut log = LogViewer::new().deduplicate_lines(true);
+        write!(log, "a\nb\nb\n").unwrap();
+        test_log_viewer((5, 3), &log, "a    |b x2 |     ");
+        write!(log, "b").unwrap();
+        test_log_viewer((5, 3), &log, "a    |b x2 |b    ");
+        write!(log, "\nc\n").unwrap();
+        test_log_viewer((5, 3), &log, "b x3 |c    |     ");
+
+        log.scroll_to_beginning().unwrap();
+        writeln!(log, "c").unwrap();
+        test_log_viewer((5, 1), &log, "a    ");
+        log.scroll_forwards().unwrap();
+        log.scroll_forwards().unwrap();
+        test_log_viewer((5, 1), &log, "c x2 ");
+    }
+
+    #[test]
+    fn test_log_viewer_styling_rules() {
+        let mut log = LogViewer::new();
+        write!(log, "an error\nok").unwrap();
+        let rules = StylingRules::new().rule(::widget::StylingRule::spans(
+            "err",
+            ::base::StyleModifier::new().bold(true),
+        ));
+        let mut term = FakeTerminal::with_size((5, 3));
+        log.as_widget()
+            .styling_rules(&rules)
+            .draw(term.create_root_window(), RenderingHints::default());
+        term.assert_looks_like("an *e**r*|*r*or  |ok   ");
+    }
+
+    #[test]
+    fn test_log_viewer_search() {
+        let mut log = LogViewer::new();
+        write!(log, "foo 1\nbar\nfoo 2\nbaz").unwrap();
+        let draw = |log: &LogViewer, after: &str| {
+            let mut term = FakeTerminal::with_size((6, 2));
+            log.as_widget()
+                .search_highlight(StyleModifier::new().bold(true))
+                .draw(term.create_root_window(), RenderingHints::default());
+            term.assert_looks_like(after);
+        };
+
+        assert!(log.start_search("qux").is_err());
+        draw(&log, "foo 2 |baz   ");
+        log.start_search("foo").unwrap();
+        draw(&log, "bar   |*f**o**o* 2 ");
+        log.next_match().unwrap();
+        draw(&log, "      |*f**o**o* 1 ");
+        log.prev_match().unwrap();
+        draw(&log, "bar   |*f**o**o* 2 ");
+        log.clear_highlights().unwrap();
+        assert!(log.clear_highlights().is_err());
+        draw(&log, "bar   |foo 2 ");
+    }
+
+    #[test]
+    fn test_log_viewer_sanitize() {
+        let draw = |log: &LogViewer, sanitize, size, after| {
+            let mut term = FakeTerminal::with_size(size);
+            log.as_widget()
+                .sanitize(sanitize)
+                .draw(term.create_root_window(), RenderingHints::default());
+            term.assert_looks_like(after);
+        };
+        let mut log = LogViewer::new();
+        log.write_bytes(b"a\x1b[1mb\xff\n\tc");
+        draw(
+            &log,
+            Some(StyleModifier::new().bold(true)),
+            (6, 3),
+            "a*␛*[1mb|*\\**x**F**F*  |*␉*c    ",
+        );
+        let mut log = LogViewer::new();
+        write!(log, "\ta").unwrap();
+        draw(&log, None, (6, 1), "    a ");
+    }
+    #[test]
+    fn test_log_viewer_folds() {
+        let mut log = LogViewer::new();
+        write!(log, "a\nb\nc\nd\ne").unwrap();
+        log.add_fold(1..4, true).unwrap();
+        assert!(log.add_fold(3..5, false).is_err());
+        assert!(log.add_fold(4..6, false).is_err());
+        assert!(log.add_fold(4..5, false).is_err());
+        test_log_viewer((13, 3), &log, "a            |b [+2 lines] |e            ");
+        assert_eq!(
+            log.visible_lines(Height::new(3).unwrap()),
+            (RowIndex::new(0), 0..5)
+        );
+
+        log.scroll_backwards().unwrap();
+        assert_eq!(log.scroll_position(), Some(1));
+        test_log_viewer((13, 3), &log, "             |a            |b [+2 lines] ");
+        log.scroll_forwards().unwrap();
+        assert_eq!(log.scroll_position(), Some(4));
+        assert!(log.toggle_fold().is_err());
+
+        log.scroll_to(2).unwrap();
+        assert_eq!(log.scroll_position(), Some(1));
+        log.toggle_fold().unwrap();
+        assert!(!log.is_fold_collapsed(2));
+        test_log_viewer((13, 3), &log, "             |a            |b            ");
+
+        log.set_all_folds_collapsed(true);
+        log.start_search("d").unwrap();
+        assert!(!log.is_fold_collapsed(2));
+        assert_eq!(log.scroll_position(), Some(3));
+    }
+
+    #[test]
+    fn test_log_viewer_selection() {
+        let mut log = LogViewer::new();
+        write!(log, "a\nb\nc\nd").unwrap();
+        let draw = |log: &LogViewer, after: &str| {
+            let mut term = FakeTerminal::with_size((2, 3));
+            log.as_widget()
+                .selection_style(StyleModifier::new().bold(true))
+                .draw(term.create_root_window(), RenderingHints::default());
+            term.assert_looks_like(after);
+        };
+        assert_eq!(log.selected_text(), None);
+        log.scroll_backwards().unwrap();
+        log.start_selection();
+        draw(&log, "a |b |*c** *");
+        log.extend_selection(-1).unwrap();
+        assert_eq!(log.selected_text(), Some("b\nc".to_owned()));
+        draw(&log, "  |a |*b** *");
+        assert!(log.extend_selection(-5).is_ok());
+        assert!(log.extend_selection(-1).is_err());
+        assert_eq!(log.selected_text(), Some("a\nb\nc".to_owned()));
+        log.clear_selection().unwrap();
+        assert!(log.clear_selection().is_err());
+
+        log.scroll_to_end().unwrap();
+        log.select_visible(Height::new(2).unwrap());
+        assert_eq!(log.selected_text(), Some("c\nd".to_owned()));
+    }
+}
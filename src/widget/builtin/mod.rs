@@ -1,12 +1,177 @@
 //! This module contains several basic widgets that are built into the core library.
+pub mod braille;
+pub mod cells;
+pub mod colorpicker;
+pub mod datacolumn;
+pub mod datepicker;
+pub mod gutter;
+pub mod heatmap;
+pub mod keycapture;
 pub mod lineedit;
 pub mod logviewer;
+pub mod numberinput;
 pub mod promptline;
+pub mod propertysheet;
+pub mod slider;
 pub mod table;
 pub mod textedit;
+pub mod timeseries;
+pub mod truncatedlabel;
 
+pub use self::braille::*;
+pub use self::cells::*;
+pub use self::colorpicker::*;
+pub use self::datacolumn::*;
+pub use self::datepicker::*;
+pub use self::gutter::*;
+pub use self::heatmap::*;
+pub use self::keycapture::*;
 pub use self::lineedit::*;
 pub use self::logviewer::*;
+pub use self::numberinput::*;
 pub use self::promptline::*;
+pub use self::propertysheet::*;
+pub use self::slider::*;
 pub use self::table::*;
 pub use self::textedit::*;
+pub use self::timeseries::*;
+pub use self::truncatedlabel::*;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+    use base::{Color, Height, Style, Width};
+    use input::{Navigatable, Scrollable};
+    use std::fmt::Write;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::time::{Duration, Instant};
+    use widget::{RenderingHints, Widget};
+
+    struct Row(String);
+    impl TableRow for Row {
+        type BehaviorContext = ();
+        const COLUMNS: &'static [Column<Self>] = &[
+            Column {
+                access: |r| Box::new(r.0.as_str()),
+                behavior: |_, _, _| None,
+            },
+            Column {
+                access: |r| Box::new(r.0.as_str()),
+                behavior: |_, _, _| None,
+            },
+        ];
+    }
+
+    /// Draw `widget` into windows of all tiny sizes (including empty windows), with and without
+    /// being active.
+    fn draw_into_tiny_windows(name: &str, widget: &dyn Widget) {
+        for width in 0..4 {
+            for height in 0..4 {
+                for &active in &[false, true] {
+                    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                        let mut term = FakeTerminal::with_size((width, height));
+                        widget.draw(
+                            term.create_root_window(),
+                            RenderingHints::new().active(active),
+                        );
+                    }));
+                    if res.is_err() {
+                        panic!(
+                            "{} panicked in a {}x{} window (active: {})",
+                            name, width, height, active
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fuzz_tiny_windows() {
+        let values = [0.5, 1.0, 0.0];
+        let text = "some text\nwith\tseveral lines and a long line at the end";
+
+        draw_into_tiny_windows(
+            "BrailleCanvas",
+            &BrailleCanvas::new(Width::new(3).unwrap(), Height::new(2).unwrap()),
+        );
+        draw_into_tiny_windows("NumberCell", &NumberCell::new(123.456));
+        draw_into_tiny_windows("ProgressCell", &ProgressCell::new(0.5));
+        draw_into_tiny_windows("SparklineCell", &SparklineCell::new(&values));
+        draw_into_tiny_windows(
+            "WrappedTextCell",
+            &WrappedTextCell::new(text, Width::new(5).unwrap()),
+        );
+        draw_into_tiny_windows("ColorPicker", &ColorPicker::new().as_widget());
+        draw_into_tiny_windows("StylePreview", &StylePreview::new(Style::plain()));
+        draw_into_tiny_windows("DataCell", &DataColumn::default().cell(1.5));
+        draw_into_tiny_windows(
+            "DatePicker",
+            &DatePicker::new(Date::new(2020, 2, 29).unwrap()).as_widget(),
+        );
+        draw_into_tiny_windows("Gutter", &Gutter::new(98, 200));
+        let mut heatmap = Heatmap::new(3, 5);
+        heatmap.set(1, 2, 1.0).unwrap();
+        draw_into_tiny_windows("Heatmap", &heatmap.as_widget());
+        let _ = heatmap.move_down();
+        let _ = heatmap.move_right();
+        draw_into_tiny_windows(
+            "Heatmap (scaled)",
+            &heatmap
+                .as_widget()
+                .scale(ColorScale::new(vec![Color::Blue, Color::Red])),
+        );
+        draw_into_tiny_windows("KeyCapture", &KeyCapture::new().as_widget());
+
+        let mut line = LineEdit::new();
+        line.set(text);
+        draw_into_tiny_windows("LineEdit", &line.as_widget());
+
+        let mut log = LogViewer::new();
+        write!(log, "{}\n{}", text, text).unwrap();
+        draw_into_tiny_windows("LogViewer", &log.as_widget());
+        let _ = log.scroll_backwards();
+        draw_into_tiny_windows("LogViewer (scrolled)", &log.as_widget());
+
+        draw_into_tiny_windows("NumberInput", &NumberInput::float(-1.5, 2).as_widget());
+
+        let mut prompt = PromptLine::with_prompt("> ".to_owned());
+        prompt.set(text);
+        draw_into_tiny_windows("PromptLine", &prompt.as_widget());
+
+        let sheet = PropertySheet::new()
+            .entry("key", NumberCell::new(1.0))
+            .group("group")
+            .entry("longer key", ProgressCell::new(0.5));
+        draw_into_tiny_windows("PropertySheet", &sheet.as_widget());
+
+        draw_into_tiny_windows("Slider", &Slider::new(-1.0, 1.0).as_widget());
+
+        let mut table = Table::<Row>::new();
+        table.rows_mut().push(Row("a cell".to_owned()));
+        table.rows_mut().push(Row("another cell".to_owned()));
+        draw_into_tiny_windows("Table", &table.as_widget());
+        let _ = table.move_down();
+        let _ = table.move_right();
+        draw_into_tiny_windows("Table (navigated)", &table.as_widget());
+        draw_into_tiny_windows("Table (empty)", &Table::<Row>::new().as_widget());
+
+        let mut edit = TextEdit::new();
+        edit.set(text);
+        draw_into_tiny_windows("TextEdit", &edit.as_widget());
+        let _ = edit.move_down();
+        let _ = edit.move_right();
+        draw_into_tiny_windows("TextEdit (navigated)", &edit.as_widget());
+        draw_into_tiny_windows("TextEdit (empty)", &TextEdit::new().as_widget());
+
+        let mut series = TimeSeries::new(Duration::from_secs(10));
+        let start = Instant::now();
+        for i in 0..5 {
+            series.push(start + Duration::from_secs(i), i as f64);
+        }
+        draw_into_tiny_windows("TimeSeries", &series.as_widget());
+
+        draw_into_tiny_windows("TruncatedLabel", &TruncatedLabel::new(text));
+    }
+}
@@ -0,0 +1,351 @@
+//! A user-editable number with optional bounds and unit.
+use base::basic_types::*;
+use base::{Color, StyleModifier, Window};
+use input::{Editable, Navigatable, OperationResult, Writable};
+use widget::builtin::{LineEdit, LineEditWidget};
+use widget::{text_width, Demand, Demand2D, RenderingHints, Widget};
+
+/// A user-editable (integer or floating point) number.
+///
+/// The number can be entered as text or incremented/decremented in steps (using `move_up` and
+/// `move_down` of `Navigatable`). Text that does not represent a number within the bounds of the
+/// `NumberInput` is considered invalid, in which case `value` keeps returning the last valid
+/// number.
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::{Editable, Navigatable, Writable};
+/// use unsegen::widget::builtin::NumberInput;
+///
+/// let mut n = NumberInput::integer(5).min(0.0).max(10.0).step(2.0);
+/// assert_eq!(n.text(), "5");
+///
+/// n.move_up().unwrap();
+/// assert_eq!(n.value(), 7.0);
+/// n.move_up().unwrap();
+/// n.move_up().unwrap(); // Clamped to the maximum
+/// assert_eq!(n.value(), 10.0);
+/// assert!(n.move_up().is_err());
+///
+/// n.write('0').unwrap();
+/// assert!(!n.is_valid());
+/// assert_eq!(n.value(), 10.0);
+/// n.delete_backwards().unwrap();
+/// n.delete_backwards().unwrap();
+/// assert!(n.is_valid());
+/// assert_eq!(n.value(), 1.0);
+/// ```
+pub struct NumberInput {
+    edit: LineEdit,
+    value: f64,
+    min: f64,
+    max: f64,
+    step: f64,
+    decimals: usize,
+    unit: Option<String>,
+}
+
+impl NumberInput {
+    /// Create an unbounded integer input with initial `value` that is incremented in steps of 1.
+    pub fn integer(value: i64) -> Self {
+        Self::float(value as f64, 0)
+    }
+
+    /// Create an unbounded input for floating point numbers with `decimals` digits after the
+    /// decimal point. The initial `value` is incremented in steps of 1.
+    pub fn float(value: f64, decimals: usize) -> Self {
+        let mut input = NumberInput {
+            edit: LineEdit::new(),
+            value: 0.0,
+            min: f64::NEG_INFINITY,
+            max: f64::INFINITY,
+            step: 1.0,
+            decimals,
+            unit: None,
+        };
+        input.set_value(value);
+        input
+    }
+
+    /// Set the minimum (inclusive) valid value.
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = min;
+        let value = self.value;
+        self.set_value(value);
+        self
+    }
+
+    /// Set the maximum (inclusive) valid value.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = max;
+        let value = self.value;
+        self.set_value(value);
+        self
+    }
+
+    /// Set the amount the value changes on increment/decrement.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Set a unit (e.g., "ms") that is displayed after the number.
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    /// Get the last valid value that was entered.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the text as currently entered by the user.
+    pub fn text(&self) -> &str {
+        self.edit.get()
+    }
+
+    /// Set the value (clamped to the bounds and rounded to the specified number of decimals) and
+    /// overwrite the current text.
+    pub fn set_value(&mut self, value: f64) {
+        let value = self.round(value.max(self.min).min(self.max));
+        self.value = value;
+        self.edit.set(format!("{:.*}", self.decimals, value));
+    }
+
+    /// Check whether the current text represents a number that is within bounds.
+    pub fn is_valid(&self) -> bool {
+        self.parse().is_some()
+    }
+
+    /// Increase the value by one step. Fails if the value is already at the maximum.
+    pub fn increment(&mut self) -> OperationResult {
+        let step = self.step;
+        self.change_by(step)
+    }
+
+    /// Decrease the value by one step. Fails if the value is already at the minimum.
+    pub fn decrement(&mut self) -> OperationResult {
+        let step = self.step;
+        self.change_by(-step)
+    }
+
+    fn change_by(&mut self, diff: f64) -> OperationResult {
+        let old = self.value;
+        self.set_value(old + diff);
+        if self.value != old {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn round(&self, value: f64) -> f64 {
+        let factor = 10f64.powi(self.decimals as i32);
+        (value * factor).round() / factor
+    }
+
+    fn parse(&self) -> Option<f64> {
+        let text = self.edit.get();
+        let num_decimals = text.split('.').nth(1).map(str::len).unwrap_or(0);
+        if num_decimals > self.decimals || (self.decimals == 0 && text.contains('.')) {
+            return None;
+        }
+        text.parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite() && self.min <= *v && *v <= self.max)
+    }
+
+    fn update_value(&mut self) {
+        if let Some(value) = self.parse() {
+            self.value = value;
+        }
+    }
+
+    /// Prepare for drawing as a `Widget`.
+    pub fn as_widget<'a>(&'a self) -> NumberInputWidget<'a> {
+        NumberInputWidget {
+            input: self,
+            edit: self.edit.as_widget(),
+            invalid_style: StyleModifier::new().fg_color(Color::Red),
+        }
+    }
+}
+
+/// Move up/down to increment/decrement the value and left/right to move the cursor.
+impl Navigatable for NumberInput {
+    fn move_up(&mut self) -> OperationResult {
+        self.increment()
+    }
+    fn move_down(&mut self) -> OperationResult {
+        self.decrement()
+    }
+    fn move_left(&mut self) -> OperationResult {
+        self.edit.move_left()
+    }
+    fn move_right(&mut self) -> OperationResult {
+        self.edit.move_right()
+    }
+}
+
+/// Only digits, a minus sign (at the beginning) and a decimal point (if decimals are allowed) can
+/// be written.
+impl Writable for NumberInput {
+    fn write(&mut self, c: char) -> OperationResult {
+        let allowed = match c {
+            '0'..='9' => true,
+            '-' => self.min < 0.0 && self.edit.cursor_pos() == 0 && !self.text().contains('-'),
+            '.' => self.decimals > 0 && !self.text().contains('.'),
+            _ => false,
+        };
+        if allowed {
+            self.edit.write(c)?;
+            self.update_value();
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl Editable for NumberInput {
+    fn delete_forwards(&mut self) -> OperationResult {
+        self.edit.delete_forwards()?;
+        self.update_value();
+        Ok(())
+    }
+    fn delete_backwards(&mut self) -> OperationResult {
+        self.edit.delete_backwards()?;
+        self.update_value();
+        Ok(())
+    }
+    fn go_to_beginning_of_line(&mut self) -> OperationResult {
+        self.edit.go_to_beginning_of_line()
+    }
+    fn go_to_end_of_line(&mut self) -> OperationResult {
+        self.edit.go_to_end_of_line()
+    }
+    fn clear(&mut self) -> OperationResult {
+        self.edit.clear()
+    }
+}
+
+/// A `Widget` representing a `NumberInput`
+///
+/// It allows for customization of cursor styles and the style of invalid input.
+pub struct NumberInputWidget<'a> {
+    input: &'a NumberInput,
+    edit: LineEditWidget<'a>,
+    invalid_style: StyleModifier,
+}
+
+impl<'a> NumberInputWidget<'a> {
+    /// Define the style that the cursor will be drawn with on the "on" tick when the widget is
+    /// active.
+    pub fn cursor_blink_on(mut self, style: StyleModifier) -> Self {
+        self.edit = self.edit.cursor_blink_on(style);
+        self
+    }
+
+    /// Define the style that the cursor will be drawn with on the "off" tick when the widget is
+    /// active.
+    pub fn cursor_blink_off(mut self, style: StyleModifier) -> Self {
+        self.edit = self.edit.cursor_blink_off(style);
+        self
+    }
+
+    /// Define the style that the cursor will be drawn with when the widget is inactive.
+    pub fn cursor_inactive(mut self, style: StyleModifier) -> Self {
+        self.edit = self.edit.cursor_inactive(style);
+        self
+    }
+
+    /// Define the style that the text will be drawn with if it is not valid.
+    pub fn invalid(mut self, style: StyleModifier) -> Self {
+        self.invalid_style = style;
+        self
+    }
+
+    fn unit_width(&self) -> Width {
+        self.input
+            .unit
+            .as_ref()
+            .map(|u| text_width(u) + 1)
+            .unwrap_or(Width::new(0).unwrap())
+    }
+}
+
+impl<'a> Widget for NumberInputWidget<'a> {
+    fn space_demand(&self) -> Demand2D {
+        let edit = self.edit.space_demand();
+        Demand2D {
+            width: edit.width + Demand::exact(self.unit_width()),
+            height: edit.height,
+        }
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        if !self.input.is_valid() {
+            window.modify_default_style(self.invalid_style);
+        }
+        let split_pos = (window.get_width() - self.unit_width()).positive_or_zero();
+        match (&self.input.unit, window.split(split_pos.from_origin())) {
+            (Some(unit), Ok((edit_window, mut unit_window))) => {
+                self.edit.draw(edit_window, hints);
+                let mut cursor = ::base::Cursor::new(&mut unit_window);
+                cursor.write(" ");
+                cursor.write(unit);
+            }
+            (_, Ok((window, _))) | (_, Err(window)) => {
+                self.edit.draw(window, hints);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+    use widget::Blink;
+
+    fn test_number_input(window_dim: (u32, u32), input: &NumberInput, after: &str) {
+        let mut term = FakeTerminal::with_size(window_dim);
+        input
+            .as_widget()
+            .cursor_blink_on(StyleModifier::new().bold(true))
+            .draw(
+                term.create_root_window(),
+                RenderingHints::default().active(true).blink(Blink::On),
+            );
+        term.assert_looks_like(after);
+    }
+
+    #[test]
+    fn test_number_input_decimals() {
+        let mut n = NumberInput::float(1.234, 2).min(-1.0).unit("s");
+        assert_eq!(n.text(), "1.23");
+        test_number_input((7, 1), &n, "1.23* * s");
+
+        n.clear().unwrap();
+        n.write('.').unwrap();
+        assert!(n.write('.').is_err());
+        assert!(n.write('-').is_err());
+        n.go_to_beginning_of_line().unwrap();
+        n.write('-').unwrap();
+        n.go_to_end_of_line().unwrap();
+        n.write('5').unwrap();
+        assert!(n.is_valid());
+        assert_eq!(n.value(), -0.5);
+        n.write('5').unwrap();
+        assert_eq!(n.value(), -0.55);
+        n.write('5').unwrap();
+        assert!(!n.is_valid());
+        assert_eq!(n.value(), -0.55);
+        assert!(n.write('x').is_err());
+
+        n.decrement().unwrap();
+        assert_eq!(n.value(), -1.0);
+        assert_eq!(n.text(), "-1.00");
+        assert!(n.decrement().is_err());
+    }
+}
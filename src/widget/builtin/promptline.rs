@@ -2,7 +2,9 @@
 use super::super::{HLayout, Widget};
 use super::LineEdit;
 use input::{Editable, Navigatable, OperationResult, Scrollable, Writable};
+use std::io;
 use std::ops::{Deref, DerefMut};
+use util::{MatchMode, Storage};
 
 /// A widget implementing "readline"-like functionality.
 ///
@@ -14,6 +16,7 @@ pub struct PromptLine {
     #[allow(missing_docs)]
     pub line: LineEdit,
     history: Vec<String>,
+    search_mode: MatchMode,
     state: State,
 }
 
@@ -33,6 +36,7 @@ fn search_prev(
     current: Option<usize>,
     history: &Vec<String>,
     search_pattern: &str,
+    mode: MatchMode,
 ) -> Option<usize> {
     if search_pattern.is_empty() {
         return None;
@@ -43,13 +47,14 @@ fn search_prev(
         .iter()
         .enumerate()
         .rev()
-        .find(|(_, line)| line.contains(search_pattern))
+        .find(|(_, line)| mode.contains(line, search_pattern))
         .map(|(i, _)| i)
 }
 fn search_next(
     current: Option<usize>,
     history: &Vec<String>,
     search_pattern: &str,
+    mode: MatchMode,
 ) -> Option<usize> {
     if search_pattern.is_empty() {
         return None;
@@ -57,7 +62,7 @@ fn search_next(
     let start = current.map(|c| c + 1).unwrap_or(0);
     history[start..]
         .iter()
-        .position(|line| line.contains(search_pattern))
+        .position(|line| mode.contains(line, search_pattern))
         .map(|v| v + start)
 }
 
@@ -71,6 +76,7 @@ impl PromptLine {
             search_prompt: prompt.clone(),
             line: LineEdit::new(),
             history: Vec::new(),
+            search_mode: MatchMode::exact(),
             state: State::Editing,
         }
     }
@@ -101,6 +107,12 @@ impl PromptLine {
         self.update_display();
     }
 
+    /// Specify how the search pattern is matched against history entries, e.g., ignoring case
+    /// (default: exactly).
+    pub fn set_search_mode(&mut self, mode: MatchMode) {
+        self.search_mode = mode;
+    }
+
     /// Get the `n`'th line from the history.
     pub fn previous_line(&self, n: usize) -> Option<&str> {
         self.history
@@ -108,6 +120,26 @@ impl PromptLine {
             .map(String::as_str)
     }
 
+    /// Save the history (one line per entry) under `name` in `storage`, e.g., before the
+    /// application exits.
+    pub fn save_history<S: Storage + ?Sized>(&self, storage: &mut S, name: &str) -> io::Result<()> {
+        storage.save(name, self.history.join("\n").as_bytes())
+    }
+
+    /// Replace the history with the one saved under `name` in `storage` (see `save_history`).
+    /// The history is left unchanged if nothing has been saved under that name.
+    pub fn load_history<S: Storage + ?Sized>(&mut self, storage: &S, name: &str) -> io::Result<()> {
+        if let Some(data) = storage.load(name)? {
+            self.history = String::from_utf8_lossy(&data)
+                .lines()
+                .map(str::to_owned)
+                .collect();
+            self.state = State::Editing;
+            self.update_display();
+        }
+        Ok(())
+    }
+
     /// Get the current content of the `LineEdit`
     pub fn active_line(&self) -> &str {
         self.line.get()
@@ -143,7 +175,7 @@ impl PromptLine {
             } => (pos, search_pattern),
             State::Scrollback { pos, .. } => (Some(pos), "".to_owned()),
         };
-        let pos = search_prev(pos, &self.history, &search_pattern);
+        let pos = search_prev(pos, &self.history, &search_pattern, self.search_mode);
         self.state = State::Searching {
             pos,
             search_pattern,
@@ -226,7 +258,7 @@ impl Scrollable for PromptLine {
                 search_pattern,
                 pos,
             } => {
-                let pos = search_next(pos, &self.history, &search_pattern);
+                let pos = search_next(pos, &self.history, &search_pattern, self.search_mode);
                 result = pos.map(|_| ()).ok_or(());
 
                 State::Searching {
@@ -271,7 +303,7 @@ impl Scrollable for PromptLine {
                 search_pattern,
                 pos,
             } => {
-                let pos = search_prev(pos, &self.history, &search_pattern);
+                let pos = search_prev(pos, &self.history, &search_pattern, self.search_mode);
                 result = pos.map(|_| ()).ok_or(());
 
                 State::Searching {
@@ -301,7 +333,7 @@ impl Scrollable for PromptLine {
                 }
             }
             State::Searching { search_pattern, .. } => {
-                let pos = search_next(None, &self.history, &search_pattern);
+                let pos = search_next(None, &self.history, &search_pattern, self.search_mode);
                 result = pos.map(|_| ()).ok_or(());
 
                 State::Searching {
@@ -328,7 +360,7 @@ impl Scrollable for PromptLine {
                 State::Editing
             }
             State::Searching { search_pattern, .. } => {
-                let pos = search_prev(None, &self.history, &search_pattern);
+                let pos = search_prev(None, &self.history, &search_pattern, self.search_mode);
                 result = pos.map(|_| ()).ok_or(());
 
                 State::Searching {
@@ -383,7 +415,12 @@ impl Writable for PromptLine {
                 '\n' => Err(()),
                 o => {
                     search_pattern.push(o);
-                    *pos = search_prev(pos.map(|p| p + 1), &self.history, &search_pattern);
+                    *pos = search_prev(
+                        pos.map(|p| p + 1),
+                        &self.history,
+                        &search_pattern,
+                        self.search_mode,
+                    );
                     pos.map(|_| ()).ok_or(())
                 }
             },
@@ -409,7 +446,12 @@ impl Editable for PromptLine {
                 pos,
             } => {
                 if search_pattern.pop().is_some() {
-                    *pos = search_prev(pos.map(|p| p + 1), &self.history, &search_pattern);
+                    *pos = search_prev(
+                        pos.map(|p| p + 1),
+                        &self.history,
+                        &search_pattern,
+                        self.search_mode,
+                    );
                 } else {
                     self.state = State::Editing;
                 }
@@ -441,6 +483,22 @@ impl Editable for PromptLine {
         self.update_display();
         res
     }
+    fn kill_to_end_of_line(&mut self) -> OperationResult {
+        let res = self.line.kill_to_end_of_line();
+        self.note_edit_operation(res)
+    }
+    fn kill_to_beginning_of_line(&mut self) -> OperationResult {
+        let res = self.line.kill_to_beginning_of_line();
+        self.note_edit_operation(res)
+    }
+    fn kill_word_backwards(&mut self) -> OperationResult {
+        let res = self.line.kill_word_backwards();
+        self.note_edit_operation(res)
+    }
+    fn yank(&mut self) -> OperationResult {
+        let res = self.line.yank();
+        self.note_edit_operation(res)
+    }
 }
 
 impl Deref for PromptLine {
@@ -470,14 +528,17 @@ mod test {
             "".to_string(),
             "foo".to_string(),
         ];
+        let exact = MatchMode::exact();
 
-        assert_eq!(search_prev(Some(0), &history, ""), None);
-        assert_eq!(search_prev(Some(1), &history, "a"), None);
-        assert_eq!(search_prev(Some(5), &history, "foo"), Some(4));
-        assert_eq!(search_prev(Some(4), &history, "foo"), Some(2));
-        assert_eq!(search_prev(Some(2), &history, "foo"), None);
-        assert_eq!(search_prev(Some(5), &history, "foo2"), None);
-        assert_eq!(search_prev(None, &history, ""), None);
+        assert_eq!(search_prev(Some(0), &history, "", exact), None);
+        assert_eq!(search_prev(Some(1), &history, "a", exact), None);
+        assert_eq!(search_prev(Some(5), &history, "foo", exact), Some(4));
+        assert_eq!(search_prev(Some(4), &history, "foo", exact), Some(2));
+        assert_eq!(search_prev(Some(2), &history, "foo", exact), None);
+        assert_eq!(search_prev(Some(5), &history, "foo2", exact), None);
+        let caseless = MatchMode::exact().ignore_case(true);
+        assert_eq!(search_prev(Some(5), &history, "FOO", caseless), Some(4));
+        assert_eq!(search_prev(None, &history, "", exact), None);
     }
 
     #[test]
@@ -489,13 +550,14 @@ mod test {
             "".to_string(),
             "foo".to_string(),
         ];
+        let exact = MatchMode::exact();
 
-        assert_eq!(search_next(Some(4), &history, ""), None);
-        assert_eq!(search_next(Some(0), &history, "a"), Some(1));
-        assert_eq!(search_next(Some(0), &history, "foo"), Some(2));
-        assert_eq!(search_next(Some(2), &history, "foo"), Some(4));
-        assert_eq!(search_next(Some(4), &history, "foo"), None);
-        assert_eq!(search_next(Some(0), &history, "foo2"), None);
-        assert_eq!(search_next(None, &history, ""), None);
+        assert_eq!(search_next(Some(4), &history, "", exact), None);
+        assert_eq!(search_next(Some(0), &history, "a", exact), Some(1));
+        assert_eq!(search_next(Some(0), &history, "foo", exact), Some(2));
+        assert_eq!(search_next(Some(2), &history, "foo", exact), Some(4));
+        assert_eq!(search_next(Some(4), &history, "foo", exact), None);
+        assert_eq!(search_next(Some(0), &history, "foo2", exact), None);
+        assert_eq!(search_next(None, &history, "", exact), None);
     }
 }
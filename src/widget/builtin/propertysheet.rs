@@ -0,0 +1,459 @@
+//! A widget displaying aligned `key: value` pairs, e.g., the details of an object.
+//!
+//! # Examples:
+//! ```
+//! use unsegen::input::Navigatable;
+//! use unsegen::widget::builtin::{NumberCell, PropertySheet};
+//!
+//! let mut sheet = PropertySheet::new()
+//!     .entry("name", "config.toml")
+//!     .group("Size")
+//!     .entry("bytes", NumberCell::new(1024.0))
+//!     .entry("lines", NumberCell::new(42.0));
+//!
+//! sheet.move_down().unwrap(); // The "Size" header
+//! assert_eq!(sheet.selected_group(), Some("Size"));
+//! sheet.toggle_group().unwrap();
+//! assert!(sheet.is_group_collapsed("Size"));
+//! assert!(sheet.move_down().is_err());
+//! ```
+use base::basic_types::*;
+use base::{rendering_profile, BoolModifyMode, Cursor, StyleModifier, Window};
+use input::{Navigatable, OperationResult};
+use widget::{text_width, ColDemand, Demand, Demand2D, RenderingHints, RowDemand, Widget};
+
+const GROUP_INDENT: i32 = 2;
+const KEY_SEPARATOR: &str = ": ";
+
+struct PropertyEntry<'w> {
+    key: String,
+    value: Box<dyn Widget + 'w>,
+}
+
+struct PropertyGroup<'w> {
+    title: Option<String>,
+    collapsed: bool,
+    entries: Vec<PropertyEntry<'w>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Row {
+    Header(usize),
+    Entry(usize, usize),
+}
+
+/// A list of `key: value` pairs with aligned values, optionally divided into groups with headers.
+///
+/// Values are arbitrary widgets that are drawn right of the keys, in as many rows as they need
+/// for the remaining width (see `Widget::height_for_width`), i.e., long text values are wrapped.
+/// Groups can be collapsed to hide their entries. The selection (of an entry or a group header)
+/// can be moved using the `Navigatable` implementation, where left and right collapse and expand
+/// the group of the selection.
+pub struct PropertySheet<'w> {
+    groups: Vec<PropertyGroup<'w>>,
+    selected: usize,
+}
+
+impl<'w> Default for PropertySheet<'w> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'w> PropertySheet<'w> {
+    /// Create an empty property sheet.
+    pub fn new() -> Self {
+        PropertySheet {
+            groups: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// Start a new group with a header. All following entries belong to this group.
+    pub fn group<S: Into<String>>(mut self, title: S) -> Self {
+        self.groups.push(PropertyGroup {
+            title: Some(title.into()),
+            collapsed: false,
+            entries: Vec::new(),
+        });
+        self
+    }
+
+    /// Add an entry to the current group. Entries that are added before the first call to
+    /// `group` are displayed without a header (and cannot be collapsed).
+    pub fn entry<S: Into<String>, W: Widget + 'w>(mut self, key: S, value: W) -> Self {
+        if self.groups.is_empty() {
+            self.groups.push(PropertyGroup {
+                title: None,
+                collapsed: false,
+                entries: Vec::new(),
+            });
+        }
+        self.groups.last_mut().unwrap().entries.push(PropertyEntry {
+            key: key.into(),
+            value: Box::new(value),
+        });
+        self
+    }
+
+    /// Replace the value of the (first) entry with the given key. Fails if there is no such
+    /// entry.
+    pub fn set_value<W: Widget + 'w>(&mut self, key: &str, value: W) -> OperationResult {
+        let entry = self
+            .groups
+            .iter_mut()
+            .flat_map(|g| g.entries.iter_mut())
+            .find(|e| e.key == key)
+            .ok_or(())?;
+        entry.value = Box::new(value);
+        Ok(())
+    }
+
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (g, group) in self.groups.iter().enumerate() {
+            if group.title.is_some() {
+                rows.push(Row::Header(g));
+            }
+            if !group.collapsed {
+                rows.extend((0..group.entries.len()).map(|e| Row::Entry(g, e)));
+            }
+        }
+        rows
+    }
+
+    fn selected_row(&self) -> Option<Row> {
+        let rows = self.rows();
+        rows.get(self.selected.min(rows.len().saturating_sub(1)))
+            .cloned()
+    }
+
+    fn group_index(&self, title: &str) -> Option<usize> {
+        self.groups
+            .iter()
+            .position(|g| g.title.as_ref().is_some_and(|t| t == title))
+    }
+
+    /// Get the key of the selected entry or `None` if a group header is selected.
+    pub fn selected_key(&self) -> Option<&str> {
+        match self.selected_row()? {
+            Row::Entry(g, e) => Some(&self.groups[g].entries[e].key),
+            Row::Header(_) => None,
+        }
+    }
+
+    /// Get the title of the group of the selected entry or header (`None` for entries without a
+    /// group).
+    pub fn selected_group(&self) -> Option<&str> {
+        let (Row::Header(g) | Row::Entry(g, _)) = self.selected_row()?;
+        self.groups[g].title.as_deref()
+    }
+
+    /// Check whether the group with the given title is collapsed.
+    pub fn is_group_collapsed(&self, title: &str) -> bool {
+        self.group_index(title)
+            .is_some_and(|g| self.groups[g].collapsed)
+    }
+
+    /// Collapse or expand the group with the given title. Fails if there is no such group.
+    pub fn set_group_collapsed(&mut self, title: &str, collapsed: bool) -> OperationResult {
+        let g = self.group_index(title).ok_or(())?;
+        self.set_collapsed(g, collapsed)
+    }
+
+    /// Collapse or expand the group of the selection. Fails if the selection does not belong to a
+    /// group.
+    pub fn toggle_group(&mut self) -> OperationResult {
+        let g = match self.selected_row().ok_or(())? {
+            Row::Header(g) | Row::Entry(g, _) => g,
+        };
+        let collapsed = !self.groups[g].collapsed;
+        self.set_collapsed(g, collapsed)
+    }
+
+    fn set_collapsed(&mut self, g: usize, collapsed: bool) -> OperationResult {
+        if self.groups[g].title.is_none() || self.groups[g].collapsed == collapsed {
+            return Err(());
+        }
+        let selected = self.selected_row();
+        self.groups[g].collapsed = collapsed;
+        // Keep the selection on the same row, or move it to the header if its entry was hidden.
+        let rows = self.rows();
+        let target = match selected {
+            Some(Row::Entry(sg, _)) if sg == g && collapsed => Some(Row::Header(g)),
+            other => other,
+        };
+        self.selected = target
+            .and_then(|t| rows.iter().position(|&r| r == t))
+            .unwrap_or(0);
+        Ok(())
+    }
+
+    fn move_selection_to(&mut self, index: usize) -> OperationResult {
+        let current = self.selected.min(self.rows().len().saturating_sub(1));
+        if index == current || index >= self.rows().len() {
+            return Err(());
+        }
+        self.selected = index;
+        Ok(())
+    }
+
+    /// Prepare for drawing as a `Widget`.
+    pub fn as_widget<'s>(&'s self) -> PropertySheetWidget<'s, 'w> {
+        PropertySheetWidget {
+            sheet: self,
+            key_style: StyleModifier::new(),
+            header_style: StyleModifier::new().bold(true),
+            selected_style: StyleModifier::new().invert(BoolModifyMode::Toggle),
+        }
+    }
+}
+
+impl<'w> Navigatable for PropertySheet<'w> {
+    fn move_up(&mut self) -> OperationResult {
+        let current = self.selected.min(self.rows().len().saturating_sub(1));
+        self.move_selection_to(current.checked_sub(1).ok_or(())?)
+    }
+    fn move_down(&mut self) -> OperationResult {
+        let current = self.selected.min(self.rows().len().saturating_sub(1));
+        self.move_selection_to(current + 1)
+    }
+    fn move_left(&mut self) -> OperationResult {
+        match self.selected_row().ok_or(())? {
+            Row::Header(g) | Row::Entry(g, _) => self.set_collapsed(g, true),
+        }
+    }
+    fn move_right(&mut self) -> OperationResult {
+        match self.selected_row().ok_or(())? {
+            Row::Header(g) | Row::Entry(g, _) => self.set_collapsed(g, false),
+        }
+    }
+}
+
+/// A `Widget` representing a `PropertySheet`.
+///
+/// It allows for customization of the styles of keys, group headers and the selection.
+pub struct PropertySheetWidget<'s, 'w: 's> {
+    sheet: &'s PropertySheet<'w>,
+    key_style: StyleModifier,
+    header_style: StyleModifier,
+    selected_style: StyleModifier,
+}
+
+impl<'s, 'w: 's> PropertySheetWidget<'s, 'w> {
+    /// Define the style of the keys.
+    pub fn key_style(mut self, style: StyleModifier) -> Self {
+        self.key_style = style;
+        self
+    }
+
+    /// Define the style of the group headers (default: bold).
+    pub fn header_style(mut self, style: StyleModifier) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Define the style of the key or header of the selection (default: inverted). It is only
+    /// applied if the widget is active.
+    pub fn selected_style(mut self, style: StyleModifier) -> Self {
+        self.selected_style = style;
+        self
+    }
+
+    fn key_column_width(&self, rows: &[Row]) -> Width {
+        let key_width = rows
+            .iter()
+            .filter_map(|row| match *row {
+                Row::Entry(g, e) => {
+                    let group = &self.sheet.groups[g];
+                    let indent = if group.title.is_some() {
+                        GROUP_INDENT
+                    } else {
+                        0
+                    };
+                    Some(text_width(&group.entries[e].key) + Width::new(indent).unwrap())
+                }
+                Row::Header(_) => None,
+            })
+            .max()
+            .unwrap_or_else(|| Width::new(0).unwrap());
+        key_width + text_width(KEY_SEPARATOR)
+    }
+
+    fn row_height(&self, row: Row, value_width: Width) -> Height {
+        match row {
+            Row::Header(_) => Height::new(1).unwrap(),
+            Row::Entry(g, e) => {
+                let demand = self.sheet.groups[g].entries[e]
+                    .value
+                    .height_for_width(value_width);
+                demand.min.max(Height::new(1).unwrap())
+            }
+        }
+    }
+}
+
+impl<'s, 'w: 's> Widget for PropertySheetWidget<'s, 'w> {
+    fn space_demand(&self) -> Demand2D {
+        let rows = self.sheet.rows();
+        let mut value_width = ColDemand::exact(0);
+        let mut height = RowDemand::exact(0);
+        for row in &rows {
+            match *row {
+                Row::Header(g) => {
+                    let title = self.sheet.groups[g].title.as_ref().unwrap();
+                    value_width = value_width.max(ColDemand::exact(
+                        text_width(title) + Width::new(GROUP_INDENT).unwrap(),
+                    ));
+                    height += Demand::exact(1);
+                }
+                Row::Entry(g, e) => {
+                    let demand = self.sheet.groups[g].entries[e].value.space_demand();
+                    value_width = value_width.max(demand.width);
+                    height += Demand::at_least(1).max(demand.height);
+                }
+            }
+        }
+        Demand2D {
+            width: ColDemand::exact(self.key_column_width(&rows)) + value_width,
+            height,
+        }
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        let rows = self.sheet.rows();
+        let value_width = (width - self.key_column_width(&rows)).positive_or_zero();
+        let height = rows.iter().fold(Height::new(0).unwrap(), |sum, &row| {
+            sum + self.row_height(row, value_width)
+        });
+        Demand::exact(height)
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        let rows = self.sheet.rows();
+        if rows.is_empty() {
+            return;
+        }
+        let selected = self.sheet.selected.min(rows.len() - 1);
+        let key_width = self.key_column_width(&rows);
+        let value_width = (window.get_width() - key_width).positive_or_zero();
+        let heights = rows
+            .iter()
+            .map(|&row| self.row_height(row, value_width))
+            .collect::<Vec<_>>();
+
+        // Scroll such that the selection is visible.
+        let window_height = window.get_height();
+        let mut first = 0;
+        while first < selected
+            && heights[first..=selected]
+                .iter()
+                .fold(Height::new(0).unwrap(), |sum, &h| sum + h)
+                > window_height
+        {
+            first += 1;
+        }
+
+        let (collapsed, expanded) = rendering_profile().select(("▸ ", "▾ "), ("> ", "v "));
+        let mut y = RowIndex::new(0);
+        for (i, (&row, &height)) in rows.iter().zip(heights.iter()).enumerate().skip(first) {
+            if y >= window_height.from_origin() {
+                break;
+            }
+            let mut style = match row {
+                Row::Header(_) => self.header_style,
+                Row::Entry(..) => self.key_style,
+            };
+            if i == selected && hints.active {
+                style = self.selected_style.on_top_of(style);
+            }
+            match row {
+                Row::Header(g) => {
+                    let group = &self.sheet.groups[g];
+                    let mut cursor = Cursor::new(&mut window).position(ColIndex::new(0), y);
+                    cursor.apply_style_modifier(style);
+                    cursor.write(if group.collapsed { collapsed } else { expanded });
+                    cursor.write(group.title.as_ref().unwrap());
+                }
+                Row::Entry(g, e) => {
+                    let group = &self.sheet.groups[g];
+                    let entry = &group.entries[e];
+                    let indent = if group.title.is_some() {
+                        GROUP_INDENT
+                    } else {
+                        0
+                    };
+                    {
+                        let mut cursor =
+                            Cursor::new(&mut window).position(ColIndex::new(indent), y);
+                        cursor.apply_style_modifier(style);
+                        cursor.write(&entry.key);
+                        let padding =
+                            key_width - text_width(KEY_SEPARATOR) - text_width(&entry.key) - indent;
+                        cursor.write(&" ".repeat(padding.positive_or_zero().raw_value() as usize));
+                        cursor.write(KEY_SEPARATOR);
+                    }
+                    let end = (y + height).min(window_height.from_origin());
+                    if value_width > 0 {
+                        let value_window =
+                            window.create_subwindow(key_width.from_origin().., y..end);
+                        entry.value.draw(value_window, hints);
+                    }
+                }
+            }
+            y += height;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    #[test]
+    fn test_property_sheet() {
+        let mut sheet = PropertySheet::new()
+            .entry("path", "/tmp/some/file")
+            .group("Size")
+            .entry("bytes", "1024")
+            .entry("n", "42");
+        let draw = |sheet: &PropertySheet, size, active| {
+            let mut term = FakeTerminal::with_size(size);
+            sheet
+                .as_widget()
+                .selected_style(StyleModifier::new().bold(true))
+                .header_style(StyleModifier::new())
+                .draw(
+                    term.create_root_window(),
+                    RenderingHints::new().active(active),
+                );
+            term
+        };
+
+        draw(&sheet, (16, 6), false).assert_looks_like(
+            "path   : /tmp/so|         me/file|▾ Size          |  bytes: 1024   |  n    : 42     |                ",
+        );
+
+        sheet.move_down().unwrap();
+        sheet.move_down().unwrap();
+        assert_eq!(sheet.selected_key(), Some("bytes"));
+        assert_eq!(sheet.selected_group(), Some("Size"));
+        draw(&sheet, (16, 2), true)
+            .assert_looks_like("▾ Size          |  *b**y**t**e**s**:** *1024   ");
+
+        sheet.move_left().unwrap();
+        assert_eq!(sheet.selected_key(), None);
+        assert!(sheet.is_group_collapsed("Size"));
+        assert!(sheet.move_down().is_err());
+        draw(&sheet, (16, 4), false).assert_looks_like(
+            "path: /tmp/some/|      file      |▸ Size          |                ",
+        );
+
+        sheet.move_right().unwrap();
+        sheet.set_value("n", "43").unwrap();
+        assert!(sheet.set_value("m", "0").is_err());
+        assert!(sheet.set_group_collapsed("Size", false).is_err());
+        draw(&sheet, (16, 5), false).assert_looks_like(
+            "path   : /tmp/so|         me/file|▾ Size          |  bytes: 1024   |  n    : 43     ",
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! A horizontal gauge with a handle to select a value within a range.
+use base::basic_types::*;
+use base::{BoolModifyMode, Cursor, GraphemeCluster, StyleModifier, Window};
+use input::{Behavior, Event, Input, MouseButton, MouseEvent, Navigatable, OperationResult};
+use widget::{Demand, Demand2D, RenderingHints, Widget};
+
+/// A value within a (closed) range that can be adjusted in steps (using `move_left` and
+/// `move_right` of `Navigatable`) or by dragging the handle with the mouse (see `drag_behavior`).
+///
+/// # Examples:
+/// ```
+/// use unsegen::input::Navigatable;
+/// use unsegen::widget::builtin::Slider;
+///
+/// let mut s = Slider::new(0.0, 100.0).step(10.0);
+/// s.move_right().unwrap();
+/// s.move_right().unwrap();
+/// assert_eq!(s.value(), 20.0);
+/// assert_eq!(s.fraction(), 0.2);
+///
+/// s.set_value(150.0);
+/// assert_eq!(s.value(), 100.0);
+/// assert!(s.move_right().is_err());
+/// ```
+pub struct Slider {
+    value: f64,
+    min: f64,
+    max: f64,
+    step: f64,
+    dragging: bool,
+}
+
+impl Slider {
+    /// Create a slider for values in `[min, max]`, initially set to `min`. The value is adjusted
+    /// in steps of 1 by default.
+    ///
+    /// Panics if `min` is not smaller than `max`.
+    pub fn new(min: f64, max: f64) -> Self {
+        assert!(min < max, "Slider requires min < max");
+        Slider {
+            value: min,
+            min,
+            max,
+            step: 1.0,
+            dragging: false,
+        }
+    }
+
+    /// Set the amount the value changes when moving left or right.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Get the current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Set the value, clamped to the range of the slider.
+    pub fn set_value(&mut self, value: f64) {
+        self.value = value.max(self.min).min(self.max);
+    }
+
+    /// Get the relative position of the value within the range, i.e., a number in `[0, 1]`.
+    pub fn fraction(&self) -> f64 {
+        (self.value - self.min) / (self.max - self.min)
+    }
+
+    /// Set the value by its relative position within the range (see `fraction`).
+    pub fn set_fraction(&mut self, fraction: f64) {
+        let value = self.min + fraction * (self.max - self.min);
+        self.set_value(value);
+    }
+
+    /// Set the value corresponding to the handle being drawn at column `col` of a slider that is
+    /// `width` cells wide.
+    pub fn set_from_column(&mut self, col: ColIndex, width: Width) {
+        let last_col = width.raw_value().max(1) - 1;
+        if last_col == 0 {
+            self.set_fraction(0.0);
+        } else {
+            self.set_fraction(f64::from(col.raw_value()) / f64::from(last_col));
+        }
+    }
+
+    fn change_by(&mut self, diff: f64) -> OperationResult {
+        let old = self.value;
+        self.set_value(old + diff);
+        if self.value != old {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    /// Create a `Behavior` that adjusts the value when the slider is clicked or its handle is
+    /// dragged with the left mouse button.
+    ///
+    /// `origin` is the (terminal) position of the leftmost cell of the slider and `width` the
+    /// width of the window it is drawn to.
+    pub fn drag_behavior(
+        &mut self,
+        origin: (ColIndex, RowIndex),
+        width: Width,
+    ) -> SliderDragBehavior<'_> {
+        SliderDragBehavior {
+            slider: self,
+            origin,
+            width,
+        }
+    }
+
+    /// Prepare for drawing as a `Widget`.
+    pub fn as_widget<'a>(&'a self) -> SliderWidget<'a> {
+        SliderWidget {
+            slider: self,
+            filled_symbol: GraphemeCluster::try_from('=').unwrap(),
+            empty_symbol: GraphemeCluster::try_from('-').unwrap(),
+            handle_symbol: GraphemeCluster::try_from('O').unwrap(),
+            handle_style_active: StyleModifier::new().invert(BoolModifyMode::Toggle),
+            handle_style_inactive: StyleModifier::new(),
+        }
+    }
+}
+
+/// Note that there is no concept of moving up or down for a `Slider`.
+impl Navigatable for Slider {
+    fn move_up(&mut self) -> OperationResult {
+        Err(())
+    }
+    fn move_down(&mut self) -> OperationResult {
+        Err(())
+    }
+    fn move_left(&mut self) -> OperationResult {
+        let step = self.step;
+        self.change_by(-step)
+    }
+    fn move_right(&mut self) -> OperationResult {
+        let step = self.step;
+        self.change_by(step)
+    }
+}
+
+/// Adjusts a `Slider` using the mouse. See `Slider::drag_behavior`.
+pub struct SliderDragBehavior<'a> {
+    slider: &'a mut Slider,
+    origin: (ColIndex, RowIndex),
+    width: Width,
+}
+
+impl<'a> SliderDragBehavior<'a> {
+    fn column(&self, x: u16) -> ColIndex {
+        // Terminal mouse coordinates are 1-based.
+        (ColIndex::new(i32::from(x) - 1) - self.origin.0).from_origin()
+    }
+}
+
+impl<'a> Behavior for SliderDragBehavior<'a> {
+    fn input(self, input: Input) -> Option<Input> {
+        match input.event {
+            Event::Mouse(MouseEvent::Press(MouseButton::Left, x, y)) => {
+                let col = self.column(x);
+                let row = RowIndex::new(i32::from(y) - 1);
+                if row == self.origin.1 && self.width.origin_range_contains(col) {
+                    self.slider.dragging = true;
+                    self.slider.set_from_column(col, self.width);
+                    None
+                } else {
+                    Some(input)
+                }
+            }
+            Event::Mouse(MouseEvent::Hold(x, _)) if self.slider.dragging => {
+                let col = self.column(x);
+                self.slider.set_from_column(col, self.width);
+                None
+            }
+            Event::Mouse(MouseEvent::Release(_, _)) if self.slider.dragging => {
+                self.slider.dragging = false;
+                None
+            }
+            _ => Some(input),
+        }
+    }
+}
+
+/// A `Widget` representing a `Slider` as a horizontal gauge.
+///
+/// It allows for customization of the symbols and the style of the handle.
+pub struct SliderWidget<'a> {
+    slider: &'a Slider,
+    filled_symbol: GraphemeCluster,
+    empty_symbol: GraphemeCluster,
+    handle_symbol: GraphemeCluster,
+    handle_style_active: StyleModifier,
+    handle_style_inactive: StyleModifier,
+}
+
+impl<'a> SliderWidget<'a> {
+    /// Define the symbol that fills the gauge left of the handle.
+    pub fn filled_symbol(mut self, symbol: GraphemeCluster) -> Self {
+        self.filled_symbol = symbol;
+        self
+    }
+
+    /// Define the symbol that fills the gauge right of the handle.
+    pub fn empty_symbol(mut self, symbol: GraphemeCluster) -> Self {
+        self.empty_symbol = symbol;
+        self
+    }
+
+    /// Define the symbol of the handle.
+    pub fn handle_symbol(mut self, symbol: GraphemeCluster) -> Self {
+        self.handle_symbol = symbol;
+        self
+    }
+
+    /// Define the style that the handle will be drawn with when the widget is active.
+    pub fn handle_active(mut self, style: StyleModifier) -> Self {
+        self.handle_style_active = style;
+        self
+    }
+
+    /// Define the style that the handle will be drawn with when the widget is inactive.
+    pub fn handle_inactive(mut self, style: StyleModifier) -> Self {
+        self.handle_style_inactive = style;
+        self
+    }
+}
+
+impl<'a> Widget for SliderWidget<'a> {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::at_least(self.handle_symbol.width() + 1),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        let width = window.get_width().raw_value();
+        let handle_width = self.handle_symbol.width() as i32;
+        let track_width = (width - handle_width).max(0);
+        let handle_pos = (self.slider.fraction() * f64::from(track_width)).round() as i32;
+
+        let handle_style = if hints.active {
+            self.handle_style_active
+        } else {
+            self.handle_style_inactive
+        };
+
+        let mut cursor = Cursor::new(&mut window);
+        for _ in 0..handle_pos {
+            cursor.write(self.filled_symbol.as_str());
+        }
+        {
+            let mut cursor = cursor.save().style_modifier();
+            cursor.apply_style_modifier(handle_style);
+            cursor.write(self.handle_symbol.as_str());
+        }
+        for _ in handle_pos..track_width {
+            cursor.write(self.empty_symbol.as_str());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    fn test_slider(width: u32, slider: &Slider, after: &str) {
+        let mut term = FakeTerminal::with_size((width, 1));
+        slider
+            .as_widget()
+            .handle_active(StyleModifier::new().bold(true))
+            .draw(term.create_root_window(), RenderingHints::default());
+        term.assert_looks_like(after);
+    }
+
+    fn mouse(event: MouseEvent) -> Input {
+        Input::new(event)
+    }
+
+    #[test]
+    fn test_slider_draw() {
+        let mut s = Slider::new(-1.0, 1.0);
+        test_slider(5, &s, "*O*----");
+        s.set_value(0.0);
+        test_slider(5, &s, "==*O*--");
+        s.set_value(1.0);
+        test_slider(5, &s, "====*O*");
+    }
+
+    #[test]
+    fn test_slider_drag() {
+        let mut s = Slider::new(0.0, 8.0);
+        let origin = (ColIndex::new(2), RowIndex::new(1));
+        let width = Width::new(5).unwrap();
+
+        let outside = mouse(MouseEvent::Press(MouseButton::Left, 1, 2));
+        assert!(s.drag_behavior(origin, width).input(outside).is_some());
+        let press = mouse(MouseEvent::Press(MouseButton::Left, 4, 2));
+        assert!(s.drag_behavior(origin, width).input(press).is_none());
+        assert_eq!(s.value(), 2.0);
+        let hold = mouse(MouseEvent::Hold(20, 5));
+        assert!(s.drag_behavior(origin, width).input(hold.clone()).is_none());
+        assert_eq!(s.value(), 8.0);
+        let release = mouse(MouseEvent::Release(20, 5));
+        assert!(s.drag_behavior(origin, width).input(release).is_none());
+        assert!(s.drag_behavior(origin, width).input(hold).is_some());
+    }
+}
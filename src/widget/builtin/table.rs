@@ -5,10 +5,11 @@ use base::basic_types::*;
 use base::{StyleModifier, Window};
 use input::Scrollable;
 use input::{Behavior, Input, Navigatable, OperationResult};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use util::{Command, UndoHistory};
 use widget::{
-    layout_linearly, ColDemand, Demand, Demand2D, RenderingHints, RowDemand, SeparatingStyle,
-    Widget,
+    layout_linearly, ColDemand, DataProvider, Demand, Demand2D, RenderingHints, RowDemand,
+    SeparatingStyle, Widget,
 };
 
 /// A single column in a `Table`.
@@ -50,14 +51,90 @@ pub trait TableRow: 'static {
     }
 }
 
+/// Determines which rows are considered when computing the widths of the columns of a `Table`.
+///
+/// The resulting column widths are cached in the `Table`. The cache is invalidated automatically
+/// when the table is modified via `rows_mut`, `current_row_mut`, `update` or
+/// `current_cell_behavior`. If the content of cells changes in any other way (e.g., via interior
+/// mutability), call `Table::invalidate_column_widths`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColumnSampling {
+    /// Consider all rows (default). The widths are only recomputed after invalidation.
+    AllRows,
+    /// Only consider the rows that may be visible when drawing. Widths of all rows that have been
+    /// visible since the last invalidation are remembered, so columns do not shrink while
+    /// scrolling.
+    VisibleRows,
+    /// Like `VisibleRows`, but additionally consider the first `n` rows of the table.
+    FirstAndVisible(usize),
+}
+
+struct ColumnWidthCache {
+    sampling: ColumnSampling,
+    demands: Vec<ColDemand>,
+}
+
 /// Mutable row access mapper to enforce invariants after mutation.
+///
+/// Modifications using `insert_row`, `push_row`, `remove_row` and `replace_row` can be undone
+/// using `Table::undo`. Any other modification (via the methods of `Vec`) clears the undo history
+/// of the table.
 pub struct RowsMut<'a, R: 'static + TableRow> {
     table: &'a mut Table<R>,
+    untracked: bool,
+}
+
+impl<'a, R: 'static + TableRow> RowsMut<'a, R> {
+    /// Insert `row` at position `index`. Fails if `index` is larger than the number of rows.
+    pub fn insert_row(&mut self, index: usize, row: R) -> OperationResult {
+        if index > self.table.rows.len() {
+            return Err(());
+        }
+        let table = &mut *self.table;
+        table
+            .history
+            .execute(RowEdit::Insert(index, Some(row)), &mut table.rows);
+        Ok(())
+    }
+
+    /// Append `row` to the table.
+    pub fn push_row(&mut self, row: R) {
+        let index = self.table.rows.len();
+        let _ = self.insert_row(index, row);
+    }
+
+    /// Remove the row at position `index`. Fails if there is no such row.
+    pub fn remove_row(&mut self, index: usize) -> OperationResult {
+        if index >= self.table.rows.len() {
+            return Err(());
+        }
+        let table = &mut *self.table;
+        table
+            .history
+            .execute(RowEdit::Remove(index, None), &mut table.rows);
+        Ok(())
+    }
+
+    /// Replace the row at position `index` with `row`. Fails if there is no such row.
+    pub fn replace_row(&mut self, index: usize, row: R) -> OperationResult {
+        if index >= self.table.rows.len() {
+            return Err(());
+        }
+        let table = &mut *self.table;
+        table
+            .history
+            .execute(RowEdit::Replace(index, row), &mut table.rows);
+        Ok(())
+    }
 }
 
 impl<'a, R: 'static + TableRow> ::std::ops::Drop for RowsMut<'a, R> {
     fn drop(&mut self) {
+        if self.untracked {
+            self.table.history.clear();
+        }
         let _ = self.table.validate_row_pos();
+        self.table.invalidate_column_widths();
     }
 }
 
@@ -70,10 +147,51 @@ impl<'a, R: 'static + TableRow> ::std::ops::Deref for RowsMut<'a, R> {
 
 impl<'a, R: 'static + TableRow> ::std::ops::DerefMut for RowsMut<'a, R> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        self.untracked = true;
         &mut self.table.rows
     }
 }
 
+/// The default number of row modifications that can be undone (see `Table::set_undo_capacity`).
+const DEFAULT_UNDO_CAPACITY: usize = 100;
+
+/// A modification of the rows of a table that can be undone. The rows that are not currently
+/// part of the table are stored in the modification.
+enum RowEdit<R> {
+    Insert(usize, Option<R>),
+    Remove(usize, Option<R>),
+    Replace(usize, R),
+}
+
+impl<R> RowEdit<R> {
+    fn insert(rows: &mut Vec<R>, index: usize, row: &mut Option<R>) {
+        rows.insert(
+            index,
+            row.take().expect("row is stored while not in the table"),
+        );
+    }
+    fn remove(rows: &mut Vec<R>, index: usize, row: &mut Option<R>) {
+        *row = Some(rows.remove(index));
+    }
+}
+
+impl<R> Command<Vec<R>> for RowEdit<R> {
+    fn apply(&mut self, rows: &mut Vec<R>) {
+        match *self {
+            RowEdit::Insert(index, ref mut row) => Self::insert(rows, index, row),
+            RowEdit::Remove(index, ref mut row) => Self::remove(rows, index, row),
+            RowEdit::Replace(index, ref mut row) => ::std::mem::swap(&mut rows[index], row),
+        }
+    }
+    fn revert(&mut self, rows: &mut Vec<R>) {
+        match *self {
+            RowEdit::Insert(index, ref mut row) => Self::remove(rows, index, row),
+            RowEdit::Remove(index, ref mut row) => Self::insert(rows, index, row),
+            RowEdit::Replace(index, ref mut row) => ::std::mem::swap(&mut rows[index], row),
+        }
+    }
+}
+
 /// A table of widgets with static number of `Columns`.
 ///
 /// In order to create a table, you have to define a type for a row in the table and implement
@@ -88,6 +206,9 @@ pub struct Table<R: TableRow> {
     row_pos: u32,
     col_pos: u32,
     last_draw_pos: Cell<(u32, RowIndex)>,
+    column_widths: RefCell<Option<ColumnWidthCache>>,
+    provider: Option<DataProvider<Vec<R>>>,
+    history: UndoHistory<Vec<R>>,
 }
 
 impl<R: TableRow + 'static> Table<R> {
@@ -99,12 +220,109 @@ impl<R: TableRow + 'static> Table<R> {
             row_pos: 0,
             col_pos: 0,
             last_draw_pos: Cell::new((0, RowIndex::new(0))),
+            column_widths: RefCell::new(None),
+            provider: None,
+            history: UndoHistory::new(DEFAULT_UNDO_CAPACITY),
+        }
+    }
+
+    /// Create an empty table whose rows are replaced by the values published to the given provider
+    /// (e.g., from a background thread) whenever `update` is called.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::*;
+    /// use unsegen::widget::*;
+    ///
+    /// struct Row(String);
+    /// impl TableRow for Row {
+    ///     type BehaviorContext = ();
+    ///     const COLUMNS: &'static [Column<Self>] = &[Column {
+    ///         access: |r| Box::new(r.0.as_str()),
+    ///         behavior: |_, _, _| None,
+    ///     }];
+    /// }
+    ///
+    /// let (publisher, provider) = data_provider();
+    /// let mut table = Table::with_provider(provider);
+    ///
+    /// std::thread::spawn(move || publisher.publish(vec![Row("a".to_owned()), Row("b".to_owned())]))
+    ///     .join()
+    ///     .unwrap();
+    ///
+    /// assert!(table.update());
+    /// assert_eq!(table.rows().len(), 2);
+    /// assert!(!table.update());
+    /// ```
+    pub fn with_provider(provider: DataProvider<Vec<R>>) -> Self {
+        let mut table = Self::new();
+        table.provider = Some(provider);
+        table
+    }
+
+    /// Replace the rows of the table with the most recent value of the provider (see
+    /// `with_provider`), if it has changed. Returns whether the table was modified.
+    ///
+    /// The active cell is kept at the same position (if possible).
+    pub fn update(&mut self) -> bool {
+        if let Some(rows) = self.provider.as_mut().and_then(|p| p.take()) {
+            *self.rows_mut() = rows;
+            true
+        } else {
+            false
         }
     }
 
     /// Access the content of the table mutably.
     pub fn rows_mut<'a>(&'a mut self) -> RowsMut<'a, R> {
-        RowsMut { table: self }
+        RowsMut {
+            table: self,
+            untracked: false,
+        }
+    }
+
+    /// Revert the most recent row modification (see `RowsMut`). Fails if there is nothing to
+    /// undo.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::*;
+    ///
+    /// struct Row(u32);
+    /// impl TableRow for Row {
+    ///     type BehaviorContext = ();
+    ///     const COLUMNS: &'static [Column<Self>] = &[];
+    /// }
+    ///
+    /// let mut table = Table::new();
+    /// table.rows_mut().push_row(Row(1));
+    /// table.rows_mut().push_row(Row(2));
+    /// table.rows_mut().remove_row(0).unwrap();
+    /// assert_eq!(table.rows().len(), 1);
+    /// table.undo().unwrap();
+    /// assert_eq!(table.rows()[0].0, 1);
+    /// table.redo().unwrap();
+    /// assert_eq!(table.rows()[0].0, 2);
+    /// ```
+    pub fn undo(&mut self) -> OperationResult {
+        let res = self.history.undo(&mut self.rows);
+        let _ = self.validate_row_pos();
+        self.invalidate_column_widths();
+        res
+    }
+
+    /// Apply the most recently undone row modification again. Fails if there is nothing to redo.
+    pub fn redo(&mut self) -> OperationResult {
+        let res = self.history.redo(&mut self.rows);
+        let _ = self.validate_row_pos();
+        self.invalidate_column_widths();
+        res
+    }
+
+    /// Set the maximum number of row modifications that can be undone (default: 100). Use 0 to
+    /// disable the undo history.
+    pub fn set_undo_capacity(&mut self, capacity: usize) {
+        self.history.set_capacity(capacity);
     }
 
     /// Access the content of the table immutably.
@@ -112,6 +330,12 @@ impl<R: TableRow + 'static> Table<R> {
         &self.rows
     }
 
+    /// Discard the cached column widths so that they are recomputed in the next draw (see
+    /// `ColumnSampling`).
+    pub fn invalidate_column_widths(&mut self) {
+        *self.column_widths.get_mut() = None;
+    }
+
     fn validate_row_pos(&mut self) -> Result<(), ()> {
         let max_pos = (self.rows.len() as u32).checked_sub(1).unwrap_or(0);
         if self.row_pos > max_pos {
@@ -139,6 +363,7 @@ impl<R: TableRow + 'static> Table<R> {
 
     /// Get mutable access to the currently active row.
     pub fn current_row_mut(&mut self) -> Option<&mut R> {
+        self.invalidate_column_widths();
         self.rows.get_mut(self.row_pos as usize)
     }
 
@@ -153,6 +378,7 @@ impl<R: TableRow + 'static> Table<R> {
         p: &mut R::BehaviorContext,
     ) -> Option<Input> {
         let col_behavior = self.current_col().behavior;
+        self.invalidate_column_widths();
         if let Some(row) = self.current_row_mut() {
             col_behavior(row, i, p)
         } else {
@@ -177,6 +403,7 @@ impl<R: TableRow + 'static> Table<R> {
             col_sep_style: SeparatingStyle::None,
             focused_style: StyleModifier::new(),
             min_context: 1,
+            column_sampling: ColumnSampling::AllRows,
         }
     }
 }
@@ -203,6 +430,7 @@ pub struct TableWidget<'a, R: TableRow + 'static> {
     col_sep_style: SeparatingStyle,
     focused_style: StyleModifier,
     min_context: u32,
+    column_sampling: ColumnSampling,
 }
 
 impl<'a, R: TableRow + 'static> TableWidget<'a, R> {
@@ -231,14 +459,52 @@ impl<'a, R: TableRow + 'static> TableWidget<'a, R> {
         self
     }
 
-    fn layout_columns(&self, window: &Window) -> Box<[Width]> {
-        let mut x_demands = vec![Demand::zero(); R::num_columns()];
-        for row in self.table.rows.iter() {
+    /// Specify which rows are considered when computing the column widths (default:
+    /// `ColumnSampling::AllRows`).
+    pub fn column_sampling(mut self, sampling: ColumnSampling) -> Self {
+        self.column_sampling = sampling;
+        self
+    }
+
+    fn column_demands(&self, window: &Window) -> Vec<ColDemand> {
+        let rows = &self.table.rows;
+        let mut cache = self.table.column_widths.borrow_mut();
+        let cached = matches!(*cache, Some(ref c) if c.sampling == self.column_sampling);
+        if cached && self.column_sampling == ColumnSampling::AllRows {
+            return cache.as_ref().expect("checked above").demands.clone();
+        }
+        let cache = cache.get_or_insert_with(|| ColumnWidthCache {
+            sampling: self.column_sampling,
+            demands: vec![Demand::zero(); R::num_columns()],
+        });
+        if !cached {
+            cache.sampling = self.column_sampling;
+            cache.demands = vec![Demand::zero(); R::num_columns()];
+        }
+
+        // Every row is at least one line high, so no more rows than the height of the window
+        // above and below the active row can be visible.
+        let height = window.get_height().raw_value().max(0) as usize;
+        let current = self.table.row_pos as usize;
+        let visible = current.saturating_sub(height)..(current + height + 1).min(rows.len());
+        let (first, visible) = match self.column_sampling {
+            ColumnSampling::AllRows => (rows.len(), 0..0),
+            ColumnSampling::VisibleRows => (0, visible),
+            // The first rows only need to be considered once after invalidation.
+            ColumnSampling::FirstAndVisible(_) if cached => (0, visible),
+            ColumnSampling::FirstAndVisible(n) => (n.min(rows.len()), visible),
+        };
+        for row in rows[..first].iter().chain(&rows[visible]) {
             for (col_num, col) in R::COLUMNS.iter().enumerate() {
                 let demand2d = (col.access)(row).space_demand();
-                x_demands[col_num].max_assign(demand2d.width);
+                cache.demands[col_num].max_assign(demand2d.width);
             }
         }
+        cache.demands.clone()
+    }
+
+    fn layout_columns(&self, window: &Window) -> Box<[Width]> {
+        let x_demands = self.column_demands(window);
         let separator_width = self.col_sep_style.width();
         let weights = std::iter::repeat(1.0)
             .take(x_demands.len())
@@ -527,6 +793,18 @@ impl<R: TableRow + 'static> Scrollable for Table<R> {
             Err(())
         }
     }
+    fn scroll_position(&self) -> Option<usize> {
+        Some(self.row_pos as usize)
+    }
+    fn scroll_to(&mut self, position: usize) -> OperationResult {
+        let end = self.rows.len().saturating_sub(1);
+        self.row_pos = position.min(end) as u32;
+        if position <= end {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -574,10 +852,9 @@ mod test {
     ) {
         let mut term = FakeTerminal::with_size(terminal_size);
         f(table.as_widget()).draw(term.create_root_window(), RenderingHints::default());
-        assert_eq!(
+        ::assert_term_eq!(
             term,
-            FakeTerminal::from_str(terminal_size, solution).expect("term from str"),
-            "got <-> expected"
+            FakeTerminal::from_str(terminal_size, solution).expect("term from str")
         );
     }
     fn aeq_table_draw_focused_bold(
@@ -603,6 +880,78 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_table_update_from_provider() {
+        let (publisher, provider) = ::widget::data_provider();
+        let mut table = Table::with_provider(provider);
+        assert!(!table.update());
+
+        publisher.publish((0..5).map(|i| TestRow(i.to_string())).collect());
+        assert!(table.update());
+        table.move_down().unwrap();
+        table.move_down().unwrap();
+        aeq_table_draw_focused_bold((1, 5), "0 1 *2* 3 4", &table);
+
+        publisher.publish(vec![TestRow("a".to_owned()), TestRow("b".to_owned())]);
+        assert!(table.update());
+        aeq_table_draw_focused_bold((1, 2), "a *b*", &table);
+    }
+
+    #[test]
+    fn test_table_undo_row_edits() {
+        let mut table = test_table(3);
+        {
+            let mut rows = table.rows_mut();
+            rows.replace_row(1, TestRow("x".to_owned())).unwrap();
+            rows.insert_row(0, TestRow("y".to_owned())).unwrap();
+            rows.remove_row(3).unwrap();
+            assert!(rows.remove_row(3).is_err());
+        }
+        aeq_table_draw((1, 3), "y 0 x", &table, |w| w);
+        table.undo().unwrap();
+        table.undo().unwrap();
+        aeq_table_draw((1, 3), "0 x 2", &table, |w| w);
+        table.redo().unwrap();
+        aeq_table_draw((1, 4), "y 0 x 2", &table, |w| w);
+
+        table.rows_mut().clear();
+        assert!(table.undo().is_err());
+        assert!(table.redo().is_err());
+    }
+
+    #[test]
+    fn test_column_sampling() {
+        let mut table = test_table_str(&["a", "bbbb", "cc", "d", "eee", "f"]);
+        let mut term = FakeTerminal::with_size((5, 1));
+        let mut width = |table: &Table<TestRow>, sampling| {
+            let window = term.create_root_window();
+            let demands = table
+                .as_widget()
+                .column_sampling(sampling)
+                .column_demands(&window);
+            demands[0].min
+        };
+
+        assert_eq!(width(&table, ColumnSampling::AllRows), 4);
+        table.current_row_mut().unwrap().0 = "aaaaa".to_owned();
+        assert_eq!(width(&table, ColumnSampling::AllRows), 5);
+
+        table.scroll_to_end().unwrap();
+        table.invalidate_column_widths();
+        assert_eq!(width(&table, ColumnSampling::VisibleRows), 3);
+        table.scroll_to_beginning().unwrap();
+        assert_eq!(width(&table, ColumnSampling::VisibleRows), 5);
+        table.scroll_to_end().unwrap();
+        assert_eq!(width(&table, ColumnSampling::VisibleRows), 5);
+
+        table.invalidate_column_widths();
+        assert_eq!(width(&table, ColumnSampling::FirstAndVisible(1)), 5);
+        table.rows_mut().truncate(4);
+        assert_eq!(width(&table, ColumnSampling::FirstAndVisible(1)), 5);
+        table.rows_mut()[0].0 = "a".to_owned();
+        assert_eq!(width(&table, ColumnSampling::FirstAndVisible(1)), 2);
+    }
+
     #[test]
     fn smaller_than_terminal() {
         aeq_table_draw((1, 3), "0 1 2", &test_table(10), |t| t);
@@ -1,9 +1,16 @@
 //! A user-editable region of text.
-use base::{BoolModifyMode, ColIndex, Cursor, LineIndex, StyleModifier, Width, Window};
-use input::{Editable, Navigatable, OperationResult, Writable};
+use base::{
+    is_reordered, visual_neighbor, BidiMode, BoolModifyMode, ColIndex, Color, Cursor,
+    CursorMovement, CursorTarget, Height, LineIndex, RowIndex, StyleModifier, Width, Window,
+};
+use input::{Editable, Navigatable, OperationResult, Searchable, Writable};
 use ropey::{Rope, RopeSlice};
-use std::ops::{Bound, RangeBounds};
-use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
+use std::borrow::Cow;
+use std::ops::{Bound, Range, RangeBounds};
+use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete, UnicodeSegmentation};
+use util::{Command, KillRing, MatchMode, UndoHistory};
+use widget::builtin::lineedit::word_begin_before;
+use widget::builtin::VisibleLines;
 use widget::{text_width, Blink, Demand, Demand2D, RenderingHints, Widget};
 
 /// A part of a text that can be moved to in a `TextEdit`
@@ -27,6 +34,11 @@ pub enum TextElement {
     DocumentBoundary,
     /// First character of the a sentence
     Sentence,
+    /// First character of a paragraph, i.e., of a non-blank line following a blank line
+    Paragraph,
+    /// The bracket (`()`, `[]` or `{}`) matching the one at the cursor or, if the cursor is not on
+    /// an opening (forward) or closing (backward) bracket, the bracket enclosing the cursor
+    MatchingBracket,
 }
 
 #[derive(Copy, Clone)]
@@ -99,12 +111,20 @@ struct ClusterPair {
     right: String,
 }
 
-fn find_word_begin(mut it: impl Iterator<Item = ClusterPair>) -> Option<TextPosition> {
+fn find_word_begin(
+    mut it: impl Iterator<Item = ClusterPair>,
+    words: &WordClassifier,
+) -> Option<TextPosition> {
     it.find_map(
-        |p| match (classify_cluster(&p.left), classify_cluster(&p.right)) {
+        |p| match (words.classify(&p.left), words.classify(&p.right)) {
             (ClusterType::Whitespace, ClusterType::Other | ClusterType::Keyword)
             | (ClusterType::Keyword, ClusterType::Other)
             | (ClusterType::Other, ClusterType::Keyword) => Some(p.p_middle),
+            (ClusterType::Keyword, ClusterType::Keyword)
+                if words.keywords_separated(&p.left, &p.right) =>
+            {
+                Some(p.p_middle)
+            }
             (ClusterType::Other | ClusterType::Keyword, ClusterType::Whitespace)
             | (ClusterType::Keyword, ClusterType::Keyword)
             | (ClusterType::Whitespace, ClusterType::Whitespace)
@@ -125,12 +145,20 @@ fn find_big_word_begin(mut it: impl Iterator<Item = ClusterPair>) -> Option<Text
     })
 }
 
-fn find_word_end(mut it: impl Iterator<Item = ClusterPair>) -> Option<TextPosition> {
+fn find_word_end(
+    mut it: impl Iterator<Item = ClusterPair>,
+    words: &WordClassifier,
+) -> Option<TextPosition> {
     it.find_map(
-        |p| match (classify_cluster(&p.left), classify_cluster(&p.right)) {
+        |p| match (words.classify(&p.left), words.classify(&p.right)) {
             (ClusterType::Other | ClusterType::Keyword, ClusterType::Whitespace)
             | (ClusterType::Keyword, ClusterType::Other)
             | (ClusterType::Other, ClusterType::Keyword) => Some(p.p_left),
+            (ClusterType::Keyword, ClusterType::Keyword)
+                if words.keywords_separated(&p.left, &p.right) =>
+            {
+                Some(p.p_left)
+            }
             (ClusterType::Whitespace, ClusterType::Other | ClusterType::Keyword)
             | (ClusterType::Keyword, ClusterType::Keyword)
             | (ClusterType::Whitespace, ClusterType::Whitespace)
@@ -151,9 +179,32 @@ fn find_big_word_end(mut it: impl Iterator<Item = ClusterPair>) -> Option<TextPo
     })
 }
 
-fn find_sentence_boundary(mut it: impl Iterator<Item = ClusterPair>) -> Option<TextPosition> {
+fn is_sentence_terminator(s: &str) -> bool {
+    matches!(s, "." | "!" | "?")
+}
+
+fn is_sentence_closer(s: &str) -> bool {
+    matches!(
+        s,
+        "\"" | "'" | ")" | "]" | "\u{201D}" | "\u{2019}" | "\u{BB}"
+    )
+}
+
+fn find_sentence_boundary(
+    text: &Text,
+    mut it: impl Iterator<Item = ClusterPair>,
+) -> Option<TextPosition> {
     it.find_map(|p| {
-        if p.left == "." && classify_cluster(&p.right) == ClusterType::Whitespace {
+        if classify_cluster(&p.right) != ClusterType::Whitespace {
+            return None;
+        }
+        // Terminating punctuation may be followed by closing quotes or brackets.
+        let terminated = std::iter::once(p.left.clone())
+            .chain(text.grapheme_clusters_backwards(p.p_left).map(|p| p.left))
+            .find(|c| !is_sentence_closer(c))
+            .map(|c| is_sentence_terminator(&c))
+            .unwrap_or(false);
+        if terminated {
             Some(p.p_left)
         } else {
             None
@@ -161,10 +212,62 @@ fn find_sentence_boundary(mut it: impl Iterator<Item = ClusterPair>) -> Option<T
     })
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-enum ClusterType {
+fn bracket(s: &str) -> Option<(usize, bool)> {
+    match s {
+        "(" => Some((0, true)),
+        ")" => Some((0, false)),
+        "[" => Some((1, true)),
+        "]" => Some((1, false)),
+        "{" => Some((2, true)),
+        "}" => Some((2, false)),
+        _ => None,
+    }
+}
+
+/// Find the bracket matching `at_cursor` (if it opens a pair in search direction) or the first
+/// unmatched bracket closing a pair in search direction.
+fn find_matching_bracket(
+    at_cursor: &str,
+    it: impl Iterator<Item = (TextPosition, String)>,
+    forward: bool,
+) -> Option<TextPosition> {
+    let kind = |s: &str, opening: bool| {
+        bracket(s)
+            .filter(|&(_, o)| o == (opening == forward))
+            .map(|(k, _)| k)
+    };
+    let target_kind = kind(at_cursor, true);
+    let relevant = |k: usize| target_kind.map(|t| t == k).unwrap_or(true);
+    let mut depth = 0usize;
+    for (pos, c) in it {
+        if let Some(k) = kind(&c, true) {
+            if relevant(k) {
+                depth += 1;
+            }
+        } else if let Some(k) = kind(&c, false) {
+            if relevant(k) {
+                if depth == 0 {
+                    return Some(pos);
+                }
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
+
+/// The class of a grapheme cluster that determines how words (see `TextElement::WordBegin` and
+/// `TextElement::WordEnd`) are formed.
+///
+/// A word is a sequence of `Keyword` clusters or a sequence of `Other` clusters. Words are
+/// separated by `Whitespace`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ClusterType {
+    /// Letters, digits and the like.
     Keyword,
+    /// Blank characters.
     Whitespace,
+    /// Everything else, e.g., punctuation.
     Other,
 }
 
@@ -172,24 +275,44 @@ fn classify_cluster(s: &str) -> ClusterType {
     match s {
         " " | "\n" | "\t" => ClusterType::Whitespace,
         "_" => ClusterType::Keyword,
-        s if s.chars().all(char::is_alphanumeric) => ClusterType::Keyword,
+        s if s.chars().next().map(char::is_alphanumeric).unwrap_or(false) => ClusterType::Keyword,
+        s if s.chars().all(char::is_whitespace) => ClusterType::Whitespace,
         _ => ClusterType::Other,
     }
 }
 
+/// Determines the class of grapheme clusters for word motions.
+enum WordClassifier {
+    /// Classify using `classify_cluster` and additionally separate keywords at unicode word
+    /// boundaries (e.g., between CJK ideographs).
+    Unicode,
+    Custom(Box<dyn Fn(&str) -> ClusterType>),
+}
+
+impl WordClassifier {
+    fn classify(&self, s: &str) -> ClusterType {
+        match self {
+            WordClassifier::Unicode => classify_cluster(s),
+            WordClassifier::Custom(f) => f(s),
+        }
+    }
+
+    /// Check whether there is a word boundary between two adjacent keyword clusters.
+    fn keywords_separated(&self, left: &str, right: &str) -> bool {
+        match self {
+            WordClassifier::Unicode => {
+                let pair = format!("{}{}", left, right);
+                pair.split_word_bounds().nth(1).is_some()
+            }
+            WordClassifier::Custom(_) => false,
+        }
+    }
+}
+
 impl Text {
     fn empty() -> Self {
         Text(Rope::new())
     }
-    fn with_content(s: &str) -> Self {
-        Text(Rope::from_str(s))
-    }
-    fn clusters_forward(&self, mut pos: TextPosition, n: usize) -> Result<TextPosition, ()> {
-        for _ in 0..n {
-            pos = self.next_grapheme_cluster(pos)?;
-        }
-        Ok(pos)
-    }
 
     fn grapheme_clusters_forwards<'a>(
         &'a self,
@@ -236,13 +359,14 @@ impl Text {
         &self,
         begin: TextPosition,
         elm: TextElement,
+        words: &WordClassifier,
     ) -> Result<TextPosition, TextPosition> {
         let mut clusters = self.grapheme_clusters_forwards(begin);
         let p = match elm {
             TextElement::CurrentPosition => Some(begin),
             TextElement::GraphemeCluster => self.next_grapheme_cluster(begin).ok(),
             TextElement::WORDBegin => find_big_word_begin(clusters),
-            TextElement::WordBegin => find_word_begin(clusters),
+            TextElement::WordBegin => find_word_begin(clusters, words),
             TextElement::WORDEnd => {
                 if let Some(_) = clusters.next() {
                     find_big_word_end(clusters).or(self.prev_grapheme_cluster(self.end()).ok())
@@ -252,18 +376,29 @@ impl Text {
             }
             TextElement::WordEnd => {
                 if let Some(_) = clusters.next() {
-                    find_word_end(clusters).or(self.prev_grapheme_cluster(self.end()).ok())
+                    find_word_end(clusters, words).or(self.prev_grapheme_cluster(self.end()).ok())
                 } else {
                     None
                 }
             }
             TextElement::Sentence => {
-                if let Some(p) = find_sentence_boundary(clusters) {
+                if let Some(p) = find_sentence_boundary(self, clusters) {
                     find_big_word_begin(self.grapheme_clusters_forwards(p))
                 } else {
                     None
                 }
             }
+            TextElement::Paragraph => ((self.line_index(begin).raw_value() + 1)..self.num_lines())
+                .find(|&l| self.is_paragraph_begin(l))
+                .map(|l| self.begin_of_line(LineIndex::new(l))),
+            TextElement::MatchingBracket => Some(
+                find_matching_bracket(
+                    &self.cluster_at(begin),
+                    clusters.map(|p| (p.p_middle, p.right)),
+                    true,
+                )
+                .unwrap_or(begin),
+            ),
             TextElement::LineSeparator => Some(self.line_end(begin)),
             TextElement::DocumentBoundary => Some(self.end()),
         };
@@ -279,6 +414,7 @@ impl Text {
         &self,
         begin: TextPosition,
         elm: TextElement,
+        words: &WordClassifier,
     ) -> Result<TextPosition, TextPosition> {
         let mut clusters = self.grapheme_clusters_backwards(begin);
         let p = match elm {
@@ -293,22 +429,44 @@ impl Text {
             }
             TextElement::WordBegin => {
                 if let Some(_) = clusters.next() {
-                    find_word_begin(clusters)
+                    find_word_begin(clusters, words)
                 } else {
                     None
                 }
             }
             TextElement::WORDEnd => find_big_word_end(clusters),
-            TextElement::WordEnd => find_word_end(clusters),
+            TextElement::WordEnd => find_word_end(clusters, words),
             TextElement::Sentence => {
                 if let Some(_) = clusters.next() {
                     find_big_word_begin(clusters)
-                        .and_then(|p| find_sentence_boundary(self.grapheme_clusters_backwards(p)))
+                        .and_then(|p| {
+                            find_sentence_boundary(self, self.grapheme_clusters_backwards(p))
+                        })
                         .and_then(|p| find_big_word_begin(self.grapheme_clusters_forwards(p)))
                 } else {
                     None
                 }
             }
+            TextElement::Paragraph => {
+                let line = self.line_index(begin).raw_value();
+                let last = if self.line_begin(begin) == begin {
+                    line
+                } else {
+                    line + 1
+                };
+                (0..last)
+                    .rev()
+                    .find(|&l| self.is_paragraph_begin(l))
+                    .map(|l| self.begin_of_line(LineIndex::new(l)))
+            }
+            TextElement::MatchingBracket => Some(
+                find_matching_bracket(
+                    &self.cluster_at(begin),
+                    clusters.map(|p| (p.p_left, p.left)),
+                    false,
+                )
+                .unwrap_or(begin),
+            ),
             TextElement::LineSeparator => Some(self.line_begin(begin)),
             TextElement::DocumentBoundary => Some(self.begin()),
         };
@@ -324,17 +482,18 @@ impl Text {
         &self,
         begin: TextPosition,
         target: TextTarget,
+        words: &WordClassifier,
     ) -> Result<TextPosition, TextPosition> {
         let mut pos = begin;
         for i in 0..target.count {
             match target.direction {
-                Direction::Forward => match self.next_element(pos, target.element) {
+                Direction::Forward => match self.next_element(pos, target.element, words) {
                     Ok(p) => pos = p,
                     Err(p) => {
                         return if i == 0 { Err(p) } else { Ok(p) };
                     }
                 },
-                Direction::Backward => match self.prev_element(pos, target.element) {
+                Direction::Backward => match self.prev_element(pos, target.element, words) {
                     Ok(p) => pos = p,
                     Err(p) => {
                         return if i == 0 { Err(p) } else { Ok(p) };
@@ -385,23 +544,12 @@ impl Text {
             }
         }
     }
-    fn cluster_in_line(&self, pos: TextPosition) -> usize {
-        let begin = self.line_begin(pos);
-        self.count_clusters(begin..pos)
-    }
-    fn count_clusters(&self, mut range: std::ops::Range<TextPosition>) -> usize {
-        assert!(range.start <= range.end);
-        let mut num = 0;
-        while range.start < range.end {
-            range.start = self.next_grapheme_cluster(range.start).unwrap();
-            num += 1;
-        }
-        assert_eq!(range.start, range.end);
-        num
-    }
     fn begin(&self) -> TextPosition {
         TextPosition::begin()
     }
+    fn is_char_boundary(&self, byte: usize) -> bool {
+        byte <= self.0.len_bytes() && self.0.char_to_byte(self.0.byte_to_char(byte)) == byte
+    }
     fn end(&self) -> TextPosition {
         TextPosition(self.0.len_bytes())
     }
@@ -416,6 +564,22 @@ impl Text {
         self.0.len_lines()
     }
 
+    fn is_blank_line(&self, line: usize) -> bool {
+        let begin = self.begin_of_line(LineIndex::new(line));
+        self.slice(begin..self.line_end(begin))
+            .0
+            .chars()
+            .all(char::is_whitespace)
+    }
+    fn is_paragraph_begin(&self, line: usize) -> bool {
+        !self.is_blank_line(line) && (line == 0 || self.is_blank_line(line - 1))
+    }
+    fn cluster_at(&self, pos: TextPosition) -> String {
+        self.next_grapheme_cluster(pos)
+            .map(|end| self.slice(pos..end).to_string())
+            .unwrap_or_default()
+    }
+
     fn line_begin(&self, pos: TextPosition) -> TextPosition {
         self.as_slice().line_begin(pos)
     }
@@ -454,8 +618,11 @@ impl Text {
 struct TextSlice<'a>(RopeSlice<'a>);
 impl<'a> TextSlice<'a> {
     fn text_width(self) -> Width {
-        //TODO: it's probably possible to make this more efficient...
-        text_width(&self.to_string())
+        match self.0.as_str() {
+            Some(s) => text_width(s),
+            // Only slices spanning multiple chunks of the rope have to be copied.
+            None => text_width(&self.to_string()),
+        }
     }
     fn end(self) -> TextPosition {
         TextPosition(self.0.len_bytes())
@@ -524,6 +691,122 @@ impl TextPosition {
 pub struct TextEdit {
     text: Text,
     cursor_pos: TextPosition,
+    goal_column: Option<usize>,
+    word_classifier: WordClassifier,
+    change_listener: Option<ChangeListener>,
+    search: Option<String>,
+    search_mode: MatchMode,
+    highlight_matches: bool,
+    annotations: Vec<Annotation>,
+    bidi: BidiMode,
+    cursor_movement: CursorMovement,
+    block_anchor: Option<(LineIndex, usize)>,
+    comment_prefix: Option<String>,
+    history: UndoHistory<TextEdit>,
+    undo_group: Option<Vec<Box<dyn Command<TextEdit>>>>,
+    kill_ring: KillRing,
+}
+
+/// The default number of modifications that can be undone (see `TextEdit::set_undo_capacity`).
+const DEFAULT_UNDO_CAPACITY: usize = 1000;
+
+/// A single replacement in the content of a `TextEdit` that can be undone.
+struct TextReplacement {
+    start: usize,
+    removed: String,
+    inserted: String,
+}
+
+impl Command<TextEdit> for TextReplacement {
+    fn apply(&mut self, target: &mut TextEdit) {
+        let range = TextPosition(self.start)..TextPosition(self.start + self.removed.len());
+        let end = target.replace(range, &self.inserted);
+        target.set_cursor(end);
+    }
+    fn revert(&mut self, target: &mut TextEdit) {
+        let range = TextPosition(self.start)..TextPosition(self.start + self.inserted.len());
+        let end = target.replace(range, &self.removed);
+        target.set_cursor(end);
+    }
+}
+
+type ChangeListener = Box<dyn FnMut(&TextChange)>;
+
+/// A modification of the content of a `TextEdit` as reported to a change listener (see
+/// `TextEdit::set_change_listener`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextChange {
+    /// The byte range (in the content before the modification) that was removed.
+    pub removed: Range<usize>,
+    /// The number of bytes that were inserted at `removed.start`.
+    pub inserted: usize,
+}
+
+impl TextChange {
+    /// Map a byte position in the content before the modification to the content after it.
+    /// Positions within the removed range are mapped to the start of the removed range if
+    /// `after_insertion` is false and to the end of the inserted text otherwise.
+    fn map(&self, pos: usize, after_insertion: bool) -> usize {
+        if pos < self.removed.start || (pos == self.removed.start && !after_insertion) {
+            pos
+        } else if pos >= self.removed.end {
+            pos - self.removed.len() + self.inserted
+        } else if after_insertion {
+            self.removed.start + self.inserted
+        } else {
+            self.removed.start
+        }
+    }
+}
+
+/// A rectangular block of text in a `TextEdit` spanning from the block selection anchor to the
+/// cursor (see `TextEdit::start_block_selection`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockSelection {
+    /// The selected lines.
+    pub lines: Range<usize>,
+    /// The selected display columns. If empty, the block is a column of insertion points.
+    pub columns: Range<usize>,
+}
+
+/// The severity of an `Annotation`, which determines how it is displayed by default.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Default style: underlined
+    Hint,
+    /// Default style: underlined, blue
+    Info,
+    /// Default style: underlined, yellow
+    Warning,
+    /// Default style: underlined, red
+    Error,
+}
+
+/// A range of the content of a `TextEdit` that is marked with a message, e.g., a misspelled word
+/// or a compiler error.
+///
+/// The range is kept up to date when the content is modified: Text that is inserted within the
+/// range extends it, text that is inserted directly before or after it does not. If the whole
+/// range is removed, the annotation is removed as well.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    /// The byte range of the annotated text.
+    pub range: Range<usize>,
+    /// The severity that determines the style of the annotated text.
+    pub severity: Severity,
+    /// A message describing the annotation.
+    pub message: String,
+}
+
+impl Annotation {
+    /// Create an annotation for the specified byte range.
+    pub fn new(range: Range<usize>, severity: Severity, message: impl Into<String>) -> Self {
+        Annotation {
+            range,
+            severity,
+            message: message.into(),
+        }
+    }
 }
 
 impl TextEdit {
@@ -532,7 +815,163 @@ impl TextEdit {
         TextEdit {
             text: Text::empty(),
             cursor_pos: TextPosition::begin(),
+            goal_column: None,
+            word_classifier: WordClassifier::Unicode,
+            change_listener: None,
+            search: None,
+            search_mode: MatchMode::exact(),
+            highlight_matches: false,
+            annotations: Vec::new(),
+            bidi: BidiMode::Disabled,
+            cursor_movement: CursorMovement::Logical,
+            block_anchor: None,
+            comment_prefix: None,
+            history: UndoHistory::new(DEFAULT_UNDO_CAPACITY),
+            undo_group: None,
+            kill_ring: KillRing::new(),
+        }
+    }
+
+    /// Set how lines are reordered for display if they contain right-to-left text (default:
+    /// `BidiMode::Disabled`).
+    pub fn set_bidi(&mut self, mode: BidiMode) {
+        self.bidi = mode;
+    }
+
+    /// Set whether moving the cursor left or right (using `Navigatable`) follows the logical or
+    /// the visual order of reordered lines (default: `CursorMovement::Logical`).
+    pub fn set_cursor_movement(&mut self, movement: CursorMovement) {
+        self.cursor_movement = movement;
+    }
+
+    /// Customize which grapheme clusters form words (see `TextElement::WordBegin` and
+    /// `TextElement::WordEnd`), e.g., to treat '-' as part of keywords for lisp-like languages.
+    ///
+    /// By default, letters, digits and '_' form keywords and keywords are additionally separated
+    /// at unicode word boundaries.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::*;
+    ///
+    /// let mut t = TextEdit::new();
+    /// t.set("(foo-bar baz)");
+    /// t.set_word_classifier(|s| match s {
+    ///     "-" => ClusterType::Keyword,
+    ///     " " => ClusterType::Whitespace,
+    ///     s if s.chars().all(char::is_alphanumeric) => ClusterType::Keyword,
+    ///     _ => ClusterType::Other,
+    /// });
+    /// assert_eq!(t.get(TextTarget::backward(TextElement::WordBegin).nth(2)..), "baz)");
+    /// ```
+    pub fn set_word_classifier<F: Fn(&str) -> ClusterType + 'static>(&mut self, classifier: F) {
+        self.word_classifier = WordClassifier::Custom(Box::new(classifier));
+    }
+
+    /// Restore the default word classification (see `set_word_classifier`).
+    pub fn reset_word_classifier(&mut self) {
+        self.word_classifier = WordClassifier::Unicode;
+    }
+
+    /// Register a function that is called for every modification of the content (e.g., to keep
+    /// syntax highlighting or an undo history up to date). Replaces any previous listener.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::*;
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let changes = Rc::new(RefCell::new(Vec::new()));
+    /// let mut t = TextEdit::new();
+    /// t.set("foo bar");
+    /// let c = changes.clone();
+    /// t.set_change_listener(move |change| c.borrow_mut().push(change.clone()));
+    /// t.replace_range(TextTarget::backward(TextElement::WordBegin)..TextTarget::cursor(), "baz!");
+    /// assert_eq!(
+    ///     *changes.borrow(),
+    ///     vec![TextChange { removed: 4..7, inserted: 4 }]
+    /// );
+    /// ```
+    pub fn set_change_listener<F: FnMut(&TextChange) + 'static>(&mut self, listener: F) {
+        self.change_listener = Some(Box::new(listener));
+    }
+
+    /// Remove the change listener (see `set_change_listener`).
+    pub fn remove_change_listener(&mut self) {
+        self.change_listener = None;
+    }
+
+    /// Replace the given range of the text and notify the change listener. Returns the end of the
+    /// inserted text.
+    fn replace(&mut self, range: Range<TextPosition>, s: &str) -> TextPosition {
+        if range.start == range.end && s.is_empty() {
+            return range.start;
+        }
+        let change = TextChange {
+            removed: range.start.0..range.end.0,
+            inserted: s.len(),
+        };
+        let replacement = TextReplacement {
+            start: range.start.0,
+            removed: self.text.slice(range.clone()).to_string(),
+            inserted: s.to_owned(),
+        };
+        match self.undo_group {
+            Some(ref mut group) => group.push(Box::new(replacement)),
+            None => self.history.record(replacement),
+        }
+        self.text.remove(range.clone());
+        let end = self.text.insert(range.start, s);
+        self.annotations.retain_mut(|a| {
+            let start = change.map(a.range.start, true);
+            let end = change.map(a.range.end, false);
+            a.range = start..end;
+            start < end
+        });
+        if let Some(listener) = &mut self.change_listener {
+            listener(&change);
+        }
+        end
+    }
+
+    /// Move the cursor to the specified line, as close as possible to the goal column.
+    ///
+    /// The goal column (in terms of display width) is remembered across successive vertical
+    /// movements, so that moving across a short line does not lose the original column.
+    fn move_cursor_to_line(&mut self, line: LineIndex) {
+        let goal = match self.goal_column {
+            Some(goal) => goal,
+            None => {
+                let line_begin = self.text.line_begin(self.cursor_pos);
+                let col = self
+                    .text
+                    .slice(line_begin..self.cursor_pos)
+                    .text_width()
+                    .raw_value() as usize;
+                self.goal_column = Some(col);
+                col
+            }
+        };
+        let line_begin = self.text.begin_of_line(line);
+        let line_end = self.text.line_end(line_begin);
+        let mut pos = line_begin;
+        let mut col = 0;
+        while pos < line_end {
+            let next = self.text.next_grapheme_cluster(pos).unwrap();
+            col += self.text.slice(pos..next).text_width().raw_value() as usize;
+            if col > goal {
+                break;
+            }
+            pos = next;
         }
+        self.cursor_pos = pos;
+    }
+
+    /// Move the cursor to a new position. This resets the goal column for vertical movements.
+    fn set_cursor(&mut self, pos: TextPosition) {
+        self.cursor_pos = pos;
+        self.goal_column = None;
     }
 
     /// Get the current content in the given range.
@@ -544,30 +983,172 @@ impl TextEdit {
     /// Set (and overwrite) the current content. The cursor will be placed at the very end of the
     /// text.
     pub fn set(&mut self, text: impl AsRef<str>) {
-        self.text = Text::with_content(text.as_ref());
-        self.cursor_pos = self.text.end();
+        let end = self.replace(self.text.begin()..self.text.end(), text.as_ref());
+        self.set_cursor(end);
     }
 
     /// Remove the given range from the content.
     /// The cursor will be set to the beginning of the deleted range.
     pub fn delete(&mut self, bounds: impl RangeBounds<TextTarget>) {
         let s = self.resolve_range(bounds);
-        self.text.remove(s.0..s.1);
-        self.cursor_pos = s.0;
+        self.replace(s.0..s.1, "");
+        self.set_cursor(s.0);
+    }
+
+    /// Replace the given range of the content with `text`.
+    /// The cursor will be set to the end of the inserted text.
+    pub fn replace_range(&mut self, bounds: impl RangeBounds<TextTarget>, text: &str) {
+        let s = self.resolve_range(bounds);
+        let end = self.replace(s.0..s.1, text);
+        self.set_cursor(end);
+    }
+
+    /// Apply a number of replacements, each specifying a byte range of the current content and the
+    /// text to replace it with.
+    ///
+    /// The ranges must not overlap and must lie on character boundaries. Otherwise, the content is
+    /// not modified and `Err` is returned. The cursor keeps its position relative to the
+    /// surrounding text, or is set to the end of the replacement if its range contained the
+    /// cursor.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::*;
+    ///
+    /// let mut t = TextEdit::new();
+    /// t.set("let a = b;");
+    /// t.apply_edits(vec![(4..5, "foo"), (8..9, "bar")]).unwrap();
+    /// assert_eq!(t.get(..), "let foo = bar;");
+    /// assert!(t.apply_edits(vec![(0..5, ""), (4..6, "")]).is_err());
+    /// ```
+    pub fn apply_edits<'s>(
+        &mut self,
+        edits: impl IntoIterator<Item = (Range<usize>, &'s str)>,
+    ) -> OperationResult {
+        let mut edits = edits.into_iter().collect::<Vec<_>>();
+        edits.sort_by_key(|(range, _)| range.start);
+        let mut prev_end = 0;
+        for (range, _) in &edits {
+            if range.start < prev_end
+                || range.end < range.start
+                || !self.text.is_char_boundary(range.start)
+                || !self.text.is_char_boundary(range.end)
+            {
+                return Err(());
+            }
+            prev_end = range.end;
+        }
+        // Apply back to front so that the ranges of the remaining edits stay valid.
+        let mut cursor = self.cursor_pos.0;
+        self.undo_group = Some(Vec::new());
+        for (range, text) in edits.into_iter().rev() {
+            let end = self.replace(TextPosition(range.start)..TextPosition(range.end), text);
+            if range.end <= cursor {
+                cursor = cursor - range.end + end.0;
+            } else if range.start < cursor {
+                cursor = end.0;
+            }
+        }
+        let group = self.undo_group.take().unwrap_or_default();
+        if !group.is_empty() {
+            // All edits are undone at once.
+            self.history.record(group);
+        }
+        self.set_cursor(TextPosition(cursor));
+        Ok(())
+    }
+
+    /// Revert the most recent modification of the content (or group of modifications, see
+    /// `apply_edits`) and move the cursor to the end of the restored text. Fails if there is
+    /// nothing to undo.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::*;
+    ///
+    /// let mut t = TextEdit::new();
+    /// t.set("foo");
+    /// t.insert("bar");
+    /// t.undo().unwrap();
+    /// assert_eq!(t.get(..), "foo");
+    /// t.redo().unwrap();
+    /// assert_eq!(t.get(..), "foobar");
+    /// ```
+    pub fn undo(&mut self) -> OperationResult {
+        self.replay(|history, t| history.undo(t))
+    }
+
+    /// Apply the most recently undone modification again. Fails if there is nothing to redo.
+    pub fn redo(&mut self) -> OperationResult {
+        self.replay(|history, t| history.redo(t))
+    }
+
+    /// Set the maximum number of modifications that can be undone (default: 1000). Use 0 to
+    /// disable the undo history.
+    pub fn set_undo_capacity(&mut self, capacity: usize) {
+        self.history.set_capacity(capacity);
+    }
+
+    /// Specify how search patterns are matched, e.g., ignoring case (default: exactly).
+    pub fn set_search_mode(&mut self, mode: MatchMode) {
+        self.search_mode = mode;
+    }
+
+    /// Use the given kill ring (e.g., one that is shared with other widgets) instead of the one
+    /// that the `TextEdit` was created with for `Editable::kill_to_end_of_line` etc.
+    pub fn set_kill_ring(&mut self, ring: KillRing) {
+        self.kill_ring = ring;
+    }
+
+    /// Get (a handle to) the kill ring of the `TextEdit`.
+    pub fn kill_ring(&self) -> KillRing {
+        self.kill_ring.clone()
+    }
+
+    /// Remove the given range of the content, store it in the kill ring and place the cursor at
+    /// its beginning.
+    fn kill(&mut self, range: Range<TextPosition>) -> OperationResult {
+        if range.start == range.end {
+            return Err(());
+        }
+        self.kill_ring
+            .kill(self.text.slice(range.clone()).to_string());
+        self.replace(range.clone(), "");
+        self.set_cursor(range.start);
+        Ok(())
+    }
+
+    fn replay<F: FnOnce(&mut UndoHistory<TextEdit>, &mut TextEdit) -> OperationResult>(
+        &mut self,
+        f: F,
+    ) -> OperationResult {
+        // Modifications while replaying are recorded in (and discarded with) the empty history.
+        let mut history = ::std::mem::replace(&mut self.history, UndoHistory::new(0));
+        let res = f(&mut history, self);
+        self.history = history;
+        res
     }
 
     /// Move the cursor the specified position (relative to the current position).
     pub fn move_cursor_to(&mut self, target: TextTarget) -> Result<(), ()> {
-        let r = self.text.resolve_target(self.cursor_pos, target);
-        self.cursor_pos = pos(r);
+        let r = self
+            .text
+            .resolve_target(self.cursor_pos, target, &self.word_classifier);
+        self.set_cursor(pos(r));
         op_res(r)
     }
 
     fn resolve_range(&self, bounds: impl RangeBounds<TextTarget>) -> (TextPosition, TextPosition) {
         let start = match bounds.start_bound() {
-            Bound::Included(t) => pos(self.text.resolve_target(self.cursor_pos, *t)),
+            Bound::Included(t) => {
+                pos(self
+                    .text
+                    .resolve_target(self.cursor_pos, *t, &self.word_classifier))
+            }
             Bound::Excluded(t) => {
-                let p = pos(self.text.resolve_target(self.cursor_pos, *t));
+                let p = pos(self
+                    .text
+                    .resolve_target(self.cursor_pos, *t, &self.word_classifier));
                 self.text
                     .next_grapheme_cluster(p)
                     .unwrap_or(self.text.end())
@@ -576,12 +1157,18 @@ impl TextEdit {
         };
         let end = match bounds.end_bound() {
             Bound::Included(t) => {
-                let p = pos(self.text.resolve_target(self.cursor_pos, *t));
+                let p = pos(self
+                    .text
+                    .resolve_target(self.cursor_pos, *t, &self.word_classifier));
                 self.text
                     .next_grapheme_cluster(p)
                     .unwrap_or(self.text.end())
             }
-            Bound::Excluded(t) => pos(self.text.resolve_target(self.cursor_pos, *t)),
+            Bound::Excluded(t) => {
+                pos(self
+                    .text
+                    .resolve_target(self.cursor_pos, *t, &self.word_classifier))
+            }
             Bound::Unbounded => self.text.end(),
         };
         (start, end)
@@ -590,14 +1177,7 @@ impl TextEdit {
     fn move_cursor_down(&mut self) -> Result<(), ()> {
         let line = self.text.line_index(self.cursor_pos);
         if line.raw_value() + 1 < self.text.num_lines() {
-            let pos_in_line = self.text.cluster_in_line(self.cursor_pos);
-            let line_begin = self.text.begin_of_line(line + 1);
-            let line_end = self.text.line_end(line_begin);
-            let num_clusters = self.text.count_clusters(line_begin..line_end);
-
-            let pos_in_line = pos_in_line.min(num_clusters);
-            self.cursor_pos = self.text.clusters_forward(line_begin, pos_in_line).unwrap();
-
+            self.move_cursor_to_line(line + 1);
             Ok(())
         } else {
             Err(())
@@ -608,7 +1188,8 @@ impl TextEdit {
         if self.text.line_end(self.cursor_pos) == self.cursor_pos {
             Err(())
         } else {
-            self.cursor_pos = self.text.next_grapheme_cluster(self.cursor_pos)?;
+            let pos = self.text.next_grapheme_cluster(self.cursor_pos)?;
+            self.set_cursor(pos);
             Ok(())
         }
     }
@@ -616,14 +1197,7 @@ impl TextEdit {
     fn move_cursor_up(&mut self) -> Result<(), ()> {
         let line = self.text.line_index(self.cursor_pos);
         if line.raw_value() > 0 {
-            let pos_in_line = self.text.cluster_in_line(self.cursor_pos);
-            let line_begin = self.text.begin_of_line(line - 1);
-            let line_end = self.text.line_end(line_begin);
-            let num_clusters = self.text.count_clusters(line_begin..line_end);
-
-            let pos_in_line = pos_in_line.min(num_clusters);
-            self.cursor_pos = self.text.clusters_forward(line_begin, pos_in_line).unwrap();
-
+            self.move_cursor_to_line(line - 1);
             Ok(())
         } else {
             Err(())
@@ -634,14 +1208,34 @@ impl TextEdit {
         if self.text.line_begin(self.cursor_pos) == self.cursor_pos {
             Err(())
         } else {
-            self.cursor_pos = self.text.prev_grapheme_cluster(self.cursor_pos)?;
+            let pos = self.text.prev_grapheme_cluster(self.cursor_pos)?;
+            self.set_cursor(pos);
             Ok(())
         }
     }
 
+    /// Move the cursor to the visually adjacent cluster if the current line is reordered and the
+    /// cursor moves visually.
+    fn move_cursor_visually(&mut self, right: bool) -> Option<Result<(), ()>> {
+        if self.cursor_movement != CursorMovement::Visual {
+            return None;
+        }
+        let begin = self.text.line_begin(self.cursor_pos);
+        let end = self.text.line_end(self.cursor_pos);
+        let line = self.text.slice(begin..end).to_string();
+        if !is_reordered(&line, self.bidi) {
+            return None;
+        }
+        Some(
+            visual_neighbor(&line, self.bidi, self.cursor_pos.0 - begin.0, right)
+                .ok_or(())
+                .map(|pos| self.set_cursor(TextPosition(begin.0 + pos))),
+        )
+    }
+
     /// Insert text directly *before* the current cursor position
     pub fn insert(&mut self, text: &str) {
-        self.text.insert(self.cursor_pos, text);
+        self.replace(self.cursor_pos..self.cursor_pos, text);
     }
 
     /// Returns the byte position of the cursor in the current line
@@ -649,6 +1243,254 @@ impl TextEdit {
         self.cursor_pos.0 - self.text.line_begin(self.cursor_pos).0
     }
 
+    /// Returns the byte position of the cursor in the whole content
+    pub fn cursor_byte_pos(&self) -> usize {
+        self.cursor_pos.0
+    }
+
+    /// Returns the line and the column (in grapheme clusters) of the cursor
+    pub fn cursor_position(&self) -> (LineIndex, usize) {
+        self.byte_to_position(self.cursor_pos.0).unwrap()
+    }
+
+    /// Place the cursor at the specified line and column (in grapheme clusters).
+    ///
+    /// The column may be equal to the number of grapheme clusters in the line to place the cursor
+    /// at the end of the line. If the position does not exist, the cursor is not moved and `Err`
+    /// is returned.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::LineIndex;
+    /// use unsegen::widget::builtin::*;
+    ///
+    /// let mut t = TextEdit::new();
+    /// t.set("foo\nbär baz");
+    /// t.set_cursor_position(LineIndex::new(1), 4).unwrap();
+    /// assert_eq!(t.get(TextTarget::cursor()..), "baz");
+    /// assert_eq!(t.cursor_byte_pos(), 9);
+    /// assert!(t.set_cursor_position(LineIndex::new(0), 4).is_err());
+    /// ```
+    pub fn set_cursor_position(&mut self, line: LineIndex, column: usize) -> OperationResult {
+        let pos = self.position_to_byte(line, column).ok_or(())?;
+        self.set_cursor(TextPosition(pos));
+        Ok(())
+    }
+
+    /// Convert a byte offset into the content to the line and column (in grapheme clusters) of the
+    /// grapheme cluster containing it. Returns `None` if the offset is beyond the end of the
+    /// content.
+    pub fn byte_to_position(&self, byte: usize) -> Option<(LineIndex, usize)> {
+        if byte > self.text.end().0 {
+            return None;
+        }
+        let line = self.text.line_index(TextPosition(byte));
+        let mut pos = self.text.begin_of_line(line);
+        let mut column = 0;
+        while let Ok(next) = self.text.next_grapheme_cluster(pos) {
+            if next.0 > byte {
+                break;
+            }
+            pos = next;
+            column += 1;
+        }
+        Some((line, column))
+    }
+
+    /// Convert a line and column (in grapheme clusters) to a byte offset into the content.
+    /// Returns `None` if the position does not exist.
+    pub fn position_to_byte(&self, line: LineIndex, column: usize) -> Option<usize> {
+        if line.raw_value() >= self.text.num_lines() {
+            return None;
+        }
+        let mut pos = self.text.begin_of_line(line);
+        let line_end = self.text.line_end(pos);
+        for _ in 0..column {
+            if pos >= line_end {
+                return None;
+            }
+            pos = self.text.next_grapheme_cluster(pos).ok()?;
+        }
+        Some(pos.0)
+    }
+
+    /// Returns the number of lines of the content
+    pub fn num_lines(&self) -> usize {
+        self.text.num_lines()
+    }
+
+    /// The row of a window with `height` rows that the line of the cursor is drawn to. If
+    /// possible, one line below the cursor remains visible.
+    fn cursor_row(&self, height: Height) -> RowIndex {
+        let lower_padding = 1;
+        let current_line = self.text.line_index(self.cursor_pos);
+        let num_following_lines = self.text.num_lines() - current_line.raw_value() - 1;
+        (height - 1 - lower_padding.min(num_following_lines as i32))
+            .min((current_line.raw_value() as i32).into())
+            .max(0.into())
+            .from_origin()
+    }
+
+    /// Returns the content of the specified line (without the line break), or `None` if the line
+    /// does not exist.
+    pub fn line(&self, line: LineIndex) -> Option<Cow<'_, str>> {
+        if line.raw_value() >= self.text.num_lines() {
+            return None;
+        }
+        let begin = self.text.begin_of_line(line);
+        Some(self.text.slice(begin..self.text.line_end(begin)).0.into())
+    }
+
+    /// Start a block (i.e., rectangular) selection anchored at the current cursor position.
+    ///
+    /// While the block selection is active, moving the cursor changes the block, writing inserts
+    /// on every line of the block (replacing its content) and deletion removes the block (or the
+    /// column before or after an empty block) on every line. Lines that end before the block are
+    /// left unchanged.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::LineIndex;
+    /// use unsegen::input::{Editable, Navigatable, Writable};
+    /// use unsegen::widget::builtin::*;
+    ///
+    /// let mut t = TextEdit::new();
+    /// t.set("a = 1\nb = 2\nc = 3");
+    /// t.set_cursor_position(LineIndex::new(0), 0).unwrap();
+    /// t.start_block_selection();
+    /// t.move_down().unwrap();
+    /// t.move_down().unwrap();
+    /// t.write('_').unwrap();
+    /// assert_eq!(t.get(..), "_a = 1\n_b = 2\n_c = 3");
+    ///
+    /// t.move_right().unwrap();
+    /// let block = t.block_selection().unwrap();
+    /// assert_eq!((block.lines, block.columns), (0..3, 1..2));
+    /// t.delete_forwards().unwrap();
+    /// assert_eq!(t.get(..), "_ = 1\n_ = 2\n_ = 3");
+    /// t.cancel_block_selection();
+    /// ```
+    pub fn start_block_selection(&mut self) {
+        self.block_anchor = Some((
+            self.text.line_index(self.cursor_pos),
+            self.display_column(self.cursor_pos),
+        ));
+    }
+
+    /// Stop the current block selection (if any).
+    pub fn cancel_block_selection(&mut self) {
+        self.block_anchor = None;
+    }
+
+    /// Get the current block selection (if any).
+    pub fn block_selection(&self) -> Option<BlockSelection> {
+        let (anchor_line, anchor_column) = self.block_anchor?;
+        let anchor_line = anchor_line.raw_value().min(self.text.num_lines() - 1);
+        let cursor_line = self.text.line_index(self.cursor_pos).raw_value();
+        let cursor_column = self.display_column(self.cursor_pos);
+        Some(BlockSelection {
+            lines: anchor_line.min(cursor_line)..anchor_line.max(cursor_line) + 1,
+            columns: anchor_column.min(cursor_column)..anchor_column.max(cursor_column),
+        })
+    }
+
+    /// Get the display column of the position in its line.
+    fn display_column(&self, pos: TextPosition) -> usize {
+        let begin = self.text.line_begin(pos);
+        self.text.slice(begin..pos).text_width().raw_value() as usize
+    }
+
+    /// Get the position of the cluster covering the specified display column in a line or the end
+    /// of the line if it ends exactly at the column. Returns `None` if the line is shorter.
+    fn position_at_column(&self, line: usize, column: usize) -> Option<TextPosition> {
+        let mut pos = self.text.begin_of_line(LineIndex::new(line));
+        let end = self.text.line_end(pos);
+        let mut current = 0;
+        while current < column {
+            if pos == end {
+                return None;
+            }
+            let next = self.text.next_grapheme_cluster(pos).ok()?;
+            let width = self.text.slice(pos..next).text_width().raw_value() as usize;
+            if current + width > column {
+                break;
+            }
+            current += width;
+            pos = next;
+        }
+        Some(pos)
+    }
+
+    /// Get the byte ranges of the specified columns in all lines of the block.
+    fn block_ranges(&self, lines: Range<usize>, columns: Range<usize>) -> Vec<Range<usize>> {
+        lines
+            .filter_map(|line| {
+                let start = self.position_at_column(line, columns.start)?;
+                let end = self
+                    .position_at_column(line, columns.end)
+                    .unwrap_or_else(|| self.text.line_end(start));
+                Some(start.0..end.0)
+            })
+            .collect()
+    }
+
+    /// Replace the specified columns of all lines in the block selection with `text` and continue
+    /// with an empty block after the inserted text.
+    fn replace_block(&mut self, columns: Range<usize>, text: &str) -> OperationResult {
+        let (anchor_line, _) = self.block_anchor.ok_or(())?;
+        let block = self.block_selection().ok_or(())?;
+        let ranges = self.block_ranges(block.lines, columns.clone());
+        if ranges.is_empty() || (text.is_empty() && ranges.iter().all(|r| r.is_empty())) {
+            return Err(());
+        }
+        let cursor_line = self.text.line_index(self.cursor_pos);
+        self.apply_edits(ranges.into_iter().map(|r| (r, text)))?;
+        let column = columns.start + text_width(text).raw_value() as usize;
+        self.block_anchor = Some((anchor_line, column));
+        let pos = self
+            .position_at_column(cursor_line.raw_value(), column)
+            .unwrap_or_else(|| {
+                let begin = self.text.begin_of_line(cursor_line);
+                self.text.line_end(begin)
+            });
+        self.set_cursor(pos);
+        Ok(())
+    }
+
+    /// Set the prefix (e.g., "// ") that `Editable::toggle_comment` adds to or removes from lines.
+    /// Without a prefix (the default), `toggle_comment` fails.
+    pub fn set_comment_prefix(&mut self, prefix: Option<String>) {
+        self.comment_prefix = prefix;
+    }
+
+    /// The lines affected by line operations: The lines of the block selection if there is one,
+    /// or the line of the cursor.
+    fn current_lines(&self) -> Range<usize> {
+        match self.block_selection() {
+            Some(block) => block.lines,
+            None => {
+                let line = self.text.line_index(self.cursor_pos).raw_value();
+                line..line + 1
+            }
+        }
+    }
+
+    /// Get the byte range of the lines (without the final line break).
+    fn line_range(&self, lines: Range<usize>) -> Range<TextPosition> {
+        let begin = self.text.begin_of_line(LineIndex::new(lines.start));
+        let last = self.text.begin_of_line(LineIndex::new(lines.end - 1));
+        begin..self.text.line_end(last)
+    }
+
+    /// Move the cursor and the block selection anchor (if any) by the specified number of lines.
+    fn shift_lines(&mut self, cursor: TextPosition, lines: isize) {
+        if let Some((line, column)) = self.block_anchor {
+            let line = (line.raw_value() as isize + lines) as usize;
+            self.block_anchor = Some((LineIndex::new(line), column));
+        }
+        self.set_cursor(cursor);
+    }
+
     /// Prepare for drawing as a `Widget`.
     pub fn as_widget<'a>(&'a self) -> TextEditWidget<'a> {
         TextEditWidget {
@@ -656,6 +1498,145 @@ impl TextEdit {
             cursor_style_active_blink_on: StyleModifier::new().invert(BoolModifyMode::Toggle),
             cursor_style_active_blink_off: StyleModifier::new(),
             cursor_style_inactive: StyleModifier::new().underline(true),
+            search_highlight: StyleModifier::new().bold(true).underline(true),
+            block_selection_style: StyleModifier::new().invert(true),
+            annotation_styles: [
+                StyleModifier::new().underline(true),
+                StyleModifier::new().underline(true).fg_color(Color::Blue),
+                StyleModifier::new().underline(true).fg_color(Color::Yellow),
+                StyleModifier::new().underline(true).fg_color(Color::Red),
+            ],
+        }
+    }
+
+    /// Annotate a range of the content (e.g., to mark a misspelled word). Fails if the range is
+    /// empty, out of bounds or does not lie on character boundaries.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::builtin::*;
+    ///
+    /// let mut t = TextEdit::new();
+    /// t.set("helo world");
+    /// t.annotate(Annotation::new(0..4, Severity::Error, "misspelled")).unwrap();
+    /// t.move_cursor_to(TextTarget::backward(TextElement::WordBegin).nth(2)).unwrap();
+    /// t.move_cursor_to(TextTarget::forward(TextElement::GraphemeCluster).nth(3)).unwrap();
+    /// t.insert("l");
+    ///
+    /// assert_eq!(t.annotations()[0].range, 0..5);
+    /// assert_eq!(t.annotations_at_cursor().next().unwrap().message, "misspelled");
+    /// ```
+    pub fn annotate(&mut self, annotation: Annotation) -> OperationResult {
+        let range = &annotation.range;
+        if range.start >= range.end
+            || range.end > self.text.end().0
+            || !self.text.is_char_boundary(range.start)
+            || !self.text.is_char_boundary(range.end)
+        {
+            return Err(());
+        }
+        self.annotations.push(annotation);
+        Ok(())
+    }
+
+    /// Get all annotations (in the order they were added).
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Get all annotations that contain the byte at position `byte`.
+    pub fn annotations_at(&self, byte: usize) -> impl Iterator<Item = &Annotation> {
+        self.annotations
+            .iter()
+            .filter(move |a| a.range.contains(&byte))
+    }
+
+    /// Get all annotations that contain the grapheme cluster under the cursor.
+    pub fn annotations_at_cursor(&self) -> impl Iterator<Item = &Annotation> {
+        self.annotations_at(self.cursor_pos.0)
+    }
+
+    /// Remove all annotations for which `f` returns false.
+    pub fn retain_annotations<F: FnMut(&Annotation) -> bool>(&mut self, f: F) {
+        self.annotations.retain(f);
+    }
+
+    /// Remove all annotations.
+    pub fn clear_annotations(&mut self) {
+        self.annotations.clear();
+    }
+
+    /// Get the byte ranges of all matches of the current search.
+    fn search_matches(&self) -> Vec<Range<usize>> {
+        match self.search {
+            Some(ref pattern) => self
+                .search_mode
+                .find_all(&self.text.as_slice().to_string(), pattern),
+            None => Vec::new(),
+        }
+    }
+
+    /// Move the cursor to the begin of the closest match of the current search after (or before)
+    /// the cursor, wrapping around at the end (or beginning) of the text.
+    fn jump_to_match(&mut self, forwards: bool, include_cursor: bool) -> OperationResult {
+        let cursor = self.cursor_pos.0;
+        let starts = self
+            .search_matches()
+            .into_iter()
+            .map(|m| m.start)
+            .collect::<Vec<_>>();
+        let found = if forwards {
+            starts
+                .iter()
+                .find(|&&m| m > cursor || (include_cursor && m == cursor))
+                .or_else(|| starts.first())
+        } else {
+            starts
+                .iter()
+                .rev()
+                .find(|&&m| m < cursor)
+                .or_else(|| starts.last())
+        };
+        let found = *found.ok_or(())?;
+        self.set_cursor(TextPosition(found));
+        self.highlight_matches = true;
+        Ok(())
+    }
+}
+
+/// Search the whole text. `start_search` moves the cursor to the first match at or after the
+/// cursor.
+impl VisibleLines for TextEdit {
+    fn visible_lines(&self, height: Height) -> (RowIndex, Range<usize>) {
+        let first = self.current_line() - self.cursor_row(height).raw_value() as usize;
+        let end = (first + height.raw_value() as usize).min(self.num_lines());
+        (RowIndex::new(0), first..end)
+    }
+    fn num_lines(&self) -> usize {
+        self.text.num_lines()
+    }
+    fn current_line(&self) -> usize {
+        self.text.line_index(self.cursor_pos).raw_value()
+    }
+}
+
+impl Searchable for TextEdit {
+    fn start_search(&mut self, pattern: &str) -> OperationResult {
+        self.search = Some(pattern.to_owned()).filter(|p| !p.is_empty());
+        self.jump_to_match(true, true)
+    }
+    fn next_match(&mut self) -> OperationResult {
+        self.jump_to_match(true, false)
+    }
+    fn prev_match(&mut self) -> OperationResult {
+        self.jump_to_match(false, false)
+    }
+    fn clear_highlights(&mut self) -> OperationResult {
+        if self.highlight_matches {
+            self.highlight_matches = false;
+            Ok(())
+        } else {
+            Err(())
         }
     }
 }
@@ -669,17 +1650,26 @@ impl Navigatable for TextEdit {
         self.move_cursor_down()
     }
     fn move_left(&mut self) -> OperationResult {
-        self.move_cursor_left()
+        self.move_cursor_visually(false)
+            .unwrap_or_else(|| self.move_cursor_left())
     }
     fn move_right(&mut self) -> OperationResult {
-        self.move_cursor_right()
+        self.move_cursor_visually(true)
+            .unwrap_or_else(|| self.move_cursor_right())
     }
 }
 
 impl Writable for TextEdit {
     fn write(&mut self, c: char) -> OperationResult {
+        if let Some(block) = self.block_selection() {
+            if c == '\n' {
+                return Err(());
+            }
+            return self.replace_block(block.columns, &c.to_string());
+        }
         self.insert(&c.to_string());
-        self.cursor_pos = self.text.next_grapheme_cluster(self.cursor_pos).unwrap();
+        let pos = self.text.next_grapheme_cluster(self.cursor_pos).unwrap();
+        self.set_cursor(pos);
         Ok(())
     }
 }
@@ -687,17 +1677,34 @@ impl Writable for TextEdit {
 impl Editable for TextEdit {
     fn delete_forwards(&mut self) -> OperationResult {
         //i.e., "del" key
+        if let Some(block) = self.block_selection() {
+            let columns = if block.columns.is_empty() {
+                block.columns.start..block.columns.start + 1
+            } else {
+                block.columns
+            };
+            return self.replace_block(columns, "");
+        }
         let start = self.cursor_pos;
         let end = self.text.next_grapheme_cluster(start)?;
-        self.text.remove(start..end);
+        self.replace(start..end, "");
+        self.set_cursor(start);
         Ok(())
     }
     fn delete_backwards(&mut self) -> OperationResult {
         //i.e., "backspace"
+        if let Some(block) = self.block_selection() {
+            let columns = if block.columns.is_empty() {
+                block.columns.start.checked_sub(1).ok_or(())?..block.columns.start
+            } else {
+                block.columns
+            };
+            return self.replace_block(columns, "");
+        }
         let end = self.cursor_pos;
         let start = self.text.prev_grapheme_cluster(end)?;
-        self.text.remove(start..end);
-        self.cursor_pos = start;
+        self.replace(start..end, "");
+        self.set_cursor(start);
         Ok(())
     }
     fn go_to_beginning_of_line(&mut self) -> OperationResult {
@@ -710,11 +1717,161 @@ impl Editable for TextEdit {
         if self.text.0.len_bytes() == 0 {
             Err(())
         } else {
-            self.text = Text::empty();
-            self.cursor_pos = TextPosition::begin();
+            self.replace(self.text.begin()..self.text.end(), "");
+            self.set_cursor(TextPosition::begin());
             Ok(())
         }
     }
+    fn duplicate_lines(&mut self) -> OperationResult {
+        let lines = self.current_lines();
+        let range = self.line_range(lines.clone());
+        let copy = format!("\n{}", self.text.slice(range.clone()));
+        self.replace(range.end..range.end, &copy);
+        let cursor = TextPosition(self.cursor_pos.0 + copy.len());
+        self.shift_lines(cursor, lines.len() as isize);
+        Ok(())
+    }
+    fn move_lines_up(&mut self) -> OperationResult {
+        let lines = self.current_lines();
+        if lines.start == 0 {
+            return Err(());
+        }
+        let range = self.line_range(lines.clone());
+        let above = self.line_range(lines.start - 1..lines.start);
+        let moved = format!(
+            "{}\n{}",
+            self.text.slice(range.clone()),
+            self.text.slice(above.clone())
+        );
+        let cursor = TextPosition(self.cursor_pos.0 - (above.end.0 + 1 - above.start.0));
+        self.replace(above.start..range.end, &moved);
+        self.shift_lines(cursor, -1);
+        Ok(())
+    }
+    fn move_lines_down(&mut self) -> OperationResult {
+        let lines = self.current_lines();
+        if lines.end >= self.text.num_lines() {
+            return Err(());
+        }
+        let range = self.line_range(lines.clone());
+        let below = self.line_range(lines.end..lines.end + 1);
+        let moved = format!(
+            "{}\n{}",
+            self.text.slice(below.clone()),
+            self.text.slice(range.clone())
+        );
+        let cursor = TextPosition(self.cursor_pos.0 + below.end.0 + 1 - below.start.0);
+        self.replace(range.start..below.end, &moved);
+        self.shift_lines(cursor, 1);
+        Ok(())
+    }
+    fn join_lines(&mut self) -> OperationResult {
+        let lines = self.current_lines();
+        let last = (lines.end - 1).max(lines.start + 1);
+        if last >= self.text.num_lines() {
+            return Err(());
+        }
+        // Replace each line break (and the indentation following it) by a single space (or
+        // nothing, if either side of the line break is empty).
+        let edits = (lines.start + 1..last + 1)
+            .map(|line| {
+                let begin = self.text.begin_of_line(LineIndex::new(line));
+                let end = self.text.line_end(begin);
+                let content = self.text.slice(begin..end).to_string();
+                let indentation = content.len() - content.trim_start().len();
+                let previous = self.line_range(line - 1..line);
+                let separator = if content.trim_start().is_empty() || previous.start == previous.end
+                {
+                    ""
+                } else {
+                    " "
+                };
+                (begin.0 - 1..begin.0 + indentation, separator)
+            })
+            .collect::<Vec<_>>();
+        self.block_anchor = None;
+        self.apply_edits(edits)
+    }
+    fn undo(&mut self) -> OperationResult {
+        TextEdit::undo(self)
+    }
+    fn redo(&mut self) -> OperationResult {
+        TextEdit::redo(self)
+    }
+    fn toggle_comment(&mut self) -> OperationResult {
+        let prefix = self.comment_prefix.clone().ok_or(())?;
+        let trimmed_prefix = prefix.trim_end();
+        let lines = self
+            .current_lines()
+            .map(|line| {
+                let begin = self.text.begin_of_line(LineIndex::new(line));
+                let content = self
+                    .text
+                    .slice(begin..self.text.line_end(begin))
+                    .to_string();
+                (begin.0, content)
+            })
+            .filter(|(_, content)| !content.trim().is_empty())
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            return Err(());
+        }
+        let indentation = |content: &str| content.len() - content.trim_start().len();
+        let commented = lines
+            .iter()
+            .all(|(_, content)| content.trim_start().starts_with(trimmed_prefix));
+        let edits = if commented {
+            lines
+                .iter()
+                .map(|(begin, content)| {
+                    let start = begin + indentation(content);
+                    let len = if content.trim_start().starts_with(&prefix) {
+                        prefix.len()
+                    } else {
+                        trimmed_prefix.len()
+                    };
+                    (start..start + len, "")
+                })
+                .collect::<Vec<_>>()
+        } else {
+            let column = lines
+                .iter()
+                .map(|(_, content)| indentation(content))
+                .min()
+                .unwrap();
+            lines
+                .iter()
+                .map(|(begin, _)| (begin + column..begin + column, prefix.as_str()))
+                .collect::<Vec<_>>()
+        };
+        self.apply_edits(edits)
+    }
+    /// At the end of a line, the line break is killed instead (like in emacs).
+    fn kill_to_end_of_line(&mut self) -> OperationResult {
+        let end = self.text.line_end(self.cursor_pos);
+        let end = if end == self.cursor_pos {
+            self.text.next_grapheme_cluster(end)?
+        } else {
+            end
+        };
+        self.kill(self.cursor_pos..end)
+    }
+    fn kill_to_beginning_of_line(&mut self) -> OperationResult {
+        self.kill(self.text.line_begin(self.cursor_pos)..self.cursor_pos)
+    }
+    /// Only the current line is considered, i.e., this fails at the beginning of a line.
+    fn kill_word_backwards(&mut self) -> OperationResult {
+        let begin = self.text.line_begin(self.cursor_pos);
+        let before = self.text.slice(begin..self.cursor_pos).to_string();
+        let start = word_begin_before(&before).ok_or(())?;
+        self.kill(TextPosition(begin.0 + start)..self.cursor_pos)
+    }
+    fn yank(&mut self) -> OperationResult {
+        let text = self.kill_ring.latest().ok_or(())?;
+        let end = self.replace(self.cursor_pos..self.cursor_pos, &text);
+        self.set_cursor(end);
+        Ok(())
+    }
 }
 
 /// A `Widget` representing a `TextEdit`
@@ -725,6 +1882,9 @@ pub struct TextEditWidget<'a> {
     cursor_style_active_blink_on: StyleModifier,
     cursor_style_active_blink_off: StyleModifier,
     cursor_style_inactive: StyleModifier,
+    search_highlight: StyleModifier,
+    block_selection_style: StyleModifier,
+    annotation_styles: [StyleModifier; 4],
 }
 
 impl<'a> TextEditWidget<'a> {
@@ -747,6 +1907,71 @@ impl<'a> TextEditWidget<'a> {
         self.cursor_style_inactive = style;
         self
     }
+
+    /// Define the style of matches of the current search (default: bold and underlined).
+    pub fn search_highlight(mut self, style: StyleModifier) -> Self {
+        self.search_highlight = style;
+        self
+    }
+
+    /// Define the style of the block selection (default: inverted).
+    pub fn block_selection_style(mut self, style: StyleModifier) -> Self {
+        self.block_selection_style = style;
+        self
+    }
+
+    /// Define the style of annotations of the specified severity (see `TextEdit::annotate`).
+    pub fn annotation_style(mut self, severity: Severity, style: StyleModifier) -> Self {
+        self.annotation_styles[severity as usize] = style;
+        self
+    }
+
+    /// Get the styled byte ranges (annotations and search matches) in ascending priority.
+    fn styled_spans(&self) -> Vec<(Range<usize>, StyleModifier)> {
+        let mut spans = self
+            .textedit
+            .annotations
+            .iter()
+            .map(|a| (a.range.clone(), self.annotation_styles[a.severity as usize]))
+            .collect::<Vec<_>>();
+        if self.textedit.highlight_matches {
+            spans.extend(
+                self.textedit
+                    .search_matches()
+                    .into_iter()
+                    .map(|m| (m, self.search_highlight)),
+            );
+        }
+        if let Some(block) = self.textedit.block_selection() {
+            spans.extend(
+                self.textedit
+                    .block_ranges(block.lines, block.columns)
+                    .into_iter()
+                    .map(|r| (r, self.block_selection_style)),
+            );
+        }
+        spans
+    }
+
+    /// Write the text in `range` with the combined styles of all `spans` that contain each
+    /// cluster, or only the cursor style for the cluster at the cursor position (if specified).
+    fn write_text<T: CursorTarget>(
+        &self,
+        cursor: &mut Cursor<T>,
+        range: Range<TextPosition>,
+        spans: &[(Range<usize>, StyleModifier)],
+        text_cursor: Option<(TextPosition, StyleModifier)>,
+    ) {
+        let begin = range.start.0;
+        let text = self.textedit.text.slice(range).to_string();
+        cursor.write_styled(&text, |offset| match text_cursor {
+            Some((pos, style)) if pos.0 == begin + offset => style,
+            _ => spans
+                .iter()
+                .filter(|(r, _)| r.contains(&(begin + offset)))
+                .fold(StyleModifier::new(), |style, (_, s)| s.on_top_of(style)),
+        });
+    }
 }
 
 impl<'a> Widget for TextEditWidget<'a> {
@@ -777,7 +2002,6 @@ impl<'a> Widget for TextEditWidget<'a> {
             .filter(|a| a <= &line_end);
 
         let right_padding = 1;
-        let lower_padding = 1;
 
         let text_width_before_cursor = self
             .textedit
@@ -796,77 +2020,50 @@ impl<'a> Widget for TextEditWidget<'a> {
         };
 
         let current_line = self.textedit.text.line_index(self.textedit.cursor_pos);
-        let num_following_lines = self.textedit.text.num_lines() - current_line.raw_value() - 1;
-        let cursor_row = (height - 1 - lower_padding.min(num_following_lines as i32))
-            .min((current_line.raw_value() as i32).into())
-            .max(0.into())
-            .from_origin();
+        let cursor_row = self.textedit.cursor_row(height);
 
-        let mut cursor = Cursor::new(&mut window).position(draw_cursor_start_pos, cursor_row);
+        let spans = self.styled_spans();
+
+        let mut cursor = Cursor::new(&mut window)
+            .position(draw_cursor_start_pos, cursor_row)
+            .bidi(self.textedit.bidi);
         cursor.set_line_start_column(draw_cursor_start_pos);
 
-        use std::fmt::Write;
-        if let Some(after_cursor) = after_cursor {
-            let _ = write!(
-                cursor,
-                "{}",
-                self.textedit.text.slice(line_begin..before_cursor)
-            );
-            {
-                let mut cursor = cursor.save().style_modifier();
-                cursor.apply_style_modifier(cursor_style);
-                let _ = write!(
-                    cursor,
-                    "{}",
-                    self.textedit.text.slice(before_cursor..after_cursor)
-                );
-            }
-            let _ = write!(
-                cursor,
-                "{}",
-                self.textedit.text.slice(after_cursor..line_end)
+        if after_cursor.is_some() {
+            self.write_text(
+                &mut cursor,
+                line_begin..line_end,
+                &spans,
+                Some((before_cursor, cursor_style)),
             );
         } else {
-            let _ = write!(cursor, "{}", self.textedit.text.slice(line_begin..line_end));
-            {
-                let mut cursor = cursor.save().style_modifier();
-                cursor.apply_style_modifier(cursor_style);
-                cursor.write(" ");
-            }
+            self.write_text(&mut cursor, line_begin..line_end, &spans, None);
+            let mut cursor = cursor.save().style_modifier();
+            cursor.apply_style_modifier(cursor_style);
+            cursor.write(" ");
         }
         cursor.wrap_line();
 
         cursor.move_to_x(draw_cursor_start_pos);
-        for line in self
-            .textedit
-            .text
-            .slice(self.textedit.text.begin_of_line(current_line + 1)..self.textedit.text.end())
-            .lines()
-        {
+        for line in (current_line.raw_value() + 1)..self.textedit.text.num_lines() {
             if cursor.get_row() >= height.from_origin() {
                 break;
             }
-            let _ = writeln!(cursor, "{}", line);
+            let begin = self.textedit.text.begin_of_line(LineIndex::new(line));
+            let end = self.textedit.text.line_end(begin);
+            self.write_text(&mut cursor, begin..end, &spans, None);
+            cursor.wrap_line();
         }
 
         cursor.move_to_y(0.into());
         cursor.move_to_x(draw_cursor_start_pos);
         let num_rows_above = cursor_row.raw_value() as usize;
         assert!(num_rows_above <= current_line.raw_value());
-        let first_line_begin = self
-            .textedit
-            .text
-            .begin_of_line(current_line - num_rows_above);
-        let last_line_end = line_begin;
-        if current_line.raw_value() > 0 {
-            for line in self
-                .textedit
-                .text
-                .slice(first_line_begin..last_line_end)
-                .lines()
-            {
-                let _ = writeln!(cursor, "{}", line);
-            }
+        for line in (current_line.raw_value() - num_rows_above)..current_line.raw_value() {
+            let begin = self.textedit.text.begin_of_line(LineIndex::new(line));
+            let end = self.textedit.text.line_end(begin);
+            self.write_text(&mut cursor, begin..end, &spans, None);
+            cursor.wrap_line();
         }
     }
 }
@@ -958,6 +2155,125 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_line_col_conversion() {
+        let mut t = TextEdit::new();
+        t.set("ab\nce\u{301}沐d\n");
+        assert_eq!(t.num_lines(), 3);
+        assert_eq!(t.line(LineIndex::new(1)).unwrap(), "ce\u{301}沐d");
+        assert_eq!(t.line(LineIndex::new(2)).unwrap(), "");
+        assert!(t.line(LineIndex::new(3)).is_none());
+
+        assert_eq!(t.byte_to_position(0), Some((LineIndex::new(0), 0)));
+        assert_eq!(t.byte_to_position(2), Some((LineIndex::new(0), 2)));
+        assert_eq!(t.byte_to_position(3), Some((LineIndex::new(1), 0)));
+        assert_eq!(t.byte_to_position(5), Some((LineIndex::new(1), 1)));
+        assert_eq!(t.byte_to_position(7), Some((LineIndex::new(1), 2)));
+        assert_eq!(t.byte_to_position(10), Some((LineIndex::new(1), 3)));
+        assert_eq!(t.byte_to_position(12), Some((LineIndex::new(2), 0)));
+        assert_eq!(t.byte_to_position(13), None);
+
+        assert_eq!(t.position_to_byte(LineIndex::new(1), 2), Some(7));
+        assert_eq!(t.position_to_byte(LineIndex::new(1), 4), Some(11));
+        assert_eq!(t.position_to_byte(LineIndex::new(1), 5), None);
+        assert_eq!(t.position_to_byte(LineIndex::new(2), 0), Some(12));
+        assert_eq!(t.position_to_byte(LineIndex::new(3), 0), None);
+
+        assert_eq!(t.cursor_position(), (LineIndex::new(2), 0));
+        t.set_cursor_position(LineIndex::new(1), 3).unwrap();
+        assert_eq!(t.cursor_position(), (LineIndex::new(1), 3));
+        assert_eq!(t.cursor_byte_pos(), 10);
+        assert_eq!(t.get(TextTarget::cursor()..), "d\n");
+    }
+
+    #[test]
+    fn test_replace_range() {
+        test_textedit((8, 1), "ab xy*e*f_", |t| {
+            t.set("ab cd ef");
+            t.move_cursor_to(TextTarget::backward(TextElement::WordBegin))
+                .unwrap();
+            t.replace_range(
+                TextTarget::backward(TextElement::WordBegin)..TextTarget::cursor(),
+                "xy",
+            );
+        });
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let mut t = TextEdit::new();
+        t.set("ab cd ef");
+        t.move_cursor_to(TextTarget::backward(TextElement::WordBegin))
+            .unwrap();
+        t.apply_edits(vec![(6..8, "g"), (0..0, ">> "), (2..5, "")])
+            .unwrap();
+        assert_eq!(t.get(..), ">> ab g");
+        assert_eq!(t.get(TextTarget::cursor()..), "g");
+
+        t.apply_edits(vec![(3..7, "x")]).unwrap();
+        assert_eq!(t.get(..), ">> x");
+        assert_eq!(t.get(..TextTarget::cursor()), ">> x");
+
+        assert!(t.apply_edits(vec![(5..6, "")]).is_err());
+        assert!(t.apply_edits(vec![(0..2, ""), (1..3, "")]).is_err());
+        t.set("沐");
+        assert!(t.apply_edits(vec![(1..2, "")]).is_err());
+        assert_eq!(t.get(..), "沐");
+    }
+
+    #[test]
+    fn test_change_listener() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let mut t = TextEdit::new();
+        let c = changes.clone();
+        t.set_change_listener(move |change| c.borrow_mut().push(change.clone()));
+        t.set("abc");
+        t.write('d').unwrap();
+        t.move_cursor_left().unwrap();
+        t.delete_backwards().unwrap();
+        t.delete_forwards().unwrap();
+        t.apply_edits(vec![(0..1, "xy"), (1..2, "")]).unwrap();
+        t.clear().unwrap();
+        t.remove_change_listener();
+        t.set("foo");
+        assert_eq!(
+            *changes.borrow(),
+            vec![
+                TextChange {
+                    removed: 0..0,
+                    inserted: 3
+                },
+                TextChange {
+                    removed: 3..3,
+                    inserted: 1
+                },
+                TextChange {
+                    removed: 2..3,
+                    inserted: 0
+                },
+                TextChange {
+                    removed: 2..3,
+                    inserted: 0
+                },
+                TextChange {
+                    removed: 1..2,
+                    inserted: 0
+                },
+                TextChange {
+                    removed: 0..1,
+                    inserted: 2
+                },
+                TextChange {
+                    removed: 0..2,
+                    inserted: 0
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_single_line_simple() {
         test_textedit((5, 1), "abc* *_", |t| {
@@ -1087,6 +2403,189 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_search() {
+        let mut t = TextEdit::new();
+        t.set("ab\nxab\nab");
+        assert!(t.start_search("").is_err());
+        assert!(t.start_search("abc").is_err());
+        t.start_search("ab").unwrap();
+        assert_eq!(t.cursor_position(), (LineIndex::new(0), 0));
+        t.next_match().unwrap();
+        assert_eq!(t.cursor_position(), (LineIndex::new(1), 1));
+        t.next_match().unwrap();
+        t.next_match().unwrap();
+        assert_eq!(t.cursor_position(), (LineIndex::new(0), 0));
+        t.prev_match().unwrap();
+        assert_eq!(t.cursor_position(), (LineIndex::new(2), 0));
+
+        let draw = |t: &TextEdit, after: &str| {
+            let mut term = FakeTerminal::with_size((3, 3));
+            t.as_widget()
+                .cursor_blink_on(StyleModifier::new())
+                .search_highlight(StyleModifier::new().bold(true))
+                .draw(
+                    term.create_root_window(),
+                    RenderingHints::default().active(true).blink(Blink::On),
+                );
+            term.assert_looks_like(after);
+        };
+        draw(&t, "*a**b* |x*a**b*|a*b* ");
+        t.clear_highlights().unwrap();
+        assert!(t.clear_highlights().is_err());
+        draw(&t, "ab |xab|ab ");
+    }
+
+    #[test]
+    fn test_bidi() {
+        test_textedit((8, 1), "ab *ג*בא__", |t| {
+            t.set("ab אבג");
+            t.set_bidi(BidiMode::Auto);
+            t.set_cursor_movement(CursorMovement::Visual);
+            t.set_cursor_position(LineIndex::new(0), 1).unwrap();
+            t.move_right().unwrap();
+            t.move_right().unwrap();
+        });
+        test_textedit((8, 1), "ab גב*א*__", |t| {
+            t.set("ab אבג");
+            t.set_bidi(BidiMode::Auto);
+            t.set_cursor_position(LineIndex::new(0), 2).unwrap();
+            t.move_right().unwrap();
+        });
+        test_textedit((8, 1), "ab גבא* *_", |t| {
+            t.set("ab אבג");
+            t.set_bidi(BidiMode::Auto);
+            t.set_cursor_movement(CursorMovement::Visual);
+            t.set_cursor_position(LineIndex::new(0), 3).unwrap();
+            t.move_right().unwrap();
+            assert!(t.move_right().is_err());
+        });
+    }
+
+    #[test]
+    fn test_block_selection() {
+        let mut t = TextEdit::new();
+        t.set("abc\nx\nabc");
+        t.set_cursor_position(LineIndex::new(0), 1).unwrap();
+        t.start_block_selection();
+        t.move_down().unwrap();
+        t.move_down().unwrap();
+        t.move_right().unwrap();
+
+        let mut term = FakeTerminal::with_size((4, 3));
+        t.as_widget()
+            .cursor_blink_on(StyleModifier::new())
+            .block_selection_style(StyleModifier::new().bold(true))
+            .draw(
+                term.create_root_window(),
+                RenderingHints::default().active(true).blink(Blink::On),
+            );
+        term.assert_looks_like("a*b*c |x   |a*b*c ");
+
+        t.delete_backwards().unwrap();
+        assert_eq!(t.get(..), "ac\nx\nac");
+        t.write('Z').unwrap();
+        assert_eq!(t.get(..), "aZc\nxZ\naZc");
+        assert_eq!(t.cursor_position(), (LineIndex::new(2), 2));
+        t.delete_backwards().unwrap();
+        t.delete_backwards().unwrap();
+        assert_eq!(t.get(..), "c\n\nc");
+        assert!(t.delete_backwards().is_err());
+        t.cancel_block_selection();
+        assert!(t.block_selection().is_none());
+    }
+
+    #[test]
+    fn test_line_operations() {
+        let mut t = TextEdit::new();
+        t.set("a\nbc\nd");
+        t.set_cursor_position(LineIndex::new(1), 1).unwrap();
+        t.duplicate_lines().unwrap();
+        assert_eq!(t.get(..), "a\nbc\nbc\nd");
+        assert_eq!(t.cursor_position(), (LineIndex::new(2), 1));
+
+        t.move_lines_down().unwrap();
+        assert_eq!(t.get(..), "a\nbc\nd\nbc");
+        assert_eq!(t.cursor_position(), (LineIndex::new(3), 1));
+        assert!(t.move_lines_down().is_err());
+
+        t.set_cursor_position(LineIndex::new(1), 0).unwrap();
+        t.start_block_selection();
+        t.move_down().unwrap();
+        t.move_lines_up().unwrap();
+        assert_eq!(t.get(..), "bc\nd\na\nbc");
+        assert_eq!(t.block_selection().unwrap().lines, 0..2);
+        assert!(t.move_lines_up().is_err());
+
+        assert!(t.toggle_comment().is_err());
+        t.set_comment_prefix(Some("# ".to_owned()));
+        t.toggle_comment().unwrap();
+        assert_eq!(t.get(..), "# bc\n# d\na\nbc");
+        t.toggle_comment().unwrap();
+        assert_eq!(t.get(..), "bc\nd\na\nbc");
+
+        t.join_lines().unwrap();
+        assert_eq!(t.get(..), "bc d\na\nbc");
+        assert!(t.block_selection().is_none());
+        t.set("a\n  b\n\nc");
+        t.set_cursor_position(LineIndex::new(0), 0).unwrap();
+        t.join_lines().unwrap();
+        t.join_lines().unwrap();
+        assert_eq!(t.get(..), "a b\nc");
+    }
+
+    #[test]
+    fn test_annotations() {
+        let mut t = TextEdit::new();
+        t.set("ab cd ef");
+        assert!(t
+            .annotate(Annotation::new(2..2, Severity::Hint, ""))
+            .is_err());
+        assert!(t
+            .annotate(Annotation::new(6..9, Severity::Hint, ""))
+            .is_err());
+        t.annotate(Annotation::new(0..2, Severity::Hint, "a"))
+            .unwrap();
+        t.annotate(Annotation::new(3..5, Severity::Error, "c"))
+            .unwrap();
+        t.annotate(Annotation::new(6..8, Severity::Error, "e"))
+            .unwrap();
+
+        t.replace_range(
+            TextTarget::backward(TextElement::WordBegin).nth(2)..TextTarget::cursor(),
+            "xyz",
+        );
+        assert_eq!(t.get(..), "ab xyz");
+        assert_eq!(
+            t.annotations()
+                .iter()
+                .map(|a| a.range.clone())
+                .collect::<Vec<_>>(),
+            vec![0..2]
+        );
+        t.set_cursor_position(LineIndex::new(0), 0).unwrap();
+        t.insert("_");
+        t.set_cursor_position(LineIndex::new(0), 2).unwrap();
+        t.insert("_");
+        assert_eq!(t.get(..), "_a_b xyz");
+        assert_eq!(t.annotations()[0].range, 1..4);
+        assert_eq!(t.annotations_at_cursor().next().unwrap().message, "a");
+        t.set_cursor_position(LineIndex::new(0), 0).unwrap();
+        assert_eq!(t.annotations_at_cursor().count(), 0);
+        t.annotate(Annotation::new(5..7, Severity::Error, "x"))
+            .unwrap();
+
+        let mut term = FakeTerminal::with_size((9, 1));
+        t.as_widget()
+            .cursor_blink_on(StyleModifier::new())
+            .annotation_style(Severity::Error, StyleModifier::new().bold(true))
+            .draw(
+                term.create_root_window(),
+                RenderingHints::default().active(true).blink(Blink::On),
+            );
+        term.assert_looks_like("_a_b *x**y*z ");
+    }
+
     #[test]
     fn test_multi_line_simple() {
         test_textedit((2, 5), "a_|b_|c_|d_|e* *", |t| {
@@ -1205,6 +2704,36 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_multi_line_goal_column() {
+        test_textedit((5, 3), "abcd* *|x____|abcd_", |t| {
+            t.set("abcd\nx\nabcd");
+            t.move_cursor_up().unwrap();
+            t.move_cursor_up().unwrap();
+        });
+
+        test_textedit((5, 3), "a*b*cd_|x____|abc__", |t| {
+            t.set("abcd\nx\nabc");
+            t.move_cursor_up().unwrap();
+            t.move_cursor_left().unwrap();
+            t.move_cursor_down().unwrap();
+            t.move_cursor_right().unwrap();
+            t.move_cursor_up().unwrap();
+            t.move_cursor_up().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_multi_line_goal_column_wide() {
+        let mut t = TextEdit::new();
+        t.set("沐沐\nabcd");
+        t.move_cursor_left().unwrap();
+        t.move_cursor_up().unwrap();
+        assert_eq!(t.get(..TextTarget::cursor()), "沐");
+        t.move_cursor_down().unwrap();
+        assert_eq!(t.get(..TextTarget::cursor()), "沐沐\nabc");
+    }
+
     #[test]
     fn test_move_big_word_begin_forward() {
         test_textedit((6, 1), "abc *d*e", |t| {
@@ -1512,4 +3041,247 @@ mod test {
                 .is_err());
         });
     }
+
+    #[test]
+    fn test_word_classifier_custom() {
+        let mut t = TextEdit::new();
+        t.set("(foo-bar baz)");
+        t.go_to_beginning_of_line().unwrap();
+        t.move_cursor_right().unwrap();
+        assert_eq!(t.get(..TextTarget::forward(TextElement::WordBegin)), "(foo");
+        t.set_word_classifier(|s| match s {
+            "-" => ClusterType::Keyword,
+            " " => ClusterType::Whitespace,
+            s if s.chars().all(char::is_alphanumeric) => ClusterType::Keyword,
+            _ => ClusterType::Other,
+        });
+        assert_eq!(
+            t.get(..TextTarget::forward(TextElement::WordBegin)),
+            "(foo-bar "
+        );
+        t.reset_word_classifier();
+        assert_eq!(t.get(..TextTarget::forward(TextElement::WordBegin)), "(foo");
+    }
+
+    #[test]
+    fn test_word_classifier_unicode() {
+        let mut t = TextEdit::new();
+        t.set("cafe\u{301} au lait");
+        t.go_to_beginning_of_line().unwrap();
+        assert_eq!(
+            t.get(..TextTarget::forward(TextElement::WordBegin)),
+            "cafe\u{301} "
+        );
+
+        t.set("沐沐 abc");
+        t.go_to_beginning_of_line().unwrap();
+        assert_eq!(t.get(..TextTarget::forward(TextElement::WordBegin)), "沐");
+        assert_eq!(
+            t.get(..TextTarget::forward(TextElement::WordBegin).nth(2)),
+            "沐沐 "
+        );
+    }
+
+    #[test]
+    fn test_move_sentence_punctuation() {
+        test_textedit((11, 1), "Hi! *O*k? Yes", |t| {
+            t.set("Hi! Ok? Yes");
+            t.go_to_beginning_of_line().unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::Sentence))
+                .unwrap();
+        });
+        test_textedit((11, 1), "Hi! Ok? *Y*es", |t| {
+            t.set("Hi! Ok? Yes");
+            t.go_to_beginning_of_line().unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::Sentence).nth(2))
+                .unwrap();
+        });
+        test_textedit((11, 1), "Hi! *O*k? Yes", |t| {
+            t.set("Hi! Ok? Yes");
+            t.move_cursor_to(TextTarget::backward(TextElement::Sentence).nth(2))
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_move_sentence_closing_quotes() {
+        test_textedit((13, 1), "a \"b.\" (c!) *d*", |t| {
+            t.set("a \"b.\" (c!) d");
+            t.go_to_beginning_of_line().unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::Sentence).nth(2))
+                .unwrap();
+        });
+        test_textedit((13, 1), "a \"b.\" *(*c!) d", |t| {
+            t.set("a \"b.\" (c!) d");
+            t.move_cursor_to(TextTarget::backward(TextElement::Sentence).nth(2))
+                .unwrap();
+        });
+        test_textedit((10, 1), "a (b) c. *d*", |t| {
+            t.set("a (b) c. d");
+            t.go_to_beginning_of_line().unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::Sentence))
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_move_paragraph_forward() {
+        test_textedit((3, 6), "ab_|___|*c*d_|ef_|___|gh_", |t| {
+            t.set("ab\n\ncd\nef\n\ngh");
+            t.move_cursor_to(TextTarget::backward(TextElement::DocumentBoundary))
+                .unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::Paragraph))
+                .unwrap();
+        });
+        test_textedit((3, 6), "ab_|___|cd_|ef_|___|*g*h_", |t| {
+            t.set("ab\n\ncd\nef\n\ngh");
+            t.move_cursor_to(TextTarget::backward(TextElement::DocumentBoundary))
+                .unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::Paragraph).nth(2))
+                .unwrap();
+        });
+        test_textedit((3, 6), "ab_|___|cd_|ef_|___|gh* *", |t| {
+            t.set("ab\n\ncd\nef\n\ngh");
+            t.move_cursor_to(TextTarget::backward(TextElement::DocumentBoundary))
+                .unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::Paragraph).nth(3))
+                .unwrap();
+            assert!(t
+                .move_cursor_to(TextTarget::forward(TextElement::Paragraph))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_move_paragraph_backward() {
+        test_textedit((3, 6), "ab_|___|cd_|ef_|___|*g*h_", |t| {
+            t.set("ab\n\ncd\nef\n\ngh");
+            t.move_cursor_to(TextTarget::backward(TextElement::Paragraph))
+                .unwrap();
+        });
+        test_textedit((3, 6), "ab_|___|*c*d_|ef_|___|gh_", |t| {
+            t.set("ab\n\ncd\nef\n\ngh");
+            t.move_cursor_to(TextTarget::backward(TextElement::Paragraph).nth(2))
+                .unwrap();
+        });
+        test_textedit((3, 6), "*a*b_|___|cd_|ef_|___|gh_", |t| {
+            t.set("ab\n\ncd\nef\n\ngh");
+            t.move_cursor_to(TextTarget::backward(TextElement::Paragraph).nth(4))
+                .unwrap();
+            assert!(t
+                .move_cursor_to(TextTarget::backward(TextElement::Paragraph))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_move_matching_bracket_forward() {
+        test_textedit((9, 1), "f(a[b]c*)*_", |t| {
+            t.set("f(a[b]c)");
+            t.go_to_beginning_of_line().unwrap();
+            t.move_cursor_right().unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::MatchingBracket))
+                .unwrap();
+        });
+        test_textedit((9, 1), "f(a[b*]*c)_", |t| {
+            t.set("f(a[b]c)");
+            t.go_to_beginning_of_line().unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::GraphemeCluster).nth(4))
+                .unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::MatchingBracket))
+                .unwrap();
+        });
+        test_textedit((6, 1), "(a]b*)*_", |t| {
+            t.set("(a]b)");
+            t.go_to_beginning_of_line().unwrap();
+            t.move_cursor_to(TextTarget::forward(TextElement::MatchingBracket))
+                .unwrap();
+        });
+        test_textedit((9, 1), "*f*(a[b]c)_", |t| {
+            t.set("f(a[b]c)");
+            t.go_to_beginning_of_line().unwrap();
+            assert!(t
+                .move_cursor_to(TextTarget::forward(TextElement::MatchingBracket))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_move_matching_bracket_backward() {
+        test_textedit((9, 1), "f*(*a[b]c)_", |t| {
+            t.set("f(a[b]c)");
+            t.move_cursor_left().unwrap();
+            t.move_cursor_to(TextTarget::backward(TextElement::MatchingBracket))
+                .unwrap();
+        });
+        test_textedit((9, 1), "f*(*a[b]c)_", |t| {
+            t.set("f(a[b]c)");
+            t.move_cursor_to(TextTarget::backward(TextElement::GraphemeCluster).nth(2))
+                .unwrap();
+            t.move_cursor_to(TextTarget::backward(TextElement::MatchingBracket))
+                .unwrap();
+        });
+        test_textedit((9, 1), "f(a[b]c)* *", |t| {
+            t.set("f(a[b]c)");
+            assert!(t
+                .move_cursor_to(TextTarget::backward(TextElement::MatchingBracket))
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        test_textedit((6, 1), "ab*c*___", |t| {
+            t.set("abc");
+            t.move_cursor_to(TextTarget::backward(TextElement::GraphemeCluster))
+                .unwrap();
+            t.delete_backwards().unwrap();
+            t.write('x').unwrap();
+            t.undo().unwrap();
+            assert_eq!(t.get(..), "ac");
+            t.undo().unwrap();
+        });
+        test_textedit((6, 1), "ab* *___", |t| {
+            t.set("a b");
+            t.apply_edits(vec![(0..1, "c"), (2..3, "d")]).unwrap();
+            t.undo().unwrap();
+            assert_eq!(t.get(..), "a b");
+            assert!(t.redo().is_ok());
+            assert_eq!(t.get(..), "c d");
+            t.undo().unwrap();
+            t.undo().unwrap();
+            assert!(t.undo().is_err());
+            t.set_undo_capacity(0);
+            t.set("ab");
+            assert!(t.undo().is_err());
+        });
+    }
+    #[test]
+    fn test_kill_and_yank() {
+        test_textedit((6, 1), "ab* *___", |t| {
+            let ring = t.kill_ring();
+            t.set("foo bar  ");
+            t.kill_word_backwards().unwrap();
+            assert_eq!(t.get(..), "foo ");
+            assert_eq!(ring.latest(), Some("bar  ".to_owned()));
+            t.go_to_beginning_of_line().unwrap();
+            t.kill_to_end_of_line().unwrap();
+            assert!(t.kill_to_end_of_line().is_err());
+            assert_eq!(ring.latest(), Some("foo ".to_owned()));
+
+            t.set("a\nb");
+            t.kill_to_beginning_of_line().unwrap();
+            assert!(t.kill_word_backwards().is_err());
+            t.move_up().unwrap();
+            t.go_to_end_of_line().unwrap();
+            t.kill_to_end_of_line().unwrap();
+            assert_eq!(t.get(..), "a");
+            ring.rotate();
+            t.yank().unwrap();
+            assert_eq!(t.get(..), "ab");
+            t.undo().unwrap();
+            assert_eq!(t.get(..), "a");
+            t.yank().unwrap();
+        });
+    }
 }
@@ -0,0 +1,334 @@
+//! A chart of time-stamped values, e.g., for live metrics in dashboards.
+use base::basic_types::*;
+use base::{Cursor, StyleModifier, Window};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use widget::builtin::BrailleCanvas;
+use widget::{text_width, Demand, Demand2D, RenderingHints, Widget};
+
+/// How the samples of a `TimeSeries` are displayed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChartMode {
+    /// Connect consecutive samples with lines.
+    Line,
+    /// Additionally fill the area below the line.
+    Area,
+}
+
+/// A series of time-stamped samples of which only the most recent ones (within a time window) are
+/// kept.
+///
+/// # Examples:
+/// ```
+/// use unsegen::widget::builtin::TimeSeries;
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let mut series = TimeSeries::new(Duration::from_secs(10));
+/// series.push(start, 3.0);
+/// series.push(start + Duration::from_secs(5), 1.0);
+/// series.push(start + Duration::from_secs(12), 2.0); // The first sample is aged out
+///
+/// assert_eq!(series.len(), 2);
+/// assert_eq!(series.min(), Some(1.0));
+/// assert_eq!(series.max(), Some(2.0));
+/// assert_eq!(series.last(), Some(2.0));
+/// ```
+pub struct TimeSeries {
+    samples: VecDeque<(Instant, f64)>,
+    window: Duration,
+}
+
+impl TimeSeries {
+    /// Create an empty series that keeps samples that are at most `window` older than the most
+    /// recent one.
+    pub fn new(window: Duration) -> Self {
+        TimeSeries {
+            samples: VecDeque::new(),
+            window,
+        }
+    }
+
+    /// Get the duration of the time window.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Add a sample and drop all samples that are older than the time window. Samples are expected
+    /// to be pushed in chronological order.
+    pub fn push(&mut self, timestamp: Instant, value: f64) {
+        self.samples.push_back((timestamp, value));
+        while let Some(&(t, _)) = self.samples.front() {
+            if timestamp.duration_since(t) > self.window {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Add a sample with the current time as timestamp.
+    pub fn push_now(&mut self, value: f64) {
+        self.push(Instant::now(), value);
+    }
+
+    /// Remove all samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Get the number of samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Check whether there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Iterate over all samples in chronological order.
+    pub fn samples(&self) -> impl Iterator<Item = &(Instant, f64)> {
+        self.samples.iter()
+    }
+
+    /// Get the smallest value of all samples.
+    pub fn min(&self) -> Option<f64> {
+        self.samples
+            .iter()
+            .map(|s| s.1)
+            .fold(None, |m, v| Some(m.map_or(v, |m: f64| m.min(v))))
+    }
+
+    /// Get the largest value of all samples.
+    pub fn max(&self) -> Option<f64> {
+        self.samples
+            .iter()
+            .map(|s| s.1)
+            .fold(None, |m, v| Some(m.map_or(v, |m: f64| m.max(v))))
+    }
+
+    /// Get the value of the most recent sample.
+    pub fn last(&self) -> Option<f64> {
+        self.samples.back().map(|s| s.1)
+    }
+
+    /// Prepare for drawing as a `Widget`.
+    pub fn as_widget<'a>(&'a self) -> TimeSeriesWidget<'a> {
+        TimeSeriesWidget {
+            series: self,
+            mode: ChartMode::Line,
+            precision: 1,
+            end: None,
+            range: None,
+            chart_style: StyleModifier::new(),
+            label_style: StyleModifier::new(),
+        }
+    }
+}
+
+/// A `Widget` representing a `TimeSeries` as a braille chart.
+///
+/// The first line shows the minimum, maximum and last value. Below that, the chart is drawn with
+/// the value range labeled on the left and the time range at the bottom.
+pub struct TimeSeriesWidget<'a> {
+    series: &'a TimeSeries,
+    mode: ChartMode,
+    precision: usize,
+    end: Option<Instant>,
+    range: Option<(f64, f64)>,
+    chart_style: StyleModifier,
+    label_style: StyleModifier,
+}
+
+impl<'a> TimeSeriesWidget<'a> {
+    /// Set how the samples are displayed (default: `ChartMode::Line`).
+    pub fn mode(mut self, mode: ChartMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the number of digits after the decimal point for labels and readouts (default: 1).
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Set the time at the right border of the chart (default: timestamp of the most recent
+    /// sample). Use `Instant::now()` to let the chart scroll even if no new samples arrive.
+    pub fn end(mut self, end: Instant) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Use a fixed value range instead of the minimum and maximum of the samples.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Define the style of the chart.
+    pub fn chart(mut self, style: StyleModifier) -> Self {
+        self.chart_style = style;
+        self
+    }
+
+    /// Define the style of labels and readouts.
+    pub fn labels(mut self, style: StyleModifier) -> Self {
+        self.label_style = style;
+        self
+    }
+
+    fn format(&self, value: Option<f64>) -> String {
+        value
+            .map(|v| format!("{:.*}", self.precision, v))
+            .unwrap_or_else(|| "-".to_owned())
+    }
+
+    fn value_range(&self) -> Option<(f64, f64)> {
+        self.range
+            .or_else(|| Some((self.series.min()?, self.series.max()?)))
+    }
+
+    fn draw_chart(&self, mut window: Window) {
+        let (min, max) = match self.value_range() {
+            Some(range) => range,
+            None => return,
+        };
+        let end = match self.end.or_else(|| self.series.samples.back().map(|s| s.0)) {
+            Some(end) => end,
+            None => return,
+        };
+        let mut canvas = BrailleCanvas::new(window.get_width(), window.get_height());
+        let max_x = canvas.pixel_width() as f64 - 1.0;
+        let max_y = canvas.pixel_height() as f64 - 1.0;
+        let window_secs = self.series.window.as_secs_f64();
+        let to_pixel = |&(t, v): &(Instant, f64)| {
+            let age = end
+                .checked_duration_since(t)
+                .unwrap_or_default()
+                .as_secs_f64();
+            let x = if window_secs > 0.0 {
+                (1.0 - age / window_secs) * max_x
+            } else {
+                max_x
+            };
+            let rel = if max > min {
+                ((v - min) / (max - min)).clamp(0.0, 1.0)
+            } else {
+                0.5
+            };
+            (x.round() as i32, ((1.0 - rel) * max_y).round() as i32)
+        };
+
+        let points = self.series.samples.iter().map(to_pixel).collect::<Vec<_>>();
+        if let Some(&(x, y)) = points.first() {
+            canvas.set(x, y);
+        }
+        for w in points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+            canvas.line(x0, y0, x1, y1);
+            if self.mode == ChartMode::Area {
+                for x in x0..=x1 {
+                    let y = if x1 > x0 {
+                        y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+                    } else {
+                        y0.max(y1)
+                    };
+                    canvas.line(x, y, x, max_y as i32);
+                }
+            }
+        }
+        window.modify_default_style(self.chart_style);
+        canvas.draw(window, RenderingHints::new());
+    }
+}
+
+impl<'a> Widget for TimeSeriesWidget<'a> {
+    fn space_demand(&self) -> Demand2D {
+        Demand2D {
+            width: Demand::at_least(10),
+            height: Demand::at_least(3),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let series = self.series;
+        let (min, max) = match self.value_range() {
+            Some((min, max)) => (Some(min), Some(max)),
+            None => (None, None),
+        };
+        let max_label = self.format(max);
+        let min_label = self.format(min);
+        let label_width = text_width(&max_label).max(text_width(&min_label)) + 1;
+
+        let width = window.get_width();
+        let height = window.get_height().raw_value();
+        if height < 3 || width <= label_width {
+            self.draw_chart(window);
+            return;
+        }
+
+        {
+            let mut cursor = Cursor::new(&mut window).style_modifier(self.label_style);
+            cursor.write(&format!(
+                "min {} max {} last {}",
+                self.format(series.min()),
+                self.format(series.max()),
+                self.format(series.last())
+            ));
+            cursor.move_to(ColIndex::new(0), RowIndex::new(1));
+            cursor.write(&max_label);
+            cursor.move_to(ColIndex::new(0), RowIndex::new(height - 2));
+            cursor.write(&min_label);
+
+            let window_secs = series.window.as_secs_f64();
+            let begin_label = if window_secs >= 1.0 {
+                format!("-{}s", series.window.as_secs())
+            } else {
+                format!("-{}ms", series.window.as_millis())
+            };
+            cursor.move_to(label_width.from_origin(), RowIndex::new(height - 1));
+            cursor.write(&begin_label);
+            cursor.move_to(
+                (width - text_width("now")).from_origin(),
+                RowIndex::new(height - 1),
+            );
+            cursor.write("now");
+        }
+
+        let chart = window.create_subwindow(
+            label_width.from_origin()..,
+            RowIndex::new(1)..RowIndex::new(height - 1),
+        );
+        self.draw_chart(chart);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+
+    #[test]
+    fn test_time_series_draw() {
+        let start = Instant::now();
+        let mut series = TimeSeries::new(Duration::from_secs(3));
+        for (i, v) in [0.0, 3.0, 0.0, 3.0].iter().enumerate() {
+            series.push(start + Duration::from_secs(i as u64), *v);
+        }
+        let mut term = FakeTerminal::with_size((20, 4));
+        series
+            .as_widget()
+            .precision(0)
+            .draw(term.create_root_window(), RenderingHints::new());
+        term.assert_looks_like(
+            &[
+                "min 0 max 3 last 3  ",
+                "3    ⣀⠤⠒⠑⠢⣀     ⢀⡠⠔⠊",
+                "0 ⡠⠔⠊      ⠉⠢⢄⠤⠒⠁   ",
+                "  -3s            now",
+            ]
+            .join("|"),
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! A single line of text that is shortened with an ellipsis if space is insufficient.
+use base::{Cursor, Window};
+use widget::{
+    text_width, truncate_to_width, Demand, Demand2D, RenderingHints, TruncationPosition, Widget,
+};
+
+/// A single line of text that is shortened with an ellipsis (see `truncate_to_width`) instead of
+/// being clipped if the window is too narrow, e.g., for paths in an `HLayout` or a `Table`.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::terminal::test::FakeTerminal;
+/// use unsegen::widget::builtin::TruncatedLabel;
+/// use unsegen::widget::*;
+///
+/// let mut term = FakeTerminal::with_size((8, 1));
+/// TruncatedLabel::new("src/widget/mod.rs")
+///     .position(TruncationPosition::Middle)
+///     .draw(term.create_root_window(), RenderingHints::new());
+/// term.assert_looks_like("src/….rs");
+/// ```
+pub struct TruncatedLabel<'a> {
+    text: &'a str,
+    position: TruncationPosition,
+}
+
+impl<'a> TruncatedLabel<'a> {
+    /// Display `text`, which should not contain newlines.
+    pub fn new(text: &'a str) -> Self {
+        TruncatedLabel {
+            text,
+            position: TruncationPosition::End,
+        }
+    }
+
+    /// Specify where the text is shortened (default: at the end).
+    pub fn position(mut self, position: TruncationPosition) -> Self {
+        self.position = position;
+        self
+    }
+}
+
+impl<'a> Widget for TruncatedLabel<'a> {
+    fn space_demand(&self) -> Demand2D {
+        let width = text_width(self.text).raw_value() as usize;
+        Demand2D {
+            width: Demand::from_to(width.min(1), width),
+            height: Demand::exact(1),
+        }
+    }
+    fn draw(&self, mut window: Window, _hints: RenderingHints) {
+        let text = truncate_to_width(self.text, window.get_width(), self.position);
+        Cursor::new(&mut window).write(&text);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+    use widget::HLayout;
+
+    #[test]
+    fn test_truncated_label_in_layout() {
+        let mut term = FakeTerminal::with_size((10, 1));
+        HLayout::new()
+            .widget(TruncatedLabel::new("abcdefgh"))
+            .widget(TruncatedLabel::new("12345678").position(TruncationPosition::Start))
+            .draw(term.create_root_window(), RenderingHints::new());
+        term.assert_looks_like("abcd……5678");
+    }
+}
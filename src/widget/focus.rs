@@ -0,0 +1,221 @@
+//! Keep track of the focused widget among several widgets (e.g., within a single container).
+//!
+//! # Example:
+//! ```
+//! use unsegen::base::*;
+//! use unsegen::input::*;
+//! use unsegen::widget::*;
+//! use unsegen::widget::builtin::*;
+//!
+//! #[derive(Clone, PartialEq, Debug)]
+//! enum Part {
+//!     Prompt,
+//!     Details,
+//! }
+//!
+//! let mut focus = FocusChain::new(vec![Part::Prompt, Part::Details]);
+//! let mut prompt = LineEdit::new();
+//! let details = "Some details";
+//!
+//! for input in Input::read_all(&b"\tab\tc"[..]) {
+//!     input
+//!         .unwrap()
+//!         .chain(focus.traversal_behavior())
+//!         .chain(focus.route(|part, input| match part {
+//!             Part::Prompt => input.chain(EditBehavior::new(&mut prompt)).finish(),
+//!             Part::Details => Some(input),
+//!         }));
+//! }
+//! assert_eq!(prompt.get(), "c");
+//!
+//! let mut term = unsegen::base::terminal::test::FakeTerminal::with_size((12, 2));
+//! let layout = VLayout::new()
+//!     .widget(focus.widget(&Part::Prompt, prompt.as_widget()))
+//!     .widget(focus.widget(&Part::Details, details));
+//! layout.draw(term.create_root_window(), RenderingHints::new());
+//! ```
+use base::{Width, Window};
+use input::{Behavior, Input, Key, OperationResult, ToEvent};
+use std::collections::HashSet;
+use widget::{Demand2D, RenderingHints, RowDemand, Widget};
+
+/// Tracks which of a number of widgets (identified by `I`) is focused, i.e., receives input and is
+/// drawn as `active`.
+///
+/// The order of the identifiers specifies the traversal order (see `traversal_behavior`).
+#[derive(Clone, Debug)]
+pub struct FocusChain<I: Clone + PartialEq> {
+    order: Vec<I>,
+    focused: usize,
+}
+
+impl<I: Clone + PartialEq> FocusChain<I> {
+    /// Create a focus chain from identifiers in traversal order. Initially, the first one is
+    /// focused.
+    ///
+    /// Panics if `order` is empty.
+    pub fn new(order: Vec<I>) -> Self {
+        assert!(
+            !order.is_empty(),
+            "FocusChain requires at least one element"
+        );
+        FocusChain { order, focused: 0 }
+    }
+
+    /// Get the identifier of the currently focused widget.
+    pub fn focused(&self) -> &I {
+        &self.order[self.focused]
+    }
+
+    /// Check whether the specified widget is focused.
+    pub fn is_focused(&self, index: &I) -> bool {
+        self.focused() == index
+    }
+
+    /// Focus the specified widget. Fails if `index` is not part of the chain.
+    pub fn focus(&mut self, index: &I) -> OperationResult {
+        let pos = self.order.iter().position(|i| i == index).ok_or(())?;
+        self.focused = pos;
+        Ok(())
+    }
+
+    /// Focus the next widget in traversal order, wrapping around at the end.
+    pub fn focus_next(&mut self) {
+        self.focused = (self.focused + 1) % self.order.len();
+    }
+
+    /// Focus the previous widget in traversal order, wrapping around at the beginning.
+    pub fn focus_prev(&mut self) {
+        self.focused = (self.focused + self.order.len() - 1) % self.order.len();
+    }
+
+    /// Create a `Behavior` that changes the focus. By default, `Tab` focuses the next and
+    /// `Shift-Tab` the previous widget.
+    pub fn traversal_behavior(&mut self) -> FocusTraversalBehavior<'_, I> {
+        FocusTraversalBehavior {
+            chain: self,
+            next_on: HashSet::new(),
+            prev_on: HashSet::new(),
+        }
+    }
+
+    /// Create a `Behavior` that passes input to `f` along with the identifier of the currently
+    /// focused widget.
+    pub fn route<'a, F: FnOnce(&I, Input) -> Option<Input> + 'a>(
+        &'a self,
+        f: F,
+    ) -> impl Behavior + 'a {
+        move |input| f(self.focused(), input)
+    }
+
+    /// Adapt the `RenderingHints` for the specified widget, i.e., only the focused widget is drawn
+    /// as `active` (if `hints` are active at all).
+    pub fn hints(&self, index: &I, hints: RenderingHints) -> RenderingHints {
+        hints.active(hints.active && self.is_focused(index))
+    }
+
+    /// Wrap the widget (identified by `index`) so that it receives the appropriate `active` hint
+    /// when drawn (see `hints`).
+    pub fn widget<W: Widget>(&self, index: &I, widget: W) -> FocusedWidget<W> {
+        FocusedWidget {
+            widget,
+            focused: self.is_focused(index),
+        }
+    }
+}
+
+/// A widget that is only drawn as active if focused. See `FocusChain::widget`.
+pub struct FocusedWidget<W> {
+    widget: W,
+    focused: bool,
+}
+
+impl<W: Widget> Widget for FocusedWidget<W> {
+    fn space_demand(&self) -> Demand2D {
+        self.widget.space_demand()
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.widget.height_for_width(width)
+    }
+    fn draw(&self, window: Window, hints: RenderingHints) {
+        self.widget
+            .draw(window, hints.active(hints.active && self.focused));
+    }
+}
+
+/// Change the focus of a `FocusChain` in response to input. See
+/// `FocusChain::traversal_behavior`.
+pub struct FocusTraversalBehavior<'a, I: Clone + PartialEq + 'a> {
+    chain: &'a mut FocusChain<I>,
+    next_on: HashSet<::input::Event>,
+    prev_on: HashSet<::input::Event>,
+}
+
+impl<'a, I: Clone + PartialEq + 'a> FocusTraversalBehavior<'a, I> {
+    /// Focus the next widget on the specified event (instead of `Tab`).
+    pub fn next_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.next_on.insert(event.to_event());
+        self
+    }
+
+    /// Focus the previous widget on the specified event (instead of `Shift-Tab`).
+    pub fn prev_on<E: ToEvent>(mut self, event: E) -> Self {
+        self.prev_on.insert(event.to_event());
+        self
+    }
+}
+
+impl<'a, I: Clone + PartialEq + 'a> Behavior for FocusTraversalBehavior<'a, I> {
+    fn input(mut self, input: Input) -> Option<Input> {
+        if self.next_on.is_empty() && self.prev_on.is_empty() {
+            self.next_on.insert(Key::Char('\t').to_event());
+            self.prev_on.insert(Key::BackTab.to_event());
+        }
+        if self.next_on.contains(&input.event) {
+            self.chain.focus_next();
+            None
+        } else if self.prev_on.contains(&input.event) {
+            self.chain.focus_prev();
+            None
+        } else {
+            Some(input)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(k: Key) -> Input {
+        Input::new(k)
+    }
+
+    #[test]
+    fn test_focus_chain_traversal() {
+        let mut focus = FocusChain::new(vec!['a', 'b', 'c']);
+        assert_eq!(*focus.focused(), 'a');
+        assert!(focus
+            .traversal_behavior()
+            .input(key(Key::BackTab))
+            .is_none());
+        assert_eq!(*focus.focused(), 'c');
+        assert!(focus
+            .traversal_behavior()
+            .input(key(Key::Char('\t')))
+            .is_none());
+        assert_eq!(*focus.focused(), 'a');
+        assert!(focus
+            .traversal_behavior()
+            .next_on(Key::Right)
+            .input(key(Key::Char('\t')))
+            .is_some());
+        assert!(focus.focus(&'d').is_err());
+        focus.focus(&'b').unwrap();
+
+        let hints = RenderingHints::new();
+        assert!(focus.hints(&'b', hints).active);
+        assert!(!focus.hints(&'a', hints).active);
+        assert!(!focus.hints(&'b', hints.active(false)).active);
+    }
+}
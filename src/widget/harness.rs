@@ -0,0 +1,139 @@
+//! A deterministic test harness for the input handling and rendering of widgets.
+//!
+//! A `Harness` owns the state of a widget (e.g., a `TextEdit`), feeds scripted key presses (see
+//! `input::keys`) through the behaviors of the test and renders the widget to a `FakeTerminal`.
+//!
+//! # Example:
+//! ```
+//! use unsegen::input::*;
+//! use unsegen::widget::builtin::LineEdit;
+//! use unsegen::widget::harness::Harness;
+//! use unsegen::widget::*;
+//!
+//! let mut harness = Harness::new(LineEdit::new(), (6, 1));
+//! let report = harness.feed("abc<Left><BS><C-x>", |line, input| {
+//!     input
+//!         .chain(
+//!             EditBehavior::new(line)
+//!                 .left_on(Key::Left)
+//!                 .delete_backwards_on(Key::Backspace),
+//!         )
+//!         .finish()
+//! });
+//! report.assert_passed_through("<C-x>");
+//! assert_eq!(harness.state.get(), "ac");
+//! harness.assert_looks_like("a*c*    ", |line, window, hints| {
+//!     line.as_widget()
+//!         .cursor_blink_on(unsegen::base::StyleModifier::new().bold(true))
+//!         .draw(window, hints)
+//! });
+//! ```
+use base::test::FakeTerminal;
+use base::Window;
+use input::{keys, Event, Input};
+use widget::{Blink, RenderingHints};
+
+/// Owns the state of a widget under test, see the module documentation.
+pub struct Harness<S> {
+    /// The state of the widget under test.
+    pub state: S,
+    size: (u32, u32),
+    hints: RenderingHints,
+}
+
+impl<S> Harness<S> {
+    /// Create a harness that renders to a fake terminal of the specified (width, height).
+    pub fn new(state: S, size: (u32, u32)) -> Self {
+        Harness {
+            state,
+            size,
+            hints: RenderingHints::new().active(true).blink(Blink::On),
+        }
+    }
+
+    /// Set the hints used for rendering (default: active, blink on).
+    pub fn hints(mut self, hints: RenderingHints) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    /// Pass the inputs described by `key_description` (see `input::keys`) one after another to
+    /// `handle`, which usually chains the behaviors under test and returns the unconsumed input.
+    pub fn feed<F: FnMut(&mut S, Input) -> Option<Input>>(
+        &mut self,
+        key_description: &str,
+        mut handle: F,
+    ) -> FeedReport {
+        FeedReport {
+            inputs: keys(key_description)
+                .into_iter()
+                .map(|input| {
+                    let event = input.event.clone();
+                    (event, handle(&mut self.state, input).is_none())
+                })
+                .collect(),
+        }
+    }
+
+    /// Render the state using `draw` to a new fake terminal.
+    pub fn render<F: FnOnce(&S, Window, RenderingHints)>(&self, draw: F) -> FakeTerminal {
+        let mut term = FakeTerminal::with_size(self.size);
+        draw(&self.state, term.create_root_window(), self.hints);
+        term
+    }
+
+    /// Render the state using `draw` and check that the result looks like `expected` (see
+    /// `FakeTerminal::assert_looks_like`).
+    pub fn assert_looks_like<F: FnOnce(&S, Window, RenderingHints)>(
+        &self,
+        expected: &str,
+        draw: F,
+    ) {
+        self.render(draw).assert_looks_like(expected);
+    }
+}
+
+/// Records which of the inputs passed to `Harness::feed` were consumed.
+#[derive(Clone, Debug)]
+pub struct FeedReport {
+    inputs: Vec<(Event, bool)>,
+}
+
+impl FeedReport {
+    /// The events that were consumed.
+    pub fn consumed(&self) -> Vec<Event> {
+        self.filter(true)
+    }
+
+    /// The events that were passed through (i.e., not consumed).
+    pub fn passed_through(&self) -> Vec<Event> {
+        self.filter(false)
+    }
+
+    fn filter(&self, consumed: bool) -> Vec<Event> {
+        self.inputs
+            .iter()
+            .filter(|(_, c)| *c == consumed)
+            .map(|(e, _)| e.clone())
+            .collect()
+    }
+
+    /// Check that all events were consumed.
+    pub fn assert_all_consumed(&self) {
+        assert_eq!(
+            self.passed_through(),
+            Vec::new(),
+            "Not all events were consumed"
+        );
+    }
+
+    /// Check that exactly the events described by `key_description` (see `input::keys`) were
+    /// passed through.
+    pub fn assert_passed_through(&self, key_description: &str) {
+        let expected = keys(key_description)
+            .into_iter()
+            .map(|i| i.event)
+            .collect::<Vec<_>>();
+        assert_eq!(self.passed_through(), expected);
+    }
+}
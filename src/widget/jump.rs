@@ -0,0 +1,290 @@
+//! Keyboard navigation by typing short labels that are displayed on top of the possible targets
+//! (e.g., containers or the visible rows of a list).
+//!
+//! `ContainerManager::start_jump_mode` uses this to activate containers. For other targets, start
+//! a `JumpMode` with the positions of the labels, draw it on top of the targets and pass input to
+//! its `behavior` until a target is selected.
+//!
+//! # Example:
+//! ```
+//! use unsegen::base::*;
+//! use unsegen::base::terminal::test::FakeTerminal;
+//! use unsegen::input::*;
+//! use unsegen::widget::*;
+//!
+//! let items = ["foo", "bar", "baz"];
+//! let mut selected = None;
+//! let mut jump = JumpMode::new();
+//!
+//! // Label the visible rows of the list:
+//! jump.start(
+//!     (0..items.len())
+//!         .map(|i| (i, ColIndex::new(0), RowIndex::new(i as i32)))
+//!         .collect(),
+//! );
+//!
+//! let mut term = FakeTerminal::with_size((3, 3));
+//! {
+//!     let mut window = term.create_root_window();
+//!     for (i, item) in items.iter().enumerate() {
+//!         Cursor::new(&mut window)
+//!             .position(ColIndex::new(0), RowIndex::new(i as i32))
+//!             .write(item);
+//!     }
+//!     jump.draw(&mut window);
+//! }
+//! term.assert_looks_like("*a*oo|*b*ar|*c*az");
+//!
+//! Input::new(Key::Char('c'))
+//!     .chain(jump.behavior(|i| selected = Some(i)))
+//!     .finish();
+//! assert_eq!(selected, Some(2));
+//! assert!(!jump.is_active());
+//! ```
+use base::basic_types::*;
+use base::{Cursor, StyleModifier, Window};
+use input::{Behavior, Event, Input, Key};
+
+/// Create `n` distinct labels of equal length from the characters of `alphabet`, i.e., no label
+/// is a prefix of another one.
+///
+/// Labels consist of a single character if `n` does not exceed the size of the alphabet, of two
+/// characters if `n` does not exceed its square and so on.
+///
+/// Panics if `alphabet` contains less than two characters and `n` is greater than its size.
+///
+/// # Examples:
+/// ```
+/// use unsegen::widget::jump_labels;
+///
+/// assert_eq!(jump_labels(3, &['a', 'b']), vec!["aa", "ab", "ba"]);
+/// ```
+pub fn jump_labels(n: usize, alphabet: &[char]) -> Vec<String> {
+    let base = alphabet.len();
+    let mut length = 1;
+    let mut capacity = base;
+    while capacity < n {
+        assert!(base > 1, "Alphabet too small for {} labels", n);
+        length += 1;
+        capacity = capacity.saturating_mul(base);
+    }
+    (0..n)
+        .map(|i| {
+            let mut digits = vec![alphabet[0]; length];
+            let mut rest = i;
+            for digit in digits.iter_mut().rev() {
+                *digit = alphabet[rest % base];
+                rest /= base;
+            }
+            digits.into_iter().collect()
+        })
+        .collect()
+}
+
+/// The outcome of passing input to an active `JumpMode`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JumpResult<T> {
+    /// The typed characters are a prefix of several labels, so more input is required.
+    Pending,
+    /// The label of the target has been typed completely. Jump mode has ended.
+    Selected(T),
+    /// The input did not match any label (or was not a character at all). Jump mode has ended.
+    Cancelled,
+}
+
+struct JumpTarget<T> {
+    label: String,
+    target: T,
+    x: ColIndex,
+    y: RowIndex,
+}
+
+/// Labels for a number of targets (identified by `T`), one of which is selected by typing its
+/// label.
+///
+/// While active, the labels are drawn on top of the targets (see `draw`) and all input should be
+/// passed to the jump mode (see `behavior`). The labels of targets that can no longer be selected
+/// using the characters typed so far are hidden.
+pub struct JumpMode<T> {
+    alphabet: Vec<char>,
+    style: StyleModifier,
+    targets: Vec<JumpTarget<T>>,
+    typed: String,
+}
+
+impl<T> Default for JumpMode<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> JumpMode<T> {
+    /// Create an inactive jump mode with labels made from the characters `a` to `z`.
+    pub fn new() -> Self {
+        JumpMode {
+            alphabet: ('a'..='z').collect(),
+            style: StyleModifier::new().invert(true).bold(true),
+            targets: Vec::new(),
+            typed: String::new(),
+        }
+    }
+
+    /// Create labels from the characters of `alphabet` instead (e.g., the home row of the
+    /// keyboard). The alphabet should consist of at least two distinct characters.
+    pub fn alphabet(mut self, alphabet: &str) -> Self {
+        self.alphabet = alphabet.chars().collect();
+        self
+    }
+
+    /// Specify the style of the labels (default: bold and inverted).
+    pub fn style(mut self, style: StyleModifier) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Activate jump mode for the targets, each with the position of its label. Labels are
+    /// assigned in the order of the targets. An active jump mode is restarted.
+    pub fn start(&mut self, targets: Vec<(T, ColIndex, RowIndex)>) {
+        let labels = jump_labels(targets.len(), &self.alphabet);
+        self.targets = targets
+            .into_iter()
+            .zip(labels)
+            .map(|((target, x, y), label)| JumpTarget {
+                label,
+                target,
+                x,
+                y,
+            })
+            .collect();
+        self.typed.clear();
+    }
+
+    /// Check whether jump mode is active, i.e., waiting for a label to be typed.
+    pub fn is_active(&self) -> bool {
+        !self.targets.is_empty()
+    }
+
+    /// End jump mode without selecting a target.
+    pub fn cancel(&mut self) {
+        self.targets.clear();
+        self.typed.clear();
+    }
+
+    /// Get the label of the specified target while jump mode is active.
+    pub fn label(&self, target: &T) -> Option<&str>
+    where
+        T: PartialEq,
+    {
+        self.targets
+            .iter()
+            .find(|t| t.target == *target)
+            .map(|t| t.label.as_str())
+    }
+
+    /// React to the input: Characters are matched against the labels, any other input cancels
+    /// jump mode. Returns `JumpResult::Cancelled` if jump mode is not active.
+    pub fn input(&mut self, input: &Input) -> JumpResult<T> {
+        let c = match input.event {
+            Event::Key(Key::Char(c)) if self.is_active() => c,
+            _ => {
+                self.cancel();
+                return JumpResult::Cancelled;
+            }
+        };
+        self.typed.push(c);
+        let typed = &self.typed;
+        self.targets.retain(|t| t.label.starts_with(typed.as_str()));
+        match self.targets.iter().position(|t| t.label == *typed) {
+            Some(pos) => {
+                let selected = self.targets.swap_remove(pos);
+                self.cancel();
+                JumpResult::Selected(selected.target)
+            }
+            None if self.targets.is_empty() => {
+                self.cancel();
+                JumpResult::Cancelled
+            }
+            None => JumpResult::Pending,
+        }
+    }
+
+    /// Create a `Behavior` that consumes all input while jump mode is active and calls
+    /// `on_select` once a target has been selected. Input is passed on if jump mode is not
+    /// active.
+    pub fn behavior<F: FnOnce(T)>(&mut self, on_select: F) -> JumpBehavior<'_, T, F> {
+        JumpBehavior {
+            jump: self,
+            on_select,
+        }
+    }
+
+    /// Draw the labels (without the characters that have already been typed) onto the window.
+    pub fn draw(&self, window: &mut Window) {
+        for target in &self.targets {
+            Cursor::new(window)
+                .position(target.x, target.y)
+                .style_modifier(self.style)
+                .write(&target.label[self.typed.len()..]);
+        }
+    }
+}
+
+/// Select a target of a `JumpMode` in response to input. See `JumpMode::behavior`.
+pub struct JumpBehavior<'a, T: 'a, F> {
+    jump: &'a mut JumpMode<T>,
+    on_select: F,
+}
+
+impl<'a, T: 'a, F: FnOnce(T)> Behavior for JumpBehavior<'a, T, F> {
+    fn input(self, input: Input) -> Option<Input> {
+        if !self.jump.is_active() {
+            return Some(input);
+        }
+        if let JumpResult::Selected(target) = self.jump.input(&input) {
+            (self.on_select)(target);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(c: char) -> Input {
+        Input::new(Key::Char(c))
+    }
+
+    #[test]
+    fn test_jump_labels() {
+        assert_eq!(jump_labels(0, &['a']), Vec::<String>::new());
+        assert_eq!(jump_labels(2, &['a', 'b', 'c']), vec!["a", "b"]);
+        let labels = jump_labels(27, &('a'..='z').collect::<Vec<_>>());
+        assert_eq!(labels[0], "aa");
+        assert_eq!(labels[26], "ba");
+    }
+
+    #[test]
+    fn test_jump_mode_multi_char_labels() {
+        let mut jump = JumpMode::new().alphabet("xy");
+        jump.start(
+            (0..3)
+                .map(|i| (i, ColIndex::new(0), RowIndex::new(i)))
+                .collect(),
+        );
+        assert_eq!(jump.label(&2), Some("yx"));
+        assert_eq!(jump.input(&key('y')), JumpResult::Pending);
+        assert_eq!(jump.label(&0), None);
+        assert_eq!(jump.input(&key('x')), JumpResult::Selected(2));
+        assert!(!jump.is_active());
+
+        jump.start(vec![(0, ColIndex::new(0), RowIndex::new(0))]);
+        assert_eq!(jump.input(&key('z')), JumpResult::Cancelled);
+        jump.start(vec![(0, ColIndex::new(0), RowIndex::new(0))]);
+        assert_eq!(jump.input(&Input::new(Key::Esc)), JumpResult::Cancelled);
+        assert!(jump
+            .behavior(|_| panic!("inactive"))
+            .input(key('x'))
+            .is_some());
+    }
+}
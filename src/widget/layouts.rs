@@ -1,7 +1,7 @@
 //! Basic linear layouting for `Widget`s.
 use super::{ColDemand, Demand, Demand2D, RenderingHints, RowDemand, Widget};
 use base::basic_types::*;
-use base::{GraphemeCluster, StyleModifier, Window};
+use base::{rendering_profile, GraphemeCluster, StyleModifier, Window};
 use std::cmp::Ord;
 use std::fmt::Debug;
 
@@ -15,15 +15,64 @@ use std::fmt::Debug;
 /// 2. Every demands minimum should be honored.
 /// 3. Each demand should be treated equally, but the assigned length shall not exceed the maximum.
 /// 4. All space will be distributed.
+///
+/// Demands that request a fraction of the available space (see `Demand::fraction`) are assigned
+/// their share (clamped to their minimum and maximum) before all other demands.
 pub fn layout_linearly<T: AxisDimension + Ord + Debug + Clone>(
     available_space: PositiveAxisDiff<T>,
     separator_width: PositiveAxisDiff<T>,
     demands: &[Demand<T>],
     weights: &[f64],
 ) -> Box<[PositiveAxisDiff<T>]> {
-    //eprintln!("av {}, sep {}, dem, {:?}", available_space, separator_width, demands);
     assert_eq!(demands.len(), weights.len());
 
+    if demands.iter().all(|d| d.fraction.is_none()) {
+        return distribute_linearly(available_space, separator_width, demands, weights);
+    }
+
+    let num_separators = demands.len().saturating_sub(1);
+    let total = available_space.saturating_sub(separator_width.saturating_mul(num_separators));
+    let mut remaining = total;
+    let mut assigned = vec![None; demands.len()];
+    for (i, demand) in demands.iter().enumerate() {
+        if let Some(fraction) = demand.fraction {
+            let share =
+                PositiveAxisDiff::new_unchecked((total.raw_value() as f64 * fraction) as i32);
+            let share = share
+                .max(demand.min)
+                .min(demand.max_or_unbounded())
+                .min(remaining);
+            remaining = remaining.saturating_sub(share);
+            assigned[i] = Some(share);
+        }
+    }
+
+    let others = (0..demands.len())
+        .filter(|&i| assigned[i].is_none())
+        .collect::<Vec<_>>();
+    let other_spaces = distribute_linearly(
+        remaining,
+        PositiveAxisDiff::new_unchecked(0),
+        &others.iter().map(|&i| demands[i]).collect::<Vec<_>>(),
+        &others.iter().map(|&i| weights[i]).collect::<Vec<_>>(),
+    );
+    for (&i, &space) in others.iter().zip(other_spaces.iter()) {
+        assigned[i] = Some(space);
+    }
+    assigned
+        .into_iter()
+        .map(|a| a.expect("all demands assigned"))
+        .collect()
+}
+
+fn distribute_linearly<T: AxisDimension + Ord + Debug + Clone>(
+    available_space: PositiveAxisDiff<T>,
+    separator_width: PositiveAxisDiff<T>,
+    demands: &[Demand<T>],
+    weights: &[f64],
+) -> Box<[PositiveAxisDiff<T>]> {
+    //eprintln!("av {}, sep {}, dem, {:?}", available_space, separator_width, demands);
+
     let mut assigned_spaces = vec![0.0; demands.len()].into_boxed_slice();
 
     struct DemandF {
@@ -40,7 +89,7 @@ pub fn layout_linearly<T: AxisDimension + Ord + Debug + Clone>(
         .collect::<Vec<_>>();
 
     // Reserve space for separators
-    let diff = available_space - separator_width * demands.len().saturating_sub(1);
+    let diff = available_space - separator_width.saturating_mul(demands.len().saturating_sub(1));
     if diff < 0 {
         return vec![PositiveAxisDiff::new(0).unwrap(); demands.len()].into_boxed_slice();
     }
@@ -174,7 +223,10 @@ pub fn layout_linearly<T: AxisDimension + Ord + Debug + Clone>(
         .collect::<Vec<_>>();
 
     let total_assigned: PositiveAxisDiff<T> = assigned_int.iter().sum();
-    let total_demand: AxisDiff<T> = demands.iter().map(|d| AxisDiff::new(d.max as i32)).sum();
+    let total_demand: AxisDiff<T> = demands
+        .iter()
+        .map(|d| AxisDiff::new(d.max as i32))
+        .fold(AxisDiff::new(0), AxisDiff::saturating_add);
     let mut still_to_assign = (diff - total_assigned).min(total_demand);
 
     // Distribute spaces accumulated through rounding errors
@@ -223,20 +275,17 @@ fn draw_linearly<'a, T: AxisDimension + Ord + Debug + Copy, S, L, M, D>(
     split: S,
     window_length: L,
     separator_length: M,
-    demand_dimension: D,
+    demand: D,
 ) where
     S: Fn(Window, AxisIndex<T>) -> (Window, Window),
     L: Fn(&Window) -> PositiveAxisDiff<T>,
     M: Fn(&SeparatingStyle) -> PositiveAxisDiff<T>,
-    D: Fn(Demand2D) -> Demand<T>,
+    D: Fn(&(dyn Widget + 'a)) -> Demand<T>,
 {
     assert_eq!(widgets.len(), weights.len());
     assert_eq!(widgets.len(), rendering_hints.len());
     let separator_length = separator_length(separating_style);
-    let demands: Vec<Demand<T>> = widgets
-        .iter()
-        .map(|w| demand_dimension(w.space_demand()))
-        .collect();
+    let demands: Vec<Demand<T>> = widgets.iter().map(|w| demand(w.as_ref())).collect();
     let assigned_spaces = layout_linearly(
         window_length(&window),
         separator_length,
@@ -351,6 +400,25 @@ impl<'a> Widget for HLayout<'a> {
             height: total_y,
         }
     }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        let demands = self
+            .widgets
+            .iter()
+            .map(|w| w.space_demand().width)
+            .collect::<Vec<_>>();
+        let widths = layout_linearly(
+            width,
+            self.separating_style.width(),
+            &demands,
+            &self.weights,
+        );
+        self.widgets
+            .iter()
+            .zip(widths.iter())
+            .fold(RowDemand::exact(0), |total, (w, &width)| {
+                total.max(w.height_for_width(width))
+            })
+    }
     fn draw(&self, window: Window, hints: RenderingHints) {
         let hints = std::iter::repeat(hints)
             .take(self.widgets.len())
@@ -364,7 +432,7 @@ impl<'a> Widget for HLayout<'a> {
             |w, p| w.split(p).expect("valid split pos"),
             |w| w.get_width(),
             SeparatingStyle::width,
-            |d| d.width,
+            |w| w.space_demand().width,
         );
     }
 }
@@ -447,8 +515,24 @@ impl<'a> Widget for VLayout<'a> {
         }
     }
 
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        let mut total = self
+            .widgets
+            .iter()
+            .map(|w| w.height_for_width(width))
+            .sum::<RowDemand>();
+        if let SeparatingStyle::Draw(_) = self.separating_style {
+            total += Demand::exact(self.widgets.len());
+        }
+        total
+    }
+
     /// Draw the given widgets to the window, from top to bottom.
+    ///
+    /// The heights of the widgets are assigned according to their demand at the width of the
+    /// window (see `Widget::height_for_width`).
     fn draw(&self, window: Window, hints: RenderingHints) {
+        let width = window.get_width();
         let hints = std::iter::repeat(hints)
             .take(self.widgets.len())
             .collect::<Vec<_>>();
@@ -461,7 +545,7 @@ impl<'a> Widget for VLayout<'a> {
             |w, p| w.split(p).expect("valid split pos"),
             |w| w.get_height(),
             SeparatingStyle::height,
-            |d| d.height,
+            |w| w.height_for_width(width),
         );
     }
 }
@@ -495,6 +579,21 @@ impl SeparatingStyle {
             &SeparatingStyle::Draw(_) => Height::new(1).unwrap(),
         }
     }
+
+    /// Draw vertical lines between the widgets of a horizontal layout ('│', or '|' with the ASCII
+    /// `RenderingProfile`).
+    pub fn vertical_line() -> Self {
+        SeparatingStyle::Draw(
+            GraphemeCluster::try_from(rendering_profile().select('│', '|')).unwrap(),
+        )
+    }
+    /// Draw horizontal lines between the widgets of a vertical layout ('─', or '-' with the ASCII
+    /// `RenderingProfile`).
+    pub fn horizontal_line() -> Self {
+        SeparatingStyle::Draw(
+            GraphemeCluster::try_from(rendering_profile().select('─', '-')).unwrap(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -587,6 +686,79 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_layout_linearly_fraction() {
+        assert_eq_boxed_slices(
+            ll_unweighted(
+                w(12),
+                w(0),
+                &[
+                    Demand::fraction(0.5),
+                    Demand::at_least(1),
+                    Demand::at_least(1),
+                ],
+            ),
+            Box::new([6, 3, 3]),
+            "half",
+        );
+        assert_eq_boxed_slices(
+            ll_unweighted(
+                w(13),
+                w(1),
+                &[
+                    Demand::at_least(1),
+                    Demand::fraction(1.0 / 3.0),
+                    Demand::exact(2),
+                ],
+            ),
+            Box::new([6, 3, 2]),
+            "third with separators",
+        );
+        assert_eq_boxed_slices(
+            ll_unweighted(
+                w(10),
+                w(0),
+                &[
+                    Demand::fraction(0.1).max(Demand::exact(4)),
+                    Demand::at_least(0),
+                ],
+            ),
+            Box::new([5, 5]),
+            "fraction combined with exact is dropped",
+        );
+        assert_eq_boxed_slices(
+            ll_unweighted(w(10), w(0), &[Demand::fraction(0.8), Demand::fraction(0.8)]),
+            Box::new([8, 2]),
+            "overcommitted",
+        );
+    }
+
+    #[test]
+    fn test_layout_linearly_unbounded() {
+        let unbounded = Demand::from_to(w(0), Width::UNBOUNDED);
+        assert_eq_boxed_slices(
+            ll_unweighted(w(10), w(1), &[unbounded, unbounded, unbounded]),
+            Box::new([3, 3, 2]),
+            "huge maximum",
+        );
+        let huge = Demand::exact(Width::UNBOUNDED);
+        assert_eq_boxed_slices(
+            ll_unweighted(w(10), w(0), &[huge, huge]),
+            Box::new([5, 5]),
+            "huge minimum",
+        );
+        assert_eq_boxed_slices(
+            ll_unweighted(w(10), Width::UNBOUNDED, &[huge, huge]),
+            Box::new([0, 0]),
+            "huge separator",
+        );
+        assert_eq!(
+            (0..3).map(|_| huge).sum::<ColDemand>(),
+            huge,
+            "saturating sum"
+        );
+    }
+
     #[test]
     fn test_layout_linearly_weighted_less_than_min() {
         assert_eq_boxed_slices(
@@ -1023,6 +1195,20 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_vertical_layout_height_for_width() {
+        let layout = VLayout::new().widget("aa bb").widget("c");
+        assert_eq!(layout.space_demand().height, Demand::exact(2));
+        assert_eq!(layout.height_for_width(w(3)), Demand::exact(3));
+
+        let mut term = FakeTerminal::with_size((3, 4));
+        layout.draw(term.create_root_window(), RenderingHints::default());
+        term.assert_looks_like("aa |bb |c  |   ");
+
+        let layout = HLayout::new().widget("a b").widget("cdef");
+        assert_eq!(layout.height_for_width(w(4)), Demand::exact(2));
+    }
+
     #[test]
     fn fuzz_layout_linearly() {
         let fuzz_iterations = 10000;
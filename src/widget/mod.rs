@@ -44,12 +44,24 @@
 //! }
 //! ```
 pub mod builtin;
+pub mod focus;
+pub mod harness;
+pub mod jump;
 pub mod layouts;
+pub mod provider;
+pub mod styling;
+pub mod tailer;
 pub mod widget;
 
+pub use self::focus::*;
+pub use self::jump::*;
 pub use self::layouts::*;
+pub use self::provider::*;
+pub use self::styling::*;
+pub use self::tailer::*;
 pub use self::widget::*;
 use super::base::*;
+use std::borrow::Cow;
 
 /// Count the number of grapheme clusters in the given string.
 ///
@@ -61,8 +73,79 @@ pub fn count_grapheme_clusters(text: &str) -> usize {
 
 /// Calculate the (monospace) width of the given string.
 ///
-/// A thin convenience wrapper around unicode_width.
+/// The width is the sum of the widths of all grapheme clusters (see `cluster_width`), i.e., the
+/// number of cells that a `Cursor` occupies when writing the string.
 pub fn text_width(text: &str) -> Width {
-    use unicode_width::UnicodeWidthStr;
-    Width::new(UnicodeWidthStr::width(text) as _).unwrap()
+    let width = if text.is_ascii() {
+        ::unicode_width::UnicodeWidthStr::width(text)
+    } else {
+        GraphemeCluster::all_slices_from_str(text)
+            .map(cluster_width)
+            .sum()
+    };
+    Width::new(width as _).unwrap()
+}
+
+/// The position at which `truncate_to_width` removes text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TruncationPosition {
+    /// Keep the end of the text, e.g., "…/src/main.rs".
+    Start,
+    /// Keep the start and the end of the text, e.g., "/home/…/main.rs".
+    Middle,
+    /// Keep the start of the text, e.g., "/home/user/…".
+    End,
+}
+
+/// Shorten `text` so that it fits into `width` cells by replacing (a part of) it with an ellipsis
+/// ('…') at the specified position. Text that fits is returned unchanged.
+///
+/// Only complete grapheme clusters are removed, so the result may be narrower than `width` if
+/// wide clusters are removed.
+///
+/// # Examples:
+/// ```
+/// use unsegen::base::Width;
+/// use unsegen::widget::{truncate_to_width, TruncationPosition};
+///
+/// let path = "very/long/path/file.rs";
+/// let width = Width::new(16).unwrap();
+/// assert_eq!(truncate_to_width(path, width, TruncationPosition::Start), "…ng/path/file.rs");
+/// assert_eq!(truncate_to_width(path, width, TruncationPosition::Middle), "very/lon…file.rs");
+/// assert_eq!(truncate_to_width(path, width, TruncationPosition::End), "very/long/path/…");
+/// assert_eq!(truncate_to_width("沐沐沐", Width::new(4).unwrap(), TruncationPosition::End), "沐…");
+/// ```
+pub fn truncate_to_width(text: &str, width: Width, position: TruncationPosition) -> Cow<'_, str> {
+    if text_width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    if width == 0 {
+        return Cow::Borrowed("");
+    }
+    let budget = width.raw_value() as usize - 1;
+    let clusters = GraphemeCluster::all_slices_from_str(text).collect::<Vec<_>>();
+    let take = |budget: usize, clusters: &mut dyn Iterator<Item = &&str>| {
+        let mut used = 0;
+        let mut count = 0;
+        for cluster in clusters {
+            let w = cluster_width(cluster);
+            if used + w > budget {
+                break;
+            }
+            used += w;
+            count += 1;
+        }
+        (count, used)
+    };
+    let (head_budget, tail_budget) = match position {
+        TruncationPosition::Start => (0, budget),
+        TruncationPosition::Middle => (budget - budget / 2, budget / 2),
+        TruncationPosition::End => (budget, 0),
+    };
+    let (head, used) = take(head_budget, &mut clusters.iter());
+    let (tail, _) = take(tail_budget + head_budget - used, &mut clusters.iter().rev());
+    let mut res = clusters[..head].concat();
+    res.push('…');
+    res.push_str(&clusters[clusters.len() - tail..].concat());
+    Cow::Owned(res)
 }
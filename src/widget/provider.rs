@@ -0,0 +1,129 @@
+//! Publish data from background threads for display in widgets.
+//!
+//! A `DataPublisher` can be moved to a different thread (e.g., one that periodically queries a
+//! database) and used to publish new values. The corresponding `DataProvider` stays with the
+//! widget. The draw loop can cheaply check whether the value has `changed` and `take` it to
+//! update the widget.
+//!
+//! Only the most recent value is kept: Values that have been replaced before the widget side had a
+//! chance to take them are dropped.
+//!
+//! # Example:
+//! ```
+//! use unsegen::widget::data_provider;
+//! use std::thread;
+//!
+//! let (publisher, mut provider) = data_provider();
+//! thread::spawn(move || {
+//!     publisher.publish(vec!["some", "query", "result"]);
+//! })
+//! .join()
+//! .unwrap();
+//!
+//! assert!(provider.changed());
+//! assert_eq!(provider.take(), Some(vec!["some", "query", "result"]));
+//! assert!(!provider.changed());
+//! ```
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    value: Mutex<Option<T>>,
+    changed: AtomicBool,
+    connected: AtomicBool,
+}
+
+/// Create a connected pair of `DataPublisher` (for the producing side) and `DataProvider` (for the
+/// widget side).
+pub fn data_provider<T>() -> (DataPublisher<T>, DataProvider<T>) {
+    let shared = Arc::new(Shared {
+        value: Mutex::new(None),
+        changed: AtomicBool::new(false),
+        connected: AtomicBool::new(true),
+    });
+    (
+        DataPublisher {
+            shared: shared.clone(),
+        },
+        DataProvider { shared },
+    )
+}
+
+/// The producing side of a `data_provider`. Can be cloned to publish from multiple threads.
+pub struct DataPublisher<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for DataPublisher<T> {
+    fn clone(&self) -> Self {
+        DataPublisher {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> DataPublisher<T> {
+    /// Publish a new value, replacing any value that has not been taken by the `DataProvider` yet.
+    pub fn publish(&self, value: T) {
+        let mut slot = self.shared.value.lock().unwrap();
+        *slot = Some(value);
+        self.shared.changed.store(true, Ordering::Release);
+    }
+
+    /// Check whether the `DataProvider` still exists, i.e., if there is any point in publishing
+    /// further values.
+    pub fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::Acquire)
+    }
+}
+
+/// The widget side of a `data_provider`.
+pub struct DataProvider<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> DataProvider<T> {
+    /// Check whether a new value has been published since the last call to `take`. This does not
+    /// require any locking and can thus be called on every iteration of the draw loop.
+    pub fn changed(&self) -> bool {
+        self.shared.changed.load(Ordering::Acquire)
+    }
+
+    /// Take the most recently published value, if a new one is available.
+    pub fn take(&mut self) -> Option<T> {
+        if !self.changed() {
+            return None;
+        }
+        let mut value = self.shared.value.lock().unwrap();
+        self.shared.changed.store(false, Ordering::Release);
+        value.take()
+    }
+}
+
+impl<T> Drop for DataProvider<T> {
+    fn drop(&mut self) {
+        self.shared.connected.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_data_provider_keeps_latest() {
+        let (publisher, mut provider) = data_provider();
+        assert!(!provider.changed());
+        assert_eq!(provider.take(), None);
+
+        publisher.publish(1);
+        publisher.clone().publish(2);
+        assert!(provider.changed());
+        assert_eq!(provider.take(), Some(2));
+        assert_eq!(provider.take(), None);
+
+        assert!(publisher.is_connected());
+        drop(provider);
+        assert!(!publisher.is_connected());
+    }
+}
@@ -0,0 +1,276 @@
+//! Rule based styling of lines of text, e.g., to highlight errors in a `LogViewer`.
+//!
+//! # Example:
+//! ```
+//! use unsegen::base::{Color, StyleModifier};
+//! use unsegen::widget::{StylingRule, StylingRules};
+//!
+//! let rules = StylingRules::new()
+//!     .rule(StylingRule::line("ERROR", StyleModifier::new().fg_color(Color::Red)))
+//!     .rule(StylingRule::spans("ERROR", StyleModifier::new().bold(true)).priority(1))
+//!     // Anything that finds ranges in a line (e.g., a precompiled regex) can be used as a pattern.
+//!     .rule(StylingRule::spans(
+//!         |line: &str| line.find(char::is_numeric).map(|i| i..i + 1).into_iter().collect(),
+//!         StyleModifier::new().underline(true),
+//!     ));
+//!
+//! let spans = rules.spans("1 ERROR");
+//! assert_eq!(spans.len(), 3);
+//! ```
+use base::{Cursor, CursorTarget, StyleModifier};
+use std::ops::Range;
+
+/// Something that finds (byte) ranges in a line of text that should be styled.
+///
+/// Implemented for string literals (finding all non-overlapping occurrences) and for closures,
+/// so that, e.g., a regular expression can be used by wrapping it in a closure. Expensive
+/// preparation (like compiling a regular expression) should happen once when creating the pattern,
+/// not when it is called.
+pub trait LinePattern {
+    /// Find all ranges of the line that match the pattern.
+    fn find_all(&self, line: &str) -> Vec<Range<usize>>;
+}
+
+impl<F: Fn(&str) -> Vec<Range<usize>>> LinePattern for F {
+    fn find_all(&self, line: &str) -> Vec<Range<usize>> {
+        self(line)
+    }
+}
+
+impl LinePattern for &'static str {
+    fn find_all(&self, line: &str) -> Vec<Range<usize>> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        line.match_indices(*self)
+            .map(|(i, m)| i..i + m.len())
+            .collect()
+    }
+}
+
+impl LinePattern for String {
+    fn find_all(&self, line: &str) -> Vec<Range<usize>> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        line.match_indices(self.as_str())
+            .map(|(i, m)| i..i + m.len())
+            .collect()
+    }
+}
+
+/// A single rule of `StylingRules`: A pattern and the style that is applied to its matches (or to
+/// the whole line if it matches).
+pub struct StylingRule {
+    pattern: Box<dyn LinePattern>,
+    style: StyleModifier,
+    whole_line: bool,
+    priority: i32,
+}
+
+impl StylingRule {
+    /// Apply `style` to all ranges of a line that match the pattern.
+    pub fn spans<P: LinePattern + 'static>(pattern: P, style: StyleModifier) -> Self {
+        StylingRule {
+            pattern: Box::new(pattern),
+            style,
+            whole_line: false,
+            priority: 0,
+        }
+    }
+
+    /// Apply `style` to the whole line if the pattern matches anywhere in the line.
+    pub fn line<P: LinePattern + 'static>(pattern: P, style: StyleModifier) -> Self {
+        StylingRule {
+            whole_line: true,
+            ..Self::spans(pattern, style)
+        }
+    }
+
+    /// Set the priority of the rule (default: 0). Styles of rules with higher priority are applied
+    /// on top of those with lower priority. Rules of equal priority are applied in the order they
+    /// were added.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// An ordered list of `StylingRule`s that are applied to lines of text.
+///
+/// To limit the cost of styling very long lines, only a prefix of each line is matched against
+/// the patterns (see `max_line_length`) and the number of styled ranges per line is capped (see
+/// `max_spans`).
+pub struct StylingRules {
+    rules: Vec<StylingRule>,
+    max_line_length: usize,
+    max_spans: usize,
+}
+
+impl Default for StylingRules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StylingRules {
+    /// Create an empty set of rules.
+    pub fn new() -> Self {
+        StylingRules {
+            rules: Vec::new(),
+            max_line_length: 4096,
+            max_spans: 256,
+        }
+    }
+
+    /// Add a rule.
+    pub fn rule(mut self, rule: StylingRule) -> Self {
+        let pos = self
+            .rules
+            .iter()
+            .position(|r| r.priority > rule.priority)
+            .unwrap_or(self.rules.len());
+        self.rules.insert(pos, rule);
+        self
+    }
+
+    /// Only match (and style) the first `max` bytes of each line (default: 4096).
+    pub fn max_line_length(mut self, max: usize) -> Self {
+        self.max_line_length = max;
+        self
+    }
+
+    /// Stop evaluating rules for a line once `max` ranges have been found (default: 256).
+    pub fn max_spans(mut self, max: usize) -> Self {
+        self.max_spans = max;
+        self
+    }
+
+    /// Compute the styled ranges of `line`, in ascending order of priority.
+    pub fn spans(&self, line: &str) -> Vec<(Range<usize>, StyleModifier)> {
+        let mut end = self.max_line_length.min(line.len());
+        while !line.is_char_boundary(end) {
+            end -= 1;
+        }
+        let line = &line[..end];
+
+        let mut spans = Vec::new();
+        for rule in &self.rules {
+            if spans.len() >= self.max_spans {
+                break;
+            }
+            let matches = rule.pattern.find_all(line);
+            if rule.whole_line {
+                if !matches.is_empty() {
+                    spans.push((0..line.len(), rule.style));
+                }
+            } else {
+                let remaining = self.max_spans - spans.len();
+                spans.extend(
+                    matches
+                        .into_iter()
+                        .filter(|r| r.start < r.end && r.end <= line.len())
+                        .take(remaining)
+                        .map(|r| (r, rule.style)),
+                );
+            }
+        }
+        spans
+    }
+
+    /// Split `line` into consecutive segments, each with the combined style of all rules that
+    /// apply to it.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::StyleModifier;
+    /// use unsegen::widget::{StylingRule, StylingRules};
+    ///
+    /// let bold = StyleModifier::new().bold(true);
+    /// let rules = StylingRules::new().rule(StylingRule::spans("b", bold));
+    /// assert_eq!(
+    ///     rules.segments("abc"),
+    ///     vec![("a", StyleModifier::new()), ("b", bold), ("c", StyleModifier::new())]
+    /// );
+    /// ```
+    pub fn segments<'l>(&self, line: &'l str) -> Vec<(&'l str, StyleModifier)> {
+        Self::segments_of(line, self.spans(line))
+    }
+
+    fn segments_of(
+        line: &str,
+        spans: Vec<(Range<usize>, StyleModifier)>,
+    ) -> Vec<(&str, StyleModifier)> {
+        let mut boundaries = spans
+            .iter()
+            .flat_map(|(r, _)| vec![r.start, r.end])
+            .chain(vec![0, line.len()])
+            .collect::<Vec<_>>();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        boundaries
+            .windows(2)
+            .map(|w| {
+                let style = spans
+                    .iter()
+                    .filter(|(r, _)| r.start <= w[0] && w[1] <= r.end)
+                    .fold(StyleModifier::new(), |style, (_, s)| s.on_top_of(style));
+                (&line[w[0]..w[1]], style)
+            })
+            .collect()
+    }
+
+    /// Write `line` using the cursor, styled according to the rules.
+    pub fn write<T: CursorTarget>(&self, cursor: &mut Cursor<T>, line: &str) {
+        self.write_with_spans(cursor, line, Vec::new());
+    }
+
+    /// Like `write`, but additionally apply the given spans (e.g., search matches) on top of the
+    /// styles of all rules.
+    pub(crate) fn write_with_spans<T: CursorTarget>(
+        &self,
+        cursor: &mut Cursor<T>,
+        line: &str,
+        extra: Vec<(Range<usize>, StyleModifier)>,
+    ) {
+        let mut spans = self.spans(line);
+        spans.extend(extra);
+        for (segment, style) in Self::segments_of(line, spans) {
+            let mut cursor = cursor.save().style_modifier();
+            cursor.apply_style_modifier(style);
+            cursor.write(segment);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_styling_rules_priority_and_caps() {
+        let a = StyleModifier::new().bold(true);
+        let b = StyleModifier::new().italic(true);
+        let c = StyleModifier::new().underline(true);
+        let rules = StylingRules::new()
+            .rule(StylingRule::spans("x", a).priority(2))
+            .rule(StylingRule::line("x", b))
+            .rule(StylingRule::spans("y", c).priority(1))
+            .max_line_length(4);
+        assert_eq!(
+            rules.spans("xyxyx"),
+            vec![(0..4, b), (1..2, c), (3..4, c), (0..1, a), (2..3, a)]
+        );
+        assert_eq!(
+            rules.segments("xyxyx"),
+            vec![
+                ("x", a.on_top_of(b)),
+                ("y", c.on_top_of(b)),
+                ("x", a.on_top_of(b)),
+                ("y", c.on_top_of(b)),
+                ("x", StyleModifier::new()),
+            ]
+        );
+        assert_eq!(rules.max_spans(2).spans("xyxyx").len(), 2);
+    }
+}
@@ -0,0 +1,509 @@
+//! Follow files (like `tail -F`) or named pipes on a background thread and feed the lines into a
+//! `LogViewer` (or anything else that implements `std::fmt::Write`).
+//!
+//! # Example:
+//! ```no_run
+//! use unsegen::base::RedrawScheduler;
+//! use unsegen::widget::builtin::LogViewer;
+//! use unsegen::widget::Tailer;
+//!
+//! let scheduler = RedrawScheduler::with_max_fps(30);
+//! let tailer = Tailer::file("/var/log/syslog")
+//!     .from_end(true)
+//!     .notify(scheduler.dirty_flag())
+//!     .start();
+//! let mut log = LogViewer::new();
+//!
+//! loop {
+//!     tailer.drain_into(&mut log).unwrap();
+//!     while let Some(error) = tailer.try_recv_error() {
+//!         eprintln!("{}", error);
+//!     }
+//!     // Draw log.as_widget() ...
+//! #   break;
+//! }
+//! ```
+use base::DirtyFlag;
+use nix::fcntl::OFlag;
+use nix::poll::{poll, PollFd, PollFlags};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+enum Source {
+    File(PathBuf),
+    Pipe(PathBuf),
+}
+
+/// Configures and starts a `Tailer`. See `Tailer::file` and `Tailer::pipe`.
+pub struct TailerBuilder {
+    source: Source,
+    capacity: usize,
+    poll_interval: Duration,
+    from_end: bool,
+    dirty: Option<DirtyFlag>,
+}
+
+impl TailerBuilder {
+    /// Set the maximum number of lines that are buffered until they are drained (default: 10000).
+    /// If the buffer is full, the oldest lines are dropped.
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set the interval in which a file is checked for new content, truncation and rotation, or a
+    /// pipe for new writers (default: 100ms). The background thread notices that the `Tailer` has
+    /// been dropped within this interval.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Skip the content that is already present in the file when starting (default: false). Does
+    /// not apply to pipes.
+    pub fn from_end(mut self, from_end: bool) -> Self {
+        self.from_end = from_end;
+        self
+    }
+
+    /// Mark the flag dirty whenever new lines are available.
+    pub fn notify(mut self, dirty: DirtyFlag) -> Self {
+        self.dirty = Some(dirty);
+        self
+    }
+
+    /// Start following the source on a background thread.
+    pub fn start(self) -> Tailer {
+        let (error_sender, errors) = channel();
+        let shared = Arc::new(Shared {
+            lines: Mutex::new(VecDeque::new()),
+            capacity: self.capacity,
+            dropped: AtomicUsize::new(0),
+            stop: AtomicBool::new(false),
+            dirty: self.dirty,
+        });
+        let worker = Worker {
+            shared: shared.clone(),
+            errors: error_sender,
+            poll_interval: self.poll_interval,
+            partial: Vec::new(),
+        };
+        let from_end = self.from_end;
+        match self.source {
+            Source::File(path) => thread::spawn(move || worker.follow_file(path, from_end)),
+            Source::Pipe(path) => thread::spawn(move || worker.follow_pipe(path)),
+        };
+        Tailer { shared, errors }
+    }
+}
+
+struct Shared {
+    lines: Mutex<VecDeque<String>>,
+    capacity: usize,
+    dropped: AtomicUsize,
+    stop: AtomicBool,
+    dirty: Option<DirtyFlag>,
+}
+
+/// Reads lines from a file or named pipe on a background thread.
+///
+/// Lines are buffered until they are drained (`drain_into` or `take_lines`). If more than the
+/// configured capacity of lines arrive in between, the oldest ones are dropped. Errors (e.g., if
+/// the file does not exist (yet)) are reported via `try_recv_error`, but do not stop the
+/// `Tailer`.
+///
+/// The background thread is stopped when the `Tailer` is dropped.
+pub struct Tailer {
+    shared: Arc<Shared>,
+    errors: Receiver<io::Error>,
+}
+
+impl Tailer {
+    /// Follow the file at `path` like `tail -F`: New content is read as it is appended. If the file
+    /// is truncated, it is read again from the beginning. If the file is replaced (e.g., by log
+    /// rotation) or is created later, the new file is followed.
+    pub fn file(path: impl Into<PathBuf>) -> TailerBuilder {
+        Self::builder(Source::File(path.into()))
+    }
+
+    /// Read lines from the named pipe (FIFO) at `path`. The pipe is kept open when all writers
+    /// close it, so that lines of new writers are read as well. An incomplete last line is
+    /// flushed once there are no writers anymore.
+    pub fn pipe(path: impl Into<PathBuf>) -> TailerBuilder {
+        Self::builder(Source::Pipe(path.into()))
+    }
+
+    fn builder(source: Source) -> TailerBuilder {
+        TailerBuilder {
+            source,
+            capacity: 10000,
+            poll_interval: Duration::from_millis(100),
+            from_end: false,
+            dirty: None,
+        }
+    }
+
+    /// Take all lines that have been read since the last call.
+    pub fn take_lines(&self) -> Vec<String> {
+        self.shared.lines.lock().unwrap().drain(..).collect()
+    }
+
+    /// Write all lines that have been read since the last call to `sink` (e.g., a `LogViewer`).
+    /// Returns the number of lines written.
+    pub fn drain_into<W: fmt::Write>(&self, sink: &mut W) -> Result<usize, fmt::Error> {
+        let lines = self.take_lines();
+        for line in &lines {
+            writeln!(sink, "{}", line)?;
+        }
+        Ok(lines.len())
+    }
+
+    /// Get the total number of lines that have been dropped because the buffer was full.
+    pub fn dropped(&self) -> usize {
+        self.shared.dropped.load(Ordering::Acquire)
+    }
+
+    /// Get the next error that occurred on the background thread, if any.
+    pub fn try_recv_error(&self) -> Option<io::Error> {
+        self.errors.try_recv().ok()
+    }
+}
+
+impl Drop for Tailer {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Release);
+    }
+}
+
+struct Worker {
+    shared: Arc<Shared>,
+    errors: Sender<io::Error>,
+    poll_interval: Duration,
+    partial: Vec<u8>,
+}
+
+impl Worker {
+    fn stopped(&self) -> bool {
+        self.shared.stop.load(Ordering::Acquire)
+    }
+
+    fn report(&self, error: io::Error) {
+        let _ = self.errors.send(error);
+    }
+
+    fn push_line(&self, mut line: &[u8]) {
+        if line.last() == Some(&b'\r') {
+            line = &line[..line.len() - 1];
+        }
+        {
+            let mut lines = self.shared.lines.lock().unwrap();
+            if lines.len() == self.shared.capacity {
+                lines.pop_front();
+                self.shared.dropped.fetch_add(1, Ordering::AcqRel);
+            }
+            lines.push_back(String::from_utf8_lossy(line).into_owned());
+        }
+        if let Some(ref dirty) = self.shared.dirty {
+            dirty.mark_dirty();
+        }
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        self.partial.extend_from_slice(bytes);
+        if let Some(last_newline) = self.partial.iter().rposition(|b| *b == b'\n') {
+            let rest = self.partial.split_off(last_newline + 1);
+            let complete = ::std::mem::replace(&mut self.partial, rest);
+            for line in complete[..last_newline].split(|b| *b == b'\n') {
+                self.push_line(line);
+            }
+        }
+    }
+
+    fn flush_partial(&mut self) {
+        if !self.partial.is_empty() {
+            let line = ::std::mem::take(&mut self.partial);
+            self.push_line(&line);
+        }
+    }
+
+    fn follow_file(mut self, path: PathBuf, from_end: bool) {
+        let mut current: Option<(File, u64)> = None;
+        let mut pos = 0;
+        let mut skip_existing = from_end;
+        let mut reported_open_error = false;
+        let mut buf = [0u8; 8192];
+        while !self.stopped() {
+            if current.is_none() {
+                match File::open(&path).and_then(|f| {
+                    let inode = f.metadata()?.ino();
+                    Ok((f, inode))
+                }) {
+                    Ok((mut file, inode)) => {
+                        pos = if skip_existing {
+                            file.seek(SeekFrom::End(0)).unwrap_or(0)
+                        } else {
+                            0
+                        };
+                        current = Some((file, inode));
+                        reported_open_error = false;
+                    }
+                    Err(e) => {
+                        if !reported_open_error {
+                            self.report(e);
+                            reported_open_error = true;
+                        }
+                        thread::sleep(self.poll_interval);
+                        continue;
+                    }
+                }
+                skip_existing = false;
+            }
+            let (ref mut file, inode) = *current.as_mut().expect("opened above");
+            match file.read(&mut buf) {
+                Ok(0) => match fs::metadata(&path) {
+                    Ok(ref m) if m.ino() != inode => {
+                        // Rotated: The old file has been read completely, continue with the new one.
+                        self.flush_partial();
+                        current = None;
+                    }
+                    Ok(ref m) if m.len() < pos => {
+                        // Truncated: Read again from the beginning.
+                        self.partial.clear();
+                        pos = file.seek(SeekFrom::Start(0)).unwrap_or(0);
+                    }
+                    _ => thread::sleep(self.poll_interval),
+                },
+                Ok(n) => {
+                    pos += n as u64;
+                    self.push_bytes(&buf[..n]);
+                }
+                Err(e) => {
+                    self.report(e);
+                    current = None;
+                    thread::sleep(self.poll_interval);
+                }
+            }
+        }
+    }
+
+    fn follow_pipe(mut self, path: PathBuf) {
+        let mut reported_open_error = false;
+        let mut buf = [0u8; 8192];
+        while !self.stopped() {
+            // Opening the pipe non-blocking does not wait for a writer, so the thread never blocks
+            // and notices when the `Tailer` is dropped.
+            let mut file = match fs::OpenOptions::new()
+                .read(true)
+                .custom_flags(OFlag::O_NONBLOCK.bits())
+                .open(&path)
+            {
+                Ok(file) => {
+                    reported_open_error = false;
+                    file
+                }
+                Err(e) => {
+                    if !reported_open_error {
+                        self.report(e);
+                        reported_open_error = true;
+                    }
+                    thread::sleep(self.poll_interval);
+                    continue;
+                }
+            };
+            while !self.stopped() {
+                match file.read(&mut buf) {
+                    Ok(0) => {
+                        // There is no writer (anymore). New writers can still be read from the
+                        // same file.
+                        self.flush_partial();
+                        thread::sleep(self.poll_interval);
+                    }
+                    Ok(n) => self.push_bytes(&buf[..n]),
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        let mut fds = [PollFd::new(file.as_raw_fd(), PollFlags::POLLIN)];
+                        let _ = poll(&mut fds, self.poll_interval.as_millis() as i32);
+                    }
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        self.report(e);
+                        self.flush_partial();
+                        thread::sleep(self.poll_interval);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nix::sys::stat::Mode;
+    use nix::unistd::mkfifo;
+    use std::io::Write;
+    use std::time::Instant;
+
+    fn wait_for_lines(tailer: &Tailer, n: usize) -> Vec<String> {
+        let start = Instant::now();
+        let mut lines = Vec::new();
+        while lines.len() < n && start.elapsed() < Duration::from_secs(5) {
+            lines.extend(tailer.take_lines());
+            thread::sleep(Duration::from_millis(5));
+        }
+        lines
+    }
+
+    #[test]
+    fn test_tailer_file_truncation_and_rotation() {
+        let dir = ::std::env::temp_dir().join(format!("unsegen-tailer-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("log");
+        fs::write(&path, "old\n").unwrap();
+
+        let tailer = Tailer::file(&path)
+            .from_end(true)
+            .poll_interval(Duration::from_millis(5))
+            .start();
+        // Give the worker a chance to skip the existing content.
+        thread::sleep(Duration::from_millis(50));
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "a\r\nb").unwrap();
+        file.flush().unwrap();
+        assert_eq!(wait_for_lines(&tailer, 1), vec!["a"]);
+        writeln!(file, "c").unwrap();
+        assert_eq!(wait_for_lines(&tailer, 1), vec!["bc"]);
+
+        fs::write(&path, "d\n").unwrap();
+        assert_eq!(wait_for_lines(&tailer, 1), vec!["d"]);
+
+        let rotated = dir.join("log.1");
+        fs::rename(&path, &rotated).unwrap();
+        fs::write(&path, "e\nf\n").unwrap();
+        assert_eq!(wait_for_lines(&tailer, 2), vec!["e", "f"]);
+
+        let mut log = String::new();
+        assert_eq!(tailer.drain_into(&mut log), Ok(0));
+        assert!(tailer.try_recv_error().is_none());
+        drop(tailer);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = ::std::env::temp_dir().join(format!(
+            "unsegen-tailer-{}-{}",
+            name,
+            ::std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn wait_until<F: FnMut() -> bool>(mut condition: F) -> bool {
+        let start = Instant::now();
+        while !condition() {
+            if start.elapsed() > Duration::from_secs(5) {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        true
+    }
+
+    #[test]
+    fn test_tailer_pipe() {
+        let dir = temp_dir("pipe");
+        let path = dir.join("fifo");
+        mkfifo(&path, Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
+
+        let tailer = Tailer::pipe(&path)
+            .poll_interval(Duration::from_millis(5))
+            .start();
+        // Opening the pipe for writing blocks until the tailer has opened it for reading.
+        {
+            let mut writer = fs::OpenOptions::new().write(true).open(&path).unwrap();
+            write!(writer, "a\nb").unwrap();
+        }
+        // The incomplete line is flushed once the writer is gone.
+        assert_eq!(wait_for_lines(&tailer, 2), vec!["a", "b"]);
+        {
+            let mut writer = fs::OpenOptions::new().write(true).open(&path).unwrap();
+            writeln!(writer, "c").unwrap();
+        }
+        assert_eq!(wait_for_lines(&tailer, 1), vec!["c"]);
+        assert!(tailer.try_recv_error().is_none());
+
+        // The worker stops although no writer will ever open the pipe again.
+        let shared = tailer.shared.clone();
+        drop(tailer);
+        assert!(wait_until(|| Arc::strong_count(&shared) == 1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_tailer_capacity() {
+        let dir = temp_dir("capacity");
+        let path = dir.join("log");
+        fs::write(&path, "1\n2\n3\n4\n5\n").unwrap();
+
+        let tailer = Tailer::file(&path)
+            .capacity(2)
+            .poll_interval(Duration::from_millis(5))
+            .start();
+        assert!(wait_until(|| tailer.dropped() == 3));
+        assert_eq!(tailer.take_lines(), vec!["4", "5"]);
+        assert_eq!(tailer.dropped(), 3);
+
+        fs::write(&path, "6\n").unwrap();
+        assert_eq!(wait_for_lines(&tailer, 1), vec!["6"]);
+        assert_eq!(tailer.dropped(), 3);
+        drop(tailer);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_tailer_errors() {
+        let dir = temp_dir("errors");
+        let path = dir.join("log");
+
+        let tailer = Tailer::file(&path)
+            .poll_interval(Duration::from_millis(5))
+            .start();
+        let pipe_tailer = Tailer::pipe(dir.join("fifo"))
+            .poll_interval(Duration::from_millis(5))
+            .start();
+        for tailer in &[&tailer, &pipe_tailer] {
+            let mut error = None;
+            assert!(wait_until(|| {
+                error = tailer.try_recv_error();
+                error.is_some()
+            }));
+            assert_eq!(error.unwrap().kind(), io::ErrorKind::NotFound);
+        }
+
+        // A failing open is only reported once, and the file is followed once it exists.
+        thread::sleep(Duration::from_millis(50));
+        assert!(tailer.try_recv_error().is_none());
+        assert!(pipe_tailer.try_recv_error().is_none());
+        fs::write(&path, "a\n").unwrap();
+        assert_eq!(wait_for_lines(&tailer, 1), vec!["a"]);
+        assert!(tailer.try_recv_error().is_none());
+        drop(tailer);
+        drop(pipe_tailer);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
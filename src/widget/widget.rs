@@ -1,7 +1,12 @@
 //! The `Widget` abstraction and some related types.
 use base::basic_types::*;
-use base::{Cursor, Window, WrappingMode};
+use base::{
+    Color, Cursor, CursorTarget, ExtentEstimationWindow, Style, StyleModifier, StyledBuffer,
+    Window, WindowBuffer, WrappingMode,
+};
+use std::cell::RefCell;
 use std::cmp::max;
+use std::collections::HashMap;
 use std::iter::Sum;
 use std::marker::PhantomData;
 use std::ops::{Add, AddAssign};
@@ -14,6 +19,17 @@ pub trait Widget {
     /// demands on subsequent calls.
     fn space_demand(&self) -> Demand2D;
 
+    /// Return the demand for rows if the widget is drawn to a window of the given width.
+    ///
+    /// Widgets whose height depends on their width (e.g., wrapped text) should override this to
+    /// report the height of their content at that width, so that vertical layouts can assign them
+    /// exactly the rows they need. Measuring can be expensive, so consider wrapping such widgets
+    /// using `WidgetExt::measured`. By default, the height of `space_demand` is returned.
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        let _ = width;
+        self.space_demand().height
+    }
+
     /// Draw the widget to the given window.
     ///
     /// There is no guarantee that the window is of the size
@@ -51,6 +67,116 @@ pub trait WidgetExt: Widget + Sized {
     fn with_demand<F: Fn(Demand2D) -> Demand2D>(self, f: F) -> WithDemand<Self, F> {
         WithDemand(self, f)
     }
+
+    /// Modify the default style of the window before letting the widget draw itself in it.
+    fn with_style(self, modifier: StyleModifier) -> WithStyle<Self> {
+        WithStyle(self, modifier)
+    }
+
+    /// Demand at least the specified width and height, regardless of the demand of the widget.
+    fn min_size(self, width: Width, height: Height) -> MinSize<Self> {
+        MinSize(self, width, height)
+    }
+
+    /// Demand and draw to at most the specified width and height, regardless of the demand of the
+    /// widget.
+    fn max_size(self, width: Width, height: Height) -> MaxSize<Self> {
+        MaxSize(self, width, height)
+    }
+
+    /// Call `f` with the window and hints every time before the widget is drawn. This can be
+    /// useful for instrumentation, e.g., to record the size of the window a widget is drawn to.
+    fn on_draw<F: Fn(&Window, RenderingHints)>(self, f: F) -> OnDraw<Self, F> {
+        OnDraw(self, f)
+    }
+
+    /// Only redraw the widget if `version` (e.g., a hash of its content), the size of the window,
+    /// its default style or the rendering hints changed since the last draw using the same
+    /// `cache`. Otherwise the previous result is copied from the cache.
+    ///
+    /// This can save a lot of work for widgets that are expensive to draw (e.g., syntax
+    /// highlighted text or big tables), at the cost of keeping a copy of the drawn cells in memory.
+    /// Note that cells the widget does not draw to are cleared.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::terminal::test::FakeTerminal;
+    /// use unsegen::widget::*;
+    /// use std::cell::Cell;
+    ///
+    /// let cache = RenderCache::new();
+    /// let draws = Cell::new(0);
+    /// for (text, version) in [("foo", 0), ("foo", 0), ("bar", 1)].iter() {
+    ///     let mut term = FakeTerminal::with_size((3, 1));
+    ///     text.on_draw(|_, _| draws.set(draws.get() + 1))
+    ///         .cached(&cache, *version)
+    ///         .draw(term.create_root_window(), RenderingHints::new());
+    ///     term.assert_looks_like(text);
+    /// }
+    /// assert_eq!(draws.get(), 2);
+    /// ```
+    fn cached(self, cache: &RenderCache, version: u64) -> Cached<'_, Self> {
+        Cached(self, cache, version)
+    }
+
+    /// Only measure the widget (see `Widget::height_for_width`) if `generation` (e.g., a counter
+    /// that is incremented whenever its content changes) changed since the last measurement at
+    /// the same width. The results are stored in `cache` under the `id` of the widget, so a single
+    /// cache can be shared by all widgets of an application.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::*;
+    /// use unsegen::widget::*;
+    ///
+    /// let cache = MeasurementCache::new();
+    /// let text = "foo bar";
+    /// let width = Width::new(4).unwrap();
+    /// assert_eq!(text.measured(&cache, 0, 0).height_for_width(width), RowDemand::exact(2));
+    /// // The text changed, but the generation did not, so the cached result is returned:
+    /// assert_eq!("foo".measured(&cache, 0, 0).height_for_width(width), RowDemand::exact(2));
+    /// assert_eq!("foo".measured(&cache, 0, 1).height_for_width(width), RowDemand::exact(1));
+    /// ```
+    fn measured(self, cache: &MeasurementCache, id: u64, generation: u64) -> Measured<'_, Self> {
+        Measured {
+            widget: self,
+            cache,
+            id,
+            generation,
+        }
+    }
+
+    /// Grey out the widget if `disabled` is true (see `Disabled`).
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::base::terminal::test::FakeTerminal;
+    /// use unsegen::base::StyleModifier;
+    /// use unsegen::widget::*;
+    ///
+    /// let mut term = FakeTerminal::with_size((3, 1));
+    /// "foo"
+    ///     .on_draw(|_, hints| assert!(!hints.enabled && !hints.active))
+    ///     .disabled(true)
+    ///     .style(StyleModifier::new().bold(true))
+    ///     .draw(term.create_root_window(), RenderingHints::new());
+    /// term.assert_looks_like("*f**o**o*");
+    /// ```
+    fn disabled(self, disabled: bool) -> Disabled<Self> {
+        Disabled {
+            widget: self,
+            disabled,
+            style: StyleModifier::new().fg_color(Color::LightBlack),
+        }
+    }
+
+    /// Box the widget, for example to store differently typed widgets in a single collection.
+    fn boxed<'a>(self) -> Box<dyn Widget + 'a>
+    where
+        Self: 'a,
+    {
+        Box::new(self)
+    }
 }
 
 impl<W: Widget + Sized> WidgetExt for W {}
@@ -66,6 +192,9 @@ impl<W: Widget> Widget for Centered<W> {
     fn space_demand(&self) -> Demand2D {
         self.0.space_demand()
     }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.0.height_for_width(width)
+    }
     fn draw(&self, mut window: Window, hints: RenderingHints) {
         let demand = self.space_demand();
 
@@ -98,6 +227,9 @@ impl<W: Widget, F: Fn(Window, RenderingHints) -> Window> Widget for WithWindow<W
     fn space_demand(&self) -> Demand2D {
         self.0.space_demand()
     }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.0.height_for_width(width)
+    }
     fn draw(&self, window: Window, hints: RenderingHints) {
         self.0.draw(self.1(window, hints), hints);
     }
@@ -112,6 +244,9 @@ impl<W: Widget, F: Fn(RenderingHints) -> RenderingHints> Widget for WithHints<W,
     fn space_demand(&self) -> Demand2D {
         self.0.space_demand()
     }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.0.height_for_width(width)
+    }
     fn draw(&self, window: Window, hints: RenderingHints) {
         self.0.draw(window, self.1(hints));
     }
@@ -127,11 +262,346 @@ impl<W: Widget, F: Fn(Demand2D) -> Demand2D> Widget for WithDemand<W, F> {
     fn space_demand(&self) -> Demand2D {
         self.1(self.0.space_demand())
     }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        let demand = Demand2D {
+            width: self.0.space_demand().width,
+            height: self.0.height_for_width(width),
+        };
+        self.1(demand).height
+    }
+    fn draw(&self, window: Window, hints: RenderingHints) {
+        self.0.draw(window, hints);
+    }
+}
+
+/// Modify the default style of the window before drawing the wrapped widget.
+///
+/// This wrapper can be created using `WidgetExt::with_style`.
+pub struct WithStyle<W>(W, StyleModifier);
+
+impl<W: Widget> Widget for WithStyle<W> {
+    fn space_demand(&self) -> Demand2D {
+        self.0.space_demand()
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.0.height_for_width(width)
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        window.modify_default_style(self.1);
+        self.0.draw(window, hints);
+    }
+}
+
+/// Draw the wrapped widget greyed out (if disabled), i.e., with a dimmed default style and with
+/// rendering hints that are neither enabled nor active. Combine with `DisabledBehavior` to also
+/// ignore the input of the widget.
+///
+/// This wrapper can be created using `WidgetExt::disabled`.
+pub struct Disabled<W> {
+    widget: W,
+    disabled: bool,
+    style: StyleModifier,
+}
+
+impl<W> Disabled<W> {
+    /// Specify the style that is applied to the default style of the window if the widget is
+    /// disabled. (Default: light black, i.e., grey foreground.)
+    pub fn style(mut self, style: StyleModifier) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl<W: Widget> Widget for Disabled<W> {
+    fn space_demand(&self) -> Demand2D {
+        self.widget.space_demand()
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.widget.height_for_width(width)
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        if self.disabled {
+            window.modify_default_style(self.style);
+            self.widget.draw(window, hints.enabled(false).active(false));
+        } else {
+            self.widget.draw(window, hints);
+        }
+    }
+}
+
+/// Demand at least the specified width and height.
+///
+/// This wrapper can be created using `WidgetExt::min_size`.
+pub struct MinSize<W>(W, Width, Height);
+
+/// Raise the minimum (and, if necessary, the maximum) of `demand` to at least `min`.
+fn raise_demand<T: AxisDimension + Ord>(demand: Demand<T>, min: PositiveAxisDiff<T>) -> Demand<T> {
+    Demand {
+        min: demand.min.max(min),
+        max: demand.max.map(|m| m.max(min)),
+        fraction: demand.fraction,
+        _dim: Default::default(),
+    }
+}
+
+impl<W: Widget> Widget for MinSize<W> {
+    fn space_demand(&self) -> Demand2D {
+        let demand = self.0.space_demand();
+        Demand2D {
+            width: raise_demand(demand.width, self.1),
+            height: raise_demand(demand.height, self.2),
+        }
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        raise_demand(self.0.height_for_width(width), self.2)
+    }
     fn draw(&self, window: Window, hints: RenderingHints) {
         self.0.draw(window, hints);
     }
 }
 
+/// Demand and draw to at most the specified width and height.
+///
+/// This wrapper can be created using `WidgetExt::max_size`.
+pub struct MaxSize<W>(W, Width, Height);
+
+fn limit_demand<T: AxisDimension + Ord>(
+    demand: Demand<T>,
+    limit: PositiveAxisDiff<T>,
+) -> Demand<T> {
+    Demand {
+        min: demand.min.min(limit),
+        max: Some(demand.max.unwrap_or(limit).min(limit)),
+        fraction: demand.fraction,
+        _dim: Default::default(),
+    }
+}
+
+impl<W: Widget> Widget for MaxSize<W> {
+    fn space_demand(&self) -> Demand2D {
+        let demand = self.0.space_demand();
+        Demand2D {
+            width: limit_demand(demand.width, self.1),
+            height: limit_demand(demand.height, self.2),
+        }
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        limit_demand(self.0.height_for_width(width.min(self.1)), self.2)
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        let width = window.get_width().min(self.1);
+        let height = window.get_height().min(self.2);
+        let window = window.create_subwindow(
+            0.into()..width.from_origin(),
+            0.into()..height.from_origin(),
+        );
+        self.0.draw(window, hints);
+    }
+}
+
+/// Call a function with the window and hints before drawing the wrapped widget.
+///
+/// This wrapper can be created using `WidgetExt::on_draw`.
+pub struct OnDraw<W, F>(W, F);
+
+impl<W: Widget, F: Fn(&Window, RenderingHints)> Widget for OnDraw<W, F> {
+    fn space_demand(&self) -> Demand2D {
+        self.0.space_demand()
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.0.height_for_width(width)
+    }
+    fn draw(&self, window: Window, hints: RenderingHints) {
+        self.1(&window, hints);
+        self.0.draw(window, hints);
+    }
+}
+
+struct CacheEntry {
+    version: u64,
+    width: Width,
+    height: Height,
+    default_style: Style,
+    hints: RenderingHints,
+    buffer: StyledBuffer,
+}
+
+/// Stores the drawn cells of a `Cached` widget between draws.
+///
+/// The cache has to outlive the (usually temporary) widget, so it should be stored alongside the
+/// state of the widget.
+#[derive(Default)]
+pub struct RenderCache {
+    entry: RefCell<Option<CacheEntry>>,
+}
+
+impl RenderCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Force a redraw during the next draw of the `Cached` widget.
+    pub fn invalidate(&self) {
+        *self.entry.borrow_mut() = None;
+    }
+}
+
+/// Only redraw the wrapped widget if its version (or the window or hints) changed.
+///
+/// This wrapper can be created using `WidgetExt::cached`.
+pub struct Cached<'a, W>(W, &'a RenderCache, u64);
+
+impl<'a, W: Widget> Widget for Cached<'a, W> {
+    fn space_demand(&self) -> Demand2D {
+        self.0.space_demand()
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.0.height_for_width(width)
+    }
+    fn draw(&self, mut window: Window, hints: RenderingHints) {
+        let width = window.get_width();
+        let height = window.get_height();
+        let default_style = *window.default_style();
+        let mut entry = self.1.entry.borrow_mut();
+        let valid = entry.as_ref().is_some_and(|e| {
+            e.version == self.2
+                && e.width == width
+                && e.height == height
+                && e.default_style == default_style
+                && e.hints == hints
+        });
+        if !valid {
+            let mut window_buffer = WindowBuffer::new(width, height);
+            let mut buffer = StyledBuffer::with_width(width);
+            buffer.set_default_style(default_style);
+            {
+                let mut offscreen = window_buffer.as_window();
+                offscreen.set_default_style(default_style);
+                offscreen.clear();
+                self.0.draw(offscreen, hints);
+            }
+            let offscreen = window_buffer.as_window();
+            for y in 0..height.raw_value() {
+                for x in 0..width.raw_value() {
+                    let (x, y) = (ColIndex::new(x), RowIndex::new(y));
+                    if let (Some(src), Some(dst)) =
+                        (offscreen.get_cell(x, y), buffer.get_cell_mut(x, y))
+                    {
+                        *dst = src.clone();
+                    }
+                }
+            }
+            *entry = Some(CacheEntry {
+                version: self.2,
+                width,
+                height,
+                default_style,
+                hints,
+                buffer,
+            });
+        }
+        if let Some(entry) = entry.as_ref() {
+            entry
+                .buffer
+                .blit(&mut window, (ColIndex::new(0), RowIndex::new(0)));
+        }
+    }
+}
+
+// Demands of a single widget, indexed by the raw width.
+type Measurements = HashMap<i32, RowDemand>;
+
+/// Stores the results of measuring `Measured` widgets (see `Widget::height_for_width`) between
+/// layout passes.
+///
+/// Results are stored per widget id and width. All results of a widget are discarded once it is
+/// measured with a different generation.
+#[derive(Default)]
+pub struct MeasurementCache {
+    entries: RefCell<HashMap<u64, (u64, Measurements)>>,
+}
+
+impl MeasurementCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Force a measurement of the widget with the given id during the next layout pass.
+    pub fn invalidate(&self, id: u64) {
+        self.entries.borrow_mut().remove(&id);
+    }
+
+    /// Force a measurement of all widgets during the next layout pass, e.g., after a change of
+    /// the rendering profile or the emoji width mode.
+    pub fn clear(&self) {
+        self.entries.borrow_mut().clear();
+    }
+
+    fn height_for_width<W: Widget + ?Sized>(
+        &self,
+        widget: &W,
+        id: u64,
+        generation: u64,
+        width: Width,
+    ) -> RowDemand {
+        if let Some((g, results)) = self.entries.borrow().get(&id) {
+            if *g == generation {
+                if let Some(demand) = results.get(&width.raw_value()) {
+                    return *demand;
+                }
+            }
+        }
+        // Measure without holding a borrow, since the widget may use the cache itself.
+        let demand = widget.height_for_width(width);
+        let mut entries = self.entries.borrow_mut();
+        let entry = entries
+            .entry(id)
+            .or_insert_with(|| (generation, HashMap::new()));
+        if entry.0 != generation {
+            *entry = (generation, HashMap::new());
+        }
+        entry.1.insert(width.raw_value(), demand);
+        demand
+    }
+}
+
+/// Only measure the wrapped widget if its generation changed.
+///
+/// This wrapper can be created using `WidgetExt::measured`.
+pub struct Measured<'a, W> {
+    widget: W,
+    cache: &'a MeasurementCache,
+    id: u64,
+    generation: u64,
+}
+
+impl<'a, W: Widget> Widget for Measured<'a, W> {
+    fn space_demand(&self) -> Demand2D {
+        self.widget.space_demand()
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.cache
+            .height_for_width(&self.widget, self.id, self.generation, width)
+    }
+    fn draw(&self, window: Window, hints: RenderingHints) {
+        self.widget.draw(window, hints);
+    }
+}
+
+impl<'a> Widget for Box<dyn Widget + 'a> {
+    fn space_demand(&self) -> Demand2D {
+        self.as_ref().space_demand()
+    }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        self.as_ref().height_for_width(width)
+    }
+    fn draw(&self, window: Window, hints: RenderingHints) {
+        self.as_ref().draw(window, hints)
+    }
+}
+
 impl<S: std::convert::AsRef<str>> Widget for S {
     fn space_demand(&self) -> Demand2D {
         let mut width = 0;
@@ -145,6 +615,13 @@ impl<S: std::convert::AsRef<str>> Widget for S {
             height: Demand::exact(height),
         }
     }
+    fn height_for_width(&self, width: Width) -> RowDemand {
+        let mut window = ExtentEstimationWindow::with_width(width);
+        Cursor::new(&mut window)
+            .wrapping_mode(WrappingMode::Wrap)
+            .write(self.as_ref());
+        Demand::exact(window.extent_y())
+    }
     fn draw(&self, mut window: Window, _hints: RenderingHints) {
         let mut cursor = Cursor::new(&mut window).wrapping_mode(WrappingMode::Wrap);
         cursor.write(self.as_ref());
@@ -153,10 +630,12 @@ impl<S: std::convert::AsRef<str>> Widget for S {
 
 /// Hints that can be used by applications to control how Widgets are rendered and used by Widgets
 /// to deduce how to render to best show the current application state.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct RenderingHints {
     /// e.g., whether or not this Widget receives input
     pub active: bool,
+    /// Whether the widget can be interacted with at all (see `Disabled`).
+    pub enabled: bool,
     /// Periodic signal that can be used to e.g. let a cursor blink.
     pub blink: Blink,
 
@@ -177,6 +656,7 @@ impl RenderingHints {
     pub fn new() -> Self {
         RenderingHints {
             active: true,
+            enabled: true,
             blink: Blink::On,
             _do_not_construct: (),
         }
@@ -189,6 +669,15 @@ impl RenderingHints {
         }
     }
 
+    /// Hint on whether the widget is enabled. Disabled widgets cannot be interacted with (e.g.,
+    /// during long running operations) and are usually also inactive.
+    pub fn enabled(self, val: bool) -> Self {
+        RenderingHints {
+            enabled: val,
+            ..self
+        }
+    }
+
     /// Use this to implement blinking effects for your widget. Usually, Blink can be expected to
     /// alternate every second or so.
     pub fn blink(self, val: Blink) -> Self {
@@ -199,7 +688,7 @@ impl RenderingHints {
 /// A value from a periodic boolean signal.
 ///
 /// Think of it like the state of an LED or cursor (block).
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(missing_docs)]
 pub enum Blink {
     On,
@@ -225,26 +714,36 @@ impl Blink {
 ///
 /// A Demand always has a minimum (although it may be zero) and may have a maximum. It is required
 /// that the minimum is smaller or equal to the maximum (if present).
-#[derive(Eq, PartialEq, PartialOrd, Clone, Copy, Debug)]
+///
+/// Additionally, a demand can request a fraction of the available space (see `Demand::fraction`).
+#[derive(PartialEq, PartialOrd, Clone, Copy, Debug)]
 #[allow(missing_docs)]
 pub struct Demand<T: AxisDimension> {
     pub min: PositiveAxisDiff<T>,
     pub max: Option<PositiveAxisDiff<T>>,
+    /// Fraction (in [0, 1]) of the available space, which is resolved before the remaining space
+    /// is distributed among other demands. (See `layout_linearly`.)
+    pub fraction: Option<f64>,
     _dim: PhantomData<T>,
 }
 
+// fraction is never NaN, so equality is reflexive.
+impl<T: AxisDimension + Eq> Eq for Demand<T> {}
+
+/// Combine two fractions of demands. If only one of the demands has a fraction, it is dropped.
+fn combine_fractions<F: Fn(f64, f64) -> f64>(l: Option<f64>, r: Option<f64>, f: F) -> Option<f64> {
+    if let (Some(l), Some(r)) = (l, r) {
+        Some(f(l, r))
+    } else {
+        None
+    }
+}
+
+/// Adding demands saturates (see `Demand::saturating_add`).
 impl<T: AxisDimension> Add<Demand<T>> for Demand<T> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
-        Demand {
-            min: self.min + rhs.min,
-            max: if let (Some(l), Some(r)) = (self.max, rhs.max) {
-                Some(l + r)
-            } else {
-                None
-            },
-            _dim: Default::default(),
-        }
+        self.saturating_add(rhs)
     }
 }
 impl<T: AxisDimension> AddAssign for Demand<T> {
@@ -269,6 +768,74 @@ impl<'a, T: AxisDimension + PartialOrd + Ord> Sum<&'a Demand<T>> for Demand<T> {
     }
 }
 
+impl<T: AxisDimension> Demand<T> {
+    /// Add two demands, returning None if the minimum or maximum would overflow.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::Demand;
+    /// use unsegen::base::*;
+    ///
+    /// let d1 = Demand::<ColDimension>::exact(5);
+    /// let d2 = Demand::<ColDimension>::at_least(2);
+    /// let big = Demand::<ColDimension>::exact(Width::UNBOUNDED);
+    ///
+    /// assert_eq!(d1.checked_add(d2), Some(Demand::<ColDimension>::at_least(7)));
+    /// assert_eq!(d1.checked_add(big), None);
+    /// ```
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Demand {
+            min: self.min.checked_add(rhs.min)?,
+            max: if let (Some(l), Some(r)) = (self.max, rhs.max) {
+                Some(l.checked_add(r)?)
+            } else {
+                None
+            },
+            fraction: combine_fractions(self.fraction, rhs.fraction, |l, r| (l + r).min(1.0)),
+            _dim: Default::default(),
+        })
+    }
+
+    /// Add two demands, saturating the minimum and maximum at `PositiveAxisDiff::UNBOUNDED`.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::Demand;
+    /// use unsegen::base::*;
+    ///
+    /// let d1 = Demand::<ColDimension>::exact(5);
+    /// let big = Demand::<ColDimension>::exact(Width::UNBOUNDED);
+    ///
+    /// assert_eq!(d1.saturating_add(big), big);
+    /// ```
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Demand {
+            min: self.min.saturating_add(rhs.min),
+            max: if let (Some(l), Some(r)) = (self.max, rhs.max) {
+                Some(l.saturating_add(r))
+            } else {
+                None
+            },
+            fraction: combine_fractions(self.fraction, rhs.fraction, |l, r| (l + r).min(1.0)),
+            _dim: Default::default(),
+        }
+    }
+
+    /// The maximum of the demand or `PositiveAxisDiff::UNBOUNDED` if there is none.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::Demand;
+    /// use unsegen::base::*;
+    ///
+    /// assert_eq!(Demand::<ColDimension>::exact(5).max_or_unbounded(), Width::new(5).unwrap());
+    /// assert_eq!(Demand::<ColDimension>::at_least(5).max_or_unbounded(), Width::UNBOUNDED);
+    /// ```
+    pub fn max_or_unbounded(&self) -> PositiveAxisDiff<T> {
+        self.max.unwrap_or(PositiveAxisDiff::UNBOUNDED)
+    }
+}
+
 impl<T: AxisDimension + PartialOrd + Ord> Demand<T> {
     /// A minimum and maximum demand of exactly 0.
     pub fn zero() -> Self {
@@ -280,6 +847,7 @@ impl<T: AxisDimension + PartialOrd + Ord> Demand<T> {
         Demand {
             min: size.into(),
             max: Some(size.into()),
+            fraction: None,
             _dim: Default::default(),
         }
     }
@@ -288,6 +856,7 @@ impl<T: AxisDimension + PartialOrd + Ord> Demand<T> {
         Demand {
             min: size.into(),
             max: None,
+            fraction: None,
             _dim: Default::default(),
         }
     }
@@ -297,6 +866,41 @@ impl<T: AxisDimension + PartialOrd + Ord> Demand<T> {
         Demand {
             min: min.into(),
             max: Some(max.into()),
+            fraction: None,
+            _dim: Default::default(),
+        }
+    }
+
+    /// Request the specified fraction (in [0, 1]) of the available space, without a minimum or
+    /// maximum.
+    ///
+    /// The fraction is resolved against the available space (after subtracting separators) before
+    /// the remaining space is distributed among all other demands, independent of any weights.
+    /// Combining demands (e.g., using `+` or `max`) only preserves the fraction if both demands
+    /// have one.
+    ///
+    /// # Examples:
+    /// ```
+    /// use unsegen::widget::{layout_linearly, Demand};
+    /// use unsegen::base::*;
+    ///
+    /// let demands = [
+    ///     Demand::<ColDimension>::fraction(0.25),
+    ///     Demand::<ColDimension>::at_least(0),
+    /// ];
+    /// let assigned = layout_linearly(Width::new(20).unwrap(), Width::new(0).unwrap(), &demands, &[1.0, 100.0]);
+    /// assert_eq!(assigned[0], Width::new(5).unwrap());
+    /// assert_eq!(assigned[1], Width::new(15).unwrap());
+    /// ```
+    pub fn fraction(fraction: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "Fraction must be within [0, 1]"
+        );
+        Demand {
+            min: PositiveAxisDiff::new_unchecked(0),
+            max: None,
+            fraction: Some(fraction),
             _dim: Default::default(),
         }
     }
@@ -322,6 +926,7 @@ impl<T: AxisDimension + PartialOrd + Ord> Demand<T> {
             } else {
                 None
             },
+            fraction: combine_fractions(self.fraction, other.fraction, f64::max),
             _dim: Default::default(),
         }
     }
@@ -414,3 +1019,106 @@ impl Demand2D {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::test::FakeTerminal;
+    use base::GraphemeCluster;
+    use std::cell::Cell;
+
+    struct Filled(Demand2D);
+
+    impl Widget for Filled {
+        fn space_demand(&self) -> Demand2D {
+            self.0
+        }
+        fn draw(&self, mut window: Window, _: RenderingHints) {
+            window.fill(GraphemeCluster::try_from('x').unwrap());
+        }
+    }
+
+    fn demand(width: ColDemand, height: RowDemand) -> Demand2D {
+        Demand2D { width, height }
+    }
+
+    #[test]
+    fn test_min_size() {
+        let w = Width::new(3).unwrap();
+        let h = Height::new(2).unwrap();
+        let exact = Filled(demand(ColDemand::exact(1), RowDemand::exact(1))).min_size(w, h);
+        assert_eq!(
+            exact.space_demand(),
+            demand(ColDemand::exact(3), RowDemand::exact(2))
+        );
+        assert_eq!(exact.height_for_width(w), RowDemand::exact(2));
+
+        let bounded = Filled(demand(ColDemand::from_to(1, 5), RowDemand::from_to(3, 4)));
+        assert_eq!(
+            bounded.min_size(w, h).space_demand(),
+            demand(ColDemand::from_to(3, 5), RowDemand::from_to(3, 4))
+        );
+
+        let unbounded = Filled(demand(ColDemand::at_least(0), RowDemand::at_least(5)));
+        assert_eq!(
+            unbounded.min_size(w, h).space_demand(),
+            demand(ColDemand::at_least(3), RowDemand::at_least(5))
+        );
+    }
+
+    #[test]
+    fn test_max_size() {
+        let widget = Filled(demand(ColDemand::at_least(1), RowDemand::from_to(1, 5)))
+            .max_size(Width::new(2).unwrap(), Height::new(3).unwrap());
+        assert_eq!(
+            widget.space_demand(),
+            demand(ColDemand::from_to(1, 2), RowDemand::from_to(1, 3))
+        );
+        assert_eq!(
+            widget.height_for_width(Width::new(10).unwrap()),
+            RowDemand::from_to(1, 3)
+        );
+
+        let mut term = FakeTerminal::with_size((3, 4));
+        term.create_root_window()
+            .fill(GraphemeCluster::try_from('_').unwrap());
+        widget.draw(term.create_root_window(), RenderingHints::new());
+        term.assert_looks_like("xx_|xx_|xx_|___");
+    }
+
+    #[test]
+    fn test_with_style() {
+        let widget = Filled(demand(ColDemand::exact(1), RowDemand::exact(1)))
+            .with_style(StyleModifier::new().bold(true));
+        assert_eq!(
+            widget.space_demand(),
+            demand(ColDemand::exact(1), RowDemand::exact(1))
+        );
+        let mut term = FakeTerminal::with_size((2, 1));
+        widget.draw(term.create_root_window(), RenderingHints::new());
+        term.assert_looks_like("*x**x*");
+    }
+
+    #[test]
+    fn test_on_draw() {
+        let size = Cell::new(None);
+        let active = Cell::new(None);
+        let widget = Filled(demand(ColDemand::exact(1), RowDemand::exact(1))).on_draw(
+            |window: &Window, hints: RenderingHints| {
+                size.set(Some((window.get_width(), window.get_height())));
+                active.set(Some(hints.active));
+            },
+        );
+        let mut term = FakeTerminal::with_size((3, 2));
+        widget.draw(
+            term.create_root_window(),
+            RenderingHints::new().active(false),
+        );
+        assert_eq!(
+            size.get(),
+            Some((Width::new(3).unwrap(), Height::new(2).unwrap()))
+        );
+        assert_eq!(active.get(), Some(false));
+        term.assert_looks_like("xxx|xxx");
+    }
+}